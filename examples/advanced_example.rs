@@ -2,12 +2,11 @@
 //! 
 //! This example demonstrates using custom parsing options and handling various error cases.
 
-use inih::{ini_parse_string_with_options, IniHandler, ParseOptions, IniParseError};
+use inih::prelude::*;
 
 #[derive(Debug, Default)]
 struct AdvancedConfig {
     values: Vec<(String, String, String)>, // (section, name, value)
-    errors: Vec<String>,
 }
 
 impl IniHandler for AdvancedConfig {
@@ -68,13 +67,15 @@ key7 = final value
 
     // Test with custom options
     println!("\n=== Testing with custom options ===");
-    let mut custom_options = ParseOptions::default();
-    custom_options.allow_multiline = true;
-    custom_options.allow_inline_comments = true;
-    custom_options.inline_comment_prefixes = ";#".to_string();
-    custom_options.start_comment_prefixes = ";#".to_string();
-    custom_options.allow_no_value = true;
-    custom_options.stop_on_first_error = false;
+    let custom_options = ParseOptions {
+        allow_multiline: true,
+        allow_inline_comments: true,
+        inline_comment_prefixes: CommentPrefixes::chars(";#"),
+        start_comment_prefixes: CommentPrefixes::chars(";#"),
+        allow_no_value: true,
+        stop_on_first_error: false,
+        ..ParseOptions::default()
+    };
 
     let mut config2 = AdvancedConfig::default();
     match ini_parse_string_with_options(ini_content, &mut config2, &custom_options) {
@@ -110,8 +111,10 @@ key2 = value2
 
     // Test with stop_on_first_error = true
     println!("\n=== Testing with stop_on_first_error = true ===");
-    let mut strict_options = ParseOptions::default();
-    strict_options.stop_on_first_error = true;
+    let strict_options = ParseOptions {
+        stop_on_first_error: true,
+        ..ParseOptions::default()
+    };
 
     let mut config4 = AdvancedConfig::default();
     match ini_parse_string_with_options(error_content, &mut config4, &strict_options) {
@@ -2,7 +2,7 @@
 //! 
 //! This example demonstrates the high-level IniReader API for easy access to INI values.
 
-use inih::IniReader;
+use inih::prelude::*;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Create a sample INI file content
@@ -31,8 +31,8 @@ trillion = 1000000000000 ; Test 64-bit integers
     
     // Read values with defaults
     let version = reader.get_integer("protocol", "version", -1);
-    let name = reader.get_string("user", "name", "UNKNOWN");
-    let email = reader.get_string("user", "email", "UNKNOWN");
+    let name = reader.get_nonempty_or("user", "name", "UNKNOWN");
+    let email = reader.get_nonempty_or("user", "email", "UNKNOWN");
     let active = reader.get_boolean("user", "active", false);
     let pi = reader.get_real("user", "pi", 0.0);
     let trillion = reader.get_integer64("user", "trillion", 0);
@@ -2,7 +2,7 @@
 //! 
 //! This example demonstrates the low-level IniHandler trait for custom parsing logic.
 
-use inih::{ini_parse_string, IniHandler, IniParseError};
+use inih::prelude::*;
 
 #[derive(Debug, Default)]
 struct Config {
@@ -28,11 +28,8 @@ impl IniHandler for Config {
                 self.email = value.to_string();
             }
             ("user", "active") => {
-                self.active = match value.to_lowercase().as_str() {
-                    "true" | "yes" | "on" | "1" => true,
-                    "false" | "no" | "off" | "0" => false,
-                    _ => return Err(format!("Invalid boolean value '{}'", value)),
-                };
+                self.active = inih::value::parse_bool(value)
+                    .ok_or_else(|| format!("Invalid boolean value '{}'", value))?;
             }
             ("user", "pi") => {
                 self.pi = value.parse()
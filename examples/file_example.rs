@@ -2,8 +2,7 @@
 //! 
 //! This example demonstrates reading INI data from an actual file.
 
-use inih::IniReader;
-use std::path::Path;
+use inih::prelude::*;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Create a sample INI file
@@ -44,15 +43,15 @@ max_size = 1024
     
     // Read database configuration
     println!("Database Configuration:");
-    println!("  Host: {}", reader.get_string("database", "host", "localhost"));
+    println!("  Host: {}", reader.get_nonempty_or("database", "host", "localhost"));
     println!("  Port: {}", reader.get_integer("database", "port", 5432));
-    println!("  Name: {}", reader.get_string("database", "name", ""));
-    println!("  User: {}", reader.get_string("database", "user", ""));
-    println!("  Password: {}", reader.get_string("database", "password", ""));
+    println!("  Name: {}", reader.get_nonempty_or("database", "name", ""));
+    println!("  User: {}", reader.get_nonempty_or("database", "user", ""));
+    println!("  Password: {}", reader.get_nonempty_or("database", "password", ""));
     
     // Read server configuration
     println!("\nServer Configuration:");
-    println!("  Host: {}", reader.get_string("server", "host", "127.0.0.1"));
+    println!("  Host: {}", reader.get_nonempty_or("server", "host", "127.0.0.1"));
     println!("  Port: {}", reader.get_integer("server", "port", 3000));
     println!("  Debug: {}", reader.get_boolean("server", "debug", false));
     println!("  Max Connections: {}", reader.get_integer("server", "max_connections", 10));
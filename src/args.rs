@@ -0,0 +1,128 @@
+//! Conversion of reader sections into process arguments
+//!
+//! [`IniReader::to_args`] maps each key of a section to zero or more
+//! arguments through a closure. The helpers here cover the usual flag
+//! conventions:
+//!
+//! ```rust
+//! use std::process::Command;
+//!
+//! use inih::args::{long_flag, shell_join, skip_empty};
+//! use inih::IniReader;
+//!
+//! let reader = IniReader::from_string("[ffmpeg]\ninput = my clip.mp4\noverwrite = yes\nquiet = no\npreset =\n").unwrap();
+//! let args = reader.to_args("ffmpeg", skip_empty(long_flag)).unwrap();
+//! assert_eq!(shell_join(&args), "--input='my clip.mp4' --overwrite");
+//!
+//! Command::new("ffmpeg").args(&args);
+//! ```
+//!
+//! The arguments are passed to the process as they are, so they are never
+//! quoted; [`shell_join`] quotes them only for logging.
+
+use std::ffi::{OsStr, OsString};
+
+use crate::error::ArgsError;
+use crate::reader::IniReader;
+use crate::value;
+
+impl IniReader {
+    /// Map the keys of a section to process arguments, in document order
+    ///
+    /// `map` receives the section, key and value and returns the arguments
+    /// for that key: an `Option` for at most one, a `Vec` for several. An
+    /// argument containing a NUL byte, which no process can receive, is an
+    /// error.
+    pub fn to_args<F, I, A>(&self, section: &str, mut map: F) -> Result<Vec<OsString>, ArgsError>
+    where
+        F: FnMut(&str, &str, &str) -> I,
+        I: IntoIterator<Item = A>,
+        A: Into<OsString>,
+    {
+        let mut args = Vec::new();
+        for key in self.keys(section) {
+            let value = self.get(section, &key, "");
+            for arg in map(section, &key, &value) {
+                let arg = arg.into();
+                if arg.as_encoded_bytes().contains(&0) {
+                    return Err(ArgsError::NulByte { section: section.to_string(), key });
+                }
+                args.push(arg);
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// The boolean a flag value stands for, if it is a boolean word
+///
+/// Numbers are left to the value, so `threads = 0` is not a false flag.
+fn flag_value(value: &str) -> Option<bool> {
+    match value::parse_integer(value) {
+        Some(_) => None,
+        None => value::parse_bool(value),
+    }
+}
+
+/// GNU long option: `--key=value`, `--key` for a true boolean and nothing for a false one
+pub fn long_flag(_section: &str, key: &str, value: &str) -> Option<String> {
+    match flag_value(value) {
+        Some(true) => Some(format!("--{}", key)),
+        Some(false) => None,
+        None => Some(format!("--{}={}", key, value)),
+    }
+}
+
+/// Option and value as two arguments: `-key value`, `-key` for a true boolean and nothing for a false one
+pub fn key_value_pair(_section: &str, key: &str, value: &str) -> Vec<String> {
+    match flag_value(value) {
+        Some(true) => vec![format!("-{}", key)],
+        Some(false) => Vec::new(),
+        None => vec![format!("-{}", key), value.to_string()],
+    }
+}
+
+/// Wrap a mapping so that keys with an empty or blank value give no arguments
+pub fn skip_empty<F, I>(mut map: F) -> impl FnMut(&str, &str, &str) -> Vec<I::Item>
+where
+    F: FnMut(&str, &str, &str) -> I,
+    I: IntoIterator,
+{
+    move |section, key, value| {
+        if value.trim().is_empty() {
+            Vec::new()
+        } else {
+            map(section, key, value).into_iter().collect()
+        }
+    }
+}
+
+/// Join arguments into one line for logging, quoting them for a POSIX shell
+///
+/// Arguments made only of characters a shell leaves alone are written as
+/// they are; others are single-quoted, and within an option only the part
+/// after `=` is quoted, so `--name='a b'` stays readable. Arguments that
+/// are not valid Unicode are shown lossily.
+pub fn shell_join<S: AsRef<OsStr>>(args: &[S]) -> String {
+    let quoted: Vec<String> = args.iter().map(|arg| shell_quote(&arg.as_ref().to_string_lossy())).collect();
+    quoted.join(" ")
+}
+
+/// Quote one argument for a POSIX shell
+fn shell_quote(arg: &str) -> String {
+    let plain = |text: &str| !text.is_empty() && text.chars().all(|c| c.is_ascii_alphanumeric() || "-_./,:=@%+".contains(c));
+    if plain(arg) {
+        return arg.to_string();
+    }
+    if let Some((option, value)) = arg.split_once('=') {
+        if option.starts_with('-') && plain(option) {
+            return format!("{}={}", option, single_quote(value));
+        }
+    }
+    single_quote(arg)
+}
+
+/// Wrap text in single quotes, closing and reopening them around each `'`
+fn single_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
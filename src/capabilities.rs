@@ -0,0 +1,84 @@
+//! Build-time description of the parser, for diagnostics
+
+use std::fmt;
+
+use crate::expansion::ExpansionBudget;
+use crate::handlers::push_json_string;
+use crate::parser::ParseOptions;
+
+/// Cargo features known to this version, each with whether it is enabled
+///
+/// Every optional feature of the crate is listed here through `cfg!`, so the
+/// report always matches the build.
+const FEATURES: &[(&str, bool)] = &[("serde", cfg!(feature = "serde"))];
+
+/// What this build of the parser can do and how it behaves by default
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    /// Crate version
+    pub version: &'static str,
+    /// Enabled cargo features
+    pub features: Vec<&'static str>,
+    /// Options used when none are given
+    pub default_options: ParseOptions,
+    /// Default limits on value expansion
+    pub expansion_budget: ExpansionBudget,
+}
+
+/// Describe this build of the parser
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        features: FEATURES.iter().filter(|(_, enabled)| *enabled).map(|(name, _)| *name).collect(),
+        default_options: ParseOptions::default(),
+        expansion_budget: ExpansionBudget::default(),
+    }
+}
+
+impl Capabilities {
+    /// The report as a single-line JSON object
+    pub fn to_json_string(&self) -> String {
+        let options = &self.default_options;
+        let budget = &self.expansion_budget;
+        let mut json = String::from("{\"version\":");
+        push_json_string(&mut json, self.version);
+        json.push_str(",\"features\":[");
+        for (index, feature) in self.features.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+            push_json_string(&mut json, feature);
+        }
+        json.push_str(&format!(
+            "],\"defaults\":{{\"max_line\":{},\"allow_multiline\":{},\"allow_inline_comments\":{},\"allow_bom\":{},\"allow_no_value\":{},\"stop_on_first_error\":{}}}",
+            options.max_line,
+            options.allow_multiline,
+            options.allow_inline_comments,
+            options.allow_bom,
+            options.allow_no_value,
+            options.stop_on_first_error,
+        ));
+        json.push_str(&format!(
+            ",\"limits\":{{\"max_depth\":{},\"max_total_expansions\":{},\"max_expanded_len\":{}}}}}",
+            budget.max_depth, budget.max_total_expansions, budget.max_expanded_len,
+        ));
+        json
+    }
+}
+
+impl fmt::Display for Capabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let features = if self.features.is_empty() { "none".to_string() } else { self.features.join(",") };
+        write!(
+            f,
+            "inih {} features={} max_line={} multiline={} inline_comments={} bom={} no_value={}",
+            self.version,
+            features,
+            self.default_options.max_line,
+            self.default_options.allow_multiline,
+            self.default_options.allow_inline_comments,
+            self.default_options.allow_bom,
+            self.default_options.allow_no_value,
+        )
+    }
+}
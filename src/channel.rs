@@ -0,0 +1,141 @@
+//! Parsing on a background thread into a channel
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use crate::error::IniParseError;
+use crate::parser::{ini_parse_file_with_options, EntryInfo, IniHandler, ParseOptions};
+
+/// An event sent by a [`ChannelHandler`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum IniEvent {
+    /// A complete entry, with continuation lines joined by `\n`
+    Entry { section: String, name: String, value: String, line: usize },
+    /// The outcome of the parse; nothing is sent after it
+    Finished(Result<(), IniParseError>),
+}
+
+/// Handler that sends every entry as an owned [`IniEvent`] over a channel
+///
+/// An entry is sent once its value is complete. Section headers are not sent.
+/// With a bounded channel the parser blocks while the channel is full. Once
+/// the receiver is gone every further entry fails with a handler error and
+/// [`ChannelHandler::is_disconnected`] becomes true.
+pub struct ChannelHandler {
+    sender: SyncSender<IniEvent>,
+    pending: Option<(String, String, String, usize)>,
+    disconnected: Arc<AtomicBool>,
+}
+
+impl ChannelHandler {
+    /// Create a handler sending to `sender`
+    pub fn new(sender: SyncSender<IniEvent>) -> Self {
+        Self { sender, pending: None, disconnected: Arc::new(AtomicBool::new(false)) }
+    }
+
+    /// Whether the receiver has been dropped
+    pub fn is_disconnected(&self) -> bool {
+        self.disconnected.load(Ordering::Relaxed)
+    }
+
+    /// Send the outcome of the parse and close the channel
+    pub fn finish(self, result: Result<(), IniParseError>) {
+        let _ = self.sender.send(IniEvent::Finished(result));
+    }
+
+    fn send(&mut self, event: IniEvent) -> Result<(), String> {
+        if self.sender.send(event).is_err() {
+            self.disconnected.store(true, Ordering::Relaxed);
+            return Err("receiver dropped".to_string());
+        }
+        Ok(())
+    }
+
+    /// Send the pending entry, if there is one
+    fn send_pending(&mut self) -> Result<(), String> {
+        match self.pending.take() {
+            Some((section, name, value, line)) => self.send(IniEvent::Entry { section, name, value, line }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl IniHandler for ChannelHandler {
+    fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.handle_at(0, section, name, value)
+    }
+
+    fn handle_at(&mut self, line: usize, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.send_pending()?;
+        if !name.is_empty() {
+            self.pending = Some((section.to_string(), name.to_string(), value.to_string(), line));
+        }
+        Ok(())
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        if let Some((_, _, pending, _)) = self.pending.as_mut().filter(|_| info.continuation) {
+            pending.push('\n');
+            pending.push_str(value);
+            return Ok(());
+        }
+        self.handle_at(info.line, section, name, value)
+    }
+
+    fn end_value(&mut self, _section: &str, _name: &str) -> Result<(), String> {
+        self.send_pending()
+    }
+
+    fn end_document(&mut self) -> Result<(), String> {
+        self.send_pending()
+    }
+}
+
+/// Reader that reports end of input once the receiver has gone away
+struct StopWhenDisconnected<R> {
+    inner: R,
+    disconnected: Arc<AtomicBool>,
+}
+
+impl<R: Read> Read for StopWhenDisconnected<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.disconnected.load(Ordering::Relaxed) {
+            return Ok(0);
+        }
+        self.inner.read(buf)
+    }
+}
+
+/// Parse a file on a new thread, receiving its entries over a bounded channel
+///
+/// At most `capacity` events are buffered; the parser waits while the channel
+/// is full. The last event is always [`IniEvent::Finished`] unless the
+/// receiver was dropped. Dropping the receiver stops the parse early, and
+/// the thread then returns a handler error. The thread's result matches the
+/// one in `Finished`.
+pub fn ini_parse_to_channel<P: AsRef<Path>>(
+    path: P,
+    options: ParseOptions,
+    capacity: usize,
+) -> (Receiver<IniEvent>, JoinHandle<Result<(), IniParseError>>) {
+    let path = path.as_ref().to_path_buf();
+    let (sender, receiver) = sync_channel(capacity);
+    let worker = thread::spawn(move || {
+        let mut handler = ChannelHandler::new(sender);
+        let result = match File::open(&path) {
+            Ok(file) => {
+                let input = StopWhenDisconnected { inner: file, disconnected: Arc::clone(&handler.disconnected) };
+                ini_parse_file_with_options(input, &mut handler, &options)
+            }
+            Err(e) => Err(IniParseError::file_open(&path, e)),
+        };
+        handler.finish(result.clone());
+        result
+    });
+    (receiver, worker)
+}
@@ -0,0 +1,424 @@
+//! Filling your own types from INI data with serde (feature `serde`)
+//!
+//! Sections map to fields holding nested structs and their keys to the
+//! fields of those; keys of the global section map to top-level fields:
+//!
+//! ```rust
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     name: String,
+//!     protocol: Protocol,
+//!     user: Option<User>,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct Protocol {
+//!     version: u32,
+//!     #[serde(default)]
+//!     compress: bool,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct User {
+//!     email: Option<String>,
+//! }
+//!
+//! let config: Config = inih::from_str("name = demo\n[Protocol]\nversion = 6\n").unwrap();
+//! assert_eq!(config.name, "demo");
+//! assert_eq!(config.protocol.version, 6);
+//! assert!(!config.protocol.compress);
+//! assert!(config.user.is_none());
+//! ```
+//!
+//! Values convert with the rules of the [`IniReader`] getters (see
+//! [`value`](crate::value)): booleans accept the same words as
+//! [`get_boolean`](IniReader::get_boolean) and integers the same prefixes as
+//! [`get_integer`](IniReader::get_integer). A sequence is read from a
+//! comma-separated list, as [`get_list`](IniReader::get_list) splits it, and
+//! a unit enum variant from its name.
+//!
+//! Sections and keys are matched to the fields of a struct the way lookups
+//! compare names: case-insensitively, or exactly with
+//! [`ReaderOptions::case_sensitive`](crate::ReaderOptions::case_sensitive),
+//! so `MaxConn = 5` fills a field `max_conn` renamed to `maxConn`. Names that
+//! match no field, such as the keys of a map, reach serde as first written.
+//! A missing key is `None` for an `Option` field and
+//! the default for a `#[serde(default)]` one; any other missing key is an
+//! error. Sections and keys the type does not name are ignored unless it has
+//! `#[serde(deny_unknown_fields)]`. Errors name the section and key they
+//! concern.
+
+use serde::de::value::StrDeserializer;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::error::IniDeError;
+use crate::parser::ParseOptions;
+use crate::reader::IniReader;
+use crate::value;
+
+/// Parse `data` with the default options and fill a `T` from it
+pub fn from_str<T: DeserializeOwned>(data: &str) -> Result<T, IniDeError> {
+    from_str_with_options(data, &ParseOptions::default())
+}
+
+/// Parse `data` with `options` and fill a `T` from it
+pub fn from_str_with_options<T: DeserializeOwned>(data: &str, options: &ParseOptions) -> Result<T, IniDeError> {
+    from_ini(&IniReader::from_string_with_options(data, options)?)
+}
+
+/// Fill a `T` from the values of `reader`
+pub fn from_ini<T: DeserializeOwned>(reader: &IniReader) -> Result<T, IniDeError> {
+    T::deserialize(ReaderDeserializer { tables: Tables::new(reader) })
+}
+
+impl de::Error for IniDeError {
+    fn custom<T: std::fmt::Display>(message: T) -> Self {
+        IniDeError::Message { section: None, name: None, message: message.to_string() }
+    }
+}
+
+/// Keys and values of one section, in document order
+struct Table<'r> {
+    /// Name as first written; the global section is `""`
+    name: &'r str,
+    entries: Vec<(&'r str, &'r str)>,
+}
+
+/// The reader's sections, global first, one per folded name
+struct Tables<'r> {
+    reader: &'r IniReader,
+    tables: Vec<Table<'r>>,
+}
+
+impl<'r> Tables<'r> {
+    fn new(reader: &'r IniReader) -> Self {
+        let mut tables = vec![Table { name: "", entries: Vec::new() }];
+        tables.extend(reader.section_names().map(|name| Table { name, entries: Vec::new() }));
+        // Sections are spelled in `iter` as `section_names` lists them
+        for (section, name, value) in reader.iter() {
+            if let Some(table) = tables.iter_mut().find(|table| table.name == section) {
+                table.entries.push((name, value));
+            }
+        }
+        Self { reader, tables }
+    }
+}
+
+/// The field of `fields` that `name` stands for, compared as the reader compares names, or else `name` itself
+fn field_name<'a>(reader: &IniReader, name: &'a str, fields: &'static [&'static str]) -> &'a str {
+    let folded = reader.fold(name);
+    fields.iter().find(|field| reader.fold(field) == folded).map_or(name, |field| field)
+}
+
+/// A top-level field: a global key or a section
+enum Field<'t, 'r> {
+    Key(&'r str, &'r str),
+    Section(&'t Table<'r>),
+}
+
+impl<'r> Field<'_, 'r> {
+    fn name(&self) -> &'r str {
+        match self {
+            Field::Key(name, _) => name,
+            Field::Section(table) => table.name,
+        }
+    }
+}
+
+/// The whole reader, seen as a map of global keys and sections
+struct ReaderDeserializer<'r> {
+    tables: Tables<'r>,
+}
+
+impl<'r> ReaderDeserializer<'r> {
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V, names: &'static [&'static str]) -> Result<V::Value, IniDeError> {
+        let reader = self.tables.reader;
+        let (global, sections) = self.tables.tables.split_first().expect("the global table is always present");
+        // A section shadows a global key of the same name
+        let keys = global
+            .entries
+            .iter()
+            .filter(|(name, _)| !sections.iter().any(|table| reader.fold(table.name) == reader.fold(name)))
+            .map(|&(name, value)| Field::Key(name, value));
+        let fields = keys.chain(sections.iter().map(Field::Section)).collect();
+        visitor.visit_map(ReaderAccess { reader, names, fields, index: 0 })
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ReaderDeserializer<'_> {
+    type Error = IniDeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        self.visit(visitor, &[])
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, IniDeError> {
+        self.visit(visitor, fields)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct ReaderAccess<'t, 'r> {
+    reader: &'r IniReader,
+    /// Fields of the struct being filled, empty for a map
+    names: &'static [&'static str],
+    fields: Vec<Field<'t, 'r>>,
+    index: usize,
+}
+
+impl<'de> MapAccess<'de> for ReaderAccess<'_, '_> {
+    type Error = IniDeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, IniDeError> {
+        let Some(field) = self.fields.get(self.index) else {
+            return Ok(None);
+        };
+        let key = seed.deserialize(StrDeserializer::<IniDeError>::new(field_name(self.reader, field.name(), self.names)));
+        match field {
+            Field::Key(name, _) => key.map(Some).map_err(|error| error.in_key(name).in_section("")),
+            Field::Section(table) => key.map(Some).map_err(|error| error.in_section(table.name)),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, IniDeError> {
+        let field = &self.fields[self.index];
+        self.index += 1;
+        match *field {
+            Field::Key(name, value) => {
+                seed.deserialize(ValueDeserializer { value }).map_err(|error| error.in_key(name).in_section(""))
+            }
+            Field::Section(table) => {
+                seed.deserialize(SectionDeserializer { reader: self.reader, table }).map_err(|error| error.in_section(table.name))
+            }
+        }
+    }
+}
+
+/// One section, seen as a map of its keys
+struct SectionDeserializer<'t, 'r> {
+    reader: &'r IniReader,
+    table: &'t Table<'r>,
+}
+
+impl<'de> de::Deserializer<'de> for SectionDeserializer<'_, '_> {
+    type Error = IniDeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_map(SectionAccess { reader: self.reader, names: &[], entries: &self.table.entries, index: 0 })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, IniDeError> {
+        visitor.visit_map(SectionAccess { reader: self.reader, names: fields, entries: &self.table.entries, index: 0 })
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map enum identifier
+    }
+}
+
+struct SectionAccess<'t, 'r> {
+    reader: &'r IniReader,
+    /// Fields of the struct being filled, empty for a map
+    names: &'static [&'static str],
+    entries: &'t [(&'r str, &'r str)],
+    index: usize,
+}
+
+impl<'de> MapAccess<'de> for SectionAccess<'_, '_> {
+    type Error = IniDeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, IniDeError> {
+        let Some((name, _)) = self.entries.get(self.index) else {
+            return Ok(None);
+        };
+        seed.deserialize(StrDeserializer::<IniDeError>::new(field_name(self.reader, name, self.names)))
+            .map(Some)
+            .map_err(|error| error.in_key(name))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, IniDeError> {
+        let (name, value) = &self.entries[self.index];
+        self.index += 1;
+        seed.deserialize(ValueDeserializer { value }).map_err(|error| error.in_key(name))
+    }
+}
+
+/// One value, converted with the getters' rules
+struct ValueDeserializer<'r> {
+    value: &'r str,
+}
+
+impl ValueDeserializer<'_> {
+    fn parse<T>(&self, parse: impl Fn(&str) -> Option<T>, expected: &str) -> Result<T, IniDeError> {
+        parse(self.value).ok_or_else(|| de::Error::custom(format!("invalid value \"{}\": expected {}", self.value, expected)))
+    }
+}
+
+/// Deserialize methods for integer types, read through `$parse` and narrowed to the type
+macro_rules! deserialize_integers {
+    ($parse:path: $($method:ident $visit:ident $ty:ty),*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+                let expected = format!("an integer from {} to {}", <$ty>::MIN, <$ty>::MAX);
+                visitor.$visit(self.parse(|value| $parse(value).and_then(|n| <$ty>::try_from(n).ok()), &expected)?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'_> {
+    type Error = IniDeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_str(self.value)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_bool(self.parse(value::parse_bool, "a boolean")?)
+    }
+
+    deserialize_integers!(value::parse_integer:
+        deserialize_i8 visit_i8 i8, deserialize_i16 visit_i16 i16, deserialize_i32 visit_i32 i32, deserialize_i64 visit_i64 i64);
+    deserialize_integers!(value::parse_unsigned:
+        deserialize_u8 visit_u8 u8, deserialize_u16 visit_u16 u16, deserialize_u32 visit_u32 u32, deserialize_u64 visit_u64 u64);
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_f32(self.parse(|value| value.parse().ok(), "a number")?)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_f64(self.parse(value::parse_real, "a number")?)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        let mut chars = self.value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(de::Error::custom(format!("invalid value \"{}\": expected a single character", self.value))),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_bytes(self.value.as_bytes())
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_bytes(self.value.as_bytes())
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_seq(ListAccess { items: value::parse_list(self.value).into_iter() })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, IniDeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, IniDeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, IniDeError> {
+        Err(de::Error::custom("expected a section, found a single value"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, IniDeError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, IniDeError> {
+        visitor.visit_enum(self.value.into_deserializer())
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, IniDeError> {
+        visitor.visit_unit()
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 str string identifier
+    }
+}
+
+/// Items of a comma-separated list
+struct ListAccess<'r> {
+    items: std::vec::IntoIter<&'r str>,
+}
+
+impl<'de> SeqAccess<'de> for ListAccess<'_> {
+    type Error = IniDeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, IniDeError> {
+        match self.items.next() {
+            Some(value) => seed.deserialize(ValueDeserializer { value }).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.items.len())
+    }
+}
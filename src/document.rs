@@ -0,0 +1,262 @@
+//! Lossless line-based model of an INI document
+
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::error::IniParseError;
+use crate::parser::{ini_parse_string_with_options, EntryInfo, IniHandler, ParseOptions};
+
+/// Classification of a single physical line
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineKind {
+    /// Empty or whitespace-only line
+    Blank,
+    /// Start-of-line comment
+    Comment,
+    /// Section header
+    Section { name: String },
+    /// name=value pair
+    Entry { section: String, name: String, value: String },
+    /// Continuation of the previous entry (multiline mode)
+    Continuation { section: String, name: String, value: String },
+    /// Anything the parser ignored
+    Other,
+}
+
+/// A physical line together with its classification
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentLine {
+    /// The line exactly as written, without the line terminator
+    pub raw: String,
+    /// What the parser made of the line
+    pub kind: LineKind,
+}
+
+/// An INI document that remembers every line as written
+///
+/// Writing the document back reproduces the input byte for byte, except that
+/// line endings are normalized to `\n` and the output always ends with one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IniDocument {
+    lines: Vec<DocumentLine>,
+}
+
+impl IniDocument {
+    /// Parse a document from a string
+    pub fn parse(data: &str, options: &ParseOptions) -> Result<Self, IniParseError> {
+        let raw: Vec<&str> = data.lines().collect();
+        let mut recorder = LineRecorder { kinds: vec![None; raw.len()] };
+        // Headers are recorded from the calls that carry their line
+        let recording = ParseOptions { call_handler_on_new_section: true, ..options.clone() };
+        ini_parse_string_with_options(data, &mut recorder, &recording)?;
+
+        let lines = raw
+            .iter()
+            .zip(recorder.kinds)
+            .map(|(raw, kind)| DocumentLine {
+                raw: raw.to_string(),
+                kind: kind.unwrap_or_else(|| classify_unreported(raw, options)),
+            })
+            .collect();
+        Ok(Self { lines })
+    }
+
+    /// All lines of the document in order
+    pub fn lines(&self) -> &[DocumentLine] {
+        &self.lines
+    }
+
+    /// Give back spare capacity, for [`IniReader::shrink_to_fit`](crate::IniReader::shrink_to_fit)
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.lines.shrink_to_fit();
+        for line in &mut self.lines {
+            line.raw.shrink_to_fit();
+            match &mut line.kind {
+                LineKind::Section { name } => name.shrink_to_fit(),
+                LineKind::Entry { section, name, value } | LineKind::Continuation { section, name, value } => {
+                    section.shrink_to_fit();
+                    name.shrink_to_fit();
+                    value.shrink_to_fit();
+                }
+                LineKind::Blank | LineKind::Comment | LineKind::Other => {}
+            }
+        }
+    }
+
+    /// Heap bytes held, for [`IniReader::approx_memory_bytes`](crate::IniReader::approx_memory_bytes)
+    pub(crate) fn approx_memory_bytes(&self) -> usize {
+        let text = |line: &DocumentLine| {
+            line.raw.capacity()
+                + match &line.kind {
+                    LineKind::Section { name } => name.capacity(),
+                    LineKind::Entry { section, name, value } | LineKind::Continuation { section, name, value } => {
+                        section.capacity() + name.capacity() + value.capacity()
+                    }
+                    LineKind::Blank | LineKind::Comment | LineKind::Other => 0,
+                }
+        };
+        self.lines.capacity() * size_of::<DocumentLine>() + self.lines.iter().map(text).sum::<usize>()
+    }
+
+    /// Section names in document order, as first written
+    pub fn sections(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        self.lines
+            .iter()
+            .filter_map(|line| match &line.kind {
+                LineKind::Section { name } if seen.insert(name.to_lowercase()) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Key names of a section in document order, as first written
+    pub fn keys(&self, section: &str) -> Vec<&str> {
+        let section = section.to_lowercase();
+        let mut seen = HashSet::new();
+        self.lines
+            .iter()
+            .filter_map(|line| match &line.kind {
+                LineKind::Entry { section: s, name, .. }
+                    if s.to_lowercase() == section && seen.insert(name.to_lowercase()) =>
+                {
+                    Some(name.as_str())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Replace the value of every entry and continuation line that `f` rewrites
+    ///
+    /// `f` gets the section, the name as written and the value of each line
+    /// in order, a multi-line value line by line; returning `Some` replaces
+    /// the value and `None` keeps it. Only the value is replaced, so the rest
+    /// of the line, such as its spacing and an inline comment, stays as
+    /// written; a value that cannot be found in its line as is, like a quoted
+    /// or empty one, has its line rewritten as `name = value`. Returns the
+    /// number of lines whose value changed.
+    pub fn map_values_in_place(&mut self, mut f: impl FnMut(&str, &str, &str) -> Option<String>) -> usize {
+        let mut changed = 0;
+        for line in &mut self.lines {
+            let replacement = match &line.kind {
+                LineKind::Entry { section, name, value } | LineKind::Continuation { section, name, value } => {
+                    f(section, name, value).filter(|new| new != value)
+                }
+                _ => None,
+            };
+            let Some(value) = replacement else { continue };
+            if !set_line_value(line, &value) {
+                match &mut line.kind {
+                    LineKind::Entry { name, value: old, .. } => {
+                        line.raw = format!("{} = {}", name, value);
+                        *old = value;
+                    }
+                    LineKind::Continuation { value: old, .. } => {
+                        let indent = line.raw.len() - line.raw.trim_start().len();
+                        line.raw = format!("{}{}", &line.raw[..indent], value);
+                        *old = value;
+                    }
+                    _ => {}
+                }
+            }
+            changed += 1;
+        }
+        changed
+    }
+
+    /// Replace the value of the entry on line `line` (1-based) in place, if it is `old`
+    ///
+    /// Returns false, changing nothing, otherwise; a multi-line value is
+    /// never `old` of its first line.
+    pub(crate) fn replace_value_at(&mut self, line: usize, old: &str, value: &str) -> bool {
+        let entry = line.checked_sub(1).and_then(|index| self.lines.get_mut(index));
+        entry.is_some_and(|line| matches!(&line.kind, LineKind::Entry { value: v, .. } if v == old) && set_line_value(line, value))
+    }
+
+    /// Write the document to a Write object
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "{}", self)
+    }
+}
+
+impl fmt::Display for IniDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line.raw)?;
+        }
+        Ok(())
+    }
+}
+
+/// Handler that classifies the lines the parser reports
+struct LineRecorder {
+    kinds: Vec<Option<LineKind>>,
+}
+
+impl LineRecorder {
+    fn record(&mut self, line: usize, kind: LineKind) {
+        if let Some(slot) = line.checked_sub(1).and_then(|index| self.kinds.get_mut(index)) {
+            *slot = Some(kind);
+        }
+    }
+}
+
+impl IniHandler for LineRecorder {
+    fn handle(&mut self, _section: &str, _name: &str, _value: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn handle_at(&mut self, line: usize, section: &str, name: &str, _value: &str) -> Result<(), String> {
+        if name.is_empty() {
+            self.record(line, LineKind::Section { name: section.to_string() });
+        }
+        Ok(())
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        let (section, name, value) = (section.to_string(), name.to_string(), value.to_string());
+        let kind = if info.continuation {
+            LineKind::Continuation { section, name, value }
+        } else {
+            LineKind::Entry { section, name, value }
+        };
+        self.record(info.line, kind);
+        Ok(())
+    }
+}
+
+/// Replace the value of an entry or continuation line where it is written, keeping the rest of the line
+///
+/// Returns false, changing nothing, if the value is empty or not written
+/// as is right after the separator or the indentation.
+fn set_line_value(line: &mut DocumentLine, value: &str) -> bool {
+    let DocumentLine { raw, kind } = line;
+    let (from, old) = match kind {
+        LineKind::Entry { value: old, .. } => (raw.find(['=', ':']).map(|separator| separator + 1), old),
+        LineKind::Continuation { value: old, .. } => (Some(0), old),
+        _ => return false,
+    };
+    let Some(from) = from else { return false };
+    let after = &raw[from..];
+    let Some(start) = after.find(old.as_str()).filter(|&start| !old.is_empty() && after[..start].trim().is_empty()) else {
+        return false;
+    };
+    let start = from + start;
+    raw.replace_range(start..start + old.len(), value);
+    *old = value.to_string();
+    true
+}
+
+/// Classify a line for which the parser made no handler call
+fn classify_unreported(raw: &str, options: &ParseOptions) -> LineKind {
+    let trimmed = raw.trim_start_matches('\u{FEFF}').trim();
+    if trimmed.is_empty() {
+        LineKind::Blank
+    } else if options.start_comment_prefixes.prefix_of(trimmed).is_some() {
+        LineKind::Comment
+    } else {
+        LineKind::Other
+    }
+}
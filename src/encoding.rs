@@ -0,0 +1,126 @@
+//! Byte encodings for reading and writing INI text
+
+use std::borrow::Cow;
+
+use crate::error::{IniParseError, IniWarning};
+
+/// Encoding used when writing INI text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WriteEncoding {
+    /// Plain UTF-8 without a byte order mark
+    #[default]
+    Utf8,
+    /// UTF-8 preceded by the EF BB BF byte order mark
+    Utf8WithBom,
+    /// UTF-16 little endian preceded by the FF FE byte order mark
+    Utf16Le,
+}
+
+/// How input that is not valid UTF-8 is read
+///
+/// UTF-16 input is recognized by its byte order mark whatever the mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EncodingMode {
+    /// Fail with `Encoding` at the first invalid byte
+    #[default]
+    Utf8Strict,
+    /// Replace every invalid sequence with U+FFFD
+    Utf8Lossy,
+    /// Read each byte as the character with the same code point (ISO 8859-1)
+    Latin1,
+    /// UTF-8 if the whole document is valid UTF-8, otherwise Latin-1
+    ///
+    /// The choice is made once per document, so its lines are never decoded
+    /// differently, and falling back records a warning naming the line of
+    /// the first invalid byte. A stream is therefore read whole before it is
+    /// parsed.
+    Auto,
+}
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Encode text for writing
+pub fn encode(text: &str, encoding: WriteEncoding) -> Vec<u8> {
+    match encoding {
+        WriteEncoding::Utf8 => text.as_bytes().to_vec(),
+        WriteEncoding::Utf8WithBom => {
+            let mut bytes = UTF8_BOM.to_vec();
+            bytes.extend_from_slice(text.as_bytes());
+            bytes
+        }
+        WriteEncoding::Utf16Le => {
+            let mut bytes = UTF16LE_BOM.to_vec();
+            bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+            bytes
+        }
+    }
+}
+
+/// Decode bytes read from an INI file
+///
+/// UTF-16 input is recognized by its byte order mark and transcoded, dropping
+/// the mark. Anything else must be valid UTF-8; a UTF-8 byte order mark is
+/// kept so that the parser's `allow_bom` option decides what happens to it.
+pub fn decode(bytes: &[u8]) -> Result<String, IniParseError> {
+    decode_with(bytes, EncodingMode::Utf8Strict).map(|(text, _)| text)
+}
+
+/// Decode bytes read from an INI file as `mode` says, as [`decode`] does
+///
+/// Returns the warning to record when [`EncodingMode::Auto`] fell back to Latin-1.
+pub(crate) fn decode_with(bytes: &[u8], mode: EncodingMode) -> Result<(String, Option<IniWarning>), IniParseError> {
+    if let Some(rest) = bytes.strip_prefix(UTF16LE_BOM) {
+        return Ok((decode_utf16(rest, u16::from_le_bytes)?, None));
+    }
+    if let Some(rest) = bytes.strip_prefix(UTF16BE_BOM) {
+        return Ok((decode_utf16(rest, u16::from_be_bytes)?, None));
+    }
+    if mode == EncodingMode::Latin1 {
+        return Ok((latin1(bytes), None));
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Ok((text.to_string(), None)),
+        Err(e) => match mode {
+            EncodingMode::Utf8Strict => Err(IniParseError::Encoding(format!("invalid UTF-8 at byte {}", e.valid_up_to()))),
+            EncodingMode::Utf8Lossy => Ok((String::from_utf8_lossy(bytes).into_owned(), None)),
+            EncodingMode::Latin1 | EncodingMode::Auto => {
+                let line = bytes[..e.valid_up_to()].iter().filter(|&&b| b == b'\n').count() + 1;
+                let warning = IniWarning {
+                    line: Some(line),
+                    section: String::new(),
+                    name: String::new(),
+                    message: "invalid UTF-8, the document was read as Latin-1".to_string(),
+                };
+                Ok((latin1(bytes), Some(warning)))
+            }
+        },
+    }
+}
+
+/// Decode one line of a stream; `Auto` is taken as strict UTF-8, as it needs the whole document
+pub(crate) fn decode_line(bytes: &[u8], mode: EncodingMode) -> Option<Cow<'_, str>> {
+    match mode {
+        EncodingMode::Utf8Strict | EncodingMode::Auto => std::str::from_utf8(bytes).ok().map(Cow::Borrowed),
+        EncodingMode::Utf8Lossy => Some(String::from_utf8_lossy(bytes)),
+        EncodingMode::Latin1 if bytes.is_ascii() => std::str::from_utf8(bytes).ok().map(Cow::Borrowed),
+        EncodingMode::Latin1 => Some(Cow::Owned(latin1(bytes))),
+    }
+}
+
+/// Read each byte as the character with the same code point
+fn latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| char::from(b)).collect()
+}
+
+/// Transcode UTF-16 code units assembled by `unit`
+fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> Result<String, IniParseError> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(IniParseError::Encoding("truncated UTF-16 input".to_string()));
+    }
+    let units = bytes.chunks_exact(2).map(|pair| unit([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(|e| IniParseError::Encoding(format!("unpaired UTF-16 surrogate {:#06x}", e.unpaired_surrogate())))
+}
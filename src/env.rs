@@ -0,0 +1,84 @@
+//! Export of reader sections as environment variables
+
+use std::collections::HashMap;
+use std::process::Command;
+
+use crate::error::EnvExportError;
+use crate::reader::IniReader;
+
+/// Options controlling how a section is turned into environment variables
+#[derive(Debug, Clone)]
+pub struct EnvExportOptions {
+    /// Maps a key name to the variable name (before the prefix is prepended)
+    pub key_transform: fn(&str) -> String,
+    /// Reject values containing newlines or NUL bytes instead of passing them through
+    pub reject_unsafe_values: bool,
+}
+
+impl Default for EnvExportOptions {
+    fn default() -> Self {
+        Self {
+            key_transform: upper_snake_case,
+            reject_unsafe_values: false,
+        }
+    }
+}
+
+/// Convert a key to UPPER_SNAKE_CASE, replacing anything not alphanumeric with '_'
+pub fn upper_snake_case(key: &str) -> String {
+    key.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect()
+}
+
+impl IniReader {
+    /// Export a section as environment variables with the default options
+    ///
+    /// Variables come in the document order of the keys they were made from.
+    pub fn export_env(&self, section: &str, prefix: &str) -> Result<Vec<(String, String)>, EnvExportError> {
+        self.export_env_with(section, prefix, &EnvExportOptions::default())
+    }
+
+    /// Export a section as environment variables
+    ///
+    /// Each key is transformed and prefixed, values are passed through as-is.
+    /// Two keys mapping to the same variable name are reported as a collision.
+    /// NUL bytes can never be part of an environment variable, so values
+    /// containing them (or newlines) are rejected when `reject_unsafe_values` is set.
+    pub fn export_env_with(
+        &self,
+        section: &str,
+        prefix: &str,
+        options: &EnvExportOptions,
+    ) -> Result<Vec<(String, String)>, EnvExportError> {
+        let mut seen: HashMap<String, String> = HashMap::new();
+        let mut vars = Vec::new();
+
+        for key in self.keys(section) {
+            let name = format!("{}{}", prefix, (options.key_transform)(&key));
+            if let Some(previous) = seen.get(&name) {
+                return Err(EnvExportError::Collision {
+                    name,
+                    first_key: previous.clone(),
+                    second_key: key,
+                });
+            }
+
+            let value = self.get(section, &key, "");
+            if options.reject_unsafe_values && (value.contains('\n') || value.contains('\0')) {
+                return Err(EnvExportError::UnsafeValue { key });
+            }
+
+            seen.insert(name.clone(), key);
+            vars.push((name, value));
+        }
+        Ok(vars)
+    }
+
+    /// Add a section's variables to the environment of a command
+    pub fn apply_to_command(&self, command: &mut Command, section: &str, prefix: &str) -> Result<(), EnvExportError> {
+        let options = EnvExportOptions { reject_unsafe_values: true, ..EnvExportOptions::default() };
+        command.envs(self.export_env_with(section, prefix, &options)?);
+        Ok(())
+    }
+}
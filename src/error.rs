@@ -1,37 +1,431 @@
 //! Error types for INI parsing
 
 use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::parser::EntryInfo;
 
 /// Errors that can occur during INI parsing
 #[derive(Debug, Clone, PartialEq)]
 pub enum IniParseError {
-    /// File could not be opened
+    /// Input could not be read (the message of the underlying I/O error)
     FileOpen(String),
-    /// Parse error at specific line number
-    ParseError { line: usize, message: String },
+    /// File could not be opened or read, with the path exactly as it was given
+    ///
+    /// The path is kept as a [`PathBuf`] rather than formatted, so paths that
+    /// are not valid UTF-8 survive unchanged and can be inspected or retried.
+    FileOpenContext { path: PathBuf, kind: io::ErrorKind, message: String },
+    /// Parse error at specific line number, with a fix for the line if one is known
+    ParseError { line: usize, message: String, suggestion: Option<Suggestion> },
     /// Memory allocation error
     MemoryError,
     /// Custom error from handler
     HandlerError(String),
+    /// A handler rejected an entry or continuation line, quoted as written
+    RejectedEntry { line: usize, raw_line: String, message: String },
+    /// Error inside one document of a multi-document stream
+    Document { index: usize, error: Box<IniParseError> },
+    /// Error in the contents of a file, with the path exactly as it was given
+    InFile { path: PathBuf, error: Box<IniParseError> },
+    /// Input bytes are not in a supported encoding
+    Encoding(String),
+    /// An expanding feature ran out of its [`ExpansionBudget`](crate::expansion::ExpansionBudget)
+    LimitExceeded { limit: ExpansionLimit, chain: Vec<String> },
+    /// No configuration file was found, with every path that was tried
+    NotFound { tried: Vec<PathBuf> },
 }
 
 impl fmt::Display for IniParseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             IniParseError::FileOpen(path) => write!(f, "Unable to open file: {}", path),
-            IniParseError::ParseError { line, message } => {
+            IniParseError::FileOpenContext { path, message, .. } => {
+                write!(f, "Unable to open file: {}: {}", path.display(), message)
+            }
+            IniParseError::ParseError { line, message, .. } => {
                 write!(f, "Parse error on line {}: {}", line, message)
             }
             IniParseError::MemoryError => write!(f, "Memory allocation error"),
             IniParseError::HandlerError(msg) => write!(f, "Handler error: {}", msg),
+            IniParseError::RejectedEntry { line, raw_line, message } => {
+                write!(f, "Handler error on line {}: {}\n    {}", line, message, raw_line)
+            }
+            IniParseError::Document { index, error } => write!(f, "Document {}: {}", index, error),
+            IniParseError::InFile { path, error } => write!(f, "{}: {}", path.display(), error),
+            IniParseError::Encoding(msg) => write!(f, "Encoding error: {}", msg),
+            IniParseError::LimitExceeded { limit, chain } => {
+                write!(f, "Expansion limit exceeded ({}): {}", limit, chain.join(" -> "))
+            }
+            IniParseError::NotFound { tried } => {
+                let tried: Vec<_> = tried.iter().map(|path| path.display().to_string()).collect();
+                write!(f, "No configuration file found; tried: {}", tried.join(", "))
+            }
         }
     }
 }
 
 impl std::error::Error for IniParseError {}
 
-impl From<std::io::Error> for IniParseError {
-    fn from(err: std::io::Error) -> Self {
+impl IniParseError {
+    /// `RejectedEntry` for a handler error on the entry described by `info`
+    pub(crate) fn rejected_entry(info: &EntryInfo, message: String) -> Self {
+        IniParseError::RejectedEntry { line: info.line, raw_line: info.raw_line.to_string(), message }
+    }
+
+    /// Line the error is on, for errors that have one
+    pub(crate) fn line(&self) -> Option<usize> {
+        match self {
+            IniParseError::ParseError { line, .. } | IniParseError::RejectedEntry { line, .. } => Some(*line),
+            IniParseError::Document { error, .. } | IniParseError::InFile { error, .. } => error.line(),
+            _ => None,
+        }
+    }
+
+    /// `FileOpenContext` for a failure to open or read `path`
+    pub(crate) fn file_open(path: &Path, error: io::Error) -> Self {
+        IniParseError::FileOpenContext { path: path.to_path_buf(), kind: error.kind(), message: error.to_string() }
+    }
+}
+
+/// A replacement for a line that fixes its syntax error, found by guessing
+///
+/// The replacement is only offered when it parses cleanly in place of the
+/// line, so a tool can apply it as it is, for example in a `--fix` mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// The whole line, corrected, without its terminator
+    pub replacement_line: String,
+    /// What the correction does, such as `insert ']' at byte 7`
+    pub description: String,
+}
+
+/// The limit of an expansion budget that was exceeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpansionLimit {
+    /// Expansions nested deeper than `max_depth`
+    Depth(usize),
+    /// More than `max_total_expansions` expansions in total
+    TotalExpansions(usize),
+    /// An expanded value longer than `max_expanded_len` bytes
+    ExpandedLength(usize),
+}
+
+impl fmt::Display for ExpansionLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpansionLimit::Depth(max) => write!(f, "depth over {}", max),
+            ExpansionLimit::TotalExpansions(max) => write!(f, "more than {} expansions", max),
+            ExpansionLimit::ExpandedLength(max) => write!(f, "value longer than {} bytes", max),
+        }
+    }
+}
+
+impl From<io::Error> for IniParseError {
+    fn from(err: io::Error) -> Self {
         IniParseError::FileOpen(err.to_string())
     }
 }
+
+/// Errors that can occur when exporting a section as environment variables
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvExportError {
+    /// Two keys map to the same variable name
+    Collision { name: String, first_key: String, second_key: String },
+    /// A value contains a newline or NUL byte
+    UnsafeValue { key: String },
+}
+
+impl fmt::Display for EnvExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvExportError::Collision { name, first_key, second_key } => {
+                write!(f, "Keys '{}' and '{}' both map to {}", first_key, second_key, name)
+            }
+            EnvExportError::UnsafeValue { key } => {
+                write!(f, "Value of '{}' contains a newline or NUL byte", key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnvExportError {}
+
+/// Errors that can occur when turning a section into process arguments
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgsError {
+    /// An argument made from this key contains a NUL byte
+    NulByte { section: String, key: String },
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgsError::NulByte { section, key } => {
+                write!(f, "Argument for '{}' in [{}] contains a NUL byte", key, section)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArgsError {}
+
+/// Errors returned by [`IniWriter`](crate::writer::IniWriter) for text that would not read back unchanged
+#[derive(Debug, Clone, PartialEq)]
+pub enum IniWriteError {
+    /// The section name cannot be written as a header
+    InvalidSection { section: String, reason: &'static str },
+    /// The key name cannot be written as the name of an entry
+    InvalidName { section: String, name: String, reason: &'static str },
+    /// The value would be read back differently
+    InvalidValue { section: String, name: String, reason: &'static str },
+    /// The items of a list would not be split back into the same items
+    InvalidList { reason: &'static str },
+}
+
+impl fmt::Display for IniWriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IniWriteError::InvalidSection { section, reason } => write!(f, "cannot write section [{}]: {}", section, reason),
+            IniWriteError::InvalidName { section, name, reason } => {
+                write!(f, "cannot write key '{}' in [{}]: {}", name, section, reason)
+            }
+            IniWriteError::InvalidValue { section, name, reason } => {
+                write!(f, "cannot write value of {}.{}: {}", section, name, reason)
+            }
+            IniWriteError::InvalidList { reason } => write!(f, "cannot write list: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for IniWriteError {}
+
+/// Errors returned by the strict `try_get_*` lookups of `IniReader`
+#[derive(Debug, Clone, PartialEq)]
+pub enum IniGetError {
+    /// The key is not present
+    MissingKey { section: String, name: String },
+    /// The key is present but its value is empty or only whitespace
+    Empty { section: String, name: String },
+    /// The value cannot be converted to the requested type
+    InvalidValue { section: String, name: String, value: String, expected: &'static str },
+    /// The value was rejected by a [`FromStr`](std::str::FromStr) implementation, whose error is `message`
+    Unparsable { section: String, name: String, value: String, message: String },
+    /// The value is a well-formed integer too large or too small for the requested type
+    Overflow { section: String, name: String, value: String, expected: &'static str },
+    /// The value is outside the accepted range
+    OutOfRange { section: String, name: String, value: String, range: String, line: Option<usize> },
+}
+
+impl fmt::Display for IniGetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IniGetError::MissingKey { section, name } => write!(f, "missing key {}.{}", section, name),
+            IniGetError::Empty { section, name } => write!(f, "empty value for {}.{}", section, name),
+            IniGetError::InvalidValue { section, name, value, expected } => {
+                write!(f, "invalid value \"{}\" for {}.{}: expected {}", value, section, name, expected)
+            }
+            IniGetError::Unparsable { section, name, value, message } => {
+                write!(f, "invalid value \"{}\" for {}.{}: {}", value, section, name, message)
+            }
+            IniGetError::Overflow { section, name, value, expected } => {
+                write!(f, "value {} for {}.{} does not fit in a 64-bit {}", value, section, name, expected)
+            }
+            IniGetError::OutOfRange { section, name, value, range, line } => {
+                write!(f, "value {} for {}.{} is outside {}", value, section, name, range)?;
+                match line {
+                    Some(line) => write!(f, " (line {})", line),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl std::error::Error for IniGetError {}
+
+/// A reference interpolation could not resolve, see [`crate::interpolation`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum InterpolationError {
+    /// The value of `section.name` refers to a key that does not exist,
+    /// `reference` as written; only reported with strict interpolation
+    UnknownReference { section: String, name: String, reference: String },
+    /// The value of `section.name` refers back to itself through `chain`,
+    /// which lists the keys as `section:name` and starts and ends with it
+    Cycle { section: String, name: String, chain: Vec<String> },
+    /// Resolving the value of `section.name` went over the expansion budget
+    LimitExceeded { section: String, name: String, error: IniParseError },
+}
+
+impl fmt::Display for InterpolationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterpolationError::UnknownReference { section, name, reference } => {
+                write!(f, "unknown reference {} in {}.{}", reference, section, name)
+            }
+            InterpolationError::Cycle { section, name, chain } => {
+                write!(f, "reference cycle in {}.{}: {}", section, name, chain.join(" -> "))
+            }
+            InterpolationError::LimitExceeded { section, name, error } => write!(f, "{}.{}: {}", section, name, error),
+        }
+    }
+}
+
+impl std::error::Error for InterpolationError {}
+
+/// A field that failed its checks in an [`Extractor`](crate::extract::Extractor)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldError {
+    /// Section of the field
+    pub section: String,
+    /// Key of the field
+    pub name: String,
+    /// The value as written, `None` if the key is missing
+    pub value: Option<String>,
+    /// Line the value was read from, if it was read from a document
+    pub line: Option<usize>,
+    /// The constraint the value violates, such as `expected integer`
+    pub constraint: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        match &self.value {
+            Some(value) => write!(f, "invalid value \"{}\" for {}.{}: {}", value, self.section, self.name, self.constraint),
+            None => write!(f, "missing key {}.{}", self.section, self.name),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
+
+/// Errors from filling a type through [`de`](crate::de)
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum IniDeError {
+    /// The input could not be parsed
+    Parse(IniParseError),
+    /// The data does not fit the type
+    ///
+    /// `section` and `name` locate the problem when it lies within a section
+    /// or key; the global section is `""`.
+    Message { section: Option<String>, name: Option<String>, message: String },
+}
+
+#[cfg(feature = "serde")]
+impl IniDeError {
+    /// Record that the error arose within key `name`, unless a key is already known
+    pub(crate) fn in_key(self, name: &str) -> Self {
+        match self {
+            IniDeError::Message { section, name: None, message } => {
+                IniDeError::Message { section, name: Some(name.to_string()), message }
+            }
+            other => other,
+        }
+    }
+
+    /// Record that the error arose within `section`, unless a section is already known
+    pub(crate) fn in_section(self, section: &str) -> Self {
+        match self {
+            IniDeError::Message { section: None, name, message } => {
+                IniDeError::Message { section: Some(section.to_string()), name, message }
+            }
+            other => other,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for IniDeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IniDeError::Parse(error) => write!(f, "{}", error),
+            IniDeError::Message { section: Some(section), name: Some(name), message } => {
+                write!(f, "{}.{}: {}", section, name, message)
+            }
+            IniDeError::Message { section: Some(section), name: None, message } => {
+                write!(f, "section {}: {}", section, message)
+            }
+            IniDeError::Message { message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for IniDeError {}
+
+#[cfg(feature = "serde")]
+impl From<IniParseError> for IniDeError {
+    fn from(error: IniParseError) -> Self {
+        IniDeError::Parse(error)
+    }
+}
+
+/// Errors from writing a type out through [`ser`](crate::ser)
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum IniSerError {
+    /// The value has a shape INI cannot hold
+    ///
+    /// `path` leads to it from the top level, one field or key name per step.
+    Unsupported { path: Vec<String>, message: String },
+    /// A section, key or value would not read back unchanged
+    Write(IniWriteError),
+}
+
+#[cfg(feature = "serde")]
+impl IniSerError {
+    /// Record that the error arose within field or key `name`
+    pub(crate) fn within(mut self, name: &str) -> Self {
+        if let IniSerError::Unsupported { path, .. } = &mut self {
+            path.insert(0, name.to_string());
+        }
+        self
+    }
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for IniSerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IniSerError::Unsupported { path, message } if path.is_empty() => write!(f, "{}", message),
+            IniSerError::Unsupported { path, message } => write!(f, "{}: {}", path.join("."), message),
+            IniSerError::Write(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::error::Error for IniSerError {}
+
+#[cfg(feature = "serde")]
+impl From<IniWriteError> for IniSerError {
+    fn from(error: IniWriteError) -> Self {
+        IniSerError::Write(error)
+    }
+}
+
+/// A non-fatal finding recorded while parsing or reading values
+#[derive(Debug, Clone, PartialEq)]
+pub struct IniWarning {
+    /// Line the warning refers to, if known
+    pub line: Option<usize>,
+    /// Section concerned
+    pub section: String,
+    /// Key concerned (empty for section-level warnings)
+    pub name: String,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl fmt::Display for IniWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
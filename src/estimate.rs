@@ -0,0 +1,84 @@
+//! Cheap pre-scan of INI input for capacity planning
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::error::IniParseError;
+use crate::parser::{ini_parse_file_with_options, IniHandler, ParseOptions, SectionHeader};
+
+/// Size and count figures gathered by [`ini_estimate`]
+///
+/// Counts are upper bounds: a section header or key that appears several times
+/// is counted once per occurrence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Estimate {
+    /// Number of section headers
+    pub sections: usize,
+    /// Number of name=value entries (continuation lines are not counted)
+    pub keys: usize,
+    /// Length in bytes of the longest value, multi-line values joined with '\n'
+    pub max_value_len: usize,
+    /// Total length in bytes of all values
+    pub total_value_bytes: usize,
+}
+
+/// Estimate the size of an INI file from a file path
+pub fn ini_estimate<P: AsRef<Path>>(path: P, options: &ParseOptions) -> Result<Estimate, IniParseError> {
+    let file = File::open(path.as_ref()).map_err(|e| IniParseError::file_open(path.as_ref(), e))?;
+    ini_estimate_file(file, options)
+}
+
+/// Estimate the size of INI data from a Read object
+///
+/// Nothing but counters is kept while scanning. Syntax errors do not abort the
+/// estimate; only I/O failures are reported.
+pub fn ini_estimate_file<R: Read>(file: R, options: &ParseOptions) -> Result<Estimate, IniParseError> {
+    let mut counter = EstimateHandler::default();
+    match ini_parse_file_with_options(file, &mut counter, options) {
+        Err(error @ IniParseError::FileOpen(_)) => Err(error),
+        _ => Ok(counter.estimate),
+    }
+}
+
+/// Handler that only keeps counters
+#[derive(Default)]
+struct EstimateHandler {
+    estimate: Estimate,
+    /// Length of the value currently being assembled, if one is open
+    open_value: Option<usize>,
+}
+
+impl IniHandler for EstimateHandler {
+    fn handle(&mut self, _section: &str, name: &str, value: &str) -> Result<(), String> {
+        if name.is_empty() {
+            // Sections are counted from start_section
+            return Ok(());
+        }
+
+        let len = match self.open_value {
+            Some(len) => {
+                self.estimate.total_value_bytes += value.len() + 1;
+                len + value.len() + 1
+            }
+            None => {
+                self.estimate.keys += 1;
+                self.estimate.total_value_bytes += value.len();
+                value.len()
+            }
+        };
+        self.open_value = Some(len);
+        self.estimate.max_value_len = self.estimate.max_value_len.max(len);
+        Ok(())
+    }
+
+    fn start_section(&mut self, _header: &SectionHeader) -> Result<(), String> {
+        self.estimate.sections += 1;
+        Ok(())
+    }
+
+    fn end_value(&mut self, _section: &str, _name: &str) -> Result<(), String> {
+        self.open_value = None;
+        Ok(())
+    }
+}
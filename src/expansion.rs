@@ -0,0 +1,129 @@
+//! Limits shared by every feature that expands values
+
+use crate::error::{ExpansionLimit, IniParseError};
+
+/// Limits on recursive expansion, shared by all expanding features
+///
+/// The include directives and the interpolation of one document draw from
+/// one account when the handler keeps it, as [`IniReader`](crate::IniReader)
+/// does through [`IniHandler::expansion`](crate::IniHandler::expansion), so
+/// combining them cannot multiply the work: every include and every
+/// interpolated reference counts against one total. Value resolvers draw
+/// from the account they are given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpansionBudget {
+    /// Deepest allowed nesting of expansions
+    pub max_depth: usize,
+    /// Most expansions allowed in total
+    pub max_total_expansions: usize,
+    /// Longest allowed expanded value, in bytes
+    pub max_expanded_len: usize,
+}
+
+impl Default for ExpansionBudget {
+    fn default() -> Self {
+        Self {
+            max_depth: 32,
+            max_total_expansions: 10_000,
+            max_expanded_len: 1024 * 1024,
+        }
+    }
+}
+
+/// Running account of one load against an [`ExpansionBudget`]
+///
+/// Every expanding feature, including resolvers written outside the crate,
+/// calls `enter` before expanding something and `leave` afterwards; the
+/// chain of entered labels is reported when a limit is hit. An error from
+/// `enter` leaves the chain as it was at the failure.
+#[derive(Debug, Clone)]
+pub struct Expansion {
+    budget: ExpansionBudget,
+    chain: Vec<String>,
+    total: usize,
+}
+
+impl Expansion {
+    /// Start accounting against `budget`
+    pub fn new(budget: ExpansionBudget) -> Self {
+        Self { budget, chain: Vec::new(), total: 0 }
+    }
+
+    /// Begin expanding `label`, nested in whatever is being expanded now
+    pub fn enter(&mut self, label: &str) -> Result<(), IniParseError> {
+        self.chain.push(label.to_string());
+        self.total += 1;
+        if self.chain.len() > self.budget.max_depth {
+            return Err(self.exceeded(ExpansionLimit::Depth(self.budget.max_depth)));
+        }
+        if self.total > self.budget.max_total_expansions {
+            return Err(self.exceeded(ExpansionLimit::TotalExpansions(self.budget.max_total_expansions)));
+        }
+        Ok(())
+    }
+
+    /// Finish the innermost expansion
+    pub fn leave(&mut self) {
+        self.chain.pop();
+    }
+
+    /// Check the length of a value produced by the current expansion
+    pub fn check_len(&self, len: usize) -> Result<(), IniParseError> {
+        if len > self.budget.max_expanded_len {
+            return Err(self.exceeded(ExpansionLimit::ExpandedLength(self.budget.max_expanded_len)));
+        }
+        Ok(())
+    }
+
+    fn exceeded(&self, limit: ExpansionLimit) -> IniParseError {
+        IniParseError::LimitExceeded { limit, chain: self.chain.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Expand `node`, where node `i` refers to node `i + 1` twice, up to `last`
+    fn expand(node: usize, last: usize, expansion: &mut Expansion) -> Result<String, IniParseError> {
+        expansion.enter(&format!("v{}", node))?;
+        let value = if node == last {
+            "x".to_string()
+        } else {
+            let half = expand(node + 1, last, expansion)?;
+            let value = format!("{}{}", half, expand(node + 1, last, expansion)?);
+            expansion.check_len(value.len())?;
+            value
+        };
+        expansion.leave();
+        Ok(value)
+    }
+
+    #[test]
+    fn test_exponential_chain_hits_total_limit() {
+        let budget = ExpansionBudget { max_total_expansions: 1000, ..ExpansionBudget::default() };
+        let mut expansion = Expansion::new(budget);
+
+        let error = expand(0, 30, &mut expansion).unwrap_err();
+
+        let IniParseError::LimitExceeded { limit, chain } = &error else {
+            panic!("unexpected error {:?}", error);
+        };
+        assert_eq!(*limit, ExpansionLimit::TotalExpansions(1000));
+        assert_eq!(chain[..3], ["v0", "v1", "v2"]);
+        assert!(error.to_string().starts_with("Expansion limit exceeded (more than 1000 expansions): v0 -> v1 -> v2"));
+    }
+
+    #[test]
+    fn test_depth_and_length_limits() {
+        let budget = ExpansionBudget { max_depth: 4, ..ExpansionBudget::default() };
+        let error = expand(0, 10, &mut Expansion::new(budget)).unwrap_err();
+        assert_eq!(error.to_string(), "Expansion limit exceeded (depth over 4): v0 -> v1 -> v2 -> v3 -> v4");
+
+        let budget = ExpansionBudget { max_expanded_len: 64, ..ExpansionBudget::default() };
+        let error = expand(0, 10, &mut Expansion::new(budget)).unwrap_err();
+        assert!(matches!(error, IniParseError::LimitExceeded { limit: ExpansionLimit::ExpandedLength(64), .. }));
+
+        assert_eq!(expand(0, 5, &mut Expansion::new(ExpansionBudget::default())).unwrap().len(), 32);
+    }
+}
@@ -0,0 +1,129 @@
+//! Step-by-step account of how a key was resolved
+//!
+//! [`IniReader::explain`](crate::IniReader::explain),
+//! [`LayeredReader::explain`](crate::layered::LayeredReader::explain) and
+//! [`FallbackChain::explain`](crate::fallback::FallbackChain::explain) answer
+//! "why does this key have this value?":
+//!
+//! ```rust
+//! use inih::layered::LayeredReader;
+//! use inih::IniReader;
+//!
+//! let file = IniReader::from_string("[server]\ntimeout = 30\n").unwrap();
+//! let env = IniReader::from_string("[server]\ntimeout = 60\n").unwrap();
+//! let layered = LayeredReader::new(vec![&file, &env]);
+//!
+//! let explanation = layered.explain("server", "timeout");
+//! assert_eq!(explanation.value.as_deref(), Some("60"));
+//! assert_eq!(
+//!     explanation.to_string(),
+//!     "[server] timeout = \"60\"\n  layer 1: [server] timeout found, line 2\n"
+//! );
+//! ```
+//!
+//! The trace is recorded by the same lookup the getters run, so it shows
+//! exactly the places they consult, in order, stopping at the one that
+//! answers.
+
+use std::fmt;
+
+use crate::reader::Occurrence;
+
+/// Where a lookup was made
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// The reader itself
+    Reader,
+    /// A layer of a [`LayeredReader`](crate::layered::LayeredReader), by index from lowest priority
+    Layer(usize),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Reader => write!(f, "reader"),
+            Source::Layer(index) => write!(f, "layer {}", index),
+        }
+    }
+}
+
+/// One lookup made while resolving a key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// Where the lookup was made
+    pub source: Source,
+    /// Section looked in, as given to the lookup
+    pub section: String,
+    /// Whether the key was there
+    pub found: bool,
+}
+
+/// How a key was resolved, from [`IniReader::explain`](crate::IniReader::explain) and its cousins
+///
+/// `Display` prints a readable multi-line trace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// Section asked for
+    pub section: String,
+    /// Key asked for
+    pub name: String,
+    /// Lookups in the order they were made; the last one answered if the key was found
+    pub steps: Vec<Step>,
+    /// Stored value the getters read, or `None` if they return their default
+    pub value: Option<String>,
+    /// The value as written, if interpolation turned it into `value`
+    pub raw_value: Option<String>,
+    /// Every occurrence of the key in the answering reader, in document order
+    ///
+    /// More than one means duplicates, combined by the duplicate key policy;
+    /// line 0 marks a value that was set rather than read.
+    pub occurrences: Vec<Occurrence>,
+}
+
+impl Explanation {
+    /// Start an explanation for `section` and `name`, with no lookups yet
+    pub(crate) fn new(section: &str, name: &str) -> Self {
+        Self {
+            section: section.to_string(),
+            name: name.to_string(),
+            steps: Vec::new(),
+            value: None,
+            raw_value: None,
+            occurrences: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            Some(value) => writeln!(f, "[{}] {} = {:?}", self.section, self.name, value)?,
+            None => writeln!(f, "[{}] {} is not set, getters return their default", self.section, self.name)?,
+        }
+        for step in &self.steps {
+            write!(f, "  {}: [{}] {}", step.source, step.section, self.name)?;
+            if !step.found {
+                writeln!(f, " not found")?;
+                continue;
+            }
+            // Lookups stop at the first match, so these are the answering reader's
+            let lines: Vec<String> = self
+                .occurrences
+                .iter()
+                .map(|occurrence| match occurrence.line {
+                    0 => "set".to_string(),
+                    line => format!("line {}", line),
+                })
+                .collect();
+            if lines.is_empty() {
+                writeln!(f, " found")?;
+            } else {
+                writeln!(f, " found, {}", lines.join(", "))?;
+            }
+        }
+        if let Some(raw_value) = &self.raw_value {
+            writeln!(f, "  interpolated from {:?}", raw_value)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+//! Reading many typed values at once, reporting every invalid one
+//!
+//! The `try_get_*` getters fail on the first bad value. An [`Extractor`]
+//! reads with the same rules but goes on, so a settings screen or a
+//! startup check can show everything that is wrong in one go:
+//!
+//! ```rust
+//! use inih::extract::Extractor;
+//! use inih::IniReader;
+//!
+//! let reader = IniReader::from_string("[server]\nport = 70000\nhost =\n").unwrap();
+//! let ex = Extractor::new(&reader);
+//! let port = ex.integer("server", "port").range(1..=65535);
+//! let host = ex.string("server", "host").non_empty();
+//!
+//! let errors = ex.finish().unwrap_err();
+//! assert_eq!(errors[0].to_string(), "line 2: invalid value \"70000\" for server.port: expected 1..=65535");
+//! assert_eq!(errors[1].to_string(), "line 3: invalid value \"\" for server.host: expected non-empty value");
+//! # let _ = (port, host);
+//! ```
+//!
+//! Once [`finish`](Extractor::finish) succeeds, each field's
+//! [`get`](Field::get) returns its value.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::error::{FieldError, IniGetError};
+use crate::reader::{IniReader, KeyName, SectionName};
+
+/// Reads fields from a reader, collecting the errors of all of them
+///
+/// Each field is read and checked when it is declared; its errors are kept
+/// until [`finish`](Self::finish), in the order the fields were declared.
+/// A field reports at most one error, the first check it fails.
+pub struct Extractor<'r> {
+    reader: &'r IniReader,
+    errors: RefCell<Vec<FieldError>>,
+}
+
+impl<'r> Extractor<'r> {
+    /// Start extracting from `reader`
+    pub fn new(reader: &'r IniReader) -> Self {
+        Self { reader, errors: RefCell::new(Vec::new()) }
+    }
+
+    /// A string field, which must be present; see [`IniReader::try_get_string`]
+    pub fn string(&self, section: impl SectionName, name: impl KeyName) -> Field<'_, String> {
+        let (section, name) = (section.section_name(), name.key_name());
+        self.field(section, name, self.reader.try_get_string(section, name))
+    }
+
+    /// An integer field; see [`IniReader::try_get_integer`]
+    pub fn integer(&self, section: impl SectionName, name: impl KeyName) -> Field<'_, i64> {
+        let (section, name) = (section.section_name(), name.key_name());
+        self.field(section, name, self.reader.try_get_integer(section, name))
+    }
+
+    /// An unsigned integer field; see [`IniReader::try_get_unsigned`]
+    pub fn unsigned(&self, section: impl SectionName, name: impl KeyName) -> Field<'_, u64> {
+        let (section, name) = (section.section_name(), name.key_name());
+        self.field(section, name, self.reader.try_get_unsigned(section, name))
+    }
+
+    /// A floating-point field; see [`IniReader::try_get_real`]
+    pub fn real(&self, section: impl SectionName, name: impl KeyName) -> Field<'_, f64> {
+        let (section, name) = (section.section_name(), name.key_name());
+        self.field(section, name, self.reader.try_get_real(section, name))
+    }
+
+    /// A boolean field; see [`IniReader::try_get_boolean`]
+    pub fn boolean(&self, section: impl SectionName, name: impl KeyName) -> Field<'_, bool> {
+        let (section, name) = (section.section_name(), name.key_name());
+        self.field(section, name, self.reader.try_get_boolean(section, name))
+    }
+
+    /// A field parsed with its [`FromStr`] implementation; see [`IniReader::try_get_parse`]
+    pub fn parse<T>(&self, section: impl SectionName, name: impl KeyName) -> Field<'_, T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let (section, name) = (section.section_name(), name.key_name());
+        self.field(section, name, self.reader.try_get_parse(section, name))
+    }
+
+    /// Every error of the fields declared so far, or `Ok` if there is none
+    pub fn finish(&self) -> Result<(), Vec<FieldError>> {
+        let errors = self.errors.borrow();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.clone())
+        }
+    }
+
+    fn field<T>(&self, section: &str, name: &str, result: Result<T, IniGetError>) -> Field<'_, T> {
+        let mut field = Field {
+            errors: &self.errors,
+            section: section.to_string(),
+            name: name.to_string(),
+            raw: self.reader.get_opt(section, name).map(str::to_string),
+            line: self.reader.line_of(section, name),
+            value: None,
+        };
+        match result {
+            Ok(value) => field.value = Some(value),
+            Err(error) => field.fail(constraint(error)),
+        }
+        field
+    }
+}
+
+/// The constraint an error from a `try_get_*` getter reports as violated
+fn constraint(error: IniGetError) -> String {
+    match error {
+        IniGetError::MissingKey { .. } => "expected a value".to_string(),
+        IniGetError::Empty { .. } => "expected non-empty value".to_string(),
+        IniGetError::InvalidValue { expected, .. } => format!("expected {}", expected),
+        IniGetError::Unparsable { message, .. } => message,
+        IniGetError::Overflow { expected, .. } => format!("does not fit in a 64-bit {}", expected),
+        IniGetError::OutOfRange { range, .. } => format!("expected {}", range),
+    }
+}
+
+/// One field declared on an [`Extractor`], with the checks it passed so far
+///
+/// Further checks apply only while the field is valid, so it reports the
+/// first one it fails.
+pub struct Field<'e, T> {
+    errors: &'e RefCell<Vec<FieldError>>,
+    section: String,
+    name: String,
+    raw: Option<String>,
+    line: Option<usize>,
+    /// `None` once a check failed
+    value: Option<T>,
+}
+
+impl<T> Field<'_, T> {
+    /// Require the value to satisfy `accept`, described by `constraint`
+    ///
+    /// The description completes the error message, as in
+    /// `invalid value "x" for server.mode: expected one of fast, safe`.
+    pub fn check(mut self, constraint: &str, accept: impl FnOnce(&T) -> bool) -> Self {
+        if self.value.as_ref().is_some_and(|value| !accept(value)) {
+            self.value = None;
+            self.fail(constraint.to_string());
+        }
+        self
+    }
+
+    /// Whether the field passed every check so far
+    pub fn is_valid(&self) -> bool {
+        self.value.is_some()
+    }
+
+    /// The value of the field
+    ///
+    /// # Panics
+    ///
+    /// If the field failed a check; call [`Extractor::finish`] first.
+    pub fn get(self) -> T {
+        match self.value {
+            Some(value) => value,
+            None => panic!("{}.{} is invalid, see Extractor::finish", self.section, self.name),
+        }
+    }
+
+    fn fail(&mut self, constraint: String) {
+        self.errors.borrow_mut().push(FieldError {
+            section: self.section.clone(),
+            name: self.name.clone(),
+            value: self.raw.clone(),
+            line: self.line,
+            constraint,
+        });
+    }
+}
+
+impl<T: PartialOrd + fmt::Display> Field<'_, T> {
+    /// Require the value to lie in `range`
+    pub fn range(self, range: RangeInclusive<T>) -> Self {
+        let constraint = format!("expected {}..={}", range.start(), range.end());
+        self.check(&constraint, |value| range.contains(value))
+    }
+}
+
+impl Field<'_, String> {
+    /// Require the value to hold more than whitespace
+    pub fn non_empty(self) -> Self {
+        self.check("expected non-empty value", |value| !value.trim().is_empty())
+    }
+}
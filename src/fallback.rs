@@ -0,0 +1,96 @@
+//! Lookups that fall back through a list of sections
+
+use crate::explain::{Explanation, Source, Step};
+use crate::reader::IniReader;
+
+/// An ordered list of sections consulted in turn for each key
+///
+/// Build the chain once and use it for many keys: lookups try each section
+/// from first to last and the first one holding the key answers. Section
+/// names follow the reader's rules, so they match case-insensitively. Typed
+/// getters use the parsing rules of [`IniReader`] on the answering value; an
+/// invalid value there gives the default rather than falling through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FallbackChain {
+    sections: Vec<String>,
+}
+
+impl FallbackChain {
+    /// Create a chain over `sections`, ordered from most to least specific
+    pub fn new(sections: &[&str]) -> Self {
+        Self { sections: sections.iter().map(|section| section.to_string()).collect() }
+    }
+
+    /// The sections of the chain, in lookup order
+    pub fn sections(&self) -> &[String] {
+        &self.sections
+    }
+
+    /// The section that supplies `name`, as spelled in the chain, if any does
+    pub fn resolve_origin(&self, reader: &IniReader, name: &str) -> Option<&str> {
+        self.resolve(reader, name, None)
+    }
+
+    /// The section that supplies `name`, recording the lookups in `trace` if given
+    fn resolve(&self, reader: &IniReader, name: &str, mut trace: Option<&mut Vec<Step>>) -> Option<&str> {
+        self.sections.iter().map(String::as_str).find(|section| {
+            let trace = trace.as_deref_mut().map(|steps| (Source::Reader, steps));
+            reader.lookup(section, name, trace).is_some()
+        })
+    }
+
+    /// How `name` resolves: the sections tried in order, the value and where it came from
+    ///
+    /// The explanation names the first section of the chain; each step shows
+    /// the section it looked in.
+    pub fn explain(&self, reader: &IniReader, name: &str) -> Explanation {
+        let first = self.sections.first().map_or("", String::as_str);
+        let mut explanation = Explanation::new(first, name);
+        if let Some(section) = self.resolve(reader, name, Some(&mut explanation.steps)) {
+            explanation.value = reader.lookup(section, name, None).map(str::to_string);
+            explanation.raw_value = reader.raw_value(section, name).map(str::to_string);
+            explanation.occurrences = reader.occurrences(section, name);
+        }
+        explanation
+    }
+
+    /// Get a string value, returning the default if no section has it
+    pub fn get(&self, reader: &IniReader, name: &str, default_value: &str) -> String {
+        match self.resolve_origin(reader, name) {
+            Some(section) => reader.get(section, name, default_value),
+            None => default_value.to_string(),
+        }
+    }
+
+    /// Get a string value, returning the default if no section has it or it is empty
+    pub fn get_nonempty_or(&self, reader: &IniReader, name: &str, default_value: &str) -> String {
+        match self.resolve_origin(reader, name) {
+            Some(section) => reader.get_nonempty_or(section, name, default_value),
+            None => default_value.to_string(),
+        }
+    }
+
+    /// Get an integer value, returning the default if no section has it, or it is empty or invalid
+    pub fn get_integer(&self, reader: &IniReader, name: &str, default_value: i64) -> i64 {
+        self.resolve_origin(reader, name)
+            .map_or(default_value, |section| reader.get_integer(section, name, default_value))
+    }
+
+    /// Get an unsigned integer value, returning the default if no section has it, or it is empty or invalid
+    pub fn get_unsigned(&self, reader: &IniReader, name: &str, default_value: u64) -> u64 {
+        self.resolve_origin(reader, name)
+            .map_or(default_value, |section| reader.get_unsigned(section, name, default_value))
+    }
+
+    /// Get a floating-point value, returning the default if no section has it, or it is empty or invalid
+    pub fn get_real(&self, reader: &IniReader, name: &str, default_value: f64) -> f64 {
+        self.resolve_origin(reader, name)
+            .map_or(default_value, |section| reader.get_real(section, name, default_value))
+    }
+
+    /// Get a boolean value, returning the default if no section has it, or it is empty or invalid
+    pub fn get_boolean(&self, reader: &IniReader, name: &str, default_value: bool) -> bool {
+        self.resolve_origin(reader, name)
+            .map_or(default_value, |section| reader.get_boolean(section, name, default_value))
+    }
+}
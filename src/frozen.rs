@@ -0,0 +1,88 @@
+//! Read-only form of a reader, for configuration that must not change after startup
+//!
+//! [`IniReader::freeze`](crate::IniReader::freeze) turns a reader into a
+//! [`FrozenIniReader`], which offers every lookup of [`IniReader`] and none of
+//! its mutating methods, so no code path holding it can change the values:
+//!
+//! ```rust
+//! use inih::IniReader;
+//!
+//! let mut reader = IniReader::from_string("[server]\nport = 8080\n").unwrap();
+//! reader.set("server", "host", "localhost");
+//! let config = reader.freeze();
+//!
+//! assert_eq!(config.get_integer("server", "port", 0), 8080);
+//! assert_eq!(config.get("server", "host", ""), "localhost");
+//! ```
+//!
+//! Mutation does not compile:
+//!
+//! ```rust,compile_fail,E0596
+//! use inih::IniReader;
+//!
+//! let mut config = IniReader::from_string("[server]\nport = 8080\n").unwrap().freeze();
+//! config.set("server", "port", "9090");
+//! ```
+//!
+//! Code that does need to change the values thaws it back explicitly, which
+//! costs nothing.
+
+use std::fmt;
+use std::ops::Deref;
+
+use crate::reader::IniReader;
+
+/// An [`IniReader`] that cannot be changed
+///
+/// Derefs to `&IniReader`, so the getters, [`LayeredReader`](crate::layered::LayeredReader)
+/// (through `&*frozen`) and everything else taking a shared reader work as
+/// before. Cloning shares the stored tables, so handing a copy to each
+/// thread is cheap.
+#[derive(Clone)]
+pub struct FrozenIniReader {
+    reader: IniReader,
+}
+
+impl FrozenIniReader {
+    /// Wrap `reader`; see [`IniReader::freeze`]
+    pub(crate) fn new(reader: IniReader) -> Self {
+        Self { reader }
+    }
+
+    /// Turn back into a reader that can be changed
+    pub fn thaw(self) -> IniReader {
+        self.reader
+    }
+}
+
+impl Deref for FrozenIniReader {
+    type Target = IniReader;
+
+    fn deref(&self) -> &IniReader {
+        &self.reader
+    }
+}
+
+impl AsRef<IniReader> for FrozenIniReader {
+    fn as_ref(&self) -> &IniReader {
+        &self.reader
+    }
+}
+
+impl From<IniReader> for FrozenIniReader {
+    fn from(reader: IniReader) -> Self {
+        Self::new(reader)
+    }
+}
+
+impl fmt::Display for FrozenIniReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.reader, f)
+    }
+}
+
+impl fmt::Debug for FrozenIniReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FrozenIniReader").field(&self.reader).finish()
+    }
+}
@@ -0,0 +1,954 @@
+//! Reusable `IniHandler` adapters
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::io::Write;
+
+use crate::error::IniWarning;
+use crate::expansion::{Expansion, ExpansionBudget};
+use crate::parser::{EntryInfo, IniHandler, NewlineStyle, SectionHeader};
+use crate::writer::{entry_line, WriteOptions};
+
+/// An owned (section, name, value) entry
+pub type Entry = (String, String, String);
+
+/// Handler adapter that delivers entries to a callback in batches
+///
+/// Entries are accumulated until `batch_size` of them have been collected, at
+/// which point the callback is invoked with the whole batch. Whatever is left
+/// over is delivered when the document ends. Section-only notifications (empty
+/// name) are not batched; multi-line continuations arrive as separate entries.
+///
+/// Errors returned by the callback are reported together with the line of the
+/// first entry in the failed batch.
+pub struct BatchingHandler<F>
+where
+    F: FnMut(&[Entry]) -> Result<(), String>,
+{
+    callback: F,
+    batch_size: usize,
+    flush_on_section_change: bool,
+    batch: Vec<Entry>,
+    first_line: usize,
+}
+
+impl<F> BatchingHandler<F>
+where
+    F: FnMut(&[Entry]) -> Result<(), String>,
+{
+    /// Create a new batching handler delivering up to `batch_size` entries at a time
+    pub fn new(batch_size: usize, callback: F) -> Self {
+        let batch_size = batch_size.max(1);
+        Self {
+            callback,
+            batch_size,
+            flush_on_section_change: false,
+            batch: Vec::with_capacity(batch_size),
+            first_line: 0,
+        }
+    }
+
+    /// Also flush the pending batch whenever the section changes
+    pub fn flush_on_section_change(mut self, enabled: bool) -> Self {
+        self.flush_on_section_change = enabled;
+        self
+    }
+
+    /// Deliver the pending entries to the callback, if there are any
+    pub fn flush(&mut self) -> Result<(), String> {
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+        let result = (self.callback)(&self.batch)
+            .map_err(|msg| format!("batch starting at line {}: {}", self.first_line, msg));
+        self.batch.clear();
+        result
+    }
+
+    /// Consume the adapter and return the callback
+    pub fn into_inner(self) -> F {
+        self.callback
+    }
+}
+
+impl<F> IniHandler for BatchingHandler<F>
+where
+    F: FnMut(&[Entry]) -> Result<(), String>,
+{
+    fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.handle_at(0, section, name, value)
+    }
+
+    fn handle_at(&mut self, line: usize, section: &str, name: &str, value: &str) -> Result<(), String> {
+        if name.is_empty() {
+            return Ok(());
+        }
+
+        if self.flush_on_section_change {
+            if let Some((last_section, _, _)) = self.batch.last() {
+                if last_section != section {
+                    self.flush()?;
+                }
+            }
+        }
+
+        if self.batch.is_empty() {
+            self.first_line = line;
+        }
+        self.batch.push((section.to_string(), name.to_string(), value.to_string()));
+
+        if self.batch.len() >= self.batch_size {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn end_document(&mut self) -> Result<(), String> {
+        self.flush()
+    }
+}
+
+/// An error raised by a routed handler, attributed to its section and line
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutedError {
+    /// Section the failing entry belonged to
+    pub section: String,
+    /// Line of the failing entry (0 for errors raised at end of document)
+    pub line: usize,
+    /// Message returned by the handler
+    pub message: String,
+}
+
+impl fmt::Display for RoutedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] line {}: {}", self.section, self.line, self.message)
+    }
+}
+
+/// Handler that dispatches entries to per-section handlers
+///
+/// Sections are matched case-insensitively unless `case_sensitive(true)` is set.
+/// Entries of sections without a route go to the fallback handler; if there is
+/// none they are dropped and a warning is recorded once per section. Every
+/// handler error is collected in `errors()` and also returned to the parser.
+pub struct SectionRouter<'a> {
+    routes: Vec<(String, &'a mut dyn IniHandler)>,
+    fallback: Option<&'a mut dyn IniHandler>,
+    case_sensitive: bool,
+    errors: Vec<RoutedError>,
+    warnings: Vec<String>,
+    warned: HashSet<String>,
+}
+
+impl<'a> SectionRouter<'a> {
+    /// Create a router without any routes
+    pub fn new() -> Self {
+        Self {
+            routes: Vec::new(),
+            fallback: None,
+            case_sensitive: false,
+            errors: Vec::new(),
+            warnings: Vec::new(),
+            warned: HashSet::new(),
+        }
+    }
+
+    /// Send entries of `section` to `handler`
+    pub fn route(mut self, section: &str, handler: &'a mut dyn IniHandler) -> Self {
+        self.routes.push((section.to_string(), handler));
+        self
+    }
+
+    /// Send entries of all unrouted sections to `handler`
+    pub fn route_default(mut self, handler: &'a mut dyn IniHandler) -> Self {
+        self.fallback = Some(handler);
+        self
+    }
+
+    /// Match section names exactly instead of case-insensitively
+    pub fn case_sensitive(mut self, enabled: bool) -> Self {
+        self.case_sensitive = enabled;
+        self
+    }
+
+    /// All errors raised by routed handlers, in the order they occurred
+    pub fn errors(&self) -> &[RoutedError] {
+        &self.errors
+    }
+
+    /// Warnings about sections that had no handler, in the order they occurred
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Find the handler for a section, recording a warning if there is none
+    fn target(&mut self, section: &str) -> Option<&mut (dyn IniHandler + 'a)> {
+        let case_sensitive = self.case_sensitive;
+        let position = self.routes.iter().position(|(name, _)| {
+            if case_sensitive {
+                name == section
+            } else {
+                name.to_lowercase() == section.to_lowercase()
+            }
+        });
+        match position {
+            Some(index) => Some(&mut *self.routes[index].1),
+            None if self.fallback.is_some() => self.fallback.as_deref_mut(),
+            None => {
+                if self.warned.insert(section.to_string()) {
+                    self.warnings.push(format!("No handler for section '{}'", section));
+                }
+                None
+            }
+        }
+    }
+
+    /// Record a handler error with its section and line
+    fn attribute(&mut self, section: &str, line: usize, result: Result<(), String>) -> Result<(), String> {
+        result.map_err(|message| {
+            let error = RoutedError { section: section.to_string(), line, message };
+            let text = error.to_string();
+            self.errors.push(error);
+            text
+        })
+    }
+}
+
+impl Default for SectionRouter<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IniHandler for SectionRouter<'_> {
+    fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.handle_at(0, section, name, value)
+    }
+
+    fn handle_at(&mut self, line: usize, section: &str, name: &str, value: &str) -> Result<(), String> {
+        let result = match self.target(section) {
+            Some(handler) => handler.handle_at(line, section, name, value),
+            None => Ok(()),
+        };
+        self.attribute(section, line, result)
+    }
+
+    fn start_section(&mut self, header: &SectionHeader) -> Result<(), String> {
+        let section = header.flattened_name();
+        let result = match self.target(&section) {
+            Some(handler) => handler.start_section(header),
+            None => Ok(()),
+        };
+        self.attribute(&section, 0, result)
+    }
+
+    fn end_section(&mut self, section: &str) -> Result<(), String> {
+        let result = match self.target(section) {
+            Some(handler) => handler.end_section(section),
+            None => Ok(()),
+        };
+        self.attribute(section, 0, result)
+    }
+
+    fn end_value(&mut self, section: &str, name: &str) -> Result<(), String> {
+        let result = match self.target(section) {
+            Some(handler) => handler.end_value(section, name),
+            None => Ok(()),
+        };
+        self.attribute(section, 0, result)
+    }
+
+    fn warning(&mut self, warning: &IniWarning) -> Result<(), String> {
+        let result = match self.target(&warning.section) {
+            Some(handler) => handler.warning(warning),
+            None => Ok(()),
+        };
+        self.attribute(&warning.section, warning.line.unwrap_or(0), result)
+    }
+
+    fn start_document(&mut self) {
+        for (_, handler) in &mut self.routes {
+            handler.start_document();
+        }
+        if let Some(fallback) = self.fallback.as_deref_mut() {
+            fallback.start_document();
+        }
+    }
+
+    fn end_document(&mut self) -> Result<(), String> {
+        let mut first_error = Ok(());
+        for index in 0..self.routes.len() {
+            let result = self.routes[index].1.end_document();
+            let section = self.routes[index].0.clone();
+            if let Err(msg) = self.attribute(&section, 0, result) {
+                first_error = first_error.and(Err(msg));
+            }
+        }
+        if let Some(fallback) = self.fallback.as_deref_mut() {
+            let result = fallback.end_document();
+            if let Err(msg) = self.attribute("", 0, result) {
+                first_error = first_error.and(Err(msg));
+            }
+        }
+        first_error
+    }
+}
+
+/// Handler that writes the entries it receives back out as INI text
+///
+/// By default every section header and entry is written as soon as it arrives,
+/// so a section that appears several times in the input also appears several
+/// times in the output. With `group_sections(true)` entries are buffered and
+/// emitted at the end of the document with each section exactly once, in order
+/// of first appearance. The buffer can be capped with `buffer_limit`; exceeding
+/// the cap is reported as a handler error.
+///
+/// Lines end with `\n` unless another [`NewlineStyle`] is chosen; with
+/// `Preserve` each entry keeps the terminator it had in the input.
+pub struct WriterHandler<W: Write> {
+    writer: W,
+    write_options: WriteOptions,
+    newline: NewlineStyle,
+    group_sections: bool,
+    buffer_limit: Option<usize>,
+    wrote_anything: bool,
+    /// Buffered sections in first-appearance order: (name as first written, terminated lines)
+    groups: Vec<(String, Vec<String>)>,
+    /// Index of each buffered section in `groups`, by folded name
+    group_index: HashMap<String, usize>,
+    current_group: usize,
+    buffered_bytes: usize,
+    peak_buffered_bytes: usize,
+}
+
+impl<W: Write> WriterHandler<W> {
+    /// Create a handler streaming to `writer`
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            write_options: WriteOptions::default(),
+            newline: NewlineStyle::Lf,
+            group_sections: false,
+            buffer_limit: None,
+            wrote_anything: false,
+            groups: vec![(String::new(), Vec::new())],
+            group_index: HashMap::from([(String::new(), 0)]),
+            current_group: 0,
+            buffered_bytes: 0,
+            peak_buffered_bytes: 0,
+        }
+    }
+
+    /// Write entries according to `options`
+    pub fn write_options(mut self, options: WriteOptions) -> Self {
+        self.write_options = options;
+        self
+    }
+
+    /// Terminate output lines according to `style`
+    pub fn newline(mut self, style: NewlineStyle) -> Self {
+        self.newline = style;
+        self
+    }
+
+    /// Buffer entries and emit each section once, at the end of the document
+    pub fn group_sections(mut self, enabled: bool) -> Self {
+        self.group_sections = enabled;
+        self
+    }
+
+    /// Fail once more than `bytes` of output are buffered in grouping mode
+    pub fn buffer_limit(mut self, bytes: usize) -> Self {
+        self.buffer_limit = Some(bytes);
+        self
+    }
+
+    /// Bytes of output currently held back in grouping mode
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffered_bytes
+    }
+
+    /// Largest number of bytes held back at any time
+    pub fn peak_buffered_bytes(&self) -> usize {
+        self.peak_buffered_bytes
+    }
+
+    /// Consume the handler and return the writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Write a section header, separated from earlier output by a blank line
+    fn write_header(&mut self, section: &str) -> Result<(), String> {
+        let newline = self.newline.terminator("");
+        if self.wrote_anything {
+            write!(self.writer, "{}", newline).map_err(|e| e.to_string())?;
+        }
+        self.wrote_anything = true;
+        write!(self.writer, "[{}]{}", section, newline).map_err(|e| e.to_string())
+    }
+
+    /// Write or buffer a single output line of the current section
+    fn emit_line(&mut self, mut line: String, source_ending: &'static str) -> Result<(), String> {
+        line.push_str(self.newline.terminator(source_ending));
+        if !self.group_sections {
+            self.wrote_anything = true;
+            return self.writer.write_all(line.as_bytes()).map_err(|e| e.to_string());
+        }
+
+        self.buffered_bytes += line.len();
+        self.peak_buffered_bytes = self.peak_buffered_bytes.max(self.buffered_bytes);
+        if let Some(limit) = self.buffer_limit {
+            if self.buffered_bytes > limit {
+                return Err(format!("grouping buffer exceeded {} bytes", limit));
+            }
+        }
+        self.groups[self.current_group].1.push(line);
+        Ok(())
+    }
+}
+
+impl<W: Write> IniHandler for WriterHandler<W> {
+    fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.handle_at(0, section, name, value)
+    }
+
+    fn handle_at(&mut self, _line: usize, _section: &str, name: &str, value: &str) -> Result<(), String> {
+        // Headers are written from start_section
+        if name.is_empty() {
+            return Ok(());
+        }
+        self.emit_line(entry_line(name, value, &self.write_options), "")
+    }
+
+    fn start_section(&mut self, header: &SectionHeader) -> Result<(), String> {
+        let section = header.flattened_name();
+        if !self.group_sections {
+            return self.write_header(&section);
+        }
+        let folded = section.to_lowercase();
+        self.current_group = match self.group_index.get(&folded) {
+            Some(&index) => index,
+            None => {
+                self.groups.push((section, Vec::new()));
+                self.group_index.insert(folded, self.groups.len() - 1);
+                self.groups.len() - 1
+            }
+        };
+        Ok(())
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, _section: &str, name: &str, value: &str) -> Result<(), String> {
+        if info.continuation {
+            // Continuation values keep their original indentation
+            return self.emit_line(value.to_string(), info.line_ending);
+        }
+        if info.separator.is_none() && !self.write_options.normalize_bare_keys {
+            return self.emit_line(name.to_string(), info.line_ending);
+        }
+        self.emit_line(entry_line(name, value, &self.write_options), info.line_ending)
+    }
+
+    fn end_document(&mut self) -> Result<(), String> {
+        if self.group_sections {
+            // Start the next document with only the global group
+            let groups = std::mem::replace(&mut self.groups, vec![(String::new(), Vec::new())]);
+            self.group_index = HashMap::from([(String::new(), 0)]);
+            self.current_group = 0;
+            self.buffered_bytes = 0;
+            for (index, (section, lines)) in groups.iter().enumerate() {
+                if index > 0 {
+                    self.write_header(section)?;
+                }
+                for line in lines {
+                    self.wrote_anything = true;
+                    self.writer.write_all(line.as_bytes()).map_err(|e| e.to_string())?;
+                }
+            }
+        }
+        self.writer.flush().map_err(|e| e.to_string())
+    }
+}
+
+/// Handler adapter that passes on only the entries of selected sections
+///
+/// Section names are matched case-insensitively unless
+/// `case_sensitive(true)` is set; the global section is selected by
+/// including `""`. Everything belonging to other sections is dropped before
+/// it reaches the inner handler. With `record_skipped(true)` the names of
+/// dropped sections are kept, in order of first appearance.
+pub struct SectionFilter<'a> {
+    inner: &'a mut dyn IniHandler,
+    sections: Vec<String>,
+    case_sensitive: bool,
+    record_skipped: bool,
+    skipped: Vec<String>,
+}
+
+impl<'a> SectionFilter<'a> {
+    /// Pass the entries of `sections` to `inner`
+    pub fn new(inner: &'a mut dyn IniHandler, sections: &[&str]) -> Self {
+        Self {
+            inner,
+            sections: sections.iter().map(|section| section.to_string()).collect(),
+            case_sensitive: false,
+            record_skipped: false,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Match section names exactly instead of case-insensitively
+    pub fn case_sensitive(mut self, enabled: bool) -> Self {
+        self.case_sensitive = enabled;
+        self
+    }
+
+    /// Remember the names of sections that were skipped
+    pub fn record_skipped(mut self, enabled: bool) -> Self {
+        self.record_skipped = enabled;
+        self
+    }
+
+    /// Sections that were skipped, if recording is enabled, in document order
+    pub fn skipped_sections(&self) -> &[String] {
+        &self.skipped
+    }
+
+    /// Whether entries of `section` are passed on
+    fn selected(&self, section: &str) -> bool {
+        self.sections.iter().any(|selected| self.same(selected, section))
+    }
+
+    /// Whether two section names are the same under the filter's folding, compared without allocating
+    fn same(&self, a: &str, b: &str) -> bool {
+        if self.case_sensitive {
+            a == b
+        } else {
+            a.chars().flat_map(char::to_lowercase).eq(b.chars().flat_map(char::to_lowercase))
+        }
+    }
+}
+
+impl IniHandler for SectionFilter<'_> {
+    fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.handle_at(0, section, name, value)
+    }
+
+    fn handle_at(&mut self, line: usize, section: &str, name: &str, value: &str) -> Result<(), String> {
+        if self.selected(section) {
+            return self.inner.handle_at(line, section, name, value);
+        }
+        Ok(())
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        if !self.selected(section) {
+            return Ok(());
+        }
+        self.inner.handle_entry(info, section, name, value)
+    }
+
+    fn start_section(&mut self, header: &SectionHeader) -> Result<(), String> {
+        let section = header.flattened_name();
+        if self.selected(&section) {
+            return self.inner.start_section(header);
+        }
+        if self.record_skipped && !self.skipped.iter().any(|s| self.same(s, &section)) {
+            self.skipped.push(section);
+        }
+        Ok(())
+    }
+
+    fn end_section(&mut self, section: &str) -> Result<(), String> {
+        if !self.selected(section) {
+            return Ok(());
+        }
+        self.inner.end_section(section)
+    }
+
+    fn end_value(&mut self, section: &str, name: &str) -> Result<(), String> {
+        if !self.selected(section) {
+            return Ok(());
+        }
+        self.inner.end_value(section, name)
+    }
+
+    fn warning(&mut self, warning: &IniWarning) -> Result<(), String> {
+        if !self.selected(&warning.section) {
+            return Ok(());
+        }
+        self.inner.warning(warning)
+    }
+
+    fn start_document(&mut self) {
+        self.inner.start_document();
+    }
+
+    fn expansion(&mut self, budget: ExpansionBudget) -> Option<&mut Expansion> {
+        self.inner.expansion(budget)
+    }
+
+    fn end_document(&mut self) -> Result<(), String> {
+        self.inner.end_document()
+    }
+}
+
+/// Join a continuation line onto the value it continues
+fn continue_value(value: &mut String, continuation: &str) {
+    value.push('\n');
+    value.push_str(continuation);
+}
+
+/// Handler collecting entries as ordered (section, name, value) triples
+///
+/// Every entry becomes one triple in input order, duplicates included.
+/// Continuation lines are joined onto the value they continue with `\n`, so a
+/// multi-line value is a single triple. Section headers are not recorded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VecHandler {
+    entries: Vec<Entry>,
+}
+
+impl VecHandler {
+    /// Create an empty handler
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Entries collected so far
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Consume the handler and return the entries
+    pub fn into_entries(self) -> Vec<Entry> {
+        self.entries
+    }
+}
+
+impl IniHandler for VecHandler {
+    fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String> {
+        if !name.is_empty() {
+            self.entries.push((section.to_string(), name.to_string(), value.to_string()));
+        }
+        Ok(())
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        match self.entries.last_mut() {
+            Some(last) if info.continuation => continue_value(&mut last.2, value),
+            _ => self.entries.push((section.to_string(), name.to_string(), value.to_string())),
+        }
+        Ok(())
+    }
+}
+
+/// Handler collecting entries into a nested `HashMap` of section to name to value
+///
+/// Names are kept as written, so lookups are case-sensitive. A later entry
+/// with the same name replaces an earlier one, and continuation lines are
+/// joined onto their value with `\n`. Every section that appears in the input
+/// is present, even when it has no entries. `Debug` prints sections and names
+/// sorted.
+#[derive(Clone, Default, PartialEq)]
+pub struct MapHandler {
+    map: HashMap<String, HashMap<String, String>>,
+}
+
+impl MapHandler {
+    /// Create an empty handler
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sections collected so far
+    pub fn map(&self) -> &HashMap<String, HashMap<String, String>> {
+        &self.map
+    }
+
+    /// Consume the handler and return the sections
+    pub fn into_map(self) -> HashMap<String, HashMap<String, String>> {
+        self.map
+    }
+}
+
+impl fmt::Debug for MapHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let map: BTreeMap<_, BTreeMap<_, _>> =
+            self.map.iter().map(|(section, values)| (section, values.iter().collect())).collect();
+        f.debug_struct("MapHandler").field("map", &map).finish()
+    }
+}
+
+impl IniHandler for MapHandler {
+    fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String> {
+        let entries = self.map.entry(section.to_string()).or_default();
+        if !name.is_empty() {
+            entries.insert(name.to_string(), value.to_string());
+        }
+        Ok(())
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        let entries = self.map.entry(section.to_string()).or_default();
+        match entries.get_mut(name) {
+            Some(existing) if info.continuation => continue_value(existing, value),
+            _ => {
+                entries.insert(name.to_string(), value.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn start_section(&mut self, header: &SectionHeader) -> Result<(), String> {
+        self.map.entry(header.flattened_name()).or_default();
+        Ok(())
+    }
+}
+
+/// Handler collecting entries into a nested `BTreeMap` of section to name to value
+///
+/// Behaves like [`MapHandler`], but sections and names iterate in sorted
+/// order, which makes output built from it deterministic.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BTreeHandler {
+    map: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl BTreeHandler {
+    /// Create an empty handler
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sections collected so far
+    pub fn map(&self) -> &BTreeMap<String, BTreeMap<String, String>> {
+        &self.map
+    }
+
+    /// Consume the handler and return the sections
+    pub fn into_map(self) -> BTreeMap<String, BTreeMap<String, String>> {
+        self.map
+    }
+}
+
+impl IniHandler for BTreeHandler {
+    fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String> {
+        let entries = self.map.entry(section.to_string()).or_default();
+        if !name.is_empty() {
+            entries.insert(name.to_string(), value.to_string());
+        }
+        Ok(())
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        let entries = self.map.entry(section.to_string()).or_default();
+        match entries.get_mut(name) {
+            Some(existing) if info.continuation => continue_value(existing, value),
+            _ => {
+                entries.insert(name.to_string(), value.to_string());
+            }
+        }
+        Ok(())
+    }
+
+    fn start_section(&mut self, header: &SectionHeader) -> Result<(), String> {
+        self.map.entry(header.flattened_name()).or_default();
+        Ok(())
+    }
+}
+
+/// Handler that streams the parsed entries as a JSON object
+///
+/// The output has one member per section, each an object of name to string
+/// value: `{"section":{"name":"value"}}`. Entries before the first header go
+/// into a section named `""`. An entry is written once its value is complete,
+/// so continuation lines end up inside it joined with `\n`. Each parsed
+/// document is written as an object of its own.
+///
+/// JSON objects should not repeat member names, but INI files can repeat
+/// sections and keys. By default the output follows the input and repeats
+/// them. With `group_sections(true)` the document is held back until its end
+/// and each section is written once, in order of first appearance, with its
+/// entries merged; a repeated key still appears once per occurrence.
+pub struct JsonWriterHandler<W: Write> {
+    writer: W,
+    group_sections: bool,
+    /// Whether an object for the current section has been opened in the output
+    section_open: bool,
+    wrote_section: bool,
+    wrote_entry: bool,
+    section: String,
+    /// The entry whose value may still receive continuation lines
+    pending: Option<(String, String)>,
+    /// Buffered sections in first-appearance order, used when grouping
+    groups: Vec<(String, Vec<(String, String)>)>,
+    group_index: HashMap<String, usize>,
+}
+
+impl<W: Write> JsonWriterHandler<W> {
+    /// Create a handler streaming to `writer`
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            group_sections: false,
+            section_open: false,
+            wrote_section: false,
+            wrote_entry: false,
+            section: String::new(),
+            pending: None,
+            groups: Vec::new(),
+            group_index: HashMap::new(),
+        }
+    }
+
+    /// Buffer the document and write each section once, at the end
+    pub fn group_sections(mut self, enabled: bool) -> Self {
+        self.group_sections = enabled;
+        self
+    }
+
+    /// Consume the handler and return the writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write(&mut self, text: &str) -> Result<(), String> {
+        self.writer.write_all(text.as_bytes()).map_err(|e| e.to_string())
+    }
+
+    /// Start the object of a section, closing the previous one
+    fn open_section(&mut self, section: &str) -> Result<(), String> {
+        let mut text = String::new();
+        if self.section_open {
+            text.push('}');
+        }
+        text.push(if self.wrote_section { ',' } else { '{' });
+        push_json_string(&mut text, section);
+        text.push_str(":{");
+        self.write(&text)?;
+        self.section_open = true;
+        self.wrote_section = true;
+        self.wrote_entry = false;
+        Ok(())
+    }
+
+    fn write_entry(&mut self, name: &str, value: &str) -> Result<(), String> {
+        if !self.section_open {
+            let section = std::mem::take(&mut self.section);
+            let result = self.open_section(&section);
+            self.section = section;
+            result?;
+        }
+        let mut text = String::new();
+        if self.wrote_entry {
+            text.push(',');
+        }
+        push_json_string(&mut text, name);
+        text.push(':');
+        push_json_string(&mut text, value);
+        self.wrote_entry = true;
+        self.write(&text)
+    }
+
+    /// Write or buffer the pending entry, if there is one
+    fn finish_entry(&mut self) -> Result<(), String> {
+        let Some((name, value)) = self.pending.take() else {
+            return Ok(());
+        };
+        if !self.group_sections {
+            return self.write_entry(&name, &value);
+        }
+        let section = self.section.clone();
+        let index = self.group_of(&section);
+        self.groups[index].1.push((name, value));
+        Ok(())
+    }
+
+    /// Index of the buffered group for `section`, created on first use
+    fn group_of(&mut self, section: &str) -> usize {
+        if let Some(&index) = self.group_index.get(section) {
+            return index;
+        }
+        self.groups.push((section.to_string(), Vec::new()));
+        self.group_index.insert(section.to_string(), self.groups.len() - 1);
+        self.groups.len() - 1
+    }
+}
+
+impl<W: Write> IniHandler for JsonWriterHandler<W> {
+    fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String> {
+        let info = EntryInfo { line: 0, separator: Some('='), continuation: false, line_ending: "", raw_line: "" };
+        self.handle_entry(&info, section, name, value)
+    }
+
+    fn handle_at(&mut self, _line: usize, section: &str, name: &str, value: &str) -> Result<(), String> {
+        // Sections are opened from start_section
+        if name.is_empty() {
+            return Ok(());
+        }
+        self.handle(section, name, value)
+    }
+
+    fn start_section(&mut self, header: &SectionHeader) -> Result<(), String> {
+        let section = header.flattened_name();
+        self.finish_entry()?;
+        if self.group_sections {
+            self.group_of(&section);
+        } else {
+            self.open_section(&section)?;
+        }
+        self.section = section;
+        Ok(())
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        if let Some((_, pending)) = self.pending.as_mut().filter(|_| info.continuation) {
+            continue_value(pending, value);
+            return Ok(());
+        }
+        self.finish_entry()?;
+        self.section = section.to_string();
+        self.pending = Some((name.to_string(), value.to_string()));
+        Ok(())
+    }
+
+    fn end_value(&mut self, _section: &str, _name: &str) -> Result<(), String> {
+        self.finish_entry()
+    }
+
+    fn end_document(&mut self) -> Result<(), String> {
+        self.finish_entry()?;
+        for (section, entries) in std::mem::take(&mut self.groups) {
+            self.open_section(&section)?;
+            for (name, value) in entries {
+                self.write_entry(&name, &value)?;
+            }
+        }
+        self.group_index.clear();
+        self.write(if self.section_open { "}}" } else { "{}" })?;
+        // A following document starts a new object
+        self.section_open = false;
+        self.wrote_section = false;
+        self.wrote_entry = false;
+        self.section.clear();
+        self.writer.flush().map_err(|e| e.to_string())
+    }
+}
+
+/// Append `text` as a quoted JSON string
+pub(crate) fn push_json_string(out: &mut String, text: &str) {
+    out.push('"');
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+}
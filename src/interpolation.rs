@@ -0,0 +1,214 @@
+//! ConfigParser-style references from one value to another
+//!
+//! With [`ReaderOptions::interpolation`](crate::ReaderOptions::interpolation)
+//! set, a reader replaces references in its values once each document has
+//! been read:
+//!
+//! ```rust
+//! use inih::interpolation::Interpolation;
+//! use inih::{IniReader, ParseOptions, ReaderOptions};
+//!
+//! let data = "[paths]\nroot = /srv\nlogs = ${root}/logs\n[app]\nlog = ${paths:logs}/app.log\nprice = $$5\n";
+//! let options = ReaderOptions { interpolation: Interpolation::Extended, ..ReaderOptions::default() };
+//! let reader = IniReader::from_string_with_reader_options(data, &ParseOptions::default(), options).unwrap();
+//! assert_eq!(reader.get("app", "log", ""), "/srv/logs/app.log");
+//! assert_eq!(reader.get("app", "price", ""), "$5");
+//! ```
+//!
+//! References are resolved recursively and each value only once, drawing
+//! from the [`ExpansionBudget`](crate::expansion::ExpansionBudget) of the
+//! parser options, in one account with the includes of the same document. A value that refers
+//! back to itself is left as written and reported as a
+//! [`Cycle`](InterpolationError::Cycle), as is every value that depends on
+//! it, without further errors. A reference to a missing key stays in the
+//! value as written; with
+//! [`ReaderOptions::strict_interpolation`](crate::ReaderOptions::strict_interpolation)
+//! it is also reported. Errors are listed by
+//! [`IniReader::interpolation_errors`](crate::IniReader::interpolation_errors).
+//!
+//! Only the values the getters return are interpolated; occurrences and a
+//! kept document hold the values as written. Values of earlier documents
+//! parsed into the same reader were interpolated when their own document
+//! ended and are taken as they are.
+
+use std::collections::HashMap;
+
+use crate::error::InterpolationError;
+use crate::expansion::Expansion;
+
+/// Which references a reader resolves in its values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Values are kept as written
+    #[default]
+    None,
+    /// `%(name)s` for a key of the same section; `%%` is a literal `%`
+    Basic,
+    /// `${name}` for a key of the same section, `${section:name}` for one of
+    /// any section (`${:name}` for the global one); `$$` is a literal `$`
+    Extended,
+}
+
+/// A value that may be interpolated or referred to
+pub(crate) struct Entry {
+    pub(crate) section: String,
+    pub(crate) name: String,
+    pub(crate) value: String,
+    /// Set by the document that just ended, so still to be interpolated
+    pub(crate) pending: bool,
+}
+
+/// Resolves the pending entries of one document
+pub(crate) struct Resolver<'a> {
+    style: Interpolation,
+    strict: bool,
+    /// Entries by lookup key
+    entries: HashMap<String, Entry>,
+    /// Lookup key of a section and name
+    key_of: &'a dyn Fn(&str, &str) -> String,
+    /// Account of the document, shared with its includes
+    expansion: &'a mut Expansion,
+    /// Interpolated values by lookup key; `None` for values left as written
+    done: HashMap<String, Option<String>>,
+    /// Keys being resolved, outermost first
+    stack: Vec<String>,
+    errors: Vec<InterpolationError>,
+}
+
+impl<'a> Resolver<'a> {
+    pub(crate) fn new(
+        style: Interpolation,
+        strict: bool,
+        expansion: &'a mut Expansion,
+        entries: HashMap<String, Entry>,
+        key_of: &'a dyn Fn(&str, &str) -> String,
+    ) -> Self {
+        Self {
+            style,
+            strict,
+            entries,
+            key_of,
+            expansion,
+            done: HashMap::new(),
+            stack: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Interpolate the pending entries among `keys`, in that order
+    ///
+    /// Returns the changed values by key and the errors found.
+    pub(crate) fn run(mut self, keys: &[String]) -> (Vec<(String, String)>, Vec<InterpolationError>) {
+        let mut changed = Vec::new();
+        for key in keys {
+            if !self.entries.get(key).is_some_and(|entry| entry.pending) {
+                continue;
+            }
+            if let Some(value) = self.resolve(key) {
+                if value != self.entries[key].value {
+                    changed.push((key.clone(), value));
+                }
+            }
+        }
+        (changed, self.errors)
+    }
+
+    /// The interpolated value of an entry, `None` if it has to stay as written
+    fn resolve(&mut self, key: &str) -> Option<String> {
+        let entry = &self.entries[key];
+        if !entry.pending {
+            return Some(entry.value.clone());
+        }
+        if let Some(done) = self.done.get(key) {
+            return done.clone();
+        }
+        if let Some(start) = self.stack.iter().position(|open| open == key) {
+            let mut chain: Vec<String> = self.stack[start..].iter().map(|open| self.label(open)).collect();
+            chain.push(self.label(key));
+            self.errors.push(InterpolationError::Cycle { section: entry.section.clone(), name: entry.name.clone(), chain });
+            return None;
+        }
+
+        let (section, value) = (entry.section.clone(), entry.value.clone());
+        let entered = self.expansion.enter(&self.label(key));
+        let result = match entered {
+            Ok(()) => {
+                self.stack.push(key.to_string());
+                let result = self.expand(&section, &value);
+                self.stack.pop();
+                result
+            }
+            Err(error) => {
+                self.fail(key, error);
+                None
+            }
+        };
+        self.expansion.leave();
+        self.done.insert(key.to_string(), result.clone());
+        result
+    }
+
+    /// Replace the references in `value`, a value of `section`
+    fn expand(&mut self, section: &str, value: &str) -> Option<String> {
+        let (marker, open, close) = match self.style {
+            Interpolation::None => return Some(value.to_string()),
+            Interpolation::Basic => ('%', "(", ")s"),
+            Interpolation::Extended => ('$', "{", "}"),
+        };
+        let mut expanded = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find(marker) {
+            expanded.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            if let Some(escaped) = after.strip_prefix(marker) {
+                expanded.push(marker);
+                rest = escaped;
+                continue;
+            }
+            let Some((reference, tail)) = after.strip_prefix(open).and_then(|body| body.split_once(close)) else {
+                expanded.push(marker);
+                rest = after;
+                continue;
+            };
+            let (target_section, name) = match self.style {
+                Interpolation::Extended => reference.rsplit_once(':').unwrap_or((section, reference)),
+                _ => (section, reference),
+            };
+            let key = (self.key_of)(target_section, name);
+            if self.entries.contains_key(&key) {
+                expanded.push_str(&self.resolve(&key)?);
+            } else {
+                let written = &rest[start..rest.len() - tail.len()];
+                if self.strict {
+                    let referrer = self.stack.last().cloned().unwrap_or_default();
+                    let entry = &self.entries[&referrer];
+                    self.errors.push(InterpolationError::UnknownReference {
+                        section: entry.section.clone(),
+                        name: entry.name.clone(),
+                        reference: written.to_string(),
+                    });
+                }
+                expanded.push_str(written);
+            }
+            rest = tail;
+        }
+        expanded.push_str(rest);
+        if let Err(error) = self.expansion.check_len(expanded.len()) {
+            let key = self.stack.last().cloned().unwrap_or_default();
+            self.fail(&key, error);
+            return None;
+        }
+        Some(expanded)
+    }
+
+    fn fail(&mut self, key: &str, error: crate::error::IniParseError) {
+        let entry = &self.entries[key];
+        self.errors.push(InterpolationError::LimitExceeded { section: entry.section.clone(), name: entry.name.clone(), error });
+    }
+
+    /// `section:name` of an entry, as chains show it
+    fn label(&self, key: &str) -> String {
+        let entry = &self.entries[key];
+        format!("{}:{}", entry.section, entry.name)
+    }
+}
@@ -0,0 +1,140 @@
+//! Read-only overlay of several readers
+
+use std::collections::HashSet;
+
+use crate::explain::{Explanation, Source, Step};
+use crate::reader::IniReader;
+
+/// A read-only view over several readers, later layers overriding earlier ones
+///
+/// Nothing is copied: lookups consult the layers from last to first and the
+/// first layer holding the value answers. Typed getters use the parsing rules
+/// of [`IniReader`] on the value of the answering layer.
+#[derive(Debug, Clone)]
+pub struct LayeredReader<'a> {
+    layers: Vec<&'a IniReader>,
+}
+
+impl<'a> LayeredReader<'a> {
+    /// Create a view over `layers`, ordered from lowest to highest priority
+    pub fn new(layers: Vec<&'a IniReader>) -> Self {
+        Self { layers }
+    }
+
+    /// Index of the layer that supplies a value, if any does
+    pub fn layer_of(&self, section: &str, name: &str) -> Option<usize> {
+        self.resolve(section, name, None)
+    }
+
+    /// Index of the layer that supplies a value, recording the lookups in `trace` if given
+    fn resolve(&self, section: &str, name: &str, mut trace: Option<&mut Vec<Step>>) -> Option<usize> {
+        (0..self.layers.len()).rev().find(|&index| {
+            let trace = trace.as_deref_mut().map(|steps| (Source::Layer(index), steps));
+            self.layers[index].lookup(section, name, trace).is_some()
+        })
+    }
+
+    /// How a key resolves: the layers consulted from highest priority down,
+    /// the value and where in the answering layer it came from
+    pub fn explain(&self, section: &str, name: &str) -> Explanation {
+        let mut explanation = Explanation::new(section, name);
+        if let Some(index) = self.resolve(section, name, Some(&mut explanation.steps)) {
+            let layer = self.layers[index];
+            explanation.value = layer.lookup(section, name, None).map(str::to_string);
+            explanation.raw_value = layer.raw_value(section, name).map(str::to_string);
+            explanation.occurrences = layer.occurrences(section, name);
+        }
+        explanation
+    }
+
+    /// The reader that supplies a value, if any does
+    fn layer_for(&self, section: &str, name: &str) -> Option<&'a IniReader> {
+        self.layer_of(section, name).map(|index| self.layers[index])
+    }
+
+    /// Get a string value, returning the default if not found
+    pub fn get(&self, section: &str, name: &str, default_value: &str) -> String {
+        match self.layer_for(section, name) {
+            Some(layer) => layer.get(section, name, default_value),
+            None => default_value.to_string(),
+        }
+    }
+
+    /// Get a string value, returning the default if not found or empty
+    pub fn get_nonempty_or(&self, section: &str, name: &str, default_value: &str) -> String {
+        match self.layer_for(section, name) {
+            Some(layer) => layer.get_nonempty_or(section, name, default_value),
+            None => default_value.to_string(),
+        }
+    }
+
+    /// Get an integer value, returning the default if not found, empty or invalid
+    pub fn get_integer(&self, section: &str, name: &str, default_value: i64) -> i64 {
+        self.layer_for(section, name)
+            .map_or(default_value, |layer| layer.get_integer(section, name, default_value))
+    }
+
+    /// Get a 64-bit integer value, returning the default if not found, empty or invalid
+    pub fn get_integer64(&self, section: &str, name: &str, default_value: i64) -> i64 {
+        self.get_integer(section, name, default_value)
+    }
+
+    /// Get an unsigned integer value, returning the default if not found, empty or invalid
+    pub fn get_unsigned(&self, section: &str, name: &str, default_value: u64) -> u64 {
+        self.layer_for(section, name)
+            .map_or(default_value, |layer| layer.get_unsigned(section, name, default_value))
+    }
+
+    /// Get a 64-bit unsigned integer value, returning the default if not found, empty or invalid
+    pub fn get_unsigned64(&self, section: &str, name: &str, default_value: u64) -> u64 {
+        self.get_unsigned(section, name, default_value)
+    }
+
+    /// Get a floating-point value, returning the default if not found, empty or invalid
+    pub fn get_real(&self, section: &str, name: &str, default_value: f64) -> f64 {
+        self.layer_for(section, name)
+            .map_or(default_value, |layer| layer.get_real(section, name, default_value))
+    }
+
+    /// Get a boolean value, returning the default if not found, empty or invalid
+    pub fn get_boolean(&self, section: &str, name: &str, default_value: bool) -> bool {
+        self.layer_for(section, name)
+            .map_or(default_value, |layer| layer.get_boolean(section, name, default_value))
+    }
+
+    /// Get all section names across all layers, sorted
+    pub fn sections(&self) -> Vec<String> {
+        let mut sections: Vec<String> = self
+            .layers
+            .iter()
+            .flat_map(|layer| layer.sections())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        sections.sort();
+        sections
+    }
+
+    /// Get all keys in a section across all layers, sorted
+    pub fn keys(&self, section: &str) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .layers
+            .iter()
+            .flat_map(|layer| layer.keys(section))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// Check if a section exists in any layer
+    pub fn has_section(&self, section: &str) -> bool {
+        self.layers.iter().any(|layer| layer.has_section(section))
+    }
+
+    /// Check if a value exists in any layer
+    pub fn has_value(&self, section: &str, name: &str) -> bool {
+        self.layer_of(section, name).is_some()
+    }
+}
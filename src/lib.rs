@@ -15,6 +15,17 @@
 //! - Both callback-based and reader-based APIs
 //! - Memory efficient (no unnecessary allocations)
 //!
+//! ## Crate layout
+//!
+//! The parser entry points, `IniHandler`, `ParseOptions`, `IniReader` and
+//! `IniParseError` are available at the crate root. Everything else lives in
+//! its module (`handlers`, `document`, `schema`, `encoding`, ...), and
+//! [`prelude`] collects the items most programs need:
+//!
+//! ```rust
+//! use inih::prelude::*;
+//! ```
+//!
 //! ## Quick Start
 //!
 //! ```rust,no_run
@@ -24,8 +35,8 @@
 //!     let reader = IniReader::from_file("config.ini")?;
 //!     
 //!     let version = reader.get_integer("protocol", "version", -1);
-//!     let name = reader.get_string("user", "name", "UNKNOWN");
-//!     let email = reader.get_string("user", "email", "UNKNOWN");
+//!     let name = reader.get_nonempty_or("user", "name", "UNKNOWN");
+//!     let email = reader.get_nonempty_or("user", "email", "UNKNOWN");
 //!     
 //!     println!("Config: version={}, name={}, email={}", version, name, email);
 //!     Ok(())
@@ -66,12 +77,54 @@
 //! ```
 
 pub mod parser;
+pub mod report;
 pub mod reader;
+pub mod section;
+pub mod sort;
+pub mod path;
+pub mod load;
+pub mod extract;
+pub mod interpolation;
+pub mod frozen;
 pub mod error;
+pub mod prelude;
+pub mod document;
+pub mod multi;
+pub mod estimate;
+pub mod env;
+pub mod args;
+pub mod layered;
+pub mod fallback;
+pub mod migrate;
+pub mod explain;
+pub mod channel;
+pub mod expansion;
+pub mod capabilities;
+pub mod tokens;
+pub mod trace;
+pub mod str_reader;
+pub mod summary;
+pub mod encoding;
+pub mod handlers;
+pub mod schema;
+pub mod writer;
+pub mod properties;
+#[cfg(feature = "serde")]
+pub mod de;
+#[cfg(feature = "serde")]
+pub mod ser;
+pub mod value;
+mod tables;
 
-pub use parser::{ini_parse, ini_parse_string, ini_parse_string_with_options, ini_parse_file, IniHandler, ParseOptions};
-pub use reader::IniReader;
+pub use parser::{ini_parse, ini_parse_bytes, ini_parse_string, ini_parse_string_collecting, ini_parse_string_with_includes, ini_parse_string_with_options, ini_parse_file, CommentPrefixes, DuplicateKeyPolicy, EntryInfo, IniHandler, LineLengthPolicy, NewlineStyle, ParseOptions, ParseOptionsDelta, SectionAnnotation, SectionHeader, SectionPattern};
+pub use reader::{ConfigSource, IniReader, InsertDecision, KeyName, Occurrence, ReaderOptions, SectionName};
 pub use error::IniParseError;
+pub use load::{load, load_all};
+#[cfg(feature = "serde")]
+pub use de::from_str;
+#[cfg(feature = "serde")]
+pub use ser::to_string;
+pub use capabilities::{capabilities, Capabilities};
 
 /// Re-export commonly used types
 pub type Result<T> = std::result::Result<T, IniParseError>;
@@ -0,0 +1,134 @@
+//! Loading an application's configuration from the conventional places
+//!
+//! [`load`] looks for `<app>.ini` where small tools usually keep it and
+//! reads the first one found; [`load_all`] reads every one found and merges
+//! them. The places are tried from the narrowest scope to the broadest:
+//!
+//! | | Unix | Windows |
+//! |---|---|---|
+//! | 1 | `./<app>.ini` in the current directory | the same |
+//! | 2 | `<app>.ini` next to the executable | the same |
+//! | 3 | `$XDG_CONFIG_HOME/<app>/<app>.ini` (`~/.config` if unset) | `%APPDATA%\<app>\<app>.ini` |
+//! | 4 | `/etc/<app>/<app>.ini` | |
+//!
+//! A directory that cannot be found, such as the home directory, is left
+//! out, and a place listed twice is tried once. The `_with` variants take
+//! the places from a [`SearchOrder`] of their own:
+//!
+//! ```rust,no_run
+//! let (reader, path) = inih::load("mytool")?;
+//! println!("{} from {}", reader.get("server", "host", "localhost"), path.display());
+//! # Ok::<(), inih::IniParseError>(())
+//! ```
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::IniParseError;
+use crate::parser::{ini_parse_bytes, ParseOptions};
+use crate::path::{base_dir, SystemBaseDirs};
+use crate::reader::IniReader;
+
+/// The places an application's configuration file is looked for
+///
+/// [`SystemSearchOrder`] gives the conventional ones; tests can supply
+/// their own.
+pub trait SearchOrder {
+    /// Paths of the configuration file of `app_name`, narrowest scope first
+    fn paths(&self, app_name: &str) -> Vec<PathBuf>;
+}
+
+/// The conventional places of the running process, see the [module documentation](self)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemSearchOrder;
+
+impl SearchOrder for SystemSearchOrder {
+    fn paths(&self, app_name: &str) -> Vec<PathBuf> {
+        let file = format!("{}.ini", app_name);
+        let mut dirs = Vec::new();
+        dirs.extend(std::env::current_dir().ok());
+        dirs.extend(std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)));
+        let base = if cfg!(windows) { "APPDATA" } else { "XDG_CONFIG_HOME" };
+        dirs.extend(base_dir(base, &SystemBaseDirs).map(|dir| PathBuf::from(dir).join(app_name)));
+        if cfg!(unix) {
+            dirs.push(Path::new("/etc").join(app_name));
+        }
+
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for path in dirs.into_iter().map(|dir| dir.join(&file)) {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+}
+
+/// Load the first configuration file of `app_name` found in the conventional places
+///
+/// Returns the reader and the path it was read from. Fails with `NotFound`
+/// listing every path tried if there is no file; a file that exists but
+/// cannot be read or parsed fails as for
+/// [`IniReader::from_first_existing`], without trying the others.
+pub fn load(app_name: &str) -> Result<(IniReader, PathBuf), IniParseError> {
+    load_with(app_name, &SystemSearchOrder, &ParseOptions::default())
+}
+
+/// Load the first configuration file of `app_name` found in the places of `order`
+pub fn load_with(app_name: &str, order: &dyn SearchOrder, options: &ParseOptions) -> Result<(IniReader, PathBuf), IniParseError> {
+    let paths = order.paths(app_name);
+    for path in &paths {
+        if let Some(bytes) = read_existing(path)? {
+            let reader = IniReader::from_bytes(&bytes, options).map_err(|error| in_file(path, error))?;
+            return Ok((reader, path.clone()));
+        }
+    }
+    Err(IniParseError::NotFound { tried: paths })
+}
+
+/// Load and merge every configuration file of `app_name` found in the conventional places
+///
+/// Files are read from the broadest scope to the narrowest, each as a
+/// document of its own, so a key set in a narrower file replaces the value
+/// of a broader one. Returns the reader and the paths read, in that order.
+/// Fails with `NotFound` if there is no file at all, and on the first file
+/// that cannot be read or parsed.
+pub fn load_all(app_name: &str) -> Result<(IniReader, Vec<PathBuf>), IniParseError> {
+    load_all_with(app_name, &SystemSearchOrder, &ParseOptions::default())
+}
+
+/// Load and merge every configuration file of `app_name` found in the places of `order`
+pub fn load_all_with(
+    app_name: &str,
+    order: &dyn SearchOrder,
+    options: &ParseOptions,
+) -> Result<(IniReader, Vec<PathBuf>), IniParseError> {
+    let paths = order.paths(app_name);
+    let mut merged: Option<IniReader> = None;
+    let mut used = Vec::new();
+    for path in paths.iter().rev() {
+        let Some(bytes) = read_existing(path)? else { continue };
+        match &mut merged {
+            Some(reader) => ini_parse_bytes(&bytes, reader, options).map_err(|error| in_file(path, error))?,
+            None => merged = Some(IniReader::from_bytes(&bytes, options).map_err(|error| in_file(path, error))?),
+        }
+        used.push(path.clone());
+    }
+    match merged {
+        Some(reader) => Ok((reader, used)),
+        None => Err(IniParseError::NotFound { tried: paths }),
+    }
+}
+
+/// Contents of the file at `path`, None if there is none
+fn read_existing(path: &Path) -> Result<Option<Vec<u8>>, IniParseError> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(IniParseError::file_open(path, e)),
+    }
+}
+
+fn in_file(path: &Path, error: IniParseError) -> IniParseError {
+    IniParseError::InFile { path: path.to_path_buf(), error: Box::new(error) }
+}
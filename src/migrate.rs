@@ -0,0 +1,217 @@
+//! Upgrading a reader's configuration with a list of migrations
+//!
+//! A product release ships its configuration changes as a list of
+//! [`Migration`]s, applied in order:
+//!
+//! ```rust
+//! use inih::migrate::{apply_migrations, Migration};
+//! use inih::IniReader;
+//!
+//! let mut reader = IniReader::from_string("[net]\nport = 80\ndebug = true\n[legacy]\nx = 1\n").unwrap();
+//! let report = apply_migrations(&mut reader, &[
+//!     Migration::move_key("net", "port", "server", "port"),
+//!     Migration::rename_key("net", "debug", "log_level"),
+//!     Migration::map_value("net", "log_level", |value| {
+//!         if value == "true" { "debug".to_string() } else { "info".to_string() }
+//!     }),
+//!     Migration::DeleteSection { section: "legacy".to_string() },
+//! ]);
+//!
+//! assert_eq!(report.applied(), vec![0, 1, 2, 3]);
+//! assert_eq!(reader.get("server", "port", ""), "80");
+//! assert_eq!(reader.get("net", "log_level", ""), "debug");
+//! assert!(!reader.has_section("legacy"));
+//! ```
+//!
+//! Names match case-insensitively, as in lookups. Migrations change only the
+//! reader's values: a preserved document no longer matches and is dropped.
+
+use std::fmt;
+
+use crate::reader::IniReader;
+
+/// One change to a configuration
+pub enum Migration {
+    /// Move a key to another section and name
+    MoveKey { section: String, name: String, to_section: String, to_name: String },
+    /// Rename a key within its section
+    RenameKey { section: String, name: String, to_name: String },
+    /// Rename a section, keeping its keys
+    RenameSection { section: String, to_section: String },
+    /// Remove a key
+    DeleteKey { section: String, name: String },
+    /// Remove a section and all its keys
+    DeleteSection { section: String },
+    /// Replace the value of a key with the result of a function of it
+    MapValue { section: String, name: String, map: Box<dyn Fn(&str) -> String> },
+}
+
+impl Migration {
+    /// [`Migration::MoveKey`] from borrowed names
+    pub fn move_key(section: &str, name: &str, to_section: &str, to_name: &str) -> Self {
+        Migration::MoveKey {
+            section: section.to_string(),
+            name: name.to_string(),
+            to_section: to_section.to_string(),
+            to_name: to_name.to_string(),
+        }
+    }
+
+    /// [`Migration::RenameKey`] from borrowed names
+    pub fn rename_key(section: &str, name: &str, to_name: &str) -> Self {
+        Migration::RenameKey { section: section.to_string(), name: name.to_string(), to_name: to_name.to_string() }
+    }
+
+    /// [`Migration::MapValue`] from borrowed names and a closure
+    pub fn map_value(section: &str, name: &str, map: impl Fn(&str) -> String + 'static) -> Self {
+        Migration::MapValue { section: section.to_string(), name: name.to_string(), map: Box::new(map) }
+    }
+}
+
+impl fmt::Display for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Migration::MoveKey { section, name, to_section, to_name } => {
+                write!(f, "move [{}] {} to [{}] {}", section, name, to_section, to_name)
+            }
+            Migration::RenameKey { section, name, to_name } => write!(f, "rename [{}] {} to {}", section, name, to_name),
+            Migration::RenameSection { section, to_section } => write!(f, "rename [{}] to [{}]", section, to_section),
+            Migration::DeleteKey { section, name } => write!(f, "delete [{}] {}", section, name),
+            Migration::DeleteSection { section } => write!(f, "delete [{}]", section),
+            Migration::MapValue { section, name, .. } => write!(f, "map [{}] {}", section, name),
+        }
+    }
+}
+
+impl fmt::Debug for Migration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Migration({})", self)
+    }
+}
+
+/// What to do when a move or rename would land on a key or section that already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Leave both the source and the existing target untouched
+    #[default]
+    KeepExisting,
+    /// Let the source replace the target; a renamed section is merged into
+    /// the existing one, its keys replacing same-named keys there
+    Overwrite,
+}
+
+/// How one migration went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationStatus {
+    /// The change was made
+    Applied,
+    /// The source key or section does not exist, so there was nothing to do
+    Skipped,
+    /// The target already existed; `overwritten` tells whether the source replaced it
+    Conflicted { overwritten: bool },
+}
+
+/// Outcome of [`apply_migrations`], one status per migration in order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub statuses: Vec<MigrationStatus>,
+}
+
+impl MigrationReport {
+    /// Indices of the migrations that were applied without conflict
+    pub fn applied(&self) -> Vec<usize> {
+        self.indices(|status| status == MigrationStatus::Applied)
+    }
+
+    /// Indices of the migrations whose source was absent
+    pub fn skipped(&self) -> Vec<usize> {
+        self.indices(|status| status == MigrationStatus::Skipped)
+    }
+
+    /// Indices of the migrations whose target already existed
+    pub fn conflicted(&self) -> Vec<usize> {
+        self.indices(|status| matches!(status, MigrationStatus::Conflicted { .. }))
+    }
+
+    fn indices(&self, wanted: impl Fn(MigrationStatus) -> bool) -> Vec<usize> {
+        (0..self.statuses.len()).filter(|&index| wanted(self.statuses[index])).collect()
+    }
+}
+
+/// Apply `migrations` in order, keeping existing targets on conflict
+pub fn apply_migrations(reader: &mut IniReader, migrations: &[Migration]) -> MigrationReport {
+    apply_migrations_with(reader, migrations, ConflictPolicy::default())
+}
+
+/// Apply `migrations` in order, resolving conflicts with `policy`
+///
+/// Each migration sees the result of the ones before it. A migration whose
+/// source is absent is skipped; deletions and value mappings never conflict.
+pub fn apply_migrations_with(reader: &mut IniReader, migrations: &[Migration], policy: ConflictPolicy) -> MigrationReport {
+    let statuses = migrations.iter().map(|migration| apply(reader, migration, policy)).collect();
+    MigrationReport { statuses }
+}
+
+fn apply(reader: &mut IniReader, migration: &Migration, policy: ConflictPolicy) -> MigrationStatus {
+    match migration {
+        Migration::MoveKey { section, name, to_section, to_name } => {
+            move_key(reader, section, name, to_section, to_name, policy)
+        }
+        Migration::RenameKey { section, name, to_name } => move_key(reader, section, name, section, to_name, policy),
+        Migration::RenameSection { section, to_section } => {
+            if !reader.section_present(section) {
+                return MigrationStatus::Skipped;
+            }
+            let same = reader.fold(section) == reader.fold(to_section);
+            if same || !reader.section_present(to_section) {
+                reader.rename_section(section, to_section);
+                return MigrationStatus::Applied;
+            }
+            resolve(policy, || reader.rename_section(section, to_section))
+        }
+        Migration::DeleteKey { section, name } => applied_if(reader.remove(section, name).is_some()),
+        Migration::DeleteSection { section } => applied_if(reader.remove_section(section)),
+        Migration::MapValue { section, name, map } => {
+            if !reader.has_value(section, name) {
+                return MigrationStatus::Skipped;
+            }
+            let value = map(&reader.get(section, name, ""));
+            applied_if(reader.replace_value(section, name, value))
+        }
+    }
+}
+
+fn move_key(
+    reader: &mut IniReader,
+    section: &str,
+    name: &str,
+    to_section: &str,
+    to_name: &str,
+    policy: ConflictPolicy,
+) -> MigrationStatus {
+    if !reader.has_value(section, name) {
+        return MigrationStatus::Skipped;
+    }
+    let same = reader.fold(section) == reader.fold(to_section) && reader.fold(name.trim()) == reader.fold(to_name.trim());
+    if same || !reader.has_value(to_section, to_name) {
+        reader.move_key(section, name, to_section, to_name);
+        return MigrationStatus::Applied;
+    }
+    resolve(policy, || reader.move_key(section, name, to_section, to_name))
+}
+
+/// Status of a conflicting migration, running `overwrite` if the policy says so
+fn resolve(policy: ConflictPolicy, overwrite: impl FnOnce() -> bool) -> MigrationStatus {
+    match policy {
+        ConflictPolicy::KeepExisting => MigrationStatus::Conflicted { overwritten: false },
+        ConflictPolicy::Overwrite => MigrationStatus::Conflicted { overwritten: overwrite() },
+    }
+}
+
+fn applied_if(done: bool) -> MigrationStatus {
+    if done {
+        MigrationStatus::Applied
+    } else {
+        MigrationStatus::Skipped
+    }
+}
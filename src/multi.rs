@@ -0,0 +1,95 @@
+//! Parsing of streams containing several INI documents
+
+use std::io::BufRead;
+
+use crate::error::IniParseError;
+use crate::parser::{read_bounded_line, ParseOptions, LINE_SLACK};
+use crate::reader::IniReader;
+
+/// Parse a stream of INI documents separated by a marker line
+///
+/// Every line whose trimmed content equals `document_separator` ends the current
+/// document. Each document is parsed on its own (sections and multi-line state
+/// never carry over) and handed to `on_document` as an [`IniReader`]. Documents
+/// containing nothing but blank lines (including the one after a trailing
+/// separator) are delivered as empty readers unless
+/// `options.skip_empty_documents` is set.
+///
+/// Errors are reported as [`IniParseError::Document`] carrying the zero-based
+/// document index, with line numbers counted from the start of that document.
+/// A document that fails to parse is not delivered; parsing continues with the
+/// next document unless `options.stop_on_first_error` is set, and the first
+/// error is returned at the end.
+///
+/// Lines are read with the same bound as [`ini_parse_reader_with_options`](crate::parser::ini_parse_reader_with_options):
+/// only the first `max_line` bytes (plus one character) of a longer line reach
+/// the document, which is enough for `line_length_policy` to apply as usual.
+/// Each document is decoded on its own, so with [`EncodingMode::Auto`](crate::encoding::EncodingMode::Auto)
+/// one document falling back to Latin-1 leaves the others as UTF-8.
+pub fn ini_parse_multi<R, F>(
+    mut reader: R,
+    options: &ParseOptions,
+    document_separator: &str,
+    mut on_document: F,
+) -> Result<(), IniParseError>
+where
+    R: BufRead,
+    F: FnMut(IniReader) -> Result<(), String>,
+{
+    let mut first_error: Option<IniParseError> = None;
+    let mut document = Vec::new();
+    let mut index = 0;
+    let limit = options.max_line.saturating_add(LINE_SLACK);
+    let mut buf = Vec::new();
+
+    loop {
+        let read = read_bounded_line(&mut reader, &mut buf, limit).map_err(|e| IniParseError::FileOpen(e.to_string()))?;
+        let line = read.map(|read| (read.bytes(&buf), read.ending));
+        let at_end = line.is_none();
+
+        if at_end || line.is_some_and(|(bytes, _)| String::from_utf8_lossy(bytes).trim() == document_separator) {
+            let result = deliver_document(&document, index, options, &mut on_document);
+            if let Err(error) = result {
+                if options.stop_on_first_error {
+                    return Err(error);
+                }
+                if first_error.is_none() {
+                    first_error = Some(error);
+                }
+            }
+            if at_end {
+                break;
+            }
+            document.clear();
+            index += 1;
+        } else if let Some((bytes, ending)) = line {
+            document.extend_from_slice(bytes);
+            document.extend_from_slice(ending.as_bytes());
+        }
+    }
+
+    if let Some(error) = first_error {
+        Err(error)
+    } else {
+        Ok(())
+    }
+}
+
+/// Parse a single document and hand it to the callback
+fn deliver_document<F>(
+    data: &[u8],
+    index: usize,
+    options: &ParseOptions,
+    on_document: &mut F,
+) -> Result<(), IniParseError>
+where
+    F: FnMut(IniReader) -> Result<(), String>,
+{
+    if options.skip_empty_documents && String::from_utf8_lossy(data).trim().is_empty() {
+        return Ok(());
+    }
+
+    let in_document = |error| IniParseError::Document { index, error: Box::new(error) };
+    let reader = IniReader::from_bytes(data, options).map_err(in_document)?;
+    on_document(reader).map_err(|msg| in_document(IniParseError::HandlerError(msg)))
+}
@@ -1,10 +1,14 @@
 //! Low-level INI parser with callback-based API
 
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
-use std::path::Path;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Component, Path, PathBuf};
 
-use crate::error::IniParseError;
+use crate::encoding::EncodingMode;
+use crate::error::{IniParseError, IniWarning, Suggestion};
+use crate::expansion::{Expansion, ExpansionBudget};
 
 /// Trait for handling INI parsing events
 pub trait IniHandler {
@@ -19,6 +23,348 @@ pub trait IniHandler {
     /// * `Ok(())` - Continue parsing
     /// * `Err(String)` - Stop parsing with error message
     fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String>;
+
+    /// Called for each name=value pair together with its physical line number
+    ///
+    /// A continuation line of a multi-line value is reported with its own
+    /// line, and so is a section header, with an empty name and value, when
+    /// `call_handler_on_new_section` is set. The default implementation forwards to [`IniHandler::handle`], so
+    /// existing handlers keep working unchanged.
+    fn handle_at(&mut self, line: usize, section: &str, name: &str, value: &str) -> Result<(), String> {
+        let _ = line;
+        self.handle(section, name, value)
+    }
+
+    /// Called for each name=value pair and continuation line with everything the parser knows about it
+    ///
+    /// An error returned from here becomes [`IniParseError::RejectedEntry`],
+    /// which quotes the line. The default implementation forwards to
+    /// [`IniHandler::handle_at`].
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.handle_at(info.line, section, name, value)
+    }
+
+    /// Called for each section header, before the section is reported to `handle`
+    ///
+    /// The header carries the parsed name and any annotation enabled through
+    /// [`ParseOptions`]. The default implementation does nothing.
+    fn start_section(&mut self, header: &SectionHeader) -> Result<(), String> {
+        let _ = header;
+        Ok(())
+    }
+
+    /// Called when a section opened by a header is complete
+    ///
+    /// Fires when the next header starts, after the last value of the section
+    /// is complete, or at the end of the input. Entries before the first
+    /// header are not in a section that is opened or closed. The default
+    /// implementation does nothing.
+    fn end_section(&mut self, section: &str) -> Result<(), String> {
+        let _ = section;
+        Ok(())
+    }
+
+    /// Called when a value is complete, i.e. no further continuation lines can follow
+    ///
+    /// Fires when the next entry or section header starts, or at the end of the
+    /// input. The default implementation does nothing.
+    fn end_value(&mut self, section: &str, name: &str) -> Result<(), String> {
+        let _ = (section, name);
+        Ok(())
+    }
+
+    /// Called for each non-fatal finding of an opt-in lint
+    ///
+    /// Warnings never change the parse result. The default implementation
+    /// ignores them.
+    fn warning(&mut self, warning: &IniWarning) -> Result<(), String> {
+        let _ = warning;
+        Ok(())
+    }
+
+    /// Called once before the first line of the input is processed
+    ///
+    /// Handlers that keep state for one document should reset it here: a
+    /// parse cut short by `stop_on_first_error` never reaches
+    /// [`end_document`](IniHandler::end_document). The default implementation
+    /// does nothing.
+    fn start_document(&mut self) {}
+
+    /// The expansion account of the document being parsed, if the handler keeps one
+    ///
+    /// Include directives draw from it instead of an account of their own,
+    /// so a handler that also expands values, as [`IniReader`](crate::IniReader)
+    /// does with interpolation, holds the document to a single
+    /// [`ExpansionBudget`]. `budget` is the one of the parse options, for a
+    /// handler opening its account. The default implementation returns `None`.
+    fn expansion(&mut self, budget: ExpansionBudget) -> Option<&mut Expansion> {
+        let _ = budget;
+        None
+    }
+
+    /// Called once after the last line of the input has been processed
+    ///
+    /// Handlers that buffer entries should flush them here. The default
+    /// implementation does nothing.
+    fn end_document(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Parser-side details of a single name=value pair or continuation line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryInfo<'a> {
+    /// Physical line number
+    pub line: usize,
+    /// Separator between name and value ('=' or ':', or ' ' in `.properties` input), None for bare names and continuations
+    pub separator: Option<char>,
+    /// Whether this line continues the value of the previous entry
+    pub continuation: bool,
+    /// Terminator of the physical line ("\n", "\r\n", "\r" in `.properties` input, or "" for an unterminated last line)
+    pub line_ending: &'static str,
+    /// The physical line as written, before trimming and comment stripping
+    ///
+    /// Only the terminator and a leading byte order mark are removed. Under
+    /// `LineLengthPolicy::Truncate` this is the truncated line.
+    pub raw_line: &'a str,
+}
+
+/// What to do when the same key appears more than once in a section
+///
+/// Keys are compared after trimming and case folding, regardless of which
+/// separator was used, so `foo: 1` and `FOO = 2` are duplicates; see
+/// [`ParseOptions::case_sensitive_duplicates`]. Multi-line continuations are
+/// never duplicates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DuplicateKeyPolicy {
+    /// The last occurrence replaces earlier ones
+    LastWins,
+    /// Later occurrences are ignored and never reach the handler
+    FirstWins,
+    /// Occurrences are joined with the separator
+    Concatenate { separator: String },
+    /// Later occurrences are a parse error
+    Error,
+}
+
+impl Default for DuplicateKeyPolicy {
+    fn default() -> Self {
+        DuplicateKeyPolicy::Concatenate { separator: "\n".to_string() }
+    }
+}
+
+/// A set of comment prefixes, each one or more characters long
+///
+/// `From<&str>` and `From<String>` treat every character of the string as a
+/// separate single-character prefix, matching the string fields this type
+/// replaced. They are kept for compatibility only and are deprecated in
+/// spirit: `"//".into()` means `/`, not `//`. Prefer [`CommentPrefixes::chars`]
+/// or [`CommentPrefixes::strings`], which say what they mean.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommentPrefixes {
+    /// Distinct prefixes, longest first so that the longest one matches
+    prefixes: Vec<String>,
+}
+
+impl CommentPrefixes {
+    /// No prefixes: nothing is a comment
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// Every character of `set` is a single-character prefix
+    pub fn chars(set: &str) -> Self {
+        Self::strings(set.chars().map(String::from))
+    }
+
+    /// Each string is a prefix; duplicates are collapsed
+    ///
+    /// Empty strings are skipped, since they would make every line a comment.
+    pub fn strings<I, S>(prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut collected: Vec<String> = Vec::new();
+        for prefix in prefixes {
+            let prefix = prefix.into();
+            if !prefix.is_empty() && !collected.contains(&prefix) {
+                collected.push(prefix);
+            }
+        }
+        collected.sort_by_key(|prefix| std::cmp::Reverse(prefix.len()));
+        Self { prefixes: collected }
+    }
+
+    /// The prefixes, longest first
+    pub fn as_slice(&self) -> &[String] {
+        &self.prefixes
+    }
+
+    /// Whether there are no prefixes
+    pub fn is_empty(&self) -> bool {
+        self.prefixes.is_empty()
+    }
+
+    /// The prefix `text` starts with, if any
+    pub fn prefix_of(&self, text: &str) -> Option<&str> {
+        self.prefixes.iter().map(String::as_str).find(|prefix| text.starts_with(prefix))
+    }
+}
+
+impl From<&str> for CommentPrefixes {
+    fn from(set: &str) -> Self {
+        Self::chars(set)
+    }
+}
+
+impl From<String> for CommentPrefixes {
+    fn from(set: String) -> Self {
+        Self::chars(&set)
+    }
+}
+
+/// Which sections a [`ParseOptionsDelta`] applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SectionPattern {
+    /// A section with exactly this name (case-insensitive)
+    Exact(String),
+    /// Sections matching a pattern where `*` matches any run of characters (case-insensitive)
+    Glob(String),
+}
+
+impl SectionPattern {
+    /// Whether the pattern matches a section name
+    pub fn matches(&self, section: &str) -> bool {
+        let section = section.to_lowercase();
+        match self {
+            SectionPattern::Exact(name) => name.to_lowercase() == section,
+            SectionPattern::Glob(pattern) => crate::summary::glob_match(&pattern.to_lowercase(), &section),
+        }
+    }
+}
+
+/// Changes to the lexical options applied inside matching sections
+///
+/// Only options that concern the contents of entries can change mid-file.
+/// Structural options (`max_line`, `line_length_policy`, `allow_bom`, the
+/// section header syntaxes, `duplicate_key_policy`, `stop_on_first_error`)
+/// always come from the top-level [`ParseOptions`]. Section headers are parsed
+/// with the options of the section they end, not the one they start.
+/// The parser does not interpret quotes, so there is nothing to override there.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseOptionsDelta {
+    /// Override `allow_multiline`
+    pub allow_multiline: Option<bool>,
+    /// Override `allow_inline_comments`
+    pub allow_inline_comments: Option<bool>,
+    /// Override `inline_comment_prefixes`
+    pub inline_comment_prefixes: Option<CommentPrefixes>,
+    /// Override `start_comment_prefixes`
+    pub start_comment_prefixes: Option<CommentPrefixes>,
+    /// Override `trim_values`
+    pub trim_values: Option<bool>,
+    /// Override `parse_escapes`
+    pub parse_escapes: Option<bool>,
+}
+
+impl ParseOptionsDelta {
+    /// Apply the changes on top of `base`
+    pub fn apply_to(&self, base: &mut ParseOptions) {
+        if let Some(allow) = self.allow_multiline {
+            base.allow_multiline = allow;
+        }
+        if let Some(allow) = self.allow_inline_comments {
+            base.allow_inline_comments = allow;
+        }
+        if let Some(prefixes) = &self.inline_comment_prefixes {
+            base.inline_comment_prefixes = prefixes.clone();
+        }
+        if let Some(prefixes) = &self.start_comment_prefixes {
+            base.start_comment_prefixes = prefixes.clone();
+        }
+        if let Some(trim) = self.trim_values {
+            base.trim_values = trim;
+        }
+        if let Some(escapes) = self.parse_escapes {
+            base.parse_escapes = escapes;
+        }
+    }
+}
+
+/// What to do with lines longer than `max_line`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineLengthPolicy {
+    /// Report a parse error and ignore the line
+    #[default]
+    Error,
+    /// Cut the line to `max_line` bytes and parse what is left
+    Truncate,
+    /// Ignore the line without reporting it
+    Skip,
+}
+
+/// How the lines of a multi-line value are joined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NewlineStyle {
+    /// Join with `\n`
+    #[default]
+    Lf,
+    /// Join with `\r\n`
+    CrLf,
+    /// Join with the platform's line terminator
+    Platform,
+    /// Join with whatever terminated the previous line in the source
+    Preserve,
+}
+
+impl NewlineStyle {
+    /// The terminator to use after a line that ended with `source_ending`
+    pub fn terminator(self, source_ending: &'static str) -> &'static str {
+        match self {
+            NewlineStyle::Lf => "\n",
+            NewlineStyle::CrLf => "\r\n",
+            NewlineStyle::Platform if cfg!(windows) => "\r\n",
+            NewlineStyle::Platform => "\n",
+            NewlineStyle::Preserve if source_ending.is_empty() => "\n",
+            NewlineStyle::Preserve => source_ending,
+        }
+    }
+}
+
+/// Extra data attached to a section header
+#[derive(Debug, Clone, PartialEq)]
+pub enum SectionAnnotation {
+    /// Git-style quoted subsection: `[remote "origin"]`
+    QuotedSubsection(String),
+    /// Section to inherit from: `[child : parent]`
+    Parent(String),
+    /// Profiles the section applies to: `[server @ dev, prod]`
+    Profiles(Vec<String>),
+    /// Uninterpreted text following the name: `[name anything else]`
+    Raw(String),
+}
+
+/// A parsed section header
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionHeader {
+    /// The section name
+    pub name: String,
+    /// Annotation following the name, if annotations are enabled and present
+    pub annotation: Option<SectionAnnotation>,
+}
+
+impl SectionHeader {
+    /// The section name as delivered to [`IniHandler::handle`]
+    ///
+    /// Quoted subsections are flattened git-style to `name.subsection`; every
+    /// other annotation is dropped, leaving just the name.
+    pub fn flattened_name(&self) -> String {
+        match &self.annotation {
+            Some(SectionAnnotation::QuotedSubsection(sub)) => format!("{}.{}", self.name, sub),
+            _ => self.name.clone(),
+        }
+    }
 }
 
 /// Configuration options for INI parsing
@@ -30,18 +376,108 @@ pub struct ParseOptions {
     pub allow_bom: bool,
     /// Allow inline comments (with whitespace before comment char)
     pub allow_inline_comments: bool,
-    /// Characters that start inline comments
-    pub inline_comment_prefixes: String,
-    /// Characters that start line comments
-    pub start_comment_prefixes: String,
+    /// Prefixes that start inline comments
+    pub inline_comment_prefixes: CommentPrefixes,
+    /// Prefixes that start line comments
+    pub start_comment_prefixes: CommentPrefixes,
     /// Stop parsing on first error
     pub stop_on_first_error: bool,
     /// Call handler on new section (with name and value as empty)
+    ///
+    /// [`IniHandler::start_section`] is called for every header either way.
     pub call_handler_on_new_section: bool,
     /// Allow names without values
     pub allow_no_value: bool,
-    /// Maximum line length
+    /// Maximum line length, not counting trailing whitespace
+    ///
+    /// When reading from a stream this also bounds memory: at most `max_line`
+    /// bytes plus one character of any physical line are kept, however long it is.
     pub max_line: usize,
+    /// What to do with lines longer than `max_line`
+    ///
+    /// An over-long continuation line never ends the value it belongs to:
+    /// `Truncate` appends the shortened line, `Skip` drops just that line, and
+    /// `Error` names the key the line continued.
+    pub line_length_policy: LineLengthPolicy,
+    /// Skip empty documents in multi-document streams instead of delivering empty readers
+    pub skip_empty_documents: bool,
+    /// Parse git-style quoted subsections in section headers
+    pub allow_quoted_subsections: bool,
+    /// Parse `[child : parent]` inheritance in section headers
+    pub allow_section_inheritance: bool,
+    /// Parse `[name @ profile, ...]` profile lists in section headers
+    pub allow_section_profiles: bool,
+    /// Keep any text after the first word of a section header as a raw annotation
+    pub allow_raw_section_annotations: bool,
+    /// Warn about continuation lines that look like `key = value` entries
+    pub warn_suspicious_continuation: bool,
+    /// Trim whitespace around values; when off, a value is everything after the separator
+    pub trim_values: bool,
+    /// Reject any byte of 0x80 or above, including a byte order mark
+    ///
+    /// The parser then sticks to ASCII-only trimming and case folding.
+    pub ascii_only: bool,
+    /// Reject section names that do not survive every writer and header syntax
+    ///
+    /// A strict name is not blank, has no surrounding whitespace or control
+    /// characters, and contains none of `[ ] = : ; # " @`. Other names are read
+    /// as written, but may be read differently once a header syntax option is
+    /// turned on, or by other INI readers.
+    pub strict_section_names: bool,
+    /// Read a header with an empty or blank name, such as `[]` or `[ ]`, as
+    /// a return to the global section
+    ///
+    /// When off, such a header is a parse error rather than silently
+    /// mixing the keys under it into the global ones.
+    pub allow_empty_section_names: bool,
+    /// Limits shared by every feature that expands values
+    pub expansion_budget: ExpansionBudget,
+    /// Options that change inside matching sections, applied in order
+    pub section_overrides: Vec<(SectionPattern, ParseOptionsDelta)>,
+    /// What to do with repeated keys
+    pub duplicate_key_policy: DuplicateKeyPolicy,
+    /// How the lines of multi-line values are joined when stored
+    pub value_newline: NewlineStyle,
+    /// Tell section and key names apart by case when looking for duplicate keys
+    ///
+    /// Off by default, so `Key` and `KEY` are the same key; the policies
+    /// other than `Error` then warn that two spellings were used. Readers
+    /// built with [`ReaderOptions::case_sensitive`](crate::ReaderOptions::case_sensitive)
+    /// turn this on.
+    pub case_sensitive_duplicates: bool,
+    /// How bytes that are not valid UTF-8 are read; input given as `&str` is already decoded
+    pub encoding: EncodingMode,
+    /// Decode backslash escapes in values
+    ///
+    /// `\n`, `\t`, `\r`, `\\`, `\;`, `\#`, `\=`, `\:` and `\xHH` (the character
+    /// U+00HH) stand for the character they name; any other backslash is a
+    /// parse error giving its byte offset in the line. Values are decoded
+    /// after inline comments are cut off, and an escaped comment character
+    /// never starts one. Each line of a multi-line value is decoded on its
+    /// own. Names are left as written.
+    pub parse_escapes: bool,
+    /// Follow `@include <path>` lines
+    ///
+    /// The named file is parsed in place of the line, through the same
+    /// handler and under the same duplicate key policy, as if its lines were
+    /// written there; a multi-line value never runs across files. A relative
+    /// path is taken from the directory of the including file. Only
+    /// [`ini_parse_with_options`] and [`ini_parse_string_with_includes`] can
+    /// read the files; elsewhere the line is a parse error.
+    ///
+    /// Every error is then reported as `InFile`, naming the file it is in,
+    /// with the line number in that file. A file that includes itself,
+    /// directly or not, is a parse error on the `@include` line, and nested
+    /// includes draw from the expansion budget, in the account of the
+    /// handler if it keeps one (see [`IniHandler::expansion`]).
+    ///
+    /// A file already included once in the same parse, under any path that
+    /// leads to it, is skipped with a warning, so that two files including a
+    /// common one do not apply it twice; see
+    /// [`allow_repeated_includes`](Self::allow_repeated_includes).
+    pub allow_includes: bool,
+    /// Parse a file again each time it is included instead of skipping repeats
+    pub allow_repeated_includes: bool,
 }
 
 impl Default for ParseOptions {
@@ -50,13 +486,46 @@ impl Default for ParseOptions {
             allow_multiline: false,
             allow_bom: true,
             allow_inline_comments: true,
-            inline_comment_prefixes: ";".to_string(),
-            start_comment_prefixes: ";#".to_string(),
+            inline_comment_prefixes: CommentPrefixes::chars(";"),
+            start_comment_prefixes: CommentPrefixes::chars(";#"),
             stop_on_first_error: false,
             call_handler_on_new_section: false,
             allow_no_value: false,
             max_line: 200,
+            line_length_policy: LineLengthPolicy::default(),
+            skip_empty_documents: false,
+            allow_quoted_subsections: false,
+            allow_section_inheritance: false,
+            allow_section_profiles: false,
+            allow_raw_section_annotations: false,
+            warn_suspicious_continuation: false,
+            trim_values: true,
+            ascii_only: false,
+            strict_section_names: false,
+            allow_empty_section_names: false,
+            expansion_budget: ExpansionBudget::default(),
+            section_overrides: Vec::new(),
+            duplicate_key_policy: DuplicateKeyPolicy::default(),
+            value_newline: NewlineStyle::default(),
+            case_sensitive_duplicates: false,
+            encoding: EncodingMode::default(),
+            parse_escapes: false,
+            allow_includes: false,
+            allow_repeated_includes: false,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// The options in effect inside `section`, if any override matches it
+    fn scoped_to(&self, section: &str) -> Option<ParseOptions> {
+        let mut matching = self.section_overrides.iter().filter(|(pattern, _)| pattern.matches(section)).peekable();
+        matching.peek()?;
+        let mut scoped = self.clone();
+        for (_, delta) in matching {
+            delta.apply_to(&mut scoped);
         }
+        Some(scoped)
     }
 }
 
@@ -71,9 +540,15 @@ pub fn ini_parse_with_options<P: AsRef<Path>>(
     handler: &mut dyn IniHandler,
     options: &ParseOptions,
 ) -> Result<(), IniParseError> {
-    let file = File::open(path.as_ref())
-        .map_err(|e| IniParseError::FileOpen(format!("{}: {}", path.as_ref().display(), e)))?;
-    ini_parse_file_with_options(file, handler, options)
+    let path = path.as_ref();
+    let mut file = File::open(path).map_err(|e| IniParseError::file_open(path, e))?;
+    if !options.allow_includes {
+        return ini_parse_file_with_options(file, handler, options);
+    }
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).map_err(|e| IniParseError::file_open(path, e))?;
+    let mut read = |path: &Path| std::fs::read(path);
+    parse_including(&bytes, Some(path), handler, options, &mut read)
 }
 
 /// Parse an INI file from a File object
@@ -97,100 +572,820 @@ pub fn ini_parse_string(data: &str, handler: &mut dyn IniHandler) -> Result<(),
 }
 
 /// Parse INI data from a string with custom options
+///
+/// The last line needs no terminator: it is parsed exactly as if it had one,
+/// including as the continuation of a multi-line value. Lines holding only
+/// whitespace are blank wherever they are. The handler sees the same calls
+/// as when the data is read through [`ini_parse_reader_with_options`].
 pub fn ini_parse_string_with_options(
     data: &str,
     handler: &mut dyn IniHandler,
     options: &ParseOptions,
 ) -> Result<(), IniParseError> {
-    let lines = data.lines().map(|s| s.to_string()).collect::<Vec<_>>();
-    ini_parse_lines_with_options(&lines, handler, options)
+    ini_parse_lines_with_options(string_lines(data), handler, options)
+}
+
+/// Parse INI data from raw bytes, detecting UTF-16 by its byte order mark
+///
+/// Other bytes are decoded as `options.encoding` says; the warning of an
+/// `Auto` fallback to Latin-1 reaches the handler before any entry.
+pub fn ini_parse_bytes(bytes: &[u8], handler: &mut dyn IniHandler, options: &ParseOptions) -> Result<(), IniParseError> {
+    let (data, fallback) = crate::encoding::decode_with(bytes, options.encoding)?;
+    if let Some(warning) = fallback {
+        handler.warning(&warning).map_err(IniParseError::HandlerError)?;
+    }
+    ini_parse_string_with_options(&data, handler, options)
+}
+
+/// Parse INI data from a string, reading the files its `@include` lines name with `read`
+///
+/// `read` gets the path of each included file: the path as written for an
+/// `@include` in `data`, and relative to the including file's directory for
+/// one in an included file. The data and the included files are decoded as
+/// [`ini_parse_bytes`] decodes its input. Includes are followed only if
+/// `options.allow_includes` is set.
+pub fn ini_parse_string_with_includes(
+    data: &str,
+    handler: &mut dyn IniHandler,
+    options: &ParseOptions,
+    read: &mut dyn FnMut(&Path) -> io::Result<Vec<u8>>,
+) -> Result<(), IniParseError> {
+    parse_including(data.as_bytes(), None, handler, options, read)
+}
+
+/// Parse the contents of a file that may include others
+fn parse_including(
+    bytes: &[u8],
+    path: Option<&Path>,
+    handler: &mut dyn IniHandler,
+    options: &ParseOptions,
+    read: &mut dyn FnMut(&Path) -> io::Result<Vec<u8>>,
+) -> Result<(), IniParseError> {
+    let in_file = |error| match path {
+        Some(path) => IniParseError::InFile { path: path.to_path_buf(), error: Box::new(error) },
+        None => error,
+    };
+    let (data, fallback) = crate::encoding::decode_with(bytes, options.encoding).map_err(in_file)?;
+    if let Some(warning) = fallback {
+        handler.warning(&warning).map_err(|message| in_file(IniParseError::HandlerError(message)))?;
+    }
+    let mut first_error: Option<IniParseError> = None;
+    let mut recovered = |error| {
+        first_error.get_or_insert(error);
+    };
+    let mut parser = LineParser::new(handler, options, &mut recovered);
+    parser.includes = Some(Includes::new(path, read, options));
+    for (line, line_ending) in data.split_inclusive('\n').map(split_line_ending) {
+        parser.feed(line, line_ending)?;
+    }
+    parser.finish()?;
+    match first_error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
 }
 
 /// Parse INI data from a BufRead object
+///
+/// Lines are parsed as they are read, each from the one buffer it was read
+/// into, so memory use does not grow with the size of the input, nor with
+/// the length of a single line beyond `max_line`. The exception is
+/// [`EncodingMode::Auto`], which must see the whole input before it can
+/// decode any of it; it is then parsed as by [`ini_parse_bytes`].
 pub fn ini_parse_reader_with_options<R: BufRead>(
     mut reader: R,
     handler: &mut dyn IniHandler,
     options: &ParseOptions,
 ) -> Result<(), IniParseError> {
-    let mut lines = Vec::new();
-    let mut line = String::new();
-    
-    while reader.read_line(&mut line).map_err(|e| IniParseError::FileOpen(e.to_string()))? > 0 {
-        lines.push(line.trim_end().to_string());
-        line.clear();
+    if options.encoding == EncodingMode::Auto {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).map_err(|e| IniParseError::FileOpen(e.to_string()))?;
+        return ini_parse_bytes(&bytes, handler, options);
+    }
+    let limit = options.max_line.saturating_add(LINE_SLACK);
+    // Every line is read into this one buffer and parsed from there
+    let mut buf = Vec::new();
+    let mut first_error: Option<IniParseError> = None;
+    let mut recovered = |error| {
+        first_error.get_or_insert(error);
+    };
+    let mut parser = LineParser::new(handler, options, &mut recovered);
+    while let Some(read) = read_bounded_line(&mut reader, &mut buf, limit).map_err(|e| IniParseError::FileOpen(e.to_string()))? {
+        let text = read.text(&buf, options.encoding).map_err(|e| IniParseError::FileOpen(e.to_string()))?;
+        parser.feed(&text, read.ending)?;
+    }
+    parser.finish()?;
+    match first_error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Bytes kept beyond `max_line` when reading a line: room for one more UTF-8
+/// character, so that an over-long line is still recognized and can be cut
+/// at a character boundary
+pub(crate) const LINE_SLACK: usize = 4;
+
+/// One physical line read by [`read_bounded_line`]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BoundedLine {
+    /// Terminator of the line ("\n", "\r\n", or "" at the end of input)
+    pub(crate) ending: &'static str,
+    /// Number of bytes past the limit that were read but not kept
+    pub(crate) dropped: usize,
+}
+
+impl BoundedLine {
+    /// The kept bytes; a character cut in half by the limit is dropped
+    ///
+    /// A line cut short also loses its trailing whitespace, which could not
+    /// be kept whole anyway.
+    pub(crate) fn bytes(self, buf: &[u8]) -> &[u8] {
+        if self.dropped == 0 {
+            return buf;
+        }
+        let end = match std::str::from_utf8(buf) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => buf.len(),
+        };
+        buf[..end].trim_ascii_end()
+    }
+
+    /// The kept bytes as text, decoded line by line as `encoding` says
+    pub(crate) fn text(self, buf: &[u8], encoding: EncodingMode) -> io::Result<Cow<'_, str>> {
+        match crate::encoding::decode_line(self.bytes(buf), encoding) {
+            Some(text) if self.dropped > 0 && text.trim_end().len() < text.len() => Ok(Cow::Owned(text.trim_end().to_string())),
+            Some(text) => Ok(text),
+            None => Err(io::Error::new(io::ErrorKind::InvalidData, "stream did not contain valid UTF-8")),
+        }
+    }
+}
+
+/// Read one line into `buf` without its terminator, keeping at most `limit` bytes
+///
+/// The rest of an over-long line is read and thrown away in the reader's own
+/// buffer, so memory use is bounded by `limit` whatever the input. Returns
+/// None at the end of input.
+pub(crate) fn read_bounded_line<R: BufRead + ?Sized>(
+    reader: &mut R,
+    buf: &mut Vec<u8>,
+    limit: usize,
+) -> io::Result<Option<BoundedLine>> {
+    buf.clear();
+    let mut read_any = false;
+    let mut found_newline = false;
+    let mut dropped = 0;
+    let mut dropped_content = false;
+    let mut last_dropped = 0;
+
+    while !found_newline {
+        let available = match reader.fill_buf() {
+            Ok(available) => available,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            break;
+        }
+        read_any = true;
+
+        let (chunk, consumed) = match available.iter().position(|&b| b == b'\n') {
+            Some(newline) => {
+                found_newline = true;
+                (&available[..newline], newline + 1)
+            }
+            None => (available, available.len()),
+        };
+        let keep = limit.saturating_sub(buf.len()).min(chunk.len());
+        buf.extend_from_slice(&chunk[..keep]);
+        if let Some(&last) = chunk[keep..].last() {
+            dropped += chunk.len() - keep;
+            dropped_content |= chunk[keep..].iter().any(|b| !b.is_ascii_whitespace());
+            last_dropped = last;
+        }
+        reader.consume(consumed);
+    }
+
+    if !read_any {
+        return Ok(None);
+    }
+    // A '\r' before the newline is part of the terminator, and so is one
+    // left at the very end of the input by a CRLF cut in half
+    let carriage_return = if dropped > 0 { last_dropped == b'\r' } else { buf.last() == Some(&b'\r') };
+    if carriage_return {
+        if dropped > 0 {
+            dropped -= 1;
+        } else {
+            buf.pop();
+        }
+    }
+    let ending = match (found_newline, carriage_return) {
+        (false, _) => "",
+        (true, true) => "\r\n",
+        (true, false) => "\n",
+    };
+    // Trailing whitespace does not count towards the line length, so a line
+    // whose dropped part has content must not end in whitespace where it was
+    // cut; the byte past `max_line` that stands in for that content is never
+    // parsed, as the line is too long
+    if dropped_content {
+        if let Some(last) = buf.last_mut().filter(|last| last.is_ascii_whitespace()) {
+            *last = b'~';
+        }
+    }
+    Ok(Some(BoundedLine { ending, dropped }))
+}
+
+/// The lines of in-memory data with their terminators
+fn string_lines(data: &str) -> impl Iterator<Item = Result<(&str, &'static str), IniParseError>> {
+    data.split_inclusive('\n').map(|line| Ok(split_line_ending(line)))
+}
+
+/// Split a line into its content and its terminator
+///
+/// An unterminated last line ending in `\r`, the first half of a CRLF cut
+/// off with the input, loses the `\r` as [`read_bounded_line`] drops it.
+fn split_line_ending(line: &str) -> (&str, &'static str) {
+    if let Some(content) = line.strip_suffix("\r\n") {
+        (content, "\r\n")
+    } else if let Some(content) = line.strip_suffix('\n') {
+        (content, "\n")
+    } else {
+        (line.strip_suffix('\r').unwrap_or(line), "")
     }
-    
-    ini_parse_lines_with_options(&lines, handler, options)
 }
 
-/// Parse INI data from a vector of lines
-fn ini_parse_lines_with_options(
-    lines: &[String],
+/// Parse INI data from a sequence of lines and their terminators
+fn ini_parse_lines_with_options<I, L>(
+    lines: I,
     handler: &mut dyn IniHandler,
     options: &ParseOptions,
-) -> Result<(), IniParseError> {
-    let mut section = String::new();
-    let mut prev_name = String::new();
-    let mut line_number = 0;
+) -> Result<(), IniParseError>
+where
+    I: IntoIterator<Item = Result<(L, &'static str), IniParseError>>,
+    L: AsRef<str>,
+{
     let mut first_error: Option<IniParseError> = None;
+    parse_lines(lines, handler, options, &mut |error| {
+        first_error.get_or_insert(error);
+    })?;
+    match first_error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
 
-    for line in lines {
-        line_number += 1;
-        
-        if line.len() > options.max_line {
-            let error = IniParseError::ParseError {
-                line: line_number,
-                message: "Line too long".to_string(),
-            };
-            if options.stop_on_first_error {
-                return Err(error);
-            }
-            if first_error.is_none() {
-                first_error = Some(error);
+/// Parse INI data from a string, returning every error instead of the first
+///
+/// Parsing goes on after each error, so one pass finds every broken line:
+/// syntax errors, over-long lines and entries the handler rejected, each
+/// line at most once, followed by any handler error raised outside an entry.
+/// Errors are in the order they occurred; with `stop_on_first_error` there
+/// is at most one.
+pub fn ini_parse_string_collecting(data: &str, handler: &mut dyn IniHandler, options: &ParseOptions) -> Result<(), Vec<IniParseError>> {
+    let mut errors = Vec::new();
+    if let Err(error) = parse_lines(string_lines(data), handler, options, &mut |error| errors.push(error)) {
+        errors.push(error);
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parse lines, passing each error parsing carries on after to `recovered`
+///
+/// Returns an error only when parsing stopped: on a read error, or on any
+/// error with `stop_on_first_error`.
+fn parse_lines<I, L>(
+    lines: I,
+    handler: &mut dyn IniHandler,
+    options: &ParseOptions,
+    recovered: &mut dyn FnMut(IniParseError),
+) -> Result<(), IniParseError>
+where
+    I: IntoIterator<Item = Result<(L, &'static str), IniParseError>>,
+    L: AsRef<str>,
+{
+    let mut parser = LineParser::new(handler, options, recovered);
+    for read in lines {
+        let (line, line_ending) = read?;
+        parser.feed(line.as_ref(), line_ending)?;
+    }
+    parser.finish()
+}
+
+/// Parser fed one physical line at a time
+///
+/// Lines are borrowed only for the call that parses them, so a reader can
+/// hand over each line from the one buffer it reads them into.
+struct LineParser<'a> {
+    handler: &'a mut dyn IniHandler,
+    options: &'a ParseOptions,
+    recovered: &'a mut dyn FnMut(IniParseError),
+    state: ParserState,
+    line_number: usize,
+    /// Options with the overrides of the current section applied, if any
+    scoped: Option<ParseOptions>,
+    scoped_section: String,
+    /// Reads included files; None where includes cannot be followed
+    includes: Option<Includes<'a>>,
+}
+
+/// The files being parsed for [`ParseOptions::allow_includes`] and how to read more
+struct Includes<'a> {
+    read: &'a mut dyn FnMut(&Path) -> io::Result<Vec<u8>>,
+    /// Files being parsed, outermost first; the outermost has no path when it is a string
+    files: Vec<IncludedFile>,
+    /// Identities of every file parsed so far, to skip repeated includes
+    seen: HashSet<PathBuf>,
+    /// Account used when the handler keeps none
+    expansion: Expansion,
+}
+
+struct IncludedFile {
+    path: Option<PathBuf>,
+    /// The path resolved through symbolic links where it exists, to recognize a file included again
+    identity: Option<PathBuf>,
+}
+
+impl<'a> Includes<'a> {
+    fn new(path: Option<&Path>, read: &'a mut dyn FnMut(&Path) -> io::Result<Vec<u8>>, options: &ParseOptions) -> Self {
+        let top = IncludedFile { path: path.map(Path::to_path_buf), identity: path.map(identity) };
+        let seen = top.identity.iter().cloned().collect();
+        Includes { read, files: vec![top], seen, expansion: Expansion::new(options.expansion_budget) }
+    }
+
+    /// Path of the file being parsed, if it has one
+    fn current(&self) -> Option<&Path> {
+        self.files.last().and_then(|file| file.path.as_deref())
+    }
+
+    /// Read the file `name` included on `line` of the current one, making it current
+    ///
+    /// Returns `None`, reading nothing, for a file already included unless
+    /// [`ParseOptions::allow_repeated_includes`] is set. Draws from `shared`,
+    /// the account of the handler, if there is one.
+    fn open(
+        &mut self,
+        name: &str,
+        line: usize,
+        options: &ParseOptions,
+        shared: Option<&mut Expansion>,
+    ) -> Result<Option<(String, Option<IniWarning>)>, IniParseError> {
+        let path = match self.current().and_then(Path::parent) {
+            Some(dir) => dir.join(name),
+            None => PathBuf::from(name),
+        };
+        let id = identity(&path);
+        if let Some(start) = self.files.iter().position(|file| file.identity.as_ref() == Some(&id)) {
+            let mut chain: Vec<String> = self.files[start..].iter().filter_map(|file| Some(file.path.as_ref()?.display().to_string())).collect();
+            chain.push(path.display().to_string());
+            let message = format!("Recursive include of '{}' ({})", name, chain.join(" -> "));
+            return Err(IniParseError::ParseError { line, message, suggestion: None });
+        }
+        if !self.seen.insert(id.clone()) && !options.allow_repeated_includes {
+            return Ok(None);
+        }
+        let expansion = shared.unwrap_or(&mut self.expansion);
+        if let Err(error) = expansion.enter(&path.display().to_string()) {
+            expansion.leave();
+            return Err(error);
+        }
+        let bytes = match (self.read)(&path) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                expansion.leave();
+                return Err(IniParseError::ParseError { line, message: format!("Cannot include '{}': {}", name, e), suggestion: None });
             }
-            continue;
+        };
+        let decoded = crate::encoding::decode_with(&bytes, options.encoding)
+            .map_err(|e| IniParseError::ParseError { line, message: format!("Cannot include '{}': {}", name, e), suggestion: None });
+        match decoded {
+            Ok(_) => self.files.push(IncludedFile { path: Some(path), identity: Some(id) }),
+            Err(_) => expansion.leave(),
         }
+        decoded.map(Some)
+    }
 
-        let result = parse_line(line, &mut section, &mut prev_name, handler, options, line_number);
-        
-        match result {
-            Ok(()) => {}
-            Err(error) => {
-                if options.stop_on_first_error {
-                    return Err(error);
+    /// Return to the including file, leaving `shared` if the file was opened with it
+    fn close(&mut self, shared: Option<&mut Expansion>) {
+        self.files.pop();
+        shared.unwrap_or(&mut self.expansion).leave();
+    }
+}
+
+/// `path` with symbolic links and `.` and `..` resolved, or without its `.` components if it cannot be
+fn identity(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.components().filter(|part| *part != Component::CurDir).collect())
+}
+
+/// The path named by an `@include` line, if `line` is one
+fn include_directive<'l>(line: &'l str, options: &ParseOptions) -> Option<&'l str> {
+    let rest = line.trim().strip_prefix("@include")?;
+    if !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let rest = if options.allow_inline_comments { strip_inline_comment(rest, &options.inline_comment_prefixes) } else { rest };
+    Some(rest.trim()).filter(|name| !name.is_empty())
+}
+
+impl<'a> LineParser<'a> {
+    fn new(handler: &'a mut dyn IniHandler, options: &'a ParseOptions, recovered: &'a mut dyn FnMut(IniParseError)) -> Self {
+        handler.start_document();
+        LineParser {
+            handler,
+            options,
+            recovered,
+            state: ParserState::default(),
+            line_number: 0,
+            scoped: options.scoped_to(""),
+            scoped_section: String::new(),
+            includes: None,
+        }
+    }
+
+    /// Parse the next line, given without its terminator
+    fn feed(&mut self, mut line: &str, line_ending: &'static str) -> Result<(), IniParseError> {
+        let options = self.options;
+        self.line_number += 1;
+        self.state.line_ending = line_ending;
+
+        if line.trim_end().len() > options.max_line {
+            match options.line_length_policy {
+                LineLengthPolicy::Error => {
+                    let message = match continued_key(line, &self.state, options) {
+                        Some(name) => format!("Line too long (continuation of '{}')", name),
+                        None => "Line too long".to_string(),
+                    };
+                    return self.recover(IniParseError::ParseError { line: self.line_number, message, suggestion: None });
                 }
-                if first_error.is_none() {
-                    first_error = Some(error);
+                LineLengthPolicy::Skip => return Ok(()),
+                LineLengthPolicy::Truncate => {
+                    let mut end = options.max_line;
+                    while !line.is_char_boundary(end) {
+                        end -= 1;
+                    }
+                    line = &line[..end];
                 }
             }
         }
+
+        if options.allow_includes && continued_key(line, &self.state, options).is_none() {
+            if let Some(name) = include_directive(line, options) {
+                return self.include(name);
+            }
+        }
+
+        let effective = self.scoped.as_ref().unwrap_or(options);
+        let result = parse_line(line, &mut self.state, &mut *self.handler, effective, self.line_number);
+        if !options.section_overrides.is_empty() && self.state.section != self.scoped_section {
+            self.scoped_section = self.state.section.clone();
+            self.scoped = options.scoped_to(&self.scoped_section);
+        }
+        match result {
+            Ok(()) => Ok(()),
+            Err(error) => self.recover(error),
+        }
     }
 
-    if let Some(error) = first_error {
-        Err(error)
-    } else {
+    /// Close what is still open at the end of input and end the document
+    fn finish(mut self) -> Result<(), IniParseError> {
+        if let Err(error) = finalize(&mut self.state, &mut *self.handler) {
+            self.recover(error)?;
+        }
+        if let Err(msg) = self.handler.end_document() {
+            (self.recovered)(IniParseError::HandlerError(msg));
+        }
+        Ok(())
+    }
+
+    /// Parse the file named by an `@include` line in place of the line
+    fn include(&mut self, name: &str) -> Result<(), IniParseError> {
+        if let Err(error) = close_value(&mut self.state, &mut *self.handler) {
+            self.recover(error)?;
+        }
+        let line = self.line_number;
+        let opened = match &mut self.includes {
+            Some(includes) => includes.open(name, line, self.options, self.handler.expansion(self.options.expansion_budget)),
+            None => {
+                let message = format!("Cannot include '{}': includes are only followed when parsing a file or with a reader function", name);
+                Err(IniParseError::ParseError { line, message, suggestion: None })
+            }
+        };
+        let (data, fallback) = match opened {
+            Ok(Some(opened)) => opened,
+            Ok(None) => {
+                let warning = IniWarning {
+                    line: Some(line),
+                    section: self.state.section.clone(),
+                    name: String::new(),
+                    message: format!("Skipping '{}': already included", name),
+                };
+                return match self.handler.warning(&warning) {
+                    Ok(()) => Ok(()),
+                    Err(message) => self.recover(IniParseError::HandlerError(message)),
+                };
+            }
+            Err(error) => return self.recover(error),
+        };
+
+        let outer_line = std::mem::replace(&mut self.line_number, 0);
+        let mut result = match fallback.map(|warning| self.handler.warning(&warning)) {
+            Some(Err(message)) => self.recover(IniParseError::HandlerError(message)),
+            _ => Ok(()),
+        };
+        for (text, line_ending) in data.split_inclusive('\n').map(split_line_ending) {
+            if result.is_err() {
+                break;
+            }
+            result = self.feed(text, line_ending);
+        }
+        if result.is_ok() {
+            if let Err(error) = close_value(&mut self.state, &mut *self.handler) {
+                result = self.recover(error);
+            }
+        }
+        self.line_number = outer_line;
+        if let Some(includes) = &mut self.includes {
+            includes.close(self.handler.expansion(self.options.expansion_budget));
+        }
+        result
+    }
+
+    /// Stop on `error` with `stop_on_first_error`, otherwise record it and go on
+    ///
+    /// When following includes, the error is first placed in the file being parsed.
+    fn recover(&mut self, error: IniParseError) -> Result<(), IniParseError> {
+        let error = match self.includes.as_ref().and_then(Includes::current) {
+            Some(path) => IniParseError::InFile { path: path.to_path_buf(), error: Box::new(error) },
+            None => error,
+        };
+        if self.options.stop_on_first_error {
+            return Err(error);
+        }
+        (self.recovered)(error);
         Ok(())
     }
 }
 
+/// Mutable state carried from one line to the next
+#[derive(Debug, Default)]
+pub(crate) struct ParserState {
+    /// Current section name
+    section: String,
+    /// The current section was opened by a header, so it still has to be closed
+    in_section: bool,
+    /// Name of the most recent entry, for multi-line continuations
+    prev_name: String,
+    /// Construct spanning several lines that is still open: (description, start line)
+    open_construct: Option<(&'static str, usize)>,
+    /// First occurrence of every key seen, by folded (section, name)
+    seen: HashMap<(String, String), FirstSeen>,
+    /// The current value is a dropped duplicate whose continuations are dropped too
+    skipping_value: bool,
+    /// Terminator of the line being parsed
+    line_ending: &'static str,
+    /// The line is a suggestion being checked, whose own errors get none
+    checking_suggestion: bool,
+}
+
+/// Where and how a key was first set, for reporting its duplicates
+#[derive(Debug)]
+struct FirstSeen {
+    line: usize,
+    separator: Option<char>,
+    section: String,
+    name: String,
+}
+
+impl FirstSeen {
+    /// How the first occurrence was spelled, if not as `section` and `name` are
+    fn spelling(&self, section: &str, name: &str) -> String {
+        let mut spelling = String::new();
+        if self.name != name {
+            spelling.push_str(&format!(" as '{}'", self.name));
+        }
+        if self.section != section {
+            spelling.push_str(&format!(" in section '{}'", self.section));
+        }
+        spelling
+    }
+}
+
+/// Report the pending value as complete, if there is one
+fn close_value(state: &mut ParserState, handler: &mut dyn IniHandler) -> Result<(), IniParseError> {
+    if std::mem::take(&mut state.skipping_value) {
+        state.prev_name.clear();
+        return Ok(());
+    }
+    if state.prev_name.is_empty() {
+        return Ok(());
+    }
+    let name = std::mem::take(&mut state.prev_name);
+    handler.end_value(&state.section, &name).map_err(IniParseError::HandlerError)
+}
+
+/// Report the section opened by the last header as complete, if there is one
+fn close_section(state: &mut ParserState, handler: &mut dyn IniHandler) -> Result<(), IniParseError> {
+    if !std::mem::take(&mut state.in_section) {
+        return Ok(());
+    }
+    handler.end_section(&state.section).map_err(IniParseError::HandlerError)
+}
+
+/// Deliver the first line of a new entry, applying the duplicate key policy
+fn start_entry(
+    state: &mut ParserState,
+    handler: &mut dyn IniHandler,
+    options: &ParseOptions,
+    info: EntryInfo,
+    name: &str,
+    value: &str,
+) -> Result<(), IniParseError> {
+    close_value(state, handler)?;
+    let (line_number, separator) = (info.line, info.separator);
+    let value = match unescaped(value, &info, options) {
+        Ok(value) => value,
+        Err(error) => {
+            // The rest of the value goes with the entry that failed
+            state.prev_name = name.to_string();
+            state.skipping_value = true;
+            return Err(error);
+        }
+    };
+
+    let trimmed = if options.ascii_only { name.trim_ascii() } else { name.trim() };
+    let key = if options.case_sensitive_duplicates {
+        (state.section.clone(), trimmed.to_string())
+    } else if options.ascii_only {
+        (state.section.to_ascii_lowercase(), trimmed.to_ascii_lowercase())
+    } else {
+        (state.section.to_lowercase(), trimmed.to_lowercase())
+    };
+    if let Some(first) = state.seen.get(&key) {
+        let spelling = first.spelling(&state.section, trimmed);
+        if let DuplicateKeyPolicy::Error = options.duplicate_key_policy {
+            let message = format!(
+                "Duplicate key '{}' in section '{}': first set{} on line {} with {}, again on line {} with {}",
+                name,
+                state.section,
+                spelling,
+                first.line,
+                describe_separator(first.separator),
+                line_number,
+                describe_separator(separator),
+            );
+            state.prev_name = name.to_string();
+            state.skipping_value = true;
+            return Err(IniParseError::ParseError { line: line_number, message, suggestion: None });
+        }
+        // The other policies combine the values, which hides that two
+        // spellings were taken for the same key
+        if !spelling.is_empty() {
+            let warning = IniWarning {
+                line: Some(line_number),
+                section: state.section.clone(),
+                name: name.to_string(),
+                message: format!(
+                    "Duplicate key '{}' in section '{}': first set{} on line {}; names differing only in case are the same key",
+                    name, state.section, spelling, first.line
+                ),
+            };
+            handler.warning(&warning).map_err(IniParseError::HandlerError)?;
+        }
+        if let DuplicateKeyPolicy::FirstWins = options.duplicate_key_policy {
+            state.prev_name = name.to_string();
+            state.skipping_value = true;
+            return Ok(());
+        }
+    } else {
+        let first = FirstSeen { line: line_number, separator, section: state.section.clone(), name: trimmed.to_string() };
+        state.seen.insert(key, first);
+    }
+
+    state.prev_name = name.to_string();
+    handler.handle_entry(&info, &state.section, name, &value)
+        .map_err(|message| IniParseError::rejected_entry(&info, message))
+}
+
+/// A value with its escapes decoded, if `parse_escapes` is on
+fn unescaped<'v>(value: &'v str, info: &EntryInfo, options: &ParseOptions) -> Result<Cow<'v, str>, IniParseError> {
+    if !options.parse_escapes {
+        return Ok(Cow::Borrowed(value));
+    }
+    decode_escapes(value).map_err(|(at, message)| {
+        let offset = crate::tokens::offset_in(info.raw_line, value).unwrap_or(0) + at;
+        IniParseError::ParseError { line: info.line, message: format!("{} at byte {}", message, offset), suggestion: None }
+    })
+}
+
+/// Decode the escapes of [`ParseOptions::parse_escapes`], borrowing text without any
+///
+/// Fails with the offset of the bad escape in `text` and a description.
+fn decode_escapes(text: &str) -> Result<Cow<'_, str>, (usize, String)> {
+    if !text.contains('\\') {
+        return Ok(Cow::Borrowed(text));
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('\\') {
+        decoded.push_str(&rest[..start]);
+        let at = text.len() - rest.len() + start;
+        let escape = &rest[start + 1..];
+        let (c, len) = match escape.chars().next() {
+            Some('n') => ('\n', 1),
+            Some('t') => ('\t', 1),
+            Some('r') => ('\r', 1),
+            Some(c @ ('\\' | ';' | '#' | '=' | ':')) => (c, 1),
+            Some('x') => {
+                let digits = escape[1..].bytes().take(2).take_while(u8::is_ascii_hexdigit).count();
+                if digits == 2 {
+                    (char::from(u8::from_str_radix(&escape[1..3], 16).unwrap_or_default()), 3)
+                } else if digits + 1 == escape.len() {
+                    return Err((at, format!("Truncated escape sequence '\\{}'", escape)));
+                } else {
+                    let written: String = escape.chars().take(digits + 2).collect();
+                    return Err((at, format!("Invalid escape sequence '\\{}'", written)));
+                }
+            }
+            Some(other) => return Err((at, format!("Invalid escape sequence '\\{}'", other))),
+            None => return Err((at, "Backslash at end of value".to_string())),
+        };
+        decoded.push(c);
+        rest = &escape[len..];
+    }
+    decoded.push_str(rest);
+    Ok(Cow::Owned(decoded))
+}
+
+/// Reject a line containing bytes outside ASCII, reporting the first one
+fn check_ascii(line: &str, line_number: usize) -> Result<(), IniParseError> {
+    let Some(offset) = line.bytes().position(|byte| !byte.is_ascii()) else {
+        return Ok(());
+    };
+    let message = if line_number == 1 && offset == 0 && line.starts_with('\u{FEFF}') {
+        "UTF-8 byte order mark in ASCII-only input (allow_bom does not apply when ascii_only is set)".to_string()
+    } else {
+        format!("Non-ASCII byte 0x{:02X} at byte {}", line.as_bytes()[offset], offset)
+    };
+    Err(IniParseError::ParseError { line: line_number, message, suggestion: None })
+}
+
+/// Reject a section name that `strict_section_names` does not allow
+fn check_section_name(name: &str, line_number: usize) -> Result<(), IniParseError> {
+    let problem = if name.trim() != name {
+        "has leading or trailing whitespace".to_string()
+    } else if let Some(c) = name.chars().find(|&c| c.is_control() || "[]=:;#\"@".contains(c)) {
+        format!("contains {:?}", c)
+    } else {
+        return Ok(());
+    };
+    Err(IniParseError::ParseError { line: line_number, message: format!("Section name '{}' {}", name, problem), suggestion: None })
+}
+
+/// Describe a separator for error messages
+fn describe_separator(separator: Option<char>) -> String {
+    match separator {
+        Some(c) => format!("'{}'", c),
+        None => "no separator".to_string(),
+    }
+}
+
+/// End-of-input step: close the pending value and reject any construct left open
+fn finalize(state: &mut ParserState, handler: &mut dyn IniHandler) -> Result<(), IniParseError> {
+    let closed = close_value(state, handler).and(close_section(state, handler));
+    if let Some((construct, line)) = state.open_construct.take() {
+        return Err(IniParseError::ParseError {
+            line,
+            message: format!("Unterminated {} at end of input", construct),
+            suggestion: None,
+        });
+    }
+    closed
+}
+
 /// Parse a single line of INI data
 fn parse_line(
     line: &str,
-    section: &mut String,
-    prev_name: &mut String,
+    state: &mut ParserState,
     handler: &mut dyn IniHandler,
     options: &ParseOptions,
     line_number: usize,
 ) -> Result<(), IniParseError> {
-    let mut line = line.to_string();
-    
-    // Handle UTF-8 BOM
-    if line_number == 1 && options.allow_bom && line.starts_with('\u{FEFF}') {
-        line = line[3..].to_string();
+    if options.ascii_only {
+        check_ascii(line, line_number)?;
     }
+
+    // Handle UTF-8 BOM
+    let line = match line.strip_prefix('\u{FEFF}') {
+        Some(rest) if line_number == 1 && options.allow_bom => rest,
+        _ => line,
+    };
     
     // Trim whitespace
-    let trimmed = line.trim();
+    let trimmed = if options.ascii_only { line.trim_ascii() } else { line.trim() };
     
     // Skip empty lines
     if trimmed.is_empty() {
@@ -198,43 +1393,74 @@ fn parse_line(
     }
     
     // Check for start-of-line comments
-    if options.start_comment_prefixes.chars().any(|c| trimmed.starts_with(c)) {
+    if options.start_comment_prefixes.prefix_of(trimmed).is_some() {
         return Ok(());
     }
     
     // Handle multi-line continuation
-    if options.allow_multiline && !prev_name.is_empty() && !trimmed.is_empty() && line.starts_with(char::is_whitespace) {
+    if continued_key(line, state, options).is_some() {
         let value = if options.allow_inline_comments {
-            // For inline comments, we need to process the trimmed version but preserve indentation
-            let comment_removed = remove_inline_comment(trimmed, &options.inline_comment_prefixes);
-            // Reconstruct with original indentation
-            let indent_len = line.len() - line.trim_start().len();
-            format!("{}{}", &line[..indent_len], comment_removed)
+            // Keep the original indentation, cut at the comment and drop what trails it
+            let kept = strip_inline_comment(trimmed, &options.inline_comment_prefixes).trim_end();
+            let indent = line.len() - line.trim_start().len();
+            &line[..indent + kept.len()]
         } else {
-            line.to_string() // Use original line to preserve indentation
+            line // Use original line to preserve indentation
         };
         
-        return handler.handle(section, prev_name, &value)
-            .map_err(|msg| IniParseError::HandlerError(msg));
+        if state.skipping_value {
+            return Ok(());
+        }
+        if options.warn_suspicious_continuation && looks_like_entry(trimmed, options) {
+            let warning = IniWarning {
+                line: Some(line_number),
+                section: state.section.clone(),
+                name: state.prev_name.clone(),
+                message: format!(
+                    "Indented line '{}' looks like an entry but continues the value of '{}'",
+                    trimmed, state.prev_name
+                ),
+            };
+            handler.warning(&warning).map_err(IniParseError::HandlerError)?;
+        }
+        let info = EntryInfo {
+            line: line_number,
+            separator: None,
+            continuation: true,
+            line_ending: state.line_ending,
+            raw_line: line,
+        };
+        let value = unescaped(value, &info, options)?;
+        return handler.handle_entry(&info, &state.section, &state.prev_name, &value)
+            .map_err(|message| IniParseError::rejected_entry(&info, message));
     }
     
     // Handle section headers
     if trimmed.starts_with('[') {
-        if let Some(end_pos) = find_char_or_comment(trimmed, ']', &options.inline_comment_prefixes, options.allow_inline_comments) {
-            if end_pos > 1 {
-                let section_name = trimmed[1..end_pos].to_string();
-                *section = section_name;
-                *prev_name = String::new();
-                
-                // Always call handler for new sections to register them
-                return handler.handle(section, "", "")
-                    .map_err(|msg| IniParseError::HandlerError(msg));
-            }
-        }
-        return Err(IniParseError::ParseError {
+        let mut header = parse_section_header(trimmed, options).ok_or_else(|| IniParseError::ParseError {
             line: line_number,
             message: "Missing ']' in section header".to_string(),
-        });
+            suggestion: close_header(line, state, options),
+        })?;
+        if header.name.trim().is_empty() {
+            if !options.allow_empty_section_names {
+                return Err(IniParseError::ParseError { line: line_number, message: "Empty section name".to_string(), suggestion: None });
+            }
+            header = SectionHeader { name: String::new(), annotation: None };
+        } else if options.strict_section_names {
+            check_section_name(&header.flattened_name(), line_number)?;
+        }
+        close_value(state, handler)?;
+        close_section(state, handler)?;
+        state.section = header.flattened_name();
+        state.in_section = true;
+
+        handler.start_section(&header).map_err(IniParseError::HandlerError)?;
+        if !options.call_handler_on_new_section {
+            return Ok(());
+        }
+        return handler.handle_at(line_number, &state.section, "", "")
+            .map_err(IniParseError::HandlerError);
     }
     
     // Handle name=value and name:value pairs
@@ -250,36 +1476,36 @@ fn parse_line(
     };
     
     if let Some(sep_pos) = sep_pos {
-        let name = trimmed[..sep_pos].trim().to_string();
-        let value = if sep_pos + 1 < trimmed.len() {
-            let value_part = &trimmed[sep_pos + 1..];
-            if options.allow_inline_comments {
-                remove_inline_comment(value_part, &options.inline_comment_prefixes)
-            } else {
-                value_part.trim().to_string()
-            }
-        } else {
-            String::new()
-        };
-        
-        *prev_name = name.clone();
+        // The separator may be a multi-byte comment prefix, so step over it by char
+        let (name_part, rest) = trimmed.split_at_checked(sep_pos).unwrap_or((trimmed, ""));
+        let mut rest = rest.chars();
+        let separator = rest.next();
+        let mut value_part = rest.as_str();
+        if !options.trim_values {
+            // `value_part` runs to the end of `trimmed`; take it from the untrimmed line instead
+            let start = (line.len() - line.trim_start().len()) + (trimmed.len() - value_part.len());
+            value_part = line.get(start..).unwrap_or(value_part);
+        }
+        let name = name_part.trim();
+        if options.allow_inline_comments {
+            value_part = strip_inline_comment(value_part, &options.inline_comment_prefixes);
+        }
+        let value = if options.trim_values { value_part.trim() } else { value_part };
         
-        return handler.handle(section, &name, &value)
-            .map_err(|msg| IniParseError::HandlerError(msg));
+        let info = EntryInfo { line: line_number, separator, continuation: false, line_ending: state.line_ending, raw_line: line };
+        return start_entry(state, handler, options, info, name, value);
     }
     
     // Handle names without values
     if options.allow_no_value && !trimmed.is_empty() {
         let name = if options.allow_inline_comments {
-            remove_inline_comment(trimmed, &options.inline_comment_prefixes)
+            strip_inline_comment(trimmed, &options.inline_comment_prefixes).trim()
         } else {
-            trimmed.to_string()
+            trimmed
         };
         
-        *prev_name = name.clone();
-        
-        return handler.handle(section, &name, "")
-            .map_err(|msg| IniParseError::HandlerError(msg));
+        let info = EntryInfo { line: line_number, separator: None, continuation: false, line_ending: state.line_ending, raw_line: line };
+        return start_entry(state, handler, options, info, name, "");
     }
     
     // If we get here and the line is not empty, it's an invalid line
@@ -289,6 +1515,7 @@ fn parse_line(
             Err(IniParseError::ParseError {
                 line: line_number,
                 message: "Invalid line format".to_string(),
+                suggestion: insert_separator(line, state, options),
             })
         } else {
             // For invalid lines, we just ignore them instead of erroring
@@ -299,11 +1526,146 @@ fn parse_line(
     }
 }
 
+/// Suggest closing a section header that lacks its ']', before any inline comment
+fn close_header(line: &str, state: &ParserState, options: &ParseOptions) -> Option<Suggestion> {
+    if state.checking_suggestion {
+        return None;
+    }
+    let header = if options.allow_inline_comments { strip_inline_comment(line, &options.inline_comment_prefixes) } else { line };
+    let at = header.trim_end().len();
+    let replacement_line = format!("{}]{}", &line[..at], &line[at..]);
+    let description = format!("insert ']' at byte {}", at);
+    parses_cleanly(&replacement_line, options).then_some(Suggestion { replacement_line, description })
+}
+
+/// Suggest turning a line without a separator into `name = rest`, the name being its first word
+fn insert_separator(line: &str, state: &ParserState, options: &ParseOptions) -> Option<Suggestion> {
+    if state.checking_suggestion {
+        return None;
+    }
+    let indent = line.len() - line.trim_start().len();
+    let name_len = line[indent..].find(char::is_whitespace).unwrap_or(line.len() - indent);
+    let (name, rest) = line.split_at(indent + name_len);
+    let replacement_line = format!("{} ={}", name, rest);
+    let description = format!("insert '=' after '{}'", name.trim_start());
+    parses_cleanly(&replacement_line, options).then_some(Suggestion { replacement_line, description })
+}
+
+/// Whether a suggested line parses without error on its own
+fn parses_cleanly(line: &str, options: &ParseOptions) -> bool {
+    struct Ignore;
+
+    impl IniHandler for Ignore {
+        fn handle(&mut self, _section: &str, _name: &str, _value: &str) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    let options = ParseOptions { stop_on_first_error: true, ..options.clone() };
+    let mut recovered = |_| {};
+    let mut handler = Ignore;
+    let mut parser = LineParser::new(&mut handler, &options, &mut recovered);
+    parser.state.checking_suggestion = true;
+    parser.feed(line, "").is_ok() && parser.finish().is_ok()
+}
+
+/// The key a line continues, if it is a multi-line continuation
+fn continued_key<'a>(line: &str, state: &'a ParserState, options: &ParseOptions) -> Option<&'a str> {
+    let trimmed = line.trim();
+    let continues = options.allow_multiline
+        && !state.prev_name.is_empty()
+        && !trimmed.is_empty()
+        && line.starts_with(char::is_whitespace)
+        && options.start_comment_prefixes.prefix_of(trimmed).is_none();
+    continues.then_some(state.prev_name.as_str())
+}
+
+/// Whether a trimmed continuation line reads like `name = value`
+///
+/// That is: an unquoted '=' or ':' before any inline comment, preceded by a
+/// single word.
+fn looks_like_entry(trimmed: &str, options: &ParseOptions) -> bool {
+    let mut quote = None;
+    let mut was_space = false;
+    for (i, ch) in trimmed.char_indices() {
+        match quote {
+            Some(open) if ch == open => quote = None,
+            Some(_) => {}
+            None if ch == '"' || ch == '\'' => quote = Some(ch),
+            None if ch == '=' || ch == ':' => {
+                let name = trimmed[..i].trim();
+                return !name.is_empty() && !name.contains(char::is_whitespace);
+            }
+            None if options.allow_inline_comments
+                && was_space
+                && options.inline_comment_prefixes.prefix_of(&trimmed[i..]).is_some() =>
+            {
+                return false;
+            }
+            None => {}
+        }
+        was_space = ch.is_whitespace();
+    }
+    false
+}
+
+/// Parse a trimmed section header line, returning None if the closing ']' is missing
+pub(crate) fn parse_section_header(trimmed: &str, options: &ParseOptions) -> Option<SectionHeader> {
+    let end_pos = find_char_or_comment(trimmed, ']', &options.inline_comment_prefixes, options.allow_inline_comments)?;
+    // An inline comment before the ']' leaves the header unclosed
+    if !trimmed[end_pos..].starts_with(']') {
+        return None;
+    }
+    let inner = trimmed.get(..end_pos)?.strip_prefix('[')?;
+    let annotated = |name: &str, annotation| SectionHeader { name: name.trim().to_string(), annotation: Some(annotation) };
+
+    if options.allow_quoted_subsections {
+        let content = inner.trim();
+        if let Some((name, rest)) = content.split_once('"') {
+            if let Some(sub) = rest.strip_suffix('"') {
+                if !name.trim().is_empty() {
+                    return Some(annotated(name, SectionAnnotation::QuotedSubsection(sub.to_string())));
+                }
+            }
+        }
+    }
+
+    if options.allow_section_inheritance {
+        if let Some((name, parent)) = inner.split_once(':') {
+            if !name.trim().is_empty() && !parent.trim().is_empty() {
+                return Some(annotated(name, SectionAnnotation::Parent(parent.trim().to_string())));
+            }
+        }
+    }
+
+    if options.allow_section_profiles {
+        if let Some((name, profiles)) = inner.split_once('@') {
+            let profiles: Vec<String> = profiles
+                .split(',')
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .map(str::to_string)
+                .collect();
+            if !name.trim().is_empty() && !profiles.is_empty() {
+                return Some(annotated(name, SectionAnnotation::Profiles(profiles)));
+            }
+        }
+    }
+
+    if options.allow_raw_section_annotations {
+        if let Some((name, rest)) = inner.trim().split_once(char::is_whitespace) {
+            return Some(annotated(name, SectionAnnotation::Raw(rest.trim().to_string())));
+        }
+    }
+
+    Some(SectionHeader { name: inner.to_string(), annotation: None })
+}
+
 /// Find a character or comment in a string
 fn find_char_or_comment(
     s: &str,
     target: char,
-    comment_prefixes: &str,
+    comment_prefixes: &CommentPrefixes,
     allow_inline_comments: bool,
 ) -> Option<usize> {
     let mut was_space = false;
@@ -313,7 +1675,7 @@ fn find_char_or_comment(
             return Some(i);
         }
         
-        if allow_inline_comments && was_space && comment_prefixes.contains(ch) {
+        if allow_inline_comments && was_space && comment_prefixes.prefix_of(&s[i..]).is_some() {
             return Some(i);
         }
         
@@ -323,16 +1685,63 @@ fn find_char_or_comment(
     None
 }
 
-/// Remove inline comment from a string
-fn remove_inline_comment(s: &str, comment_prefixes: &str) -> String {
+/// Cut a string at the start of its inline comment, without trimming
+fn strip_inline_comment<'a>(s: &'a str, comment_prefixes: &CommentPrefixes) -> &'a str {
     let mut was_space = false;
     
     for (i, ch) in s.char_indices() {
-        if was_space && comment_prefixes.contains(ch) {
-            return s[..i].trim().to_string();
+        if was_space && comment_prefixes.prefix_of(&s[i..]).is_some() {
+            return s.get(..i).unwrap_or(s);
         }
         was_space = ch.is_whitespace();
     }
     
-    s.trim().to_string()
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct ValueRecorder {
+        closed: Vec<String>,
+    }
+
+    impl IniHandler for ValueRecorder {
+        fn handle(&mut self, _section: &str, _name: &str, _value: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn end_value(&mut self, _section: &str, name: &str) -> Result<(), String> {
+            self.closed.push(name.to_string());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_finalize_closes_pending_value() {
+        let mut state = ParserState { prev_name: "key".to_string(), ..ParserState::default() };
+        let mut handler = ValueRecorder::default();
+
+        finalize(&mut state, &mut handler).unwrap();
+
+        assert_eq!(handler.closed, vec!["key"]);
+        assert!(state.prev_name.is_empty());
+    }
+
+    #[test]
+    fn test_finalize_reports_open_construct() {
+        let mut state = ParserState { open_construct: Some(("heredoc", 7)), ..ParserState::default() };
+        let mut handler = ValueRecorder::default();
+
+        let result = finalize(&mut state, &mut handler);
+
+        assert_eq!(result, Err(IniParseError::ParseError {
+            line: 7,
+            message: "Unterminated heredoc at end of input".to_string(),
+            suggestion: None,
+        }));
+        assert!(state.open_construct.is_none());
+    }
 }
@@ -0,0 +1,153 @@
+//! Path values with the home and base directories expanded
+//!
+//! [`IniReader::get_path`] reads a value as a path, replacing a leading `~`
+//! with the home directory and a few well-known variables with the base
+//! directories they name:
+//!
+//! | Variable | When unset or empty |
+//! |---|---|
+//! | `HOME`, `USERPROFILE` | the home directory |
+//! | `XDG_CONFIG_HOME` | `~/.config` |
+//! | `XDG_DATA_HOME` | `~/.local/share` |
+//! | `XDG_STATE_HOME` | `~/.local/state` |
+//! | `XDG_CACHE_HOME` | `~/.cache` |
+//! | `XDG_RUNTIME_DIR` | left as written |
+//! | `APPDATA` | `~/AppData/Roaming` |
+//! | `LOCALAPPDATA` | `~/AppData/Local` |
+//!
+//! Variables may be written `$NAME`, `${NAME}` or `%NAME%`, whatever the
+//! platform. As the XDG specification says, an `XDG_*` variable holding a
+//! relative path is ignored like an unset one. Any other variable, and one
+//! whose directory cannot be found, is left in the path as written.
+//!
+//! ```rust
+//! use std::path::PathBuf;
+//! use inih::path::{expand_path, BaseDirs};
+//!
+//! struct Fixed;
+//!
+//! impl BaseDirs for Fixed {
+//!     fn var(&self, name: &str) -> Option<std::ffi::OsString> {
+//!         (name == "HOME").then(|| "/home/ann".into())
+//!     }
+//! }
+//!
+//! assert_eq!(expand_path("$XDG_CONFIG_HOME/app.ini", &Fixed), PathBuf::from("/home/ann/.config/app.ini"));
+//! assert_eq!(expand_path("~/$TMP/x", &Fixed), PathBuf::from("/home/ann/$TMP/x"));
+//! ```
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use crate::reader::{IniReader, KeyName, SectionName};
+
+/// Source of the environment variables paths are expanded from
+///
+/// [`SystemBaseDirs`] reads the process environment; tests can supply
+/// their own.
+pub trait BaseDirs {
+    /// Value of an environment variable, None if unset
+    fn var(&self, name: &str) -> Option<OsString>;
+
+    /// The home directory: `HOME`, or `USERPROFILE` where that is unset
+    fn home_dir(&self) -> Option<PathBuf> {
+        ["HOME", "USERPROFILE"].iter().find_map(|name| self.var(name).filter(|value| !value.is_empty())).map(PathBuf::from)
+    }
+}
+
+/// The base directories of the running process, from its environment
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemBaseDirs;
+
+impl BaseDirs for SystemBaseDirs {
+    fn var(&self, name: &str) -> Option<OsString> {
+        std::env::var_os(name)
+    }
+}
+
+/// Expand `~` and the known base directory variables in `value`
+pub fn expand_path(value: &str, dirs: &dyn BaseDirs) -> PathBuf {
+    let mut expanded = OsString::new();
+    let mut rest = value;
+    if let Some(after) = value.strip_prefix('~').filter(|after| after.is_empty() || after.starts_with(['/', '\\'])) {
+        if let Some(home) = dirs.home_dir() {
+            expanded.push(home);
+            rest = after;
+        }
+    }
+
+    while let Some(start) = rest.find(['$', '%']) {
+        expanded.push(&rest[..start]);
+        rest = &rest[start..];
+        let (name, written) = match variable(rest) {
+            Some(found) => found,
+            None => {
+                expanded.push(&rest[..1]);
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        match base_dir(name, dirs) {
+            Some(dir) => expanded.push(dir),
+            None => expanded.push(&rest[..written]),
+        }
+        rest = &rest[written..];
+    }
+    expanded.push(rest);
+    PathBuf::from(expanded)
+}
+
+/// The variable name at the start of `text` and the length of the reference
+fn variable(text: &str) -> Option<(&str, usize)> {
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    if let Some(braced) = text.strip_prefix("${") {
+        let end = braced.find('}')?;
+        return Some((&braced[..end], end + 3));
+    }
+    if let Some(percent) = text.strip_prefix('%') {
+        let end = percent.find('%')?;
+        return percent[..end].chars().all(is_name).then(|| (&percent[..end], end + 2));
+    }
+    let name = &text[1..];
+    let end = name.find(|c| !is_name(c)).unwrap_or(name.len());
+    (end > 0).then(|| (&name[..end], end + 1))
+}
+
+/// Directory a known variable names, following the fallback rules
+pub(crate) fn base_dir(name: &str, dirs: &dyn BaseDirs) -> Option<OsString> {
+    let set = dirs.var(name).filter(|value| !value.is_empty());
+    let fallback: &[&str] = match name {
+        "HOME" | "USERPROFILE" => return dirs.home_dir().map(PathBuf::into_os_string),
+        "XDG_CONFIG_HOME" => &[".config"],
+        "XDG_DATA_HOME" => &[".local", "share"],
+        "XDG_STATE_HOME" => &[".local", "state"],
+        "XDG_CACHE_HOME" => &[".cache"],
+        "XDG_RUNTIME_DIR" => &[],
+        "APPDATA" => &["AppData", "Roaming"],
+        "LOCALAPPDATA" => &["AppData", "Local"],
+        _ => return None,
+    };
+    // The XDG specification has relative paths ignored
+    let set = set.filter(|value| !name.starts_with("XDG_") || Path::new(value).is_absolute());
+    if set.is_some() || fallback.is_empty() {
+        return set;
+    }
+    let mut dir = dirs.home_dir()?;
+    dir.extend(fallback);
+    Some(dir.into_os_string())
+}
+
+impl IniReader {
+    /// Get a value as a path, with the home and base directories expanded
+    ///
+    /// The variables come from the process environment; see [`crate::path`]
+    /// for what is expanded. None if the key is missing or empty.
+    pub fn get_path(&self, section: impl SectionName, name: impl KeyName) -> Option<PathBuf> {
+        self.get_path_with(section, name, &SystemBaseDirs)
+    }
+
+    /// Get a value as a path, taking the variables from `dirs`
+    pub fn get_path_with(&self, section: impl SectionName, name: impl KeyName, dirs: &dyn BaseDirs) -> Option<PathBuf> {
+        self.get_opt(section, name).filter(|value| !value.is_empty()).map(|value| expand_path(value, dirs))
+    }
+}
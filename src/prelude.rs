@@ -0,0 +1,25 @@
+//! Commonly used items, for glob import
+//!
+//! ```rust
+//! use inih::prelude::*;
+//!
+//! let reader = IniReader::from_string("[server]\nport = 8080").unwrap();
+//! assert_eq!(reader.get_integer("server", "port", 0), 8080);
+//! ```
+//!
+//! Everything exported here is prefixed or specific enough not to clash with
+//! the standard library prelude or with common names in user code (there is
+//! no `Result`, `Entry` or `Error`). Items are only ever added, never removed
+//! or renamed, outside a major release.
+
+pub use crate::encoding::{EncodingMode, WriteEncoding};
+pub use crate::error::{IniGetError, IniParseError, IniWarning, IniWriteError};
+pub use crate::fallback::FallbackChain;
+pub use crate::frozen::FrozenIniReader;
+pub use crate::handlers::{BTreeHandler, JsonWriterHandler, MapHandler, VecHandler, WriterHandler};
+pub use crate::parser::{
+    ini_parse, ini_parse_bytes, ini_parse_file, ini_parse_string, ini_parse_string_with_options, CommentPrefixes, DuplicateKeyPolicy,
+    EntryInfo, IniHandler, NewlineStyle, ParseOptions, SectionHeader,
+};
+pub use crate::reader::{ConfigSource, IniReader, InsertDecision, KeyName, ReaderOptions, SectionName};
+pub use crate::writer::{BooleanStyle, DurationStyle, IniWriter, WriteOptions};
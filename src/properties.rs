@@ -0,0 +1,251 @@
+//! Java `.properties` files, read through the INI handler machinery
+//!
+//! The `.properties` dialect has no sections: every entry reaches the
+//! [`IniHandler`] in the global section, so existing handlers and
+//! [`IniReader`](crate::IniReader) work on it unchanged:
+//!
+//! ```rust
+//! use inih::IniReader;
+//!
+//! let data = "! legacy settings\nwelcome\\ text = Hello, \\\n    world\ncopyright = \\u00A9 2024\n";
+//! let reader = IniReader::from_properties_str(data).unwrap();
+//! assert_eq!(reader.get("", "welcome text", ""), "Hello, world");
+//! assert_eq!(reader.get("", "copyright", ""), "© 2024");
+//! ```
+//!
+//! Lines are read as `java.util.Properties::load` reads them:
+//!
+//! - lines end at `\n`, `\r\n` or a lone `\r`; blank lines are skipped, and
+//!   so are lines whose first non-blank character is `#` or `!`
+//! - a line ending in an odd number of backslashes continues on the next
+//!   one: the backslash, the line break and the leading blanks of the next
+//!   line are dropped
+//! - the key runs up to the first unescaped `=`, `:` or blank; blanks around
+//!   the separator are skipped and a blank alone also separates
+//! - in keys and values, `\t`, `\n`, `\r`, `\f` and `\uXXXX` are escapes, and
+//!   a backslash before any other character stands for that character, so
+//!   `\=`, `\:`, `\ ` and `\\` put it in a key
+//! - trailing blanks belong to the value
+//!
+//! Names then go through the handler as usual: [`IniReader`](crate::IniReader)
+//! folds their case, where Java compares keys exactly. Entries are reported
+//! at the line they start on, with the separator used (`' '` for a blank) or
+//! none for a key without value, and a malformed `\u` escape is a parse error
+//! for its line.
+//!
+//! [`IniReader::to_properties_string`](crate::IniReader::to_properties_string)
+//! goes the other way, escaping as `java.util.Properties::store` does.
+
+use std::io::Read;
+
+use crate::error::IniParseError;
+use crate::parser::{EntryInfo, IniHandler};
+
+/// Parse `.properties` data from a string
+///
+/// As with INI input, parsing continues after an error and the first one is
+/// returned at the end.
+pub fn ini_parse_properties(data: &str, handler: &mut dyn IniHandler) -> Result<(), IniParseError> {
+    let mut first_error: Option<IniParseError> = None;
+    let mut lines = physical_lines(data);
+    handler.start_document();
+
+    while let Some((line_number, line, line_ending)) = lines.next() {
+        let content = line.trim_start_matches(is_blank);
+        if content.is_empty() || content.starts_with(['#', '!']) {
+            continue;
+        }
+
+        let mut logical = String::new();
+        let mut current = content;
+        let mut line_ending = line_ending;
+        while continues(current) {
+            logical.push_str(&current[..current.len() - 1]);
+            match lines.next() {
+                Some((_, next, next_ending)) => {
+                    current = next.trim_start_matches(is_blank);
+                    line_ending = next_ending;
+                }
+                None => {
+                    current = "";
+                    break;
+                }
+            }
+        }
+        logical.push_str(current);
+
+        let result = parse_entry(&logical, line_number).and_then(|(name, separator, value)| {
+            let info = EntryInfo { line: line_number, separator, continuation: false, line_ending, raw_line: line };
+            handler
+                .handle_entry(&info, "", &name, &value)
+                .map_err(|message| IniParseError::rejected_entry(&info, message))?;
+            handler.end_value("", &name).map_err(IniParseError::HandlerError)
+        });
+        if let Err(error) = result {
+            first_error.get_or_insert(error);
+        }
+    }
+
+    if let Err(message) = handler.end_document() {
+        first_error.get_or_insert(IniParseError::HandlerError(message));
+    }
+    match first_error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Parse `.properties` data from a Read object
+///
+/// The input is taken as UTF-8 if it is valid UTF-8 and as ISO-8859-1, the
+/// encoding Java reads byte streams in, otherwise. Fails with `FileOpen` on
+/// read errors.
+pub fn ini_parse_properties_file<R: Read>(mut file: R, handler: &mut dyn IniHandler) -> Result<(), IniParseError> {
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).map_err(|e| IniParseError::FileOpen(e.to_string()))?;
+    let data = match String::from_utf8(bytes) {
+        Ok(data) => data,
+        Err(e) => e.into_bytes().iter().map(|&byte| char::from(byte)).collect(),
+    };
+    ini_parse_properties(&data, handler)
+}
+
+/// Escape a key or value for a `.properties` line, as `java.util.Properties::store` does
+///
+/// Backslash, `=`, `:`, `#`, `!`, tab, line breaks and form feed are
+/// escaped everywhere, spaces everywhere in a key but only at the start of a
+/// value, and characters outside printable ASCII as `\uXXXX` (a surrogate
+/// pair for those beyond the Basic Multilingual Plane).
+pub(crate) fn escape(text: &str, is_key: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (index, c) in text.char_indices() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\x0c' => out.push_str("\\f"),
+            '=' | ':' | '#' | '!' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' ' if is_key || index == 0 => out.push_str("\\ "),
+            ' '..='~' => out.push(c),
+            c => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    out.push_str(&format!("\\u{:04X}", unit));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Blanks as `.properties` sees them: space, tab and form feed
+fn is_blank(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\x0c')
+}
+
+/// Physical lines with their number and terminator
+fn physical_lines(data: &str) -> impl Iterator<Item = (usize, &str, &'static str)> {
+    let mut rest = data;
+    let mut line_number = 0;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        line_number += 1;
+        let (line, ending, len) = match rest.find(['\n', '\r']) {
+            Some(end) if rest[end..].starts_with("\r\n") => (&rest[..end], "\r\n", end + 2),
+            Some(end) if rest[end..].starts_with('\r') => (&rest[..end], "\r", end + 1),
+            Some(end) => (&rest[..end], "\n", end + 1),
+            None => (rest, "", rest.len()),
+        };
+        rest = &rest[len..];
+        Some((line_number, line, ending))
+    })
+}
+
+/// Whether a line ends in an odd number of backslashes, i.e. continues on the next
+fn continues(line: &str) -> bool {
+    let backslashes = line.len() - line.trim_end_matches('\\').len();
+    backslashes % 2 == 1
+}
+
+/// Split a logical line into unescaped key, separator and unescaped value
+fn parse_entry(line: &str, line_number: usize) -> Result<(String, Option<char>, String), IniParseError> {
+    let mut chars = line.chars();
+    let mut name = String::new();
+    let mut separator = None;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescape(&mut chars, &mut name, line_number)?,
+            '=' | ':' => {
+                separator = Some(c);
+                break;
+            }
+            c if is_blank(c) => {
+                let rest = chars.as_str().trim_start_matches(is_blank);
+                separator = Some(' ');
+                chars = rest.chars();
+                if let Some(explicit @ ('=' | ':')) = rest.chars().next() {
+                    separator = Some(explicit);
+                    chars.next();
+                }
+                break;
+            }
+            c => name.push(c),
+        }
+    }
+
+    let mut value = String::new();
+    let mut chars = chars.as_str().trim_start_matches(is_blank).chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescape(&mut chars, &mut value, line_number)?,
+            c => value.push(c),
+        }
+    }
+    Ok((name, separator, value))
+}
+
+/// Decode the escape following a backslash into `out`
+fn unescape(chars: &mut std::str::Chars, out: &mut String, line_number: usize) -> Result<(), IniParseError> {
+    let c = match chars.next() {
+        Some('t') => '\t',
+        Some('n') => '\n',
+        Some('r') => '\r',
+        Some('f') => '\x0c',
+        Some('u') => unicode_escape(chars, line_number)?,
+        Some(c) => c,
+        None => return Ok(()),
+    };
+    out.push(c);
+    Ok(())
+}
+
+/// Decode a `\uXXXX` escape whose `\u` has been read, joining a surrogate pair
+fn unicode_escape(chars: &mut std::str::Chars, line_number: usize) -> Result<char, IniParseError> {
+    let error = |message: &str| IniParseError::ParseError { line: line_number, message: message.to_string(), suggestion: None };
+    let high = hex4(chars).ok_or_else(|| error("Malformed \\uXXXX escape"))?;
+    if !(0xD800..0xDC00).contains(&high) {
+        return char::from_u32(high).ok_or_else(|| error("Unpaired surrogate in \\u escape"));
+    }
+    let low = chars.as_str().strip_prefix("\\u").and_then(|rest| hex4(&mut rest.chars()));
+    match low {
+        Some(low @ 0xDC00..0xE000) => {
+            chars.nth(5);
+            char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)).ok_or_else(|| error("Malformed \\uXXXX escape"))
+        }
+        _ => Err(error("Unpaired surrogate in \\u escape")),
+    }
+}
+
+/// Four hexadecimal digits
+fn hex4(chars: &mut std::str::Chars) -> Option<u32> {
+    let mut code = 0;
+    for _ in 0..4 {
+        code = code * 16 + chars.next()?.to_digit(16)?;
+    }
+    Some(code)
+}
@@ -1,44 +1,429 @@
 //! High-level INI reader with easy-to-use API
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt;
 use std::fs::File;
-use std::io::Read;
-use std::path::Path;
+use std::io::{self, Read, Write};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 
-use crate::error::IniParseError;
-use crate::parser::{ini_parse_file_with_options, IniHandler, ParseOptions};
+use crate::document::IniDocument;
+use crate::encoding::WriteEncoding;
+use crate::error::{IniGetError, IniParseError, IniWarning, IniWriteError, InterpolationError};
+use crate::explain::{Explanation, Source, Step};
+use crate::expansion::{Expansion, ExpansionBudget};
+use crate::frozen::FrozenIniReader;
+use crate::handlers::SectionFilter;
+use crate::interpolation::{Entry, Interpolation, Resolver};
+use crate::parser::{ini_parse_file_with_options, ini_parse_string_collecting, DuplicateKeyPolicy, EntryInfo, IniHandler, NewlineStyle, ParseOptions, SectionHeader};
+use crate::properties::{self, ini_parse_properties, ini_parse_properties_file};
+use crate::report::ParseReport;
+use crate::tables::{self, SectionTables};
+use crate::value;
+use crate::writer::{self, entry_line, section_header, WriteOptions};
+
+/// Something that names a section, accepted wherever a getter takes a section
+///
+/// Implemented for every string type; implement it for an enum of known
+/// sections to pass its variants directly.
+pub trait SectionName {
+    /// The section name as written in the file
+    fn section_name(&self) -> &str;
+}
+
+impl<T: AsRef<str> + ?Sized> SectionName for T {
+    fn section_name(&self) -> &str {
+        self.as_ref()
+    }
+}
+
+/// Something that names a key, accepted wherever a getter takes a key name
+///
+/// Implemented for every string type; implement it for an enum of known
+/// keys to pass its variants directly.
+pub trait KeyName {
+    /// The key name as written in the file
+    fn key_name(&self) -> &str;
+}
+
+impl<T: AsRef<str> + ?Sized> KeyName for T {
+    fn key_name(&self) -> &str {
+        self.as_ref()
+    }
+}
+
+/// One occurrence of a key, as written at a given line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    /// Key as spelled at this occurrence, before case folding
+    pub name: String,
+    /// Value of this occurrence, including its continuation lines
+    pub value: String,
+    /// Line the key appeared on, 0 for values added with `Extend`
+    pub line: usize,
+    /// The key was written without a separator or value (`allow_no_value`)
+    pub bare: bool,
+}
+
+/// What a [`ReaderOptions::on_insert`] hook decides about an entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InsertDecision {
+    /// Store the value as read
+    Accept,
+    /// Store this value instead of the one read
+    AcceptRewritten(String),
+    /// Leave the entry out, as if it was not in the input
+    Skip,
+    /// Leave the entry out and record an error for its line
+    Reject(String),
+}
+
+/// Hook called with the section, name and complete value of each entry
+pub type InsertHook = Box<dyn FnMut(&str, &str, &str) -> InsertDecision + Send + Sync>;
+
+/// Options for an [`IniReader`] that the parser does not see
+///
+/// They belong to the reader, so they apply whoever parses into it, even
+/// with parser options owned by someone else.
+#[derive(Default)]
+pub struct ReaderOptions {
+    /// Called for each entry before it is stored
+    ///
+    /// The hook sees the section, the name as written and the complete value,
+    /// continuation lines included, once no more lines can be added to it.
+    /// A rejected entry is recorded as a `RejectedEntry` error for its first
+    /// line, returned by [`IniReader::parse_error`] and by the constructor
+    /// once the input has been read; loading goes on, so later entries are
+    /// still seen. Values added with the setters or [`Extend`] do not pass
+    /// through the hook.
+    pub on_insert: Option<InsertHook>,
+    /// Compare section and key names exactly instead of case-insensitively
+    ///
+    /// Lookups, `has_value`, `keys` and the mutators then tell `Key` from
+    /// `key`, and keys are listed as written (still trimmed). The constructors
+    /// taking reader options have the parser's duplicate detection tell case
+    /// apart too; when parsing into a reader yourself, set
+    /// [`ParseOptions::case_sensitive_duplicates`] to match.
+    pub case_sensitive: bool,
+    /// References between values to resolve once each document is read
+    ///
+    /// See [`crate::interpolation`]; errors are listed by
+    /// [`IniReader::interpolation_errors`].
+    pub interpolation: Interpolation,
+    /// Also report references to missing keys, which are always left as written
+    pub strict_interpolation: bool,
+    /// Section whose keys are fallbacks for every other section, as `[DEFAULT]` in Python's ConfigParser
+    ///
+    /// A getter that misses in the section asked for looks the key up in
+    /// this one before returning its default; a key set in both places is
+    /// read from the section asked for. The name matches as section names
+    /// do in lookups. [`IniReader::has_value`] and [`IniReader::keys`] do not
+    /// look at it, [`IniReader::keys_with_defaults`] does.
+    pub default_section: Option<String>,
+    /// List the sections the filtered constructors skip
+    ///
+    /// See [`IniReader::from_file_filtered`] and
+    /// [`IniReader::skipped_sections`]; other constructors skip nothing.
+    pub record_skipped_sections: bool,
+}
+
+impl fmt::Debug for ReaderOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReaderOptions")
+            .field("on_insert", &self.on_insert.is_some())
+            .field("case_sensitive", &self.case_sensitive)
+            .field("interpolation", &self.interpolation)
+            .field("strict_interpolation", &self.strict_interpolation)
+            .field("default_section", &self.default_section)
+            .field("record_skipped_sections", &self.record_skipped_sections)
+            .finish()
+    }
+}
+
+/// An entry held back until its value is complete, for the insert hook
+#[derive(Debug, Clone)]
+struct PendingEntry {
+    section: String,
+    name: String,
+    value: String,
+    line: usize,
+    raw_line: String,
+    bare: bool,
+}
+
+/// Where [`IniReader::from_file_or_default`] took the configuration from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// The file at this path
+    File(PathBuf),
+    /// The built-in defaults, because no file existed
+    BuiltinDefaults,
+}
 
 /// High-level INI reader that stores all values in memory for easy access
+///
+/// Keys are normalized before they are stored or looked up: surrounding
+/// whitespace (spaces and tabs alike) is trimmed and the result is case-folded,
+/// so `Key`, `key ` and `\tKEY` are the same key. This is the same rule the
+/// parser applies when detecting duplicates. The spelling used at each
+/// occurrence is kept in [`Occurrence::name`]. With
+/// [`ReaderOptions::case_sensitive`] names are only trimmed.
+///
+/// The reader remembers the order in which sections and keys first appeared.
+/// `sections()`, `keys()`, `iter()`, `Display` and `to_ini_string` all follow
+/// that document order; a section with several headers keeps the place of
+/// its first one. Nothing the reader outputs
+/// depends on hash order: `Debug` prints values and sections sorted, and only
+/// [`as_map`](IniReader::as_map), which returns a `HashMap`, is unordered.
+///
+/// A reader can be passed as the handler to several parses to accumulate
+/// documents. Each document is merged into what is already there: a key set
+/// by a later document replaces the earlier value outright (last document
+/// wins), while duplicates within one document still follow the duplicate key
+/// policy. New sections and keys are ordered after the existing ones. Nothing
+/// of a document's parsing state carries over into the next, even from a
+/// parse cut short by `stop_on_first_error`, and `parse_error()` keeps
+/// reporting the error recorded at construction. [`IniReader::clear`] starts
+/// over.
+///
+/// Cloning is cheap: a clone shares the values, their occurrences and order
+/// with the original, one table per section, until either side changes a
+/// section, and only the tables of that section are copied then. The list of
+/// section names is shared the same way, as one table.
+/// [`shared_storage_count`](IniReader::shared_storage_count) and
+/// [`shared_section_count`](IniReader::shared_section_count) show what is
+/// still shared.
 pub struct IniReader {
-    values: HashMap<String, String>,
-    sections: std::collections::HashSet<String>,
-    error: Option<IniParseError>,
+    values: SectionTables<String>,
+    sections: Arc<std::collections::HashSet<String>>,
+    /// Position of each section's first appearance
+    section_order: Arc<HashMap<String, usize>>,
+    /// Position of each key's first appearance, by normalized key
+    key_order: SectionTables<usize>,
+    /// Document order of the sections and keys, built on first use
+    order_index: OnceLock<OrderIndex>,
+    errors: Vec<IniParseError>,
+    document: Option<IniDocument>,
+    duplicate_policy: DuplicateKeyPolicy,
+    value_newline: NewlineStyle,
+    /// Terminator of the last entry line, for joining continuations
+    last_line_ending: &'static str,
+    /// Every occurrence of each key, in document order
+    occurrences: SectionTables<Vec<Occurrence>>,
+    /// Keys set by the document currently being parsed
+    document_keys: std::collections::HashSet<String>,
+    /// Warnings recorded while parsing and by lenient getters
+    warnings: Mutex<Vec<IniWarning>>,
+    on_insert: Option<InsertHook>,
+    /// Entry waiting for the insert hook
+    pending: Option<PendingEntry>,
+    /// Names are compared exactly, see [`ReaderOptions::case_sensitive`]
+    case_sensitive: bool,
+    interpolation: Interpolation,
+    strict_interpolation: bool,
+    /// Budget of a document whose includes opened no account, from the parser options if known
+    expansion_budget: ExpansionBudget,
+    /// Account of the document being parsed, shared by its includes and interpolation
+    expansion: Option<Expansion>,
+    interpolation_errors: Vec<InterpolationError>,
+    /// Values interpolation changed, as written and as interpolated, by normalized key
+    interpolated: Arc<HashMap<String, (String, String)>>,
+    /// Fallback section of lookups, see [`ReaderOptions::default_section`]
+    default_section: Option<String>,
+    /// Whether the filtered constructors record what they skip, see [`ReaderOptions::record_skipped_sections`]
+    record_skipped: bool,
+    /// Sections a filtered constructor skipped, in order of first appearance
+    skipped_sections: Vec<String>,
 }
 
 impl IniReader {
     /// Create a new INI reader from a file path
+    ///
+    /// Fails with `FileOpenContext` if the file cannot be opened, `FileOpen` if
+    /// it cannot be read and with `ParseError` for invalid input. Storing values
+    /// cannot fail, so reader constructors never return `HandlerError`.
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, IniParseError> {
-        let file = File::open(path)?;
+        let file = File::open(path.as_ref()).map_err(|e| IniParseError::file_open(path.as_ref(), e))?;
         Self::from_reader(file)
     }
 
+    /// Create a new INI reader from a file path with custom options
+    ///
+    /// Follows `@include` lines when [`ParseOptions::allow_includes`] is set;
+    /// included values are stored as if written in the including file.
+    pub fn from_file_with_options<P: AsRef<Path>>(path: P, options: &ParseOptions) -> Result<Self, IniParseError> {
+        let mut ini_reader = Self::with_capacity(0, 0);
+        ini_reader.duplicate_policy = options.duplicate_key_policy.clone();
+        ini_reader.value_newline = options.value_newline;
+        ini_reader.expansion_budget = options.expansion_budget;
+
+        let result = crate::parser::ini_parse_with_options(path, &mut ini_reader, options);
+        ini_reader.loaded(result)
+    }
+
+    /// Load a file if it exists, otherwise the built-in `defaults`
+    ///
+    /// Only a missing file falls back to `defaults`, which are parsed with the
+    /// same options. A file that exists but cannot be read fails with
+    /// `FileOpenContext`, and one that does not parse fails with `InFile`
+    /// naming it: falling back silently would hide a broken configuration.
+    pub fn from_file_or_default<P: AsRef<Path>>(
+        path: P,
+        defaults: &str,
+        options: &ParseOptions,
+    ) -> Result<(Self, ConfigSource), IniParseError> {
+        Self::from_first_existing(&[path], defaults, options)
+    }
+
+    /// Load the first of `paths` that exists, otherwise the built-in `defaults`
+    ///
+    /// Paths are tried in order and the search stops at the first file that
+    /// exists, whether or not it is valid; errors are as for
+    /// [`from_file_or_default`](Self::from_file_or_default).
+    pub fn from_first_existing<P: AsRef<Path>>(
+        paths: &[P],
+        defaults: &str,
+        options: &ParseOptions,
+    ) -> Result<(Self, ConfigSource), IniParseError> {
+        for path in paths {
+            let path = path.as_ref();
+            let bytes = match std::fs::read(path) {
+                Ok(bytes) => bytes,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(IniParseError::file_open(path, e)),
+            };
+            let in_file = |error| IniParseError::InFile { path: path.to_path_buf(), error: Box::new(error) };
+            let reader = Self::from_bytes(&bytes, options).map_err(in_file)?;
+            return Ok((reader, ConfigSource::File(path.to_path_buf())));
+        }
+        Ok((Self::from_string_with_options(defaults, options)?, ConfigSource::BuiltinDefaults))
+    }
+
+    /// Create an empty INI reader with room for the given number of sections and keys
+    pub fn with_capacity(sections: usize, keys: usize) -> Self {
+        Self {
+            values: SectionTables::with_capacity(sections, keys),
+            sections: Arc::new(std::collections::HashSet::with_capacity(sections)),
+            section_order: Arc::new(HashMap::with_capacity(sections)),
+            key_order: SectionTables::with_capacity(sections, keys),
+            order_index: OnceLock::new(),
+            errors: Vec::new(),
+            document: None,
+            duplicate_policy: DuplicateKeyPolicy::default(),
+            value_newline: NewlineStyle::default(),
+            last_line_ending: "",
+            occurrences: SectionTables::with_capacity(sections, keys),
+            document_keys: std::collections::HashSet::new(),
+            warnings: Mutex::new(Vec::new()),
+            on_insert: None,
+            pending: None,
+            case_sensitive: false,
+            interpolation: Interpolation::None,
+            strict_interpolation: false,
+            expansion_budget: ExpansionBudget::default(),
+            expansion: None,
+            interpolation_errors: Vec::new(),
+            interpolated: Arc::default(),
+            default_section: None,
+            record_skipped: false,
+            skipped_sections: Vec::new(),
+        }
+    }
+
+    /// Create an empty INI reader with the given options, for use as a handler
+    pub fn with_reader_options(options: ReaderOptions) -> Self {
+        let mut reader = Self::with_capacity(0, 0);
+        reader.on_insert = options.on_insert;
+        reader.case_sensitive = options.case_sensitive;
+        reader.interpolation = options.interpolation;
+        reader.strict_interpolation = options.strict_interpolation;
+        reader.default_section = options.default_section;
+        reader.record_skipped = options.record_skipped_sections;
+        reader
+    }
+
+    /// Create a new INI reader from a file path, pre-reserving storage
+    ///
+    /// The file is read twice: a cheap [`ini_estimate`](crate::ini_estimate) pass
+    /// sizes the lookup tables so that loading does not have to rehash them.
+    pub fn from_file_prereserved<P: AsRef<Path>>(path: P, options: &ParseOptions) -> Result<Self, IniParseError> {
+        let estimate = crate::estimate::ini_estimate(path.as_ref(), options)?;
+        let mut ini_reader = Self::with_capacity(estimate.sections, estimate.keys);
+        ini_reader.duplicate_policy = options.duplicate_key_policy.clone();
+        ini_reader.value_newline = options.value_newline;
+        ini_reader.expansion_budget = options.expansion_budget;
+
+        let file = File::open(path.as_ref()).map_err(|e| IniParseError::file_open(path.as_ref(), e))?;
+        let result = ini_parse_file_with_options(file, &mut ini_reader, options);
+        ini_reader.loaded(result)
+    }
+
+    /// Create a new INI reader holding only the given sections of a file
+    ///
+    /// The file is streamed; entries of other sections are dropped as they are
+    /// parsed, so memory use depends only on the sections that are kept.
+    /// Sections are matched case-insensitively and `""` selects the global
+    /// section. To list the sections that were skipped, set
+    /// [`ReaderOptions::record_skipped_sections`] and use
+    /// [`from_file_filtered_with_reader_options`](Self::from_file_filtered_with_reader_options).
+    pub fn from_file_filtered<P: AsRef<Path>>(path: P, options: &ParseOptions, sections: &[&str]) -> Result<Self, IniParseError> {
+        Self::from_file_filtered_with_reader_options(path, options, ReaderOptions::default(), sections)
+    }
+
+    /// Create a new INI reader holding only the given sections of a file, with custom reader options
+    ///
+    /// Sections are matched the way the reader compares names, so exactly
+    /// when [`ReaderOptions::case_sensitive`] is set.
+    pub fn from_file_filtered_with_reader_options<P: AsRef<Path>>(
+        path: P,
+        options: &ParseOptions,
+        reader_options: ReaderOptions,
+        sections: &[&str],
+    ) -> Result<Self, IniParseError> {
+        let mut ini_reader = Self::with_reader_options(reader_options);
+        ini_reader.duplicate_policy = options.duplicate_key_policy.clone();
+        ini_reader.value_newline = options.value_newline;
+        ini_reader.expansion_budget = options.expansion_budget;
+
+        let case_sensitive = ini_reader.case_sensitive;
+        let sensitive;
+        let options = if case_sensitive && !options.case_sensitive_duplicates {
+            sensitive = ParseOptions { case_sensitive_duplicates: true, ..options.clone() };
+            &sensitive
+        } else {
+            options
+        };
+        let record_skipped = ini_reader.record_skipped;
+        let file = File::open(path.as_ref()).map_err(|e| IniParseError::file_open(path.as_ref(), e))?;
+        let mut filter = SectionFilter::new(&mut ini_reader, sections).case_sensitive(case_sensitive).record_skipped(record_skipped);
+        let result = ini_parse_file_with_options(file, &mut filter, options);
+        let skipped = filter.skipped_sections().to_vec();
+        ini_reader.skipped_sections = skipped;
+        ini_reader.loaded(result)
+    }
+
     /// Create a new INI reader from a Read object
+    ///
+    /// Fails with `FileOpen` on read errors and with `ParseError` for invalid input.
     pub fn from_reader<R: Read>(reader: R) -> Result<Self, IniParseError> {
-        let mut ini_reader = Self {
-            values: HashMap::new(),
-            sections: std::collections::HashSet::new(),
-            error: None,
-        };
-        
-        let options = ParseOptions::default();
-        match ini_parse_file_with_options(reader, &mut ini_reader, &options) {
-            Ok(()) => Ok(ini_reader),
-            Err(e) => {
-                ini_reader.error = Some(e.clone());
-                Err(e)
-            }
-        }
+        Self::from_reader_with_options(reader, &ParseOptions::default())
+    }
+
+    /// Create a new INI reader from a Read object with custom options
+    ///
+    /// Gives the same reader as [`from_string_with_options`](Self::from_string_with_options)
+    /// on the same text, whether or not it ends with a newline.
+    pub fn from_reader_with_options<R: Read>(reader: R, options: &ParseOptions) -> Result<Self, IniParseError> {
+        let mut ini_reader = Self::with_capacity(0, 0);
+        ini_reader.duplicate_policy = options.duplicate_key_policy.clone();
+        ini_reader.value_newline = options.value_newline;
+        ini_reader.expansion_budget = options.expansion_budget;
+
+        let result = ini_parse_file_with_options(reader, &mut ini_reader, options);
+        ini_reader.loaded(result)
     }
 
     /// Create a new INI reader from a string
@@ -47,138 +432,1436 @@ impl IniReader {
     }
     
     /// Create a new INI reader from a string with custom options
+    ///
+    /// Fails only with `ParseError`.
     pub fn from_string_with_options(data: &str, options: &ParseOptions) -> Result<Self, IniParseError> {
-        let mut ini_reader = Self {
-            values: HashMap::new(),
-            sections: std::collections::HashSet::new(),
-            error: None,
+        Self::from_string_with_reader_options(data, options, ReaderOptions::default())
+    }
+
+    /// Create a new INI reader from a string with custom parser and reader options
+    ///
+    /// Fails with `ParseError`, or with `RejectedEntry` for the first entry
+    /// the insert hook rejected if that comes first in the input.
+    pub fn from_string_with_reader_options(
+        data: &str,
+        options: &ParseOptions,
+        reader_options: ReaderOptions,
+    ) -> Result<Self, IniParseError> {
+        let mut ini_reader = Self::with_reader_options(reader_options);
+        ini_reader.duplicate_policy = options.duplicate_key_policy.clone();
+        ini_reader.value_newline = options.value_newline;
+        ini_reader.expansion_budget = options.expansion_budget;
+
+        let sensitive;
+        let options = if ini_reader.case_sensitive && !options.case_sensitive_duplicates {
+            sensitive = ParseOptions { case_sensitive_duplicates: true, ..options.clone() };
+            &sensitive
+        } else {
+            options
         };
-        
-        match crate::parser::ini_parse_string_with_options(data, &mut ini_reader, options) {
-            Ok(()) => Ok(ini_reader),
-            Err(e) => {
-                ini_reader.error = Some(e.clone());
-                Err(e)
+        let result = crate::parser::ini_parse_string_with_options(data, &mut ini_reader, options);
+        ini_reader.loaded(result)
+    }
+
+    /// Load everything readable from a string, reporting every error and warning
+    ///
+    /// Unlike the other constructors this never fails: entries on invalid
+    /// lines are left out and the rest is kept. Every error is also kept by
+    /// the reader, see [`parse_errors`](Self::parse_errors).
+    pub fn from_string_lossy(data: &str, options: &ParseOptions) -> (Self, ParseReport) {
+        Self::lossy(data, options, None)
+    }
+
+    /// Load everything readable, starting from a warning the decoding recorded
+    fn lossy(data: &str, options: &ParseOptions, fallback: Option<IniWarning>) -> (Self, ParseReport) {
+        let mut ini_reader = Self::with_capacity(0, 0);
+        ini_reader.duplicate_policy = options.duplicate_key_policy.clone();
+        ini_reader.value_newline = options.value_newline;
+        ini_reader.expansion_budget = options.expansion_budget;
+        if let Some(warning) = fallback {
+            ini_reader.warn(warning);
+        }
+
+        let errors = ini_parse_string_collecting(data, &mut ini_reader, options).err().unwrap_or_default();
+        // Entries the insert hook rejected were recorded as they came
+        ini_reader.errors.extend(errors);
+        ini_reader.errors.sort_by_key(|error| error.line().unwrap_or(usize::MAX));
+        let report = ParseReport::new(ini_reader.errors.clone(), &ini_reader.warnings());
+        (ini_reader, report)
+    }
+
+    /// Load everything readable from a file, as [`from_string_lossy`](Self::from_string_lossy) does
+    ///
+    /// The report is named after the path. A file that cannot be read gives
+    /// an empty reader and a report with that single error.
+    pub fn from_file_lossy<P: AsRef<Path>>(path: P, options: &ParseOptions) -> (Self, ParseReport) {
+        let path = path.as_ref();
+        let data = std::fs::read(path)
+            .map_err(|e| IniParseError::file_open(path, e))
+            .and_then(|bytes| crate::encoding::decode_with(&bytes, options.encoding));
+        let (reader, report) = match data {
+            Ok((data, fallback)) => Self::lossy(&data, options, fallback),
+            Err(error) => {
+                let mut reader = Self::with_capacity(0, 0);
+                reader.errors.push(error.clone());
+                (reader, ParseReport::new(vec![error], &[]))
+            }
+        };
+        (reader, report.with_source(path.display().to_string()))
+    }
+
+    /// Create a new INI reader from raw bytes, detecting UTF-16 by its byte order mark
+    ///
+    /// Other bytes are decoded as `options.encoding` says. Fails with
+    /// `Encoding` for undecodable bytes and with `ParseError` for invalid input.
+    pub fn from_bytes(bytes: &[u8], options: &ParseOptions) -> Result<Self, IniParseError> {
+        let mut ini_reader = Self::with_capacity(0, 0);
+        ini_reader.duplicate_policy = options.duplicate_key_policy.clone();
+        ini_reader.value_newline = options.value_newline;
+        ini_reader.expansion_budget = options.expansion_budget;
+
+        let result = crate::parser::ini_parse_bytes(bytes, &mut ini_reader, options);
+        ini_reader.loaded(result)
+    }
+
+    /// Create a new reader from Java `.properties` data
+    ///
+    /// All keys land in the global section; see [`properties`](crate::properties)
+    /// for the dialect. A key given twice keeps its last value, as in Java.
+    /// Fails only with `ParseError`.
+    pub fn from_properties_str(data: &str) -> Result<Self, IniParseError> {
+        let mut ini_reader = Self::with_capacity(0, 0);
+        ini_reader.duplicate_policy = DuplicateKeyPolicy::LastWins;
+        let result = ini_parse_properties(data, &mut ini_reader);
+        ini_reader.loaded(result)
+    }
+
+    /// Create a new reader from a Java `.properties` file
+    ///
+    /// As [`from_properties_str`](Self::from_properties_str). Fails with `FileOpenContext` if the file cannot be opened, `FileOpen`
+    /// on read errors and `ParseError` for invalid input.
+    pub fn from_properties_file<P: AsRef<Path>>(path: P) -> Result<Self, IniParseError> {
+        let file = File::open(path.as_ref()).map_err(|e| IniParseError::file_open(path.as_ref(), e))?;
+        let mut ini_reader = Self::with_capacity(0, 0);
+        ini_reader.duplicate_policy = DuplicateKeyPolicy::LastWins;
+        let result = ini_parse_properties_file(file, &mut ini_reader);
+        ini_reader.loaded(result)
+    }
+
+    /// Create a new INI reader from a string, preserving the document as written
+    ///
+    /// In addition to the usual lookup tables the reader keeps a lossless
+    /// [`IniDocument`] (original casing, document order, separators and comments),
+    /// and [`IniReader::to_ini_string`] reproduces the input exactly, modulo
+    /// normalization of line endings to `\n`.
+    pub fn from_string_preserving(data: &str, options: &ParseOptions) -> Result<Self, IniParseError> {
+        let mut ini_reader = Self::from_string_with_options(data, options)?;
+        ini_reader.document = Some(IniDocument::parse(data, options)?);
+        Ok(ini_reader)
+    }
+
+    /// Create a new INI reader from a file path, preserving the document as written
+    pub fn from_file_preserving<P: AsRef<Path>>(path: P, options: &ParseOptions) -> Result<Self, IniParseError> {
+        let data = std::fs::read_to_string(path.as_ref()).map_err(|e| IniParseError::file_open(path.as_ref(), e))?;
+        Self::from_string_preserving(&data, options)
+    }
+
+    /// Get the preserved document, if the reader was created in preserving mode
+    pub fn document(&self) -> Option<&IniDocument> {
+        self.document.as_ref()
+    }
+
+    /// Serialize the reader back to INI text
+    ///
+    /// A reader created in preserving mode writes its document back unchanged.
+    /// Otherwise global values come first, followed by each section in order
+    /// of first appearance with its keys in order of first appearance.
+    pub fn to_ini_string(&self) -> String {
+        self.to_ini_string_with(&WriteOptions::default())
+    }
+
+    /// Serialize the reader back to INI text with the given options
+    ///
+    /// Keys that were bare in the input are written bare unless
+    /// `options.normalize_bare_keys` is set.
+    pub fn to_ini_string_with(&self, options: &WriteOptions) -> String {
+        if let Some(document) = &self.document {
+            return document.to_string();
+        }
+
+        let mut out = String::new();
+        let write_key = |out: &mut String, section: &str, key: &str| {
+            if self.is_flag(section, key) && !options.normalize_bare_keys {
+                out.push_str(&format!("{}\n", key));
+            } else {
+                out.push_str(&entry_line(key, &self.get(section, key, ""), options));
+                out.push('\n');
             }
+        };
+        for key in self.keys("") {
+            write_key(&mut out, "", &key);
         }
+        for section in self.sections() {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&section_header(&section));
+            out.push('\n');
+            for key in self.keys(&section) {
+                write_key(&mut out, &section, &key);
+            }
+        }
+        out
+    }
+
+    /// Serialize the reader to bytes in the given encoding
+    pub fn to_ini_bytes(&self, encoding: WriteEncoding) -> Vec<u8> {
+        crate::encoding::encode(&self.to_ini_string(), encoding)
+    }
+
+    /// Serialize the values as Java `.properties` text
+    ///
+    /// Every value becomes one `section<separator>key=value` line, in document
+    /// order; global values come first and have no prefix. Keys are in their
+    /// normalized form, and keys and values are escaped as
+    /// `java.util.Properties::store` escapes them, so
+    /// [`IniReader::from_properties_str`] reads every value back unchanged.
+    /// Sections without keys are left out.
+    pub fn to_properties_string(&self, section_separator: &str) -> String {
+        let mut out = String::new();
+        for (section, key, value) in self.normalized_entries() {
+            let name = if section.is_empty() { key.to_string() } else { format!("{}{}{}", section, section_separator, key) };
+            out.push_str(&properties::escape(&name, true));
+            out.push('=');
+            out.push_str(&properties::escape(value, false));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Serialize the values to a canonical, diff-friendly text form
+    ///
+    /// Every entry is one line `section<TAB>key<TAB>value`; a declared section
+    /// without entries is a line holding just its name. Lines are sorted
+    /// byte-wise by case-folded section and key, so the global section comes
+    /// first and the output does not depend on input order or locale. Keys
+    /// are in their normalized form. Backslash, tab, newline and carriage
+    /// return are escaped as `\\`, `\t`, `\n` and `\r`, which makes the form
+    /// unambiguous and reversible with [`IniReader::from_canonical_string`].
+    pub fn to_canonical_string(&self) -> String {
+        let mut sections: Vec<(String, &str)> = self.sections.iter().map(|section| (self.fold(section), section.as_str())).collect();
+        sections.push((String::new(), ""));
+        sections.sort();
+        sections.dedup_by(|later, earlier| later.0 == earlier.0);
+
+        let mut out = String::new();
+        for (_, section) in sections {
+            let mut keys = self.keys(section);
+            keys.sort();
+            if keys.is_empty() && !section.is_empty() {
+                out.push_str(&escape_canonical(section));
+                out.push('\n');
+            }
+            for key in keys {
+                let value = self.get(section, &key, "");
+                out.push_str(&format!("{}\t{}\t{}\n", escape_canonical(section), escape_canonical(&key), escape_canonical(&value)));
+            }
+        }
+        out
+    }
+
+    /// Create a reader from the output of [`IniReader::to_canonical_string`]
+    ///
+    /// Lines must hold either one field (an empty section) or three; anything
+    /// else, and unknown escapes, are parse errors.
+    pub fn from_canonical_string(data: &str) -> Result<Self, IniParseError> {
+        let mut ini_reader = Self::with_capacity(0, 0);
+        for (index, line) in data.lines().enumerate() {
+            let line_number = index + 1;
+            let error = |message: &str| IniParseError::ParseError { line: line_number, message: message.to_string(), suggestion: None };
+            let fields = line
+                .split('\t')
+                .map(|field| unescape_canonical(field).ok_or_else(|| error("Invalid escape sequence")))
+                .collect::<Result<Vec<String>, IniParseError>>()?;
+            match fields.as_slice() {
+                [section] => ini_reader.record_value(section, "", ""),
+                [section, name, value] => {
+                    let info = EntryInfo { line: line_number, separator: Some('='), continuation: false, line_ending: "\n", raw_line: line };
+                    ini_reader.record_entry(&info, section, name, value);
+                }
+                _ => return Err(error("Expected 1 or 3 tab-separated fields")),
+            }
+        }
+        ini_reader.end_recorded_document();
+        Ok(ini_reader)
+    }
+
+    /// Remove all sections, values, warnings and the recorded parse error
+    ///
+    /// The duplicate key policy, value newline style, case sensitivity,
+    /// interpolation and insert hook the reader was created with are kept, so
+    /// the reader can be reused as a handler.
+    pub fn clear(&mut self) {
+        self.values = SectionTables::default();
+        self.sections = Arc::default();
+        self.section_order = Arc::default();
+        self.key_order = SectionTables::default();
+        self.order_index.take();
+        self.errors.clear();
+        self.interpolation_errors.clear();
+        self.interpolated = Arc::default();
+        self.skipped_sections.clear();
+        self.document = None;
+        self.last_line_ending = "";
+        self.occurrences = SectionTables::default();
+        self.document_keys.clear();
+        self.expansion = None;
+        self.pending = None;
+        if let Ok(warnings) = self.warnings.get_mut() {
+            warnings.clear();
+        }
+    }
+
+    /// Sections a filtered constructor skipped, in order of first appearance
+    ///
+    /// Only recorded with [`ReaderOptions::record_skipped_sections`]; empty
+    /// otherwise and for readers not loaded by
+    /// [`from_file_filtered_with_reader_options`](Self::from_file_filtered_with_reader_options).
+    pub fn skipped_sections(&self) -> &[String] {
+        &self.skipped_sections
     }
 
     /// Get the parse error if one occurred
+    ///
+    /// This is also where the first entry rejected by the
+    /// [`on_insert`](ReaderOptions::on_insert) hook is recorded.
     pub fn parse_error(&self) -> Option<&IniParseError> {
-        self.error.as_ref()
+        self.errors.first()
+    }
+
+    /// Every parse error recorded, in line order
+    ///
+    /// The lossy constructors such as [`from_string_lossy`](Self::from_string_lossy)
+    /// record every error of the input; other constructors fail on the first.
+    /// Each entry the insert hook rejected is listed too, also when the
+    /// reader is used as a handler.
+    pub fn parse_errors(&self) -> &[IniParseError] {
+        &self.errors
+    }
+
+    /// References [`ReaderOptions::interpolation`] could not resolve, in document order
+    pub fn interpolation_errors(&self) -> &[InterpolationError] {
+        &self.interpolation_errors
     }
 
     /// Get a string value, returning the default if not found
-    pub fn get(&self, section: &str, name: &str, default_value: &str) -> String {
-        let key = Self::make_key(section, name);
-        self.values.get(&key).cloned().unwrap_or_else(|| default_value.to_string())
+    ///
+    /// An empty value (`key =`) is returned as `""`; use
+    /// [`get_nonempty_or`](Self::get_nonempty_or) to replace it with the default.
+    pub fn get(&self, section: impl SectionName, name: impl KeyName, default_value: &str) -> String {
+        self.raw(section.section_name(), name.key_name()).unwrap_or(default_value).to_string()
     }
 
     /// Get a string value, returning the default if not found or empty
-    pub fn get_string(&self, section: &str, name: &str, default_value: &str) -> String {
-        let value = self.get(section, name, "");
-        if value.is_empty() { default_value.to_string() } else { value }
+    ///
+    /// Only a zero-length value counts as empty; with `trim_values` off a
+    /// whitespace-only value is returned as is.
+    pub fn get_nonempty_or(&self, section: impl SectionName, name: impl KeyName, default_value: &str) -> String {
+        let value = self.raw(section.section_name(), name.key_name()).unwrap_or("");
+        if value.is_empty() { default_value } else { value }.to_string()
     }
 
-    /// Get an integer value, returning the default if not found or invalid
-    pub fn get_integer(&self, section: &str, name: &str, default_value: i64) -> i64 {
-        let value = self.get(section, name, "");
-        
-        // Handle hexadecimal numbers
-        if value.starts_with("0x") || value.starts_with("0X") {
-            if let Ok(hex_value) = i64::from_str_radix(&value[2..], 16) {
-                return hex_value;
-            }
-        }
-        
-        value.parse().unwrap_or(default_value)
+    /// Get a string value, returning the default if not found or empty
+    #[deprecated(
+        since = "0.2.0",
+        note = "use `get` to read an empty value as \"\", or `get_nonempty_or` to replace it with the default"
+    )]
+    pub fn get_string(&self, section: impl SectionName, name: impl KeyName, default_value: &str) -> String {
+        self.get_nonempty_or(section, name, default_value)
+    }
+
+    /// Get an integer value, returning the default if not found, empty or invalid
+    ///
+    /// Values outside `i64` are invalid, except full-width hexadecimal, octal
+    /// and binary masks such as `0xFFFFFFFFFFFFFFFF`, which are read as bit
+    /// patterns (see [`value::parse_integer`]); use
+    /// [`get_unsigned`](Self::get_unsigned) to read them as written.
+    pub fn get_integer(&self, section: impl SectionName, name: impl KeyName, default_value: i64) -> i64 {
+        self.get_integer_opt(section, name).unwrap_or(default_value)
     }
 
-    /// Get a 64-bit integer value, returning the default if not found or invalid
-    pub fn get_integer64(&self, section: &str, name: &str, default_value: i64) -> i64 {
-        self.get_integer(section, name, default_value)
+    /// Get a 64-bit integer value, returning the default if not found, empty or invalid
+    pub fn get_integer64(&self, section: impl SectionName, name: impl KeyName, default_value: i64) -> i64 {
+        self.get_integer(section.section_name(), name.key_name(), default_value)
     }
 
-    /// Get an unsigned integer value, returning the default if not found or invalid
-    pub fn get_unsigned(&self, section: &str, name: &str, default_value: u64) -> u64 {
-        let value = self.get(section, name, "");
-        value.parse().unwrap_or(default_value)
+    /// Get an unsigned integer value, returning the default if not found, empty or invalid
+    pub fn get_unsigned(&self, section: impl SectionName, name: impl KeyName, default_value: u64) -> u64 {
+        self.get_unsigned_opt(section, name).unwrap_or(default_value)
     }
 
-    /// Get a 64-bit unsigned integer value, returning the default if not found or invalid
-    pub fn get_unsigned64(&self, section: &str, name: &str, default_value: u64) -> u64 {
-        self.get_unsigned(section, name, default_value)
+    /// Get a 64-bit unsigned integer value, returning the default if not found, empty or invalid
+    pub fn get_unsigned64(&self, section: impl SectionName, name: impl KeyName, default_value: u64) -> u64 {
+        self.get_unsigned(section.section_name(), name.key_name(), default_value)
     }
 
-    /// Get a floating-point value, returning the default if not found or invalid
-    pub fn get_real(&self, section: &str, name: &str, default_value: f64) -> f64 {
-        let value = self.get(section, name, "");
-        value.parse().unwrap_or(default_value)
+    /// Get a floating-point value, returning the default if not found, empty or invalid
+    pub fn get_real(&self, section: impl SectionName, name: impl KeyName, default_value: f64) -> f64 {
+        self.get_real_opt(section, name).unwrap_or(default_value)
     }
 
-    /// Get a boolean value, returning the default if not found or invalid
+    /// Get a boolean value, returning the default if not found, empty or invalid
     /// Valid true values: "true", "yes", "on", "1"
     /// Valid false values: "false", "no", "off", "0"
-    pub fn get_boolean(&self, section: &str, name: &str, default_value: bool) -> bool {
-        let value = self.get(section, name, "").to_lowercase();
-        match value.as_str() {
-            "true" | "yes" | "on" | "1" => true,
-            "false" | "no" | "off" | "0" => false,
-            _ => default_value,
+    pub fn get_boolean(&self, section: impl SectionName, name: impl KeyName, default_value: bool) -> bool {
+        self.get_boolean_opt(section, name).unwrap_or(default_value)
+    }
+
+    /// Get a duration such as `90s`, `1m30s` or `03:30:00`, returning the default if not found, empty or invalid
+    ///
+    /// See [`value::parse_duration`] for the accepted forms.
+    pub fn get_duration(&self, section: impl SectionName, name: impl KeyName, default_value: Duration) -> Duration {
+        self.raw(section.section_name(), name.key_name()).and_then(value::parse_duration).unwrap_or(default_value)
+    }
+
+    /// The stored value of a key, or `None` if it is not present
+    ///
+    /// Unlike [`get`](Self::get) this tells a missing key from an empty value.
+    /// Together with the typed variants below it also tells a missing key
+    /// from one whose value does not parse: they return `None` for both, but
+    /// only the first returns `None` here too.
+    pub fn get_opt(&self, section: impl SectionName, name: impl KeyName) -> Option<&str> {
+        self.raw(section.section_name(), name.key_name())
+    }
+
+    /// Get an integer value, or `None` if not found, empty or invalid
+    pub fn get_integer_opt(&self, section: impl SectionName, name: impl KeyName) -> Option<i64> {
+        self.get_opt(section, name).and_then(value::parse_integer)
+    }
+
+    /// Get an unsigned integer value, or `None` if not found, empty or invalid
+    pub fn get_unsigned_opt(&self, section: impl SectionName, name: impl KeyName) -> Option<u64> {
+        self.get_opt(section, name).and_then(value::parse_unsigned)
+    }
+
+    /// Get a floating-point value, or `None` if not found, empty or invalid
+    pub fn get_real_opt(&self, section: impl SectionName, name: impl KeyName) -> Option<f64> {
+        self.get_opt(section, name).and_then(value::parse_real)
+    }
+
+    /// Get a boolean value, or `None` if not found, empty or invalid
+    ///
+    /// Accepts the same words as [`get_boolean`](Self::get_boolean).
+    pub fn get_boolean_opt(&self, section: impl SectionName, name: impl KeyName) -> Option<bool> {
+        self.get_opt(section, name).and_then(value::parse_bool)
+    }
+
+    /// Get a value parsed with its [`FromStr`] implementation, or `None` if not found or invalid
+    ///
+    /// The value is trimmed first. Unlike the typed getters, this reads
+    /// exactly what `T` accepts: `get_parse::<i64>` rejects `0x10` and
+    /// `get_parse::<bool>` rejects `yes`.
+    pub fn get_parse<T: FromStr>(&self, section: impl SectionName, name: impl KeyName) -> Option<T> {
+        self.get_opt(section, name).and_then(|raw| raw.trim().parse().ok())
+    }
+
+    /// Get a list split on commas and newlines, empty if not found
+    ///
+    /// Items are trimmed and empty ones dropped, as by [`value::parse_list`].
+    pub fn get_list(&self, section: impl SectionName, name: impl KeyName) -> Vec<String> {
+        self.get_list_with(section, name, ',')
+    }
+
+    /// Get a list split on `separator` and newlines, empty if not found
+    ///
+    /// Items are trimmed and empty ones dropped, as by [`value::parse_list_with`].
+    pub fn get_list_with(&self, section: impl SectionName, name: impl KeyName, separator: char) -> Vec<String> {
+        let raw = self.raw(section.section_name(), name.key_name()).unwrap_or("");
+        value::parse_list_with(raw, separator).into_iter().map(str::to_string).collect()
+    }
+
+    /// Get the lines of a multi-line value, empty if not found
+    ///
+    /// Lines are trimmed and empty ones dropped, so the indentation of
+    /// continuation lines does not matter.
+    pub fn get_lines(&self, section: impl SectionName, name: impl KeyName) -> Vec<String> {
+        let raw = self.raw(section.section_name(), name.key_name()).unwrap_or("");
+        value::parse_lines(raw).into_iter().map(str::to_string).collect()
+    }
+
+    /// Get a list of integers split as by [`get_list`](Self::get_list), empty if not found
+    ///
+    /// Items are read as by [`get_integer`](Self::get_integer). An item that
+    /// is not an integer is skipped with a warning, so the list holds the
+    /// valid items in order.
+    pub fn get_integer_list(&self, section: impl SectionName, name: impl KeyName) -> Vec<i64> {
+        self.typed_list(section.section_name(), name.key_name(), "an integer", value::parse_integer)
+    }
+
+    /// Get a list of floating-point values, skipping invalid items as [`get_integer_list`](Self::get_integer_list) does
+    pub fn get_real_list(&self, section: impl SectionName, name: impl KeyName) -> Vec<f64> {
+        self.typed_list(section.section_name(), name.key_name(), "a number", value::parse_real)
+    }
+
+    /// The items of a list that `parse` accepts, warning about the others
+    fn typed_list<T>(&self, section: &str, name: &str, kind: &str, parse: impl Fn(&str) -> Option<T>) -> Vec<T> {
+        let raw = self.raw(section, name).unwrap_or("");
+        let mut items = Vec::new();
+        for item in value::parse_list(raw) {
+            match parse(item) {
+                Some(parsed) => items.push(parsed),
+                None => self.warn(IniWarning {
+                    line: self.line_of(section, name),
+                    section: section.to_string(),
+                    name: name.to_string(),
+                    message: format!("item '{}' of {}.{} is not {}, skipped", item, section, name, kind),
+                }),
+            }
+        }
+        items
+    }
+
+    /// The stored value of a key, if present
+    fn raw(&self, section: &str, name: &str) -> Option<&str> {
+        self.lookup(section, name, None)
+    }
+
+    /// The stored value of a key, recording the lookup in `trace` if given
+    ///
+    /// Every getter goes through here, so an explanation shows exactly what
+    /// they do. A miss falls back to the default section, if there is one.
+    pub(crate) fn lookup(&self, section: &str, name: &str, mut trace: Option<(Source, &mut Vec<Step>)>) -> Option<&str> {
+        let mut value = self.values.get(&self.make_key(section, name)).map(String::as_str);
+        if let Some((source, steps)) = trace.as_mut() {
+            steps.push(Step { source: *source, section: section.to_string(), found: value.is_some() });
+        }
+        if let Some(default_section) = self.fallback_section(section).filter(|_| value.is_none()) {
+            value = self.values.get(&self.make_key(default_section, name)).map(String::as_str);
+            if let Some((source, steps)) = trace {
+                steps.push(Step { source, section: default_section.to_string(), found: value.is_some() });
+            }
+        }
+        value
+    }
+
+    /// The default section, unless it is `section` itself
+    fn fallback_section(&self, section: &str) -> Option<&str> {
+        self.default_section.as_deref().filter(|default_section| self.fold(default_section) != self.fold(section))
+    }
+
+    /// How a key resolves: where it was looked up, its value and where that came from
+    ///
+    /// `None` as the value means the getters return their default. See
+    /// [`explain`](crate::explain) for the output.
+    pub fn explain(&self, section: impl SectionName, name: impl KeyName) -> Explanation {
+        let (section, name) = (section.section_name(), name.key_name());
+        let mut explanation = Explanation::new(section, name);
+        explanation.value = self.lookup(section, name, Some((Source::Reader, &mut explanation.steps))).map(str::to_string);
+        let answered = explanation.steps.iter().rfind(|step| step.found).map_or(section, |step| step.section.as_str());
+        explanation.raw_value = self.raw_value(answered, name).map(str::to_string);
+        explanation.occurrences = self.occurrences(answered, name);
+        explanation
+    }
+
+    /// The value of a key as written, if interpolation changed it into the stored one
+    pub(crate) fn raw_value(&self, section: &str, name: &str) -> Option<&str> {
+        let key = self.make_key(section, name);
+        let (raw, interpolated) = self.interpolated.get(&key)?;
+        (self.values.get(&key) == Some(interpolated)).then_some(raw.as_str())
+    }
+
+    /// Get a string value from the first of `sections` that has it
+    ///
+    /// For repeated lookups over the same sections, and for typed values,
+    /// build a [`FallbackChain`](crate::fallback::FallbackChain) once instead.
+    pub fn get_chain(&self, sections: &[&str], name: &str, default_value: &str) -> String {
+        match sections.iter().find(|section| self.has_value(section, name)) {
+            Some(section) => self.get(section, name, default_value),
+            None => default_value.to_string(),
         }
     }
 
-    /// Get all section names
+    /// Get an integer value, clamping it into `range`
+    ///
+    /// Out-of-range values are clamped and a warning is recorded. Missing or
+    /// invalid values give the default, which is not clamped.
+    pub fn get_integer_clamped(&self, section: impl SectionName, name: impl KeyName, default_value: i64, range: RangeInclusive<i64>) -> i64 {
+        self.clamped(section.section_name(), name.key_name(), default_value, range, value::parse_integer)
+    }
+
+    /// Get an integer value, failing if it is missing, empty, invalid or does not fit in `i64`
+    ///
+    /// Full-width hexadecimal, octal and binary masks are read as bit
+    /// patterns, as described in [`value::parse_integer`].
+    pub fn try_get_integer(&self, section: impl SectionName, name: impl KeyName) -> Result<i64, IniGetError> {
+        self.try_get_integer_in(section, name, i64::MIN..=i64::MAX)
+    }
+
+    /// Get an integer value, failing if it is missing, empty, invalid or outside `range`
+    pub fn try_get_integer_in(&self, section: impl SectionName, name: impl KeyName, range: RangeInclusive<i64>) -> Result<i64, IniGetError> {
+        self.in_range(section.section_name(), name.key_name(), &range, value::parse_integer, "integer")
+    }
+
+    /// Get an unsigned integer value, clamping it into `range`
+    pub fn get_unsigned_clamped(&self, section: impl SectionName, name: impl KeyName, default_value: u64, range: RangeInclusive<u64>) -> u64 {
+        self.clamped(section.section_name(), name.key_name(), default_value, range, value::parse_unsigned)
+    }
+
+    /// Get an unsigned integer value, failing if it is missing, empty, invalid or does not fit in `u64`
+    pub fn try_get_unsigned(&self, section: impl SectionName, name: impl KeyName) -> Result<u64, IniGetError> {
+        self.try_get_unsigned_in(section, name, u64::MIN..=u64::MAX)
+    }
+
+    /// Get an unsigned integer value, failing if it is missing, empty, invalid or outside `range`
+    pub fn try_get_unsigned_in(&self, section: impl SectionName, name: impl KeyName, range: RangeInclusive<u64>) -> Result<u64, IniGetError> {
+        self.in_range(section.section_name(), name.key_name(), &range, value::parse_unsigned, "unsigned integer")
+    }
+
+    /// Get a floating-point value, clamping it into `range`
+    pub fn get_real_clamped(&self, section: impl SectionName, name: impl KeyName, default_value: f64, range: RangeInclusive<f64>) -> f64 {
+        self.clamped(section.section_name(), name.key_name(), default_value, range, value::parse_real)
+    }
+
+    /// Get a floating-point value, failing if it is missing, empty or invalid
+    pub fn try_get_real(&self, section: impl SectionName, name: impl KeyName) -> Result<f64, IniGetError> {
+        self.converted(section.section_name(), name.key_name(), value::parse_real, "real")
+    }
+
+    /// Get a floating-point value, failing if it is missing, empty, invalid or outside `range`
+    pub fn try_get_real_in(&self, section: impl SectionName, name: impl KeyName, range: RangeInclusive<f64>) -> Result<f64, IniGetError> {
+        self.in_range(section.section_name(), name.key_name(), &range, value::parse_real, "real")
+    }
+
+    /// Get a boolean value, failing if it is missing, empty or not one of the
+    /// words [`get_boolean`](Self::get_boolean) accepts
+    pub fn try_get_boolean(&self, section: impl SectionName, name: impl KeyName) -> Result<bool, IniGetError> {
+        self.converted(section.section_name(), name.key_name(), value::parse_bool, "boolean")
+    }
+
+    /// Get a duration, failing if it is missing, empty or not a form
+    /// [`get_duration`](Self::get_duration) accepts
+    ///
+    /// Negative durations and ones too long for [`Duration`] are invalid too.
+    pub fn try_get_duration(&self, section: impl SectionName, name: impl KeyName) -> Result<Duration, IniGetError> {
+        self.converted(section.section_name(), name.key_name(), value::parse_duration, "duration")
+    }
+
+    /// Get a string value, failing only if the key is missing
+    ///
+    /// An empty value is returned as it is.
+    pub fn try_get_string(&self, section: impl SectionName, name: impl KeyName) -> Result<String, IniGetError> {
+        self.required(section.section_name(), name.key_name())
+    }
+
+    /// The stored value of a key, failing if it is missing
+    fn required(&self, section: &str, name: &str) -> Result<String, IniGetError> {
+        self.raw(section, name).map(str::to_string).ok_or_else(|| IniGetError::MissingKey {
+            section: section.to_string(),
+            name: name.to_string(),
+        })
+    }
+
+    /// Get a value parsed with its [`FromStr`] implementation, failing if it is missing or invalid
+    ///
+    /// The value is trimmed first; the error of a failed parse is kept in the
+    /// message of [`IniGetError::Unparsable`].
+    pub fn try_get_parse<T>(&self, section: impl SectionName, name: impl KeyName) -> Result<T, IniGetError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let (section, name) = (section.section_name(), name.key_name());
+        let raw = self.required(section, name)?;
+        raw.trim().parse().map_err(|error: T::Err| IniGetError::Unparsable {
+            section: section.to_string(),
+            name: name.to_string(),
+            value: raw.clone(),
+            message: error.to_string(),
+        })
+    }
+
+    /// Warnings recorded so far, in the order they occurred
+    pub fn warnings(&self) -> Vec<IniWarning> {
+        self.warnings.lock().map(|warnings| warnings.clone()).unwrap_or_default()
+    }
+
+    /// Make the reader read-only; [`FrozenIniReader::thaw`] turns it back
+    ///
+    /// A pending entry still held for the insert hook is stored first, and
+    /// the hook is dropped since nothing can be loaded any more.
+    pub fn freeze(mut self) -> FrozenIniReader {
+        self.flush_pending();
+        self.on_insert = None;
+        FrozenIniReader::new(self)
+    }
+
+    /// A read-only copy of the reader as it is now
+    ///
+    /// The copy shares the stored tables, so this is cheap; later changes to
+    /// the reader copy them first and do not show in the snapshot.
+    pub fn snapshot(&self) -> FrozenIniReader {
+        self.clone().freeze()
+    }
+
+    /// Number of readers sharing all of this reader's values, itself included
+    ///
+    /// 1 once the reader has changed a value in any section since it was
+    /// cloned, or was never cloned at all. See
+    /// [`shared_section_count`](Self::shared_section_count) for one section.
+    pub fn shared_storage_count(&self) -> usize {
+        self.values.strong_count()
+    }
+
+    /// Number of readers sharing the values of one section, itself included
+    ///
+    /// A change copies only the values of the section it is in, so the other
+    /// sections stay shared. 0 if the section has no values.
+    pub fn shared_section_count(&self, section: impl SectionName) -> usize {
+        self.values.section_strong_count(&self.key_prefix(section.section_name()))
+    }
+
+    /// Give back the spare capacity of the tables and of the strings in them
+    ///
+    /// Useful for a reader kept for a long time after loading, since parsing
+    /// and editing leave room for entries that are no longer there. Tables
+    /// still shared with a clone are left as they are: shrinking them would
+    /// copy them and use more memory, not less.
+    pub fn shrink_to_fit(&mut self) {
+        self.values.shrink_unshared(|values| shrink_map(values, |value| value.shrink_to_fit()));
+        if let Some(sections) = Arc::get_mut(&mut self.sections) {
+            *sections = sections.drain().map(shrunk).collect();
+        }
+        if let Some(order) = Arc::get_mut(&mut self.section_order) {
+            shrink_map(order, |_| {});
+        }
+        self.key_order.shrink_unshared(|order| shrink_map(order, |_| {}));
+        // Rebuilt by the next lookup that needs it
+        self.order_index.take();
+        self.occurrences.shrink_unshared(|occurrences| {
+            shrink_map(occurrences, |list| {
+                list.shrink_to_fit();
+                for occurrence in list {
+                    occurrence.name.shrink_to_fit();
+                    occurrence.value.shrink_to_fit();
+                }
+            })
+        });
+        self.document_keys.shrink_to_fit();
+        self.errors.shrink_to_fit();
+        self.interpolation_errors.shrink_to_fit();
+        self.warnings.lock().unwrap_or_else(|e| e.into_inner()).shrink_to_fit();
+        if let Some(document) = &mut self.document {
+            document.shrink_to_fit();
+        }
+    }
+
+    /// Estimate of the heap memory the reader holds, in bytes
+    ///
+    /// This is the sum of the capacities of the tables and of the strings
+    /// stored in them, values, their occurrences and the kept document
+    /// included. It leaves out the hash tables' control bytes and allocator
+    /// overhead, so it is an estimate rather than an exact figure. Tables
+    /// shared with clones are counted in full by every reader sharing them.
+    pub fn approx_memory_bytes(&self) -> usize {
+        let values = self.values.approx_memory_bytes(String::capacity);
+        let sections = self.sections.capacity() * size_of::<String>() + self.sections.iter().map(String::capacity).sum::<usize>();
+        let order = map_bytes(&self.section_order, |_| 0)
+            + self.key_order.approx_memory_bytes(|_| 0)
+            + self.order_index.get().map_or(0, OrderIndex::approx_memory_bytes);
+        let occurrences = self.occurrences.approx_memory_bytes(|list| {
+            list.capacity() * size_of::<Occurrence>()
+                + list.iter().map(|occurrence| occurrence.name.capacity() + occurrence.value.capacity()).sum::<usize>()
+        });
+        let document_keys = self.document_keys.capacity() * size_of::<String>() + self.document_keys.iter().map(String::capacity).sum::<usize>();
+        let warnings = {
+            let warnings = self.warnings.lock().unwrap_or_else(|e| e.into_inner());
+            warnings.capacity() * size_of::<IniWarning>()
+                + warnings.iter().map(|w| w.section.capacity() + w.name.capacity() + w.message.capacity()).sum::<usize>()
+        };
+        let errors = self.errors.capacity() * size_of::<IniParseError>() + self.interpolation_errors.capacity() * size_of::<InterpolationError>();
+        let document = self.document.as_ref().map_or(0, IniDocument::approx_memory_bytes);
+        values + sections + order + occurrences + document_keys + warnings + errors + document
+    }
+
+    /// Look up and convert a value, checking it against a range
+    fn in_range<T>(
+        &self,
+        section: &str,
+        name: &str,
+        range: &RangeInclusive<T>,
+        parse: impl Fn(&str) -> Option<T>,
+        expected: &'static str,
+    ) -> Result<T, IniGetError>
+    where
+        T: PartialOrd + fmt::Display,
+    {
+        let parsed = self.converted(section, name, parse, expected)?;
+        if range.contains(&parsed) {
+            return Ok(parsed);
+        }
+        let key = self.make_key(section, name);
+        Err(IniGetError::OutOfRange {
+            section: section.to_string(),
+            name: name.to_string(),
+            value: self.values.get(&key).cloned().unwrap_or_default(),
+            range: format!("{}..={}", range.start(), range.end()),
+            line: self.value_line(&key),
+        })
+    }
+
+    /// Look up and convert a value, failing if it is missing, empty or invalid
+    fn converted<T>(
+        &self,
+        section: &str,
+        name: &str,
+        parse: impl Fn(&str) -> Option<T>,
+        expected: &'static str,
+    ) -> Result<T, IniGetError> {
+        let raw = self.required(section, name)?;
+        if raw.trim().is_empty() {
+            return Err(IniGetError::Empty { section: section.to_string(), name: name.to_string() });
+        }
+        parse(&raw).ok_or_else(|| {
+            let (section, name) = (section.to_string(), name.to_string());
+            // Only integers can be well-formed yet unrepresentable
+            if expected.ends_with("integer") && value::is_integer_literal(&raw) {
+                return IniGetError::Overflow { section, name, value: raw, expected };
+            }
+            IniGetError::InvalidValue { section, name, value: raw, expected }
+        })
+    }
+
+    /// Look up and convert a value, clamping it into a range with a warning
+    fn clamped<T>(
+        &self,
+        section: &str,
+        name: &str,
+        default_value: T,
+        range: RangeInclusive<T>,
+        parse: impl Fn(&str) -> Option<T>,
+    ) -> T
+    where
+        T: PartialOrd + Copy + fmt::Display,
+    {
+        let key = self.make_key(section, name);
+        let Some(value) = self.values.get(&key).and_then(|raw| parse(raw)) else {
+            return default_value;
+        };
+        if range.contains(&value) {
+            return value;
+        }
+
+        let clamped = if value < *range.start() { *range.start() } else { *range.end() };
+        self.warn(IniWarning {
+            line: self.value_line(&key),
+            section: section.to_string(),
+            name: name.to_string(),
+            message: format!(
+                "value {} for {}.{} is outside {}..={}, clamped to {}",
+                value,
+                section,
+                name,
+                range.start(),
+                range.end(),
+                clamped
+            ),
+        });
+        clamped
+    }
+
+    /// Get the value of the `index`-th occurrence of a key (zero-based)
+    ///
+    /// Every occurrence is remembered whatever the duplicate key policy, except
+    /// those the parser drops (`FirstWins`, `Error`).
+    pub fn get_nth(&self, section: &str, name: &str, index: usize) -> Option<&str> {
+        let occurrences = self.occurrences.get(&self.make_key(section, name))?;
+        occurrences.get(index).map(|occurrence| occurrence.value.as_str())
+    }
+
+    /// Get the values of every occurrence of a key, in document order
+    pub fn get_all(&self, section: &str, name: &str) -> Vec<String> {
+        self.occurrences(section, name).into_iter().map(|occurrence| occurrence.value).collect()
+    }
+
+    /// Get every occurrence of a key with its line, in document order
+    pub fn occurrences(&self, section: &str, name: &str) -> Vec<Occurrence> {
+        self.occurrences.get(&self.make_key(section, name)).cloned().unwrap_or_default()
+    }
+
+    /// Check if a key was written bare, without a separator or value
+    ///
+    /// Only meaningful with `allow_no_value`; refers to the occurrence the
+    /// current value comes from.
+    pub fn is_flag(&self, section: &str, name: &str) -> bool {
+        self.value_occurrence(&self.make_key(section, name)).is_some_and(|occurrence| occurrence.bare)
+    }
+
+    /// The occurrence the current value of a key comes from
+    fn value_occurrence(&self, key: &str) -> Option<&Occurrence> {
+        let occurrences = self.occurrences.get(key)?;
+        match self.duplicate_policy {
+            DuplicateKeyPolicy::Concatenate { .. } => occurrences.first(),
+            _ => occurrences.last(),
+        }
+    }
+
+    /// Line of the occurrence the current value of a key comes from
+    fn value_line(&self, key: &str) -> Option<usize> {
+        self.value_occurrence(key).map(|occurrence| occurrence.line)
+    }
+
+    /// Line the current value of a key was read from, None for a value that was set
+    pub(crate) fn line_of(&self, section: &str, name: &str) -> Option<usize> {
+        self.value_line(&self.make_key(section, name)).filter(|&line| line > 0)
+    }
+
+    /// Record a warning
+    fn warn(&self, warning: IniWarning) {
+        if let Ok(mut warnings) = self.warnings.lock() {
+            warnings.push(warning);
+        }
+    }
+
+    /// Get all section names as first written, in order of first appearance
+    ///
+    /// A section whose header appears several times, in any case unless the
+    /// reader is case-sensitive, is listed once, spelled and placed as at its
+    /// first header. The global section is not listed.
     pub fn sections(&self) -> Vec<String> {
-        let mut sections: Vec<String> = self.sections.iter().cloned().collect();
-        sections.sort();
-        sections
+        self.ordered_sections().into_iter().map(str::to_string).collect()
     }
 
-    /// Get all keys in a section
+    /// Get all keys in a section, normalized, in order of first appearance
+    ///
+    /// A key set several times, whatever the duplicate key policy, is listed
+    /// once at its first occurrence, even when a later section block of the
+    /// same name repeats it. Keys added later, by a setter or a later
+    /// document, come after the existing ones. The cost is in proportion to
+    /// the keys of the section, not of the whole reader.
     pub fn keys(&self, section: &str) -> Vec<String> {
-        let prefix = format!("{}=", section.to_lowercase());
-        let mut keys = Vec::new();
-        
-        for key in self.values.keys() {
-            if key.starts_with(&prefix) {
-                keys.push(key[prefix.len()..].to_string());
-            }
+        self.ordered_entries(section).into_iter().map(|(key, _)| key.to_string()).collect()
+    }
+
+    /// Get all keys a section has or inherits from the default section
+    ///
+    /// The section's own keys come first, as listed by [`keys`](Self::keys),
+    /// followed by the keys of the [default section](ReaderOptions::default_section)
+    /// it does not set, in their order. Without a default section this is `keys`.
+    pub fn keys_with_defaults(&self, section: &str) -> Vec<String> {
+        let mut keys = self.keys(section);
+        if let Some(default_section) = self.fallback_section(section) {
+            let own: std::collections::HashSet<String> = keys.iter().cloned().collect();
+            keys.extend(self.keys(default_section).into_iter().filter(|key| !own.contains(key)));
         }
-        keys.sort();
         keys
     }
 
+    /// Iterate over all values as (section, key, value), in document order
+    ///
+    /// Global values come first. Sections and keys appear in order of first
+    /// appearance; sections are spelled as first written and keys as written
+    /// at the occurrence their value comes from. Sections without values are
+    /// not visited, [`sections`](Self::sections) lists them. `&reader` can be
+    /// used in a `for` loop directly.
+    pub fn iter(&self) -> Iter<'_> {
+        let entries: Vec<_> = std::iter::once("")
+            .chain(self.ordered_sections())
+            .flat_map(|section| self.iter_section(section).map(move |(key, value)| (section, key, value)))
+            .collect();
+        Iter { entries: entries.into_iter() }
+    }
+
+    /// Iterate over the values of one section as (key, value), in document order
+    ///
+    /// Keys are spelled as in [`iter`](Self::iter); `""` is the global section.
+    pub fn iter_section<'a>(&'a self, section: &str) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        let prefix = self.key_prefix(section);
+        self.ordered_entries(section).into_iter().map(move |(key, value)| {
+            let spelled = self.value_occurrence(&format!("{}{}", prefix, key)).map_or(key, |occurrence| occurrence.name.as_str());
+            (spelled, value)
+        })
+    }
+
+    /// All values as (section, normalized key, value), in document order
+    pub(crate) fn normalized_entries(&self) -> impl Iterator<Item = (&str, &str, &str)> + '_ {
+        std::iter::once("")
+            .chain(self.ordered_sections())
+            .flat_map(|section| self.ordered_entries(section).into_iter().map(move |(key, value)| (section, key, value)))
+    }
+
+    /// Section names in order of first appearance, one per case-folded name
+    pub(crate) fn ordered_sections(&self) -> Vec<&str> {
+        self.section_names().collect()
+    }
+
+    /// Section names as [`ordered_sections`](Self::ordered_sections) lists them, without collecting them
+    pub(crate) fn section_names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.order_index().sections.iter().map(String::as_str)
+    }
+
+    /// Normalized keys of a section with their values, in order of first appearance
+    fn ordered_entries(&self, section: &str) -> Vec<(&str, &str)> {
+        let prefix = self.key_prefix(section);
+        let Some(keys) = self.order_index().keys.get(&prefix) else {
+            return Vec::new();
+        };
+        keys.iter()
+            .filter_map(|key| Some((&key[prefix.len()..], self.values.get(key)?.as_str())))
+            .collect()
+    }
+
+    /// The order index, built from the tables if a change dropped it
+    fn order_index(&self) -> &OrderIndex {
+        self.order_index.get_or_init(|| {
+            let mut sections: Vec<&String> = self.sections.iter().collect();
+            sections.sort_by_key(|section| self.section_order.get(*section).copied().unwrap_or(usize::MAX));
+            let mut seen = std::collections::HashSet::new();
+            sections.retain(|section| seen.insert(self.fold(section)));
+
+            let mut ordered: HashMap<&str, Vec<(usize, &String)>> = HashMap::new();
+            for key in self.values.keys() {
+                let order = self.key_order.get(key).copied().unwrap_or(usize::MAX);
+                ordered.entry(tables::prefix(key)).or_default().push((order, key));
+            }
+            let keys = ordered
+                .into_iter()
+                .map(|(prefix, mut keys)| {
+                    keys.sort();
+                    (prefix.to_string(), keys.into_iter().map(|(_, key)| key.clone()).collect())
+                })
+                .collect();
+            OrderIndex { sections: sections.into_iter().cloned().collect(), keys }
+        })
+    }
+
+    /// Get all values as a map of section to key to value
+    ///
+    /// The map is unordered; use [`IniReader::iter`] for document order.
+    /// Global values are under the section `""`, and keys are normalized.
+    pub fn as_map(&self) -> HashMap<String, HashMap<String, String>> {
+        let mut map: HashMap<String, HashMap<String, String>> = HashMap::new();
+        for (section, key, value) in self.normalized_entries() {
+            map.entry(section.to_string()).or_default().insert(key.to_string(), value.to_string());
+        }
+        map
+    }
+
+    /// Get the values of one section as a map of key to value
+    ///
+    /// Keys are normalized, as [`keys`](Self::keys) lists them, and `""` is
+    /// the global section. A section that is absent, or declared without
+    /// values, gives an empty map; use [`has_section`](Self::has_section) to
+    /// tell the two apart.
+    pub fn section_map(&self, section: &str) -> HashMap<String, String> {
+        self.section_values(section).collect()
+    }
+
+    /// Like [`section_map`](Self::section_map), sorted by key
+    pub fn section_btree(&self, section: &str) -> BTreeMap<String, String> {
+        self.section_values(section).collect()
+    }
+
+    /// Owned (normalized key, value) pairs of one section, in no particular order
+    fn section_values(&self, section: &str) -> impl Iterator<Item = (String, String)> + '_ {
+        let prefix = self.key_prefix(section);
+        self.values.section(&prefix).map(move |(key, value)| (key[prefix.len()..].to_string(), value.clone()))
+    }
+
+    /// Copy the given sections into a new, independent reader
+    ///
+    /// Section names match case-insensitively and `""` selects the global
+    /// section. The copy keeps the values, every occurrence with its line,
+    /// the document order and the duplicate key policy, but not warnings, the
+    /// parse error or a preserved document. Unknown names are ignored.
+    pub fn extract(&self, sections: &[&str]) -> IniReader {
+        let wanted: Vec<String> = sections.iter().map(|section| self.fold(section)).collect();
+        let prefixes: Vec<String> = sections.iter().map(|section| self.key_prefix(section)).collect();
+        let mut extracted = Self::with_capacity(wanted.len(), 0);
+        extracted.duplicate_policy = self.duplicate_policy.clone();
+        extracted.value_newline = self.value_newline;
+        extracted.case_sensitive = self.case_sensitive;
+        extracted.default_section = self.default_section.clone();
+
+        for section in self.sections.iter().filter(|section| wanted.contains(&self.fold(section))) {
+            Arc::make_mut(&mut extracted.sections).insert(section.clone());
+            if let Some(&order) = self.section_order.get(section) {
+                Arc::make_mut(&mut extracted.section_order).insert(section.clone(), order);
+            }
+        }
+        for (key, value) in prefixes.iter().flat_map(|prefix| self.values.section(prefix)) {
+            extracted.values.insert(key.clone(), value.clone());
+            if let Some(&order) = self.key_order.get(key) {
+                extracted.key_order.insert(key.clone(), order);
+            }
+            if let Some(occurrences) = self.occurrences.get(key) {
+                extracted.occurrences.insert(key.clone(), occurrences.clone());
+            }
+        }
+        extracted
+    }
+
+    /// Remove one section and return it as a new reader
+    ///
+    /// The name matches case-insensitively and `""` selects the global
+    /// section. Returns `None`, leaving the reader untouched, if the section
+    /// neither was declared nor has values. A preserved document no longer
+    /// matches the reader afterwards and is dropped.
+    pub fn split_off(&mut self, section: &str) -> Option<IniReader> {
+        if !self.section_present(section) {
+            return None;
+        }
+        let folded = self.fold(section);
+        let prefixes = [self.key_prefix(section)];
+
+        let extracted = self.extract(&[section]);
+        let sensitive = self.case_sensitive;
+        Arc::make_mut(&mut self.sections).retain(|name| fold(name, sensitive) != folded);
+        Arc::make_mut(&mut self.section_order).retain(|name, _| fold(name, sensitive) != folded);
+        self.values.remove_sections(&prefixes);
+        self.key_order.remove_sections(&prefixes);
+        self.occurrences.remove_sections(&prefixes);
+        self.order_index.take();
+        self.document = None;
+        Some(extracted)
+    }
+
+    /// Set a value, as one triple passed to [`Extend`] would
+    ///
+    /// The value replaces any the key had and becomes its only occurrence.
+    /// Missing sections and keys are added after the existing ones; the
+    /// section and name match as in lookups. A preserved document no longer
+    /// matches the reader afterwards and is dropped.
+    pub fn set(&mut self, section: &str, name: &str, value: &str) {
+        self.extend([(section.to_string(), name.to_string(), value.to_string())]);
+    }
+
+    /// Set an integer value, read back by [`get_integer`](Self::get_integer)
+    pub fn set_integer(&mut self, section: &str, name: &str, value: i64) {
+        self.set(section, name, &value.to_string());
+    }
+
+    /// Set a boolean value in the style of `options`, read back by [`get_boolean`](Self::get_boolean)
+    pub fn set_boolean(&mut self, section: &str, name: &str, value: bool, options: &WriteOptions) {
+        self.set(section, name, writer::format_boolean(value, options));
+    }
+
+    /// Flip a boolean value, keeping the word style it is written in
+    ///
+    /// `yes` becomes `no`, `On` becomes `Off` and `1` becomes `0`, see
+    /// [`value::toggle_bool`]. A missing key is taken as false and set to
+    /// `true`. Returns the new value, or `None`, changing nothing, if the
+    /// value is not a boolean. Only the section itself is looked at, not the
+    /// [default section](ReaderOptions::default_section).
+    ///
+    /// Unlike the other mutators this keeps a preserved document: the word
+    /// is replaced in its line and the rest of the line, comment included,
+    /// is left as written. If there is no line to change, as for a missing
+    /// key or a quoted value, the document is dropped.
+    pub fn toggle_boolean(&mut self, section: impl SectionName, name: impl KeyName) -> Option<bool> {
+        let (section, name) = (section.section_name(), name.key_name());
+        let key = self.make_key(section, name);
+        let (toggled, line) = match self.values.get(&key) {
+            Some(current) => (value::toggle_bool(current)?, self.value_occurrence(&key).map(|occurrence| (occurrence.line, current.clone()))),
+            None => ("true".to_string(), None),
+        };
+        let document = self.document.take();
+        self.set(section, name, &toggled);
+        if let (Some(mut document), Some((line, current))) = (document, line) {
+            if document.replace_value_at(line, &current, &toggled) {
+                self.document = Some(document);
+            }
+        }
+        value::parse_bool(&toggled)
+    }
+
+    /// Set a floating-point value, read back by [`get_real`](Self::get_real)
+    ///
+    /// Without `options.real_precision` the getter returns exactly `value`;
+    /// with it, `value` rounded to that many decimal places.
+    pub fn set_real(&mut self, section: &str, name: &str, value: f64, options: &WriteOptions) {
+        self.set(section, name, &writer::format_real(value, options));
+    }
+
+    /// Set a duration in the style of `options`, read back by [`get_duration`](Self::get_duration)
+    ///
+    /// Durations in whole milliseconds read back exactly; see [`writer::format_duration`].
+    pub fn set_duration(&mut self, section: &str, name: &str, value: Duration, options: &WriteOptions) {
+        self.set(section, name, &writer::format_duration(value, options));
+    }
+
+    /// Set a list joined by `delimiter`, read back by [`get_list`](Self::get_list)
+    ///
+    /// The delimiter needs a comma or newline in it, such as `", "`, for the
+    /// getter to split the items again. Fails with `InvalidList`, changing
+    /// nothing, for a delimiter or items the getter would not read back; see
+    /// [`writer::format_list`].
+    pub fn set_list(&mut self, section: &str, name: &str, items: &[&str], delimiter: &str) -> Result<(), IniWriteError> {
+        self.set(section, name, &writer::format_list(items, delimiter)?);
+        Ok(())
+    }
+
+    /// Remove a key with all its occurrences, returning its value
+    ///
+    /// The name matches as in lookups. The section stays, even without keys:
+    /// `has_section` and `sections()` keep listing it until
+    /// [`remove_section`](Self::remove_section). Returns `None`, leaving the
+    /// reader untouched, if the key is absent; otherwise a preserved document
+    /// is dropped.
+    pub fn remove(&mut self, section: &str, name: &str) -> Option<String> {
+        let key = self.make_key(section, name);
+        if !self.values.contains_key(&key) {
+            return None;
+        }
+        self.occurrences.remove(&key);
+        self.order_index.take();
+        self.document = None;
+        self.values.remove(&key)
+    }
+
+    /// Remove a section and all its keys; returns `false` if there was nothing to remove
+    ///
+    /// Same as [`split_off`](Self::split_off) without keeping the section.
+    pub fn remove_section(&mut self, section: &str) -> bool {
+        self.split_off(section).is_some()
+    }
+
+    /// Rewrite values in place, returning the number of occurrences changed
+    ///
+    /// `f` gets the section, the key as written and the value of every
+    /// occurrence of every key, in document order; a key read several times
+    /// is presented once per occurrence, each with its own value as read.
+    /// Returning `Some` replaces that occurrence's value and `None` keeps it.
+    /// The value the getters return is then combined from the occurrences
+    /// again by the duplicate key policy and, with
+    /// [`ReaderOptions::interpolation`] on, interpolated again along with the
+    /// values that were interpolated before, so references see the new
+    /// values. Sections, keys, their order and lines stay as they are.
+    ///
+    /// A preserved document is kept, with only the changed values replaced
+    /// in their lines, as long as every changed value is on one line and
+    /// written as is; otherwise it is dropped. See
+    /// [`IniDocument::map_values_in_place`] to rewrite a document by itself.
+    pub fn map_values_in_place(&mut self, mut f: impl FnMut(&str, &str, &str) -> Option<String>) -> usize {
+        let keys: Vec<(String, String)> =
+            self.normalized_entries().map(|(section, name, _)| (section.to_string(), self.make_key(section, name))).collect();
+        let mut document = self.document.take();
+        let mut changed = 0;
+        let mut rewritten = std::collections::HashSet::new();
+        for (section, key) in keys {
+            let Some(occurrences) = self.occurrences.get(&key) else { continue };
+            let replaced: Vec<Option<String>> = occurrences
+                .iter()
+                .map(|occurrence| f(&section, &occurrence.name, &occurrence.value).filter(|value| *value != occurrence.value))
+                .collect();
+            if replaced.iter().all(Option::is_none) {
+                continue;
+            }
+            for (occurrence, value) in occurrences.iter().zip(&replaced) {
+                if let (Some(kept), Some(value)) = (&mut document, value) {
+                    if !kept.replace_value_at(occurrence.line, &occurrence.value, value) {
+                        document = None;
+                    }
+                }
+            }
+
+            let Some(occurrences) = self.occurrences.get_mut(&key) else { continue };
+            for (occurrence, value) in occurrences.iter_mut().zip(replaced) {
+                if let Some(value) = value {
+                    occurrence.value = value;
+                    changed += 1;
+                }
+            }
+            let value = match &self.duplicate_policy {
+                DuplicateKeyPolicy::Concatenate { separator } => {
+                    occurrences.iter().map(|occurrence| occurrence.value.as_str()).collect::<Vec<_>>().join(separator)
+                }
+                _ => occurrences.last().map(|occurrence| occurrence.value.clone()).unwrap_or_default(),
+            };
+            self.values.insert(key.clone(), value);
+            rewritten.insert(key);
+        }
+        self.document = document;
+        if self.interpolation != Interpolation::None && !rewritten.is_empty() {
+            self.reinterpolate(rewritten);
+        }
+        changed
+    }
+
+    /// Write the current state as INI text, as [`to_ini_string`](Self::to_ini_string) would
+    pub fn save_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_ini_string().as_bytes())?;
+        writer.flush()
+    }
+
+    /// Whether a section was declared or has values, matching as in lookups
+    pub(crate) fn section_present(&self, section: &str) -> bool {
+        let folded = self.fold(section);
+        self.order_index().keys.contains_key(&self.key_prefix(section))
+            || self.sections.contains(section)
+            || self.sections.iter().any(|name| self.fold(name) == folded)
+    }
+
+    /// Move a key with its occurrences, replacing any value the target had
+    ///
+    /// The key takes its spelling from `to_name` and keeps its place in
+    /// document order unless the target already had one. Returns `false`,
+    /// changing nothing, if the source key is absent.
+    pub(crate) fn move_key(&mut self, section: &str, name: &str, to_section: &str, to_name: &str) -> bool {
+        let from = self.make_key(section, name);
+        if !self.values.contains_key(&from) {
+            return false;
+        }
+        let value = self.values.remove(&from).unwrap_or_default();
+        let mut occurrences = self.occurrences.remove(&from).unwrap_or_default();
+        for occurrence in &mut occurrences {
+            occurrence.name = to_name.to_string();
+        }
+
+        // Order entries are never removed, so positions handed out later stay unique
+        let to = self.make_key(to_section, to_name);
+        if let Some(&order) = self.key_order.get(&from).filter(|_| !self.key_order.contains_key(&to)) {
+            self.key_order.insert(to.clone(), order);
+        }
+        let folded = self.fold(to_section);
+        if !self.sections.iter().any(|name| self.fold(name) == folded) {
+            self.note_section(to_section);
+        }
+        self.occurrences.insert(to.clone(), occurrences);
+        self.values.insert(to, value);
+        self.order_index.take();
+        self.document = None;
+        true
+    }
+
+    /// Replace the value of a key, which becomes its only occurrence
+    ///
+    /// The occurrence keeps the spelling and line of the one the old value
+    /// came from. Returns `false` if the key is absent.
+    pub(crate) fn replace_value(&mut self, section: &str, name: &str, value: String) -> bool {
+        let key = self.make_key(section, name);
+        let Some(current) = self.value_occurrence(&key).cloned() else {
+            return false;
+        };
+        let occurrence = Occurrence { value: value.clone(), bare: false, ..current };
+        self.occurrences.insert(key.clone(), vec![occurrence]);
+        self.values.insert(key, value);
+        self.document = None;
+        true
+    }
+
+    /// Rename a section, moving its keys into `to` and replacing same-named keys there
+    ///
+    /// The renamed section keeps its place in document order unless `to`
+    /// already had one. Returns `false` if the section is absent.
+    pub(crate) fn rename_section(&mut self, section: &str, to: &str) -> bool {
+        if !self.section_present(section) {
+            return false;
+        }
+
+        // Register the new name first, so moving the keys does not place it at the end
+        let folded = self.fold(section);
+        let order = self
+            .sections
+            .iter()
+            .filter(|name| self.fold(name) == folded)
+            .filter_map(|name| self.section_order.get(name).copied())
+            .min();
+        let sensitive = self.case_sensitive;
+        Arc::make_mut(&mut self.sections).retain(|name| fold(name, sensitive) != folded);
+        self.order_index.take();
+        let to_folded = self.fold(to);
+        let existing = self.sections.iter().any(|name| self.fold(name) == to_folded);
+        if !to.is_empty() && !existing {
+            Arc::make_mut(&mut self.sections).insert(to.to_string());
+            let order = order.unwrap_or(self.section_order.len());
+            Arc::make_mut(&mut self.section_order).insert(to.to_string(), order);
+        }
+
+        for key in self.keys(section) {
+            let name = self.value_occurrence(&self.make_key(section, &key)).map_or(key.clone(), |o| o.name.clone());
+            self.move_key(section, &key, to, &name);
+        }
+        self.document = None;
+        true
+    }
+
+    /// Register a section, remembering when it first appeared
+    fn note_section(&mut self, section: &str) {
+        if !section.is_empty() && !self.sections.contains(section) {
+            let order = self.section_order.len();
+            Arc::make_mut(&mut self.sections).insert(section.to_string());
+            Arc::make_mut(&mut self.section_order).insert(section.to_string(), order);
+            self.order_index.take();
+        }
+    }
+
+    /// Register a key, remembering when it first appeared
+    fn note_key(&mut self, key: &str) {
+        if !self.key_order.contains_key(key) {
+            let order = self.key_order.len();
+            self.key_order.insert(key.to_string(), order);
+            self.order_index.take();
+        }
+    }
+
     /// Check if a section exists
-    pub fn has_section(&self, section: &str) -> bool {
-        self.sections.contains(section)
+    ///
+    /// A section exists once a header declares it or a value is set in it,
+    /// and keeps existing after its last key is removed; only
+    /// [`remove_section`](Self::remove_section), `split_off` and `clear` remove it.
+    pub fn has_section(&self, section: impl SectionName) -> bool {
+        self.sections.contains(section.section_name())
     }
 
     /// Check if a value exists
-    pub fn has_value(&self, section: &str, name: &str) -> bool {
-        let key = Self::make_key(section, name);
-        self.values.contains_key(&key)
+    ///
+    /// Only the section itself is looked at, not the
+    /// [default section](ReaderOptions::default_section).
+    pub fn has_value(&self, section: impl SectionName, name: impl KeyName) -> bool {
+        self.values.contains_key(&self.make_key(section.section_name(), name.key_name()))
+    }
+
+    /// A name as compared in lookups: case-folded unless the reader is case-sensitive
+    pub(crate) fn fold(&self, name: &str) -> String {
+        fold(name, self.case_sensitive)
+    }
+
+    /// Create a key from section and name (case-insensitive unless configured otherwise, ignoring padding around the name)
+    fn make_key(&self, section: &str, name: &str) -> String {
+        self.key_prefix(section) + &self.fold(name.trim())
     }
 
-    /// Create a key from section and name (case-insensitive)
-    fn make_key(section: &str, name: &str) -> String {
-        format!("{}={}", section.to_lowercase(), name.to_lowercase())
+    /// Start of the keys of a section: its folded name with `\` and `=` escaped, then `=`
+    ///
+    /// The escaping keeps a section named `a=b` from claiming the keys of `a`.
+    fn key_prefix(&self, section: &str) -> String {
+        let mut prefix = String::with_capacity(section.len() + 1);
+        for c in self.fold(section).chars() {
+            if c == '\\' || c == '=' {
+                prefix.push('\\');
+            }
+            prefix.push(c);
+        }
+        prefix.push('=');
+        prefix
     }
 }
 
-impl IniHandler for IniReader {
-    fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String> {
+/// Recording into the reader cannot fail; the `IniHandler` impl below only
+/// forwards here and always returns `Ok`
+impl IniReader {
+    /// Record a value delivered without entry details
+    fn record_value(&mut self, section: &str, name: &str, value: &str) {
         // Register section
-        if !section.is_empty() {
-            self.sections.insert(section.to_string());
-        }
+        self.note_section(section);
         
         if name.is_empty() {
-            // This happens when INI_CALL_HANDLER_ON_NEW_SECTION is enabled
-            return Ok(());
+            // A header, delivered when `call_handler_on_new_section` is set
+            return;
         }
         
-        let key = Self::make_key(section, name);
+        let key = self.make_key(section, name);
+        self.note_key(&key);
         
         // Handle multi-line values by concatenating with newlines
         if let Some(existing_value) = self.values.get_mut(&key) {
@@ -186,10 +1869,449 @@ impl IniHandler for IniReader {
             existing_value.push_str(value);
         } else {
             self.values.insert(key, value.to_string());
+            self.order_index.take();
         }
-        
+    }
+
+    /// Record an entry or one of its continuation lines
+    fn record_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) {
+        self.note_section(section);
+
+        let newline = self.value_newline.terminator(self.last_line_ending);
+        self.last_line_ending = info.line_ending;
+        if self.on_insert.is_none() {
+            let bare = info.separator.is_none();
+            self.store_entry(section, name, value, info.line, bare, info.continuation.then_some(newline));
+            return;
+        }
+
+        // Hold the entry back until its value is complete, so the hook sees all of it
+        if info.continuation {
+            if let Some(pending) = &mut self.pending {
+                pending.value.push_str(newline);
+                pending.value.push_str(value);
+            }
+            return;
+        }
+        self.flush_pending();
+        self.pending = Some(PendingEntry {
+            section: section.to_string(),
+            name: name.to_string(),
+            value: value.to_string(),
+            line: info.line,
+            raw_line: info.raw_line.to_string(),
+            bare: info.separator.is_none(),
+        });
+    }
+
+    /// Pass the held-back entry to the insert hook and store it if accepted
+    fn flush_pending(&mut self) {
+        let (Some(pending), Some(on_insert)) = (self.pending.take(), self.on_insert.as_mut()) else {
+            return;
+        };
+        let value = match on_insert(&pending.section, &pending.name, &pending.value) {
+            InsertDecision::Accept => pending.value,
+            InsertDecision::AcceptRewritten(value) => value,
+            InsertDecision::Skip => return,
+            InsertDecision::Reject(message) => {
+                self.errors.push(IniParseError::RejectedEntry { line: pending.line, raw_line: pending.raw_line, message });
+                return;
+            }
+        };
+        self.store_entry(&pending.section, &pending.name, &value, pending.line, pending.bare, None);
+    }
+
+    /// Store an entry, or with `continuation` set, append a line joined by it
+    fn store_entry(&mut self, section: &str, name: &str, value: &str, line: usize, bare: bool, continuation: Option<&str>) {
+        let key = self.make_key(section, name);
+        self.note_key(&key);
+        if continuation.is_none() && self.document_keys.insert(key.clone()) {
+            // First time this document sets the key: replace what earlier documents left
+            if self.values.contains_key(&key) {
+                self.values.remove(&key);
+                self.occurrences.remove(&key);
+            }
+        }
+        let occurrences = self.occurrences.entry_or_default(key.clone());
+        match (occurrences.last_mut(), continuation) {
+            (Some(last), Some(newline)) => {
+                last.value.push_str(newline);
+                last.value.push_str(value);
+            }
+            _ => occurrences.push(Occurrence { name: name.to_string(), value: value.to_string(), line, bare }),
+        }
+
+        match (self.values.get_mut(&key), continuation) {
+            (Some(existing), Some(newline)) => {
+                existing.push_str(newline);
+                existing.push_str(value);
+            }
+            (Some(existing), None) => match &self.duplicate_policy {
+                DuplicateKeyPolicy::Concatenate { separator } => {
+                    existing.push_str(separator);
+                    existing.push_str(value);
+                }
+                _ => *existing = value.to_string(),
+            },
+            (None, _) => {
+                self.values.insert(key, value.to_string());
+                self.order_index.take();
+            }
+        }
+    }
+
+    /// Interpolate the values the document that just ended set
+    fn interpolate(&mut self) {
+        let mut keys = Vec::new();
+        let mut entries = HashMap::new();
+        for (section, name, value) in self.normalized_entries() {
+            let key = self.make_key(section, name);
+            let pending = self.document_keys.contains(&key);
+            let entry = Entry { section: section.to_string(), name: name.to_string(), value: value.to_string(), pending };
+            keys.push(key.clone());
+            entries.insert(key, entry);
+        }
+        let mut expansion = self.expansion.take().unwrap_or_else(|| Expansion::new(self.expansion_budget));
+        let key_of = |section: &str, name: &str| self.make_key(section, name);
+        let resolver = Resolver::new(self.interpolation, self.strict_interpolation, &mut expansion, entries, &key_of);
+        let (changed, errors) = resolver.run(&keys);
+        self.interpolation_errors.extend(errors);
+        if !changed.is_empty() {
+            let interpolated = Arc::make_mut(&mut self.interpolated);
+            for (key, value) in changed {
+                if let Some(raw) = self.values.insert(key.clone(), value.clone()) {
+                    interpolated.insert(key, (raw, value));
+                }
+            }
+        }
+    }
+
+    /// Interpolate the rewritten keys again, along with every value interpolation changed before
+    ///
+    /// The others are restored as written first, so they see the rewritten
+    /// values; errors already reported are not reported twice.
+    fn reinterpolate(&mut self, mut pending: std::collections::HashSet<String>) {
+        for (key, (raw, value)) in Arc::unwrap_or_clone(std::mem::take(&mut self.interpolated)) {
+            if !pending.contains(&key) && self.values.get(&key) == Some(&value) {
+                self.values.insert(key.clone(), raw);
+                pending.insert(key);
+            }
+        }
+        let reported = self.interpolation_errors.len();
+        let document_keys = std::mem::replace(&mut self.document_keys, pending);
+        self.interpolate();
+        self.document_keys = document_keys;
+        for error in self.interpolation_errors.split_off(reported) {
+            if !self.interpolation_errors.contains(&error) {
+                self.interpolation_errors.push(error);
+            }
+        }
+    }
+
+    /// Forget the per-document state once a document has been parsed
+    fn end_recorded_document(&mut self) {
+        self.document_keys.clear();
+        self.last_line_ending = "";
+        self.expansion = None;
+    }
+
+    /// Finish a constructor: keep the reader on success, remember the error otherwise
+    ///
+    /// Recording never fails, so the error can only come from reading or
+    /// parsing the input, or be an entry the insert hook rejected.
+    fn loaded(mut self, result: Result<(), IniParseError>) -> Result<Self, IniParseError> {
+        self.flush_pending();
+        let rejected = match (self.errors.first(), &result) {
+            (Some(IniParseError::RejectedEntry { line: rejected, .. }), Err(IniParseError::ParseError { line, .. })) => rejected < line,
+            (Some(_), _) => true,
+            (None, _) => false,
+        };
+        if rejected {
+            return Err(self.errors[0].clone());
+        }
+        match result {
+            Ok(()) => Ok(self),
+            Err(e) => {
+                debug_assert!(
+                    !matches!(e, IniParseError::HandlerError(_) | IniParseError::RejectedEntry { .. }),
+                    "reader handler failed: {}",
+                    e
+                );
+                self.errors.push(e.clone());
+                Err(e)
+            }
+        }
+    }
+}
+
+impl IniHandler for IniReader {
+    fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.record_value(section, name, value);
         Ok(())
     }
+
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.record_entry(info, section, name, value);
+        Ok(())
+    }
+
+    fn start_section(&mut self, header: &SectionHeader) -> Result<(), String> {
+        self.note_section(&header.flattened_name());
+        Ok(())
+    }
+
+    fn end_value(&mut self, _section: &str, _name: &str) -> Result<(), String> {
+        self.flush_pending();
+        Ok(())
+    }
+
+    fn warning(&mut self, warning: &IniWarning) -> Result<(), String> {
+        if let Ok(warnings) = self.warnings.get_mut() {
+            warnings.push(warning.clone());
+        }
+        Ok(())
+    }
+
+    fn start_document(&mut self) {
+        // Left over only by a parse that stopped at its first error
+        self.flush_pending();
+        self.end_recorded_document();
+    }
+
+    fn expansion(&mut self, budget: ExpansionBudget) -> Option<&mut Expansion> {
+        Some(self.expansion.get_or_insert_with(|| Expansion::new(budget)))
+    }
+
+    fn end_document(&mut self) -> Result<(), String> {
+        self.flush_pending();
+        if self.interpolation != Interpolation::None {
+            self.interpolate();
+        }
+        self.end_recorded_document();
+        Ok(())
+    }
+}
+
+/// Escape a field of the canonical form
+fn escape_canonical(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for ch in field.chars() {
+        match ch {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Undo [`escape_canonical`], or `None` for an unknown or dangling escape
+fn unescape_canonical(field: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            unescaped.push(ch);
+            continue;
+        }
+        unescaped.push(match chars.next()? {
+            '\\' => '\\',
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            _ => return None,
+        });
+    }
+    Some(unescaped)
+}
+
+impl fmt::Display for IniReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_ini_string())
+    }
+}
+
+/// Parse a string with the default options, like [`IniReader::from_string`]
+impl TryFrom<&str> for IniReader {
+    type Error = IniParseError;
+
+    fn try_from(data: &str) -> Result<Self, Self::Error> {
+        Self::from_string(data)
+    }
+}
+
+/// Every section, including empty ones and the global section `""` if it has
+/// values; section names as stored, keys normalized
+impl From<IniReader> for HashMap<String, HashMap<String, String>> {
+    fn from(reader: IniReader) -> Self {
+        let mut map: Self = reader.sections().into_iter().map(|section| (section, HashMap::new())).collect();
+        for (section, key, value) in reader.normalized_entries() {
+            map.entry(section.to_string()).or_default().insert(key.to_string(), value.to_string());
+        }
+        map
+    }
+}
+
+/// Like the `HashMap` conversion, sorted by section and key
+impl From<IniReader> for BTreeMap<String, BTreeMap<String, String>> {
+    fn from(reader: IniReader) -> Self {
+        let mut map: Self = reader.sections().into_iter().map(|section| (section, BTreeMap::new())).collect();
+        for (section, key, value) in reader.normalized_entries() {
+            map.entry(section.to_string()).or_default().insert(key.to_string(), value.to_string());
+        }
+        map
+    }
+}
+
+/// Iterator over the values of an [`IniReader`] as (section, key, value), see [`IniReader::iter`]
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    entries: std::vec::IntoIter<(&'a str, &'a str, &'a str)>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a str, &'a str, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl<'a> IntoIterator for &'a IniReader {
+    type Item = (&'a str, &'a str, &'a str);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+/// Sections and keys of a reader in document order, so listing them does not sort every time
+///
+/// Any change to the sections or to which keys hold values drops it.
+#[derive(Debug, Clone, Default)]
+struct OrderIndex {
+    /// Section names as [`IniReader::sections`] lists them
+    sections: Vec<String>,
+    /// Normalized keys holding a value by key prefix, in order of first appearance
+    keys: HashMap<String, Vec<String>>,
+}
+
+impl OrderIndex {
+    fn approx_memory_bytes(&self) -> usize {
+        let strings = |list: &Vec<String>| list.capacity() * size_of::<String>() + list.iter().map(String::capacity).sum::<usize>();
+        strings(&self.sections) + map_bytes(&self.keys, strings)
+    }
+}
+
+/// Fold a name for comparison, leaving it as it is when `case_sensitive`
+fn fold(name: &str, case_sensitive: bool) -> String {
+    if case_sensitive {
+        name.to_string()
+    } else {
+        name.to_lowercase()
+    }
+}
+
+/// A string without spare capacity
+fn shrunk(mut text: String) -> String {
+    text.shrink_to_fit();
+    text
+}
+
+/// Rebuild a table at its length, shrinking the keys and, with `shrink`, the values
+fn shrink_map<V>(map: &mut HashMap<String, V>, mut shrink: impl FnMut(&mut V)) {
+    let mut rebuilt = HashMap::with_capacity(map.len());
+    for (key, mut value) in map.drain() {
+        shrink(&mut value);
+        rebuilt.insert(shrunk(key), value);
+    }
+    *map = rebuilt;
+}
+
+/// Capacity of a table and of its keys, plus what `heap` says each value holds
+fn map_bytes<V>(map: &HashMap<String, V>, heap: impl Fn(&V) -> usize) -> usize {
+    map.capacity() * size_of::<(String, V)>() + map.iter().map(|(key, value)| key.capacity() + heap(value)).sum::<usize>()
+}
+
+/// Set values from (section, key, value) triples
+///
+/// Each triple replaces any value the key had, whatever the duplicate key
+/// policy, and becomes its only occurrence (at line 0). New sections and keys
+/// come after the existing ones in document order; a section that already
+/// exists under another case keeps its spelling. A preserved document no
+/// longer matches the reader afterwards and is dropped.
+impl Extend<(String, String, String)> for IniReader {
+    fn extend<I: IntoIterator<Item = (String, String, String)>>(&mut self, iter: I) {
+        for (section, name, value) in iter {
+            let folded = self.fold(&section);
+            if !self.sections.iter().any(|name| self.fold(name) == folded) {
+                self.note_section(&section);
+            }
+            let key = self.make_key(&section, &name);
+            self.note_key(&key);
+            let occurrence = Occurrence { name, value: value.clone(), line: 0, bare: false };
+            self.occurrences.insert(key.clone(), vec![occurrence]);
+            self.values.insert(key, value);
+            self.order_index.take();
+            self.document = None;
+        }
+    }
+}
+
+/// Shares the stored tables with the original until one of them changes
+///
+/// The insert hook cannot be cloned, so the clone has none.
+impl Clone for IniReader {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            sections: Arc::clone(&self.sections),
+            section_order: Arc::clone(&self.section_order),
+            key_order: self.key_order.clone(),
+            order_index: self.order_index.clone(),
+            errors: self.errors.clone(),
+            document: self.document.clone(),
+            duplicate_policy: self.duplicate_policy.clone(),
+            value_newline: self.value_newline,
+            last_line_ending: self.last_line_ending,
+            occurrences: self.occurrences.clone(),
+            document_keys: self.document_keys.clone(),
+            warnings: Mutex::new(self.warnings()),
+            on_insert: None,
+            pending: self.pending.clone(),
+            case_sensitive: self.case_sensitive,
+            interpolation: self.interpolation,
+            strict_interpolation: self.strict_interpolation,
+            expansion_budget: self.expansion_budget,
+            expansion: None,
+            interpolation_errors: self.interpolation_errors.clone(),
+            interpolated: Arc::clone(&self.interpolated),
+            default_section: self.default_section.clone(),
+            record_skipped: self.record_skipped,
+            skipped_sections: self.skipped_sections.clone(),
+        }
+    }
+}
+
+/// Values and sections are printed sorted, so the output is the same from run to run
+impl fmt::Debug for IniReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IniReader")
+            .field("values", &self.values.iter().collect::<BTreeMap<_, _>>())
+            .field("sections", &self.sections.iter().collect::<BTreeSet<_>>())
+            .field("errors", &self.errors)
+            .field("document", &self.document)
+            .finish()
+    }
 }
 
 #[cfg(test)]
@@ -197,6 +2319,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_basic_parsing() {
         let data = r#"
 [protocol]
@@ -212,9 +2335,9 @@ pi = 3.14159
         let reader = IniReader::from_string(data).unwrap();
         
         assert_eq!(reader.get_integer("protocol", "version", -1), 6);
-        assert_eq!(reader.get_string("user", "name", "UNKNOWN"), "Bob Smith");
-        assert_eq!(reader.get_string("user", "email", "UNKNOWN"), "bob@smith.com");
-        assert_eq!(reader.get_boolean("user", "active", false), true);
+        assert_eq!(reader.get_nonempty_or("user", "name", "UNKNOWN"), "Bob Smith");
+        assert_eq!(reader.get_nonempty_or("user", "email", "UNKNOWN"), "bob@smith.com");
+        assert!(reader.get_boolean("user", "active", false));
         assert_eq!(reader.get_real("user", "pi", 0.0), 3.14159);
     }
 
@@ -246,13 +2369,3 @@ key3=value3
         assert!(!reader.has_value("section1", "key3"));
     }
 }
-
-impl fmt::Debug for IniReader {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("IniReader")
-            .field("values", &self.values)
-            .field("sections", &self.sections)
-            .field("error", &self.error)
-            .finish()
-    }
-}
@@ -0,0 +1,232 @@
+//! Every error and warning of a parse, printed together
+//!
+//! [`ini_check`] and the lossy reader constructors such as
+//! [`IniReader::from_string_lossy`](crate::IniReader::from_string_lossy) do
+//! not stop at the first problem: they return a [`ParseReport`] with all of
+//! them, which prints one line per finding:
+//!
+//! ```rust
+//! use inih::report::ini_check;
+//! use inih::ParseOptions;
+//!
+//! let report = ini_check("[server\nport = 80\n[]\n", &ParseOptions::default()).with_source("app.ini");
+//! assert!(report.is_fatal());
+//! assert_eq!(
+//!     report.to_string(),
+//!     "app.ini:1: error[syntax]: Missing ']' in section header\n\
+//!      app.ini:1: help: insert ']' at byte 7: [server]\n\
+//!      app.ini:3: error[syntax]: Empty section name\n"
+//! );
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::error::{IniParseError, IniWarning, Suggestion};
+use crate::parser::{ini_parse_string_collecting, IniHandler, ParseOptions};
+
+/// How serious a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The input is invalid; what was read around it may be incomplete
+    Error,
+    /// An opt-in lint flagged something the parser accepted
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// One error or warning in a [`ParseReport`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// Error or warning
+    pub severity: Severity,
+    /// Line the finding is on, if it has one
+    pub line: Option<usize>,
+    /// Short machine-readable kind, such as `syntax` or `lint`
+    pub code: &'static str,
+    /// Human-readable description, without the line
+    pub message: String,
+    /// A corrected line that fixes the error, printed as a `help:` line
+    pub suggestion: Option<Suggestion>,
+}
+
+impl Finding {
+    /// The finding for a parse error
+    fn from_error(error: &IniParseError) -> Self {
+        let (line, code, message) = match error {
+            IniParseError::ParseError { line, message, suggestion } => {
+                return Finding { severity: Severity::Error, line: Some(*line), code: "syntax", message: message.clone(), suggestion: suggestion.clone() };
+            }
+            IniParseError::RejectedEntry { line, message, .. } => (Some(*line), "rejected", message.clone()),
+            IniParseError::HandlerError(message) => (None, "handler", message.clone()),
+            IniParseError::LimitExceeded { .. } => (None, "limit", error.to_string()),
+            IniParseError::Encoding(message) => (None, "encoding", message.clone()),
+            IniParseError::FileOpen(_) | IniParseError::FileOpenContext { .. } | IniParseError::NotFound { .. } => {
+                (None, "io", error.to_string())
+            }
+            IniParseError::Document { error, .. } | IniParseError::InFile { error, .. } => return Self::from_error(error),
+            IniParseError::MemoryError => (None, "memory", error.to_string()),
+        };
+        Finding { severity: Severity::Error, line, code, message, suggestion: None }
+    }
+
+    /// The finding for a warning
+    fn from_warning(warning: &IniWarning) -> Self {
+        Finding { severity: Severity::Warning, line: warning.line, code: "lint", message: warning.message.clone(), suggestion: None }
+    }
+}
+
+/// All errors and warnings of one parse
+///
+/// `Display` prints one `source:line: severity[code]: message` line per
+/// finding, in line order, and at most [`max_findings`](Self::with_max_findings)
+/// of them followed by an `... and N more` line. As an [`Error`](std::error::Error)
+/// its source is the first parse error.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseReport {
+    /// Name of the input, used at the start of each line
+    pub source: String,
+    /// Errors and warnings
+    pub findings: Vec<Finding>,
+    /// Parse errors in the order they occurred
+    errors: Vec<IniParseError>,
+    max_findings: Option<usize>,
+}
+
+impl ParseReport {
+    /// A report on `errors` and `warnings`, each in the order they occurred
+    pub(crate) fn new(errors: Vec<IniParseError>, warnings: &[IniWarning]) -> Self {
+        let findings = errors.iter().map(Finding::from_error).chain(warnings.iter().map(Finding::from_warning)).collect();
+        let mut report = ParseReport { source: "<input>".to_string(), findings, errors, max_findings: None };
+        report.sort_by_line();
+        report
+    }
+
+    /// Name the input in the printed lines; `<input>` by default
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    /// Print at most `max` findings, then a line counting the rest
+    pub fn with_max_findings(mut self, max: usize) -> Self {
+        self.max_findings = Some(max);
+        self
+    }
+
+    /// Order findings by line, errors before warnings on the same line
+    ///
+    /// Findings without a line go last. Reports are built sorted; this is for
+    /// findings added or reordered afterwards.
+    pub fn sort_by_line(&mut self) {
+        self.findings.sort_by_key(|finding| (finding.line.is_none(), finding.line, finding.severity == Severity::Warning));
+    }
+
+    /// Whether there is no finding at all
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Whether there is at least one error
+    pub fn is_fatal(&self) -> bool {
+        self.error_count() > 0
+    }
+
+    /// Number of errors
+    pub fn error_count(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    /// Number of warnings
+    pub fn warning_count(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+
+    /// Parse errors in the order they occurred
+    pub fn errors(&self) -> &[IniParseError] {
+        &self.errors
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.findings.iter().filter(|finding| finding.severity == severity).count()
+    }
+}
+
+impl fmt::Display for ParseReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shown = self.max_findings.unwrap_or(usize::MAX).min(self.findings.len());
+        for finding in &self.findings[..shown] {
+            let location = match finding.line {
+                Some(line) => format!("{}:{}", self.source, line),
+                None => self.source.clone(),
+            };
+            writeln!(f, "{}: {}[{}]: {}", location, finding.severity, finding.code, finding.message)?;
+            if let Some(suggestion) = &finding.suggestion {
+                writeln!(f, "{}: help: {}: {}", location, suggestion.description, suggestion.replacement_line)?;
+            }
+        }
+        if shown < self.findings.len() {
+            writeln!(f, "... and {} more", self.findings.len() - shown)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseReport {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.errors.first().map(|error| error as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// Check INI data, collecting every error and warning instead of stopping at the first
+pub fn ini_check(data: &str, options: &ParseOptions) -> ParseReport {
+    check(data, options, None)
+}
+
+/// Check INI data, starting from a warning the decoding recorded
+fn check(data: &str, options: &ParseOptions, fallback: Option<IniWarning>) -> ParseReport {
+    let mut collector = WarningCollector { warnings: fallback.into_iter().collect() };
+    let errors = ini_parse_string_collecting(data, &mut collector, options).err().unwrap_or_default();
+    ParseReport::new(errors, &collector.warnings)
+}
+
+/// Check an INI file; the report is named after the path
+///
+/// A file that cannot be read gives a report with that single error.
+pub fn ini_check_file<P: AsRef<Path>>(path: P, options: &ParseOptions) -> ParseReport {
+    let path = path.as_ref();
+    let report = match fs::read(path) {
+        Ok(bytes) => match crate::encoding::decode_with(&bytes, options.encoding) {
+            Ok((data, fallback)) => check(&data, options, fallback),
+            Err(error) => ParseReport::new(vec![error], &[]),
+        },
+        Err(error) => ParseReport::new(vec![IniParseError::file_open(path, error)], &[]),
+    };
+    report.with_source(path.display().to_string())
+}
+
+/// Handler keeping only the warnings
+#[derive(Default)]
+struct WarningCollector {
+    warnings: Vec<IniWarning>,
+}
+
+impl IniHandler for WarningCollector {
+    fn handle(&mut self, _section: &str, _name: &str, _value: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn warning(&mut self, warning: &IniWarning) -> Result<(), String> {
+        self.warnings.push(warning.clone());
+        Ok(())
+    }
+}
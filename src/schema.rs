@@ -0,0 +1,603 @@
+//! Declarative schemas for validating INI files
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::error::IniParseError;
+use crate::parser::{ini_parse_string_with_options, EntryInfo, IniHandler, ParseOptions};
+use crate::reader::IniReader;
+use crate::value;
+
+/// Type a value must have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    /// Any string
+    Str,
+    /// Integer, as accepted by `IniReader::get_integer`
+    Int,
+    /// Floating-point number
+    Real,
+    /// Boolean, as accepted by `IniReader::get_boolean`
+    Bool,
+    /// Duration such as `30s` or `1h30m`
+    Duration,
+}
+
+impl ValueType {
+    /// Name used in error messages
+    pub fn name(self) -> &'static str {
+        match self {
+            ValueType::Str => "string",
+            ValueType::Int => "integer",
+            ValueType::Real => "real",
+            ValueType::Bool => "boolean",
+            ValueType::Duration => "duration",
+        }
+    }
+
+    /// Numeric value used for range checks, or None if the value does not have this type
+    fn check(self, value: &str) -> Option<Option<f64>> {
+        match self {
+            ValueType::Str => Some(None),
+            ValueType::Int => value::parse_integer(value).map(|v| Some(v as f64)),
+            ValueType::Real => value::parse_real(value).map(Some),
+            ValueType::Bool => value::parse_bool(value).map(|_| None),
+            ValueType::Duration => value::parse_duration(value).map(|d| Some(d.as_secs_f64())),
+        }
+    }
+}
+
+/// Rules for a single key
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeySpec {
+    /// Key name
+    pub name: String,
+    /// Type the value must have
+    pub value_type: ValueType,
+    /// The key must be present
+    pub required: bool,
+    /// Value assumed when the key is absent
+    ///
+    /// It must pass the key's own rules, see [`Schema::check_defaults`], and
+    /// is filled in by [`Schema::apply_defaults`].
+    pub default: Option<String>,
+    /// Inclusive numeric bounds (durations are compared in seconds)
+    pub range: Option<(f64, f64)>,
+    /// If not empty, the only values accepted (case-insensitive)
+    pub allowed: Vec<String>,
+}
+
+/// Rules for a section
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionSpec {
+    /// Section name ("" for the global section)
+    pub name: String,
+    /// Keys the section may contain
+    pub keys: Vec<KeySpec>,
+}
+
+/// A constraint involving several sections
+#[derive(Debug, Clone, PartialEq)]
+pub enum SectionRule {
+    /// If both sections are present, `first` must appear before `then`
+    Precedes { first: String, then: String },
+    /// At most one of the sections may be present
+    Exclusive(Vec<String>),
+    /// If `condition` is present, `section` must contain `keys`
+    RequiresIf { condition: String, section: String, keys: Vec<String> },
+}
+
+/// A description of the sections and keys an INI file may contain
+///
+/// Schemas are usually written with the [`ini_schema!`](crate::ini_schema)
+/// macro, which expands to calls of this builder. Section and key names are
+/// matched case-insensitively; sections and keys not mentioned are errors.
+/// Rules between sections ([`SectionRule`]) are added with the builder only,
+/// and compare sections by where their first header appears.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schema {
+    sections: Vec<SectionSpec>,
+    rules: Vec<SectionRule>,
+}
+
+impl Schema {
+    /// Create an empty schema
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start describing a section; following `key` calls add to it
+    pub fn section(mut self, name: &str) -> Self {
+        self.sections.push(SectionSpec { name: name.to_string(), keys: Vec::new() });
+        self
+    }
+
+    /// Add a key to the last section
+    pub fn key(mut self, name: &str, value_type: ValueType) -> Self {
+        if self.sections.is_empty() {
+            self = self.section("");
+        }
+        let section = self.sections.last_mut().expect("section was just added");
+        section.keys.push(KeySpec {
+            name: name.to_string(),
+            value_type,
+            required: false,
+            default: None,
+            range: None,
+            allowed: Vec::new(),
+        });
+        self
+    }
+
+    /// Make the last key required
+    pub fn required(self) -> Self {
+        self.modify_key(|key| key.required = true)
+    }
+
+    /// Give the last key a default value
+    pub fn default_value(self, value: impl Into<String>) -> Self {
+        let value = value.into();
+        self.modify_key(|key| key.default = Some(value))
+    }
+
+    /// Restrict the last key to an inclusive numeric range
+    pub fn range(self, min: f64, max: f64) -> Self {
+        self.modify_key(|key| key.range = Some((min, max)))
+    }
+
+    /// Restrict the last key to a list of values
+    pub fn allowed(self, values: Vec<String>) -> Self {
+        self.modify_key(|key| key.allowed = values)
+    }
+
+    /// Require `first` to appear before `then` when both are present
+    pub fn precedes(mut self, first: &str, then: &str) -> Self {
+        self.rules.push(SectionRule::Precedes { first: first.to_string(), then: then.to_string() });
+        self
+    }
+
+    /// Allow at most one of `sections` in a file
+    pub fn exclusive(mut self, sections: &[&str]) -> Self {
+        self.rules.push(SectionRule::Exclusive(sections.iter().map(|section| section.to_string()).collect()));
+        self
+    }
+
+    /// Require `keys` in `section` whenever `condition` is present
+    ///
+    /// The keys still have to be described in `section` like any other key.
+    pub fn requires_if(mut self, condition: &str, section: &str, keys: &[&str]) -> Self {
+        self.rules.push(SectionRule::RequiresIf {
+            condition: condition.to_string(),
+            section: section.to_string(),
+            keys: keys.iter().map(|key| key.to_string()).collect(),
+        });
+        self
+    }
+
+    /// The sections described by the schema
+    pub fn sections(&self) -> &[SectionSpec] {
+        &self.sections
+    }
+
+    /// The rules between sections, in the order they were added
+    pub fn rules(&self) -> &[SectionRule] {
+        &self.rules
+    }
+
+    /// Check every default value against the type, range and allowed values of its key
+    ///
+    /// [`ini_validate_string`] reports the same errors for every file.
+    pub fn check_defaults(&self) -> Result<(), Vec<ValidationError>> {
+        let errors: Vec<ValidationError> = self
+            .sections
+            .iter()
+            .flat_map(|section| section.keys.iter().map(move |key| (section, key)))
+            .filter_map(|(section, key)| {
+                let kind = check_value(key, key.default.as_deref()?)?;
+                Some(ValidationError {
+                    line: None,
+                    section: section.name.clone(),
+                    key: Some(key.name.clone()),
+                    kind: ValidationErrorKind::InvalidDefault(Box::new(kind)),
+                })
+            })
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// The schema, after checking its defaults
+    ///
+    /// [`ini_schema!`](crate::ini_schema) ends with this, so that a default
+    /// that breaks its own key's rules is caught where the schema is written.
+    ///
+    /// # Panics
+    ///
+    /// Panics listing every invalid default if [`check_defaults`](Self::check_defaults) fails.
+    pub fn checked(self) -> Self {
+        if let Err(errors) = self.check_defaults() {
+            let errors: Vec<String> = errors.iter().map(ValidationError::to_string).collect();
+            panic!("{}", errors.join("; "));
+        }
+        self
+    }
+
+    /// Set the default value of every key that has one and is missing from `reader`
+    ///
+    /// Returns the number of values set. Keys are looked up as
+    /// [`IniReader::has_value`] does, so a value the reader only inherits
+    /// from its default section does not count as present.
+    pub fn apply_defaults(&self, reader: &mut IniReader) -> usize {
+        let mut applied = 0;
+        for section in &self.sections {
+            for key in &section.keys {
+                if let Some(default) = key.default.as_deref().filter(|_| !reader.has_value(&section.name, &key.name)) {
+                    reader.set(&section.name, &key.name, default);
+                    applied += 1;
+                }
+            }
+        }
+        applied
+    }
+
+    fn modify_key(mut self, f: impl FnOnce(&mut KeySpec)) -> Self {
+        if let Some(key) = self.sections.last_mut().and_then(|section| section.keys.last_mut()) {
+            f(key);
+        }
+        self
+    }
+
+    fn find_section(&self, name: &str) -> Option<&SectionSpec> {
+        self.sections.iter().find(|section| section.name.to_lowercase() == name.to_lowercase())
+    }
+}
+
+/// What is wrong with a validated file
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationErrorKind {
+    /// The file could not be read or parsed
+    Parse(IniParseError),
+    /// A section not described by the schema
+    UnknownSection,
+    /// A key not described by the schema
+    UnknownKey,
+    /// A required key is absent
+    MissingRequired,
+    /// The value does not have the expected type
+    InvalidType { value: String, expected: ValueType },
+    /// The value is outside the allowed range
+    OutOfRange { value: String, min: f64, max: f64 },
+    /// The value is not one of the allowed values
+    NotAllowed { value: String, allowed: Vec<String> },
+    /// The section appears before `first`, whose header is on `first_line`
+    OutOfOrder { first: String, first_line: usize },
+    /// The section is exclusive with `other`, whose header is on `other_line`
+    Exclusive { other: String, other_line: usize },
+    /// The key is required because `condition`, whose header is on `condition_line`, is present
+    ConditionallyRequired { condition: String, condition_line: usize },
+    /// The schema's default value of the key breaks the key's rules as described
+    InvalidDefault(Box<ValidationErrorKind>),
+}
+
+/// A single schema violation
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    /// Line of the offending entry or section header, if there is one
+    pub line: Option<usize>,
+    /// Section concerned
+    pub section: String,
+    /// Key concerned, if the error is about a key
+    pub key: Option<String>,
+    /// What is wrong
+    pub kind: ValidationErrorKind,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        let location = match &self.key {
+            Some(key) => format!("{}.{}", self.section, key),
+            None => format!("[{}]", self.section),
+        };
+        match &self.kind {
+            ValidationErrorKind::Parse(error) => write!(f, "{}", error),
+            ValidationErrorKind::UnknownSection => write!(f, "unknown section {}", location),
+            ValidationErrorKind::UnknownKey => write!(f, "unknown key {}", location),
+            ValidationErrorKind::MissingRequired => write!(f, "missing required key {}", location),
+            ValidationErrorKind::InvalidType { value, expected } => {
+                write!(f, "invalid value \"{}\" for {}: expected {}", value, location, expected.name())
+            }
+            ValidationErrorKind::OutOfRange { value, min, max } => {
+                write!(f, "value {} for {} is outside {}..={}", value, location, min, max)
+            }
+            ValidationErrorKind::NotAllowed { value, allowed } => {
+                write!(f, "value \"{}\" for {} is not one of {}", value, location, allowed.join(", "))
+            }
+            ValidationErrorKind::OutOfOrder { first, first_line } => {
+                write!(f, "section {} must come after [{}] on line {}", location, first, first_line)
+            }
+            ValidationErrorKind::Exclusive { other, other_line } => {
+                write!(f, "section {} cannot be used together with [{}] on line {}", location, other, other_line)
+            }
+            ValidationErrorKind::ConditionallyRequired { condition, condition_line } => {
+                write!(f, "missing key {}, required by [{}] on line {}", location, condition, condition_line)
+            }
+            ValidationErrorKind::InvalidDefault(kind) => {
+                let error = ValidationError { line: None, section: self.section.clone(), key: self.key.clone(), kind: (**kind).clone() };
+                write!(f, "invalid default: {}", error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Validate an INI file against a schema, reporting every violation
+pub fn ini_validate<P: AsRef<Path>>(path: P, schema: &Schema) -> Result<(), Vec<ValidationError>> {
+    let data = std::fs::read_to_string(path.as_ref()).map_err(|e| {
+        let error = IniParseError::file_open(path.as_ref(), e);
+        vec![ValidationError { line: None, section: String::new(), key: None, kind: ValidationErrorKind::Parse(error) }]
+    })?;
+    ini_validate_string(&data, schema)
+}
+
+/// Validate INI data against a schema, reporting every violation
+///
+/// Errors are sorted by line; errors without a line (invalid defaults of
+/// the schema, missing sections) come last.
+pub fn ini_validate_string(data: &str, schema: &Schema) -> Result<(), Vec<ValidationError>> {
+    let mut collector = EntryCollector::default();
+    let mut errors = schema.check_defaults().err().unwrap_or_default();
+    // Headers are collected from the calls that carry their line
+    let options = ParseOptions { call_handler_on_new_section: true, ..ParseOptions::default() };
+    if let Err(error) = ini_parse_string_with_options(data, &mut collector, &options) {
+        let line = match error {
+            IniParseError::ParseError { line, .. } => Some(line),
+            _ => None,
+        };
+        errors.push(ValidationError { line, section: String::new(), key: None, kind: ValidationErrorKind::Parse(error) });
+    }
+
+    let error = |line, section: &str, key: Option<&str>, kind| ValidationError {
+        line,
+        section: section.to_string(),
+        key: key.map(str::to_string),
+        kind,
+    };
+
+    for (section, line) in &collector.sections {
+        if schema.find_section(section).is_none() {
+            errors.push(error(Some(*line), section, None, ValidationErrorKind::UnknownSection));
+        }
+    }
+
+    for (section, name, raw, line) in &collector.entries {
+        let Some(section_spec) = schema.find_section(section) else {
+            if section.is_empty() {
+                errors.push(error(Some(*line), section, Some(name), ValidationErrorKind::UnknownKey));
+            }
+            continue;
+        };
+        let Some(spec) = section_spec.keys.iter().find(|key| key.name.to_lowercase() == name.to_lowercase()) else {
+            errors.push(error(Some(*line), section, Some(name), ValidationErrorKind::UnknownKey));
+            continue;
+        };
+        if let Some(kind) = check_value(spec, raw) {
+            errors.push(error(Some(*line), section, Some(name), kind));
+        }
+    }
+
+    for section_spec in schema.sections() {
+        let header_line = collector
+            .sections
+            .iter()
+            .find(|(section, _)| section.to_lowercase() == section_spec.name.to_lowercase())
+            .map(|(_, line)| *line);
+        for spec in section_spec.keys.iter().filter(|key| key.required) {
+            if !collector.has_entry(&section_spec.name, &spec.name) {
+                errors.push(error(header_line, &section_spec.name, Some(&spec.name), ValidationErrorKind::MissingRequired));
+            }
+        }
+    }
+
+    for rule in schema.rules() {
+        match rule {
+            SectionRule::Precedes { first, then } => {
+                if let (Some(first_line), Some(then_line)) = (collector.header_line(first), collector.header_line(then)) {
+                    if then_line < first_line {
+                        let kind = ValidationErrorKind::OutOfOrder { first: first.clone(), first_line };
+                        errors.push(error(Some(then_line), then, None, kind));
+                    }
+                }
+            }
+            SectionRule::Exclusive(group) => {
+                let mut present: Vec<(&String, usize)> =
+                    group.iter().filter_map(|section| Some((section, collector.header_line(section)?))).collect();
+                present.sort_by_key(|&(_, line)| line);
+                if let Some((&(other, other_line), rest)) = present.split_first() {
+                    for &(section, line) in rest {
+                        let kind = ValidationErrorKind::Exclusive { other: other.clone(), other_line };
+                        errors.push(error(Some(line), section, None, kind));
+                    }
+                }
+            }
+            SectionRule::RequiresIf { condition, section, keys } => {
+                let Some(condition_line) = collector.header_line(condition) else {
+                    continue;
+                };
+                let line = collector.header_line(section).unwrap_or(condition_line);
+                for key in keys.iter().filter(|key| !collector.has_entry(section, key)) {
+                    let kind = ValidationErrorKind::ConditionallyRequired { condition: condition.clone(), condition_line };
+                    errors.push(error(Some(line), section, Some(key), kind));
+                }
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        errors.sort_by_key(|error| error.line.unwrap_or(usize::MAX));
+        Err(errors)
+    }
+}
+
+/// Check a value against the rules of its key
+fn check_value(spec: &KeySpec, value: &str) -> Option<ValidationErrorKind> {
+    let Some(number) = spec.value_type.check(value) else {
+        return Some(ValidationErrorKind::InvalidType { value: value.to_string(), expected: spec.value_type });
+    };
+    if let (Some(number), Some((min, max))) = (number, spec.range) {
+        if number < min || number > max {
+            return Some(ValidationErrorKind::OutOfRange { value: value.to_string(), min, max });
+        }
+    }
+    if !spec.allowed.is_empty() && !spec.allowed.iter().any(|allowed| allowed.eq_ignore_ascii_case(value)) {
+        return Some(ValidationErrorKind::NotAllowed { value: value.to_string(), allowed: spec.allowed.clone() });
+    }
+    None
+}
+
+/// Handler remembering every entry with its line
+#[derive(Default)]
+struct EntryCollector {
+    /// First header line of each section, in document order
+    sections: Vec<(String, usize)>,
+    /// (section, name, value, line) of each entry
+    entries: Vec<(String, String, String, usize)>,
+    /// Index of each section in `sections`, by folded name
+    section_index: HashMap<String, usize>,
+}
+
+impl EntryCollector {
+    /// Line of the first header of a section, if it has one
+    fn header_line(&self, section: &str) -> Option<usize> {
+        self.section_index.get(&section.to_lowercase()).map(|&index| self.sections[index].1)
+    }
+
+    /// Whether a section contains a key
+    fn has_entry(&self, section: &str, name: &str) -> bool {
+        self.entries.iter().any(|(entry_section, entry_name, _, _)| {
+            entry_section.to_lowercase() == section.to_lowercase() && entry_name.to_lowercase() == name.to_lowercase()
+        })
+    }
+}
+
+impl IniHandler for EntryCollector {
+    fn handle(&mut self, _section: &str, _name: &str, _value: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn handle_at(&mut self, line: usize, section: &str, name: &str, _value: &str) -> Result<(), String> {
+        if name.is_empty() && !self.section_index.contains_key(&section.to_lowercase()) {
+            self.section_index.insert(section.to_lowercase(), self.sections.len());
+            self.sections.push((section.to_string(), line));
+        }
+        Ok(())
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        match self.entries.last_mut() {
+            Some((_, _, existing, _)) if info.continuation => {
+                existing.push('\n');
+                existing.push_str(value);
+            }
+            _ => self.entries.push((section.to_string(), name.to_string(), value.to_string(), info.line)),
+        }
+        Ok(())
+    }
+}
+
+/// Build a [`Schema`] from a concise declaration
+///
+/// ```rust
+/// use inih::ini_schema;
+///
+/// let schema = ini_schema! {
+///     [server]
+///     host: str required,
+///     port: int in 1..=65535,
+///     mode: str one_of ["dev", "prod"],
+///     debug: bool default false;
+///     [cache]
+///     ttl: duration;
+/// };
+/// assert_eq!(schema.sections().len(), 2);
+/// ```
+///
+/// Types are `str`, `int`, `real`, `bool` and `duration`. Keys are separated by
+/// commas and each section ends with a semicolon. A section name that is not
+/// an identifier, such as `["my-section"]` or `[""]` for the global section,
+/// is written as a string literal.
+///
+/// # Panics
+///
+/// Panics if a default breaks the rules of its own key, as in
+/// `ttl: duration default "banana"`; see [`Schema::checked`](crate::schema::Schema::checked).
+#[macro_export]
+macro_rules! ini_schema {
+    ($($body:tt)*) => {
+        $crate::__ini_schema_sections!(($crate::schema::Schema::new()); $($body)*).checked()
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ini_schema_sections {
+    (($schema:expr);) => { $schema };
+    (($schema:expr); [$section:ident] $($rest:tt)*) => {
+        $crate::__ini_schema_keys!(($schema.section(stringify!($section))); $($rest)*)
+    };
+    (($schema:expr); [$section:literal] $($rest:tt)*) => {
+        $crate::__ini_schema_keys!(($schema.section($section)); $($rest)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ini_schema_keys {
+    (($schema:expr);) => { $schema };
+    (($schema:expr); ; $($rest:tt)*) => { $crate::__ini_schema_sections!(($schema); $($rest)*) };
+    (($schema:expr); , $($rest:tt)*) => { $crate::__ini_schema_keys!(($schema); $($rest)*) };
+    (($schema:expr); $key:ident : $ty:ident $($rest:tt)*) => {
+        $crate::__ini_schema_constraints!(
+            ($schema.key(stringify!($key), $crate::__ini_schema_type!($ty))); $($rest)*
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ini_schema_constraints {
+    (($schema:expr); required $($rest:tt)*) => {
+        $crate::__ini_schema_constraints!(($schema.required()); $($rest)*)
+    };
+    (($schema:expr); in $min:literal ..= $max:literal $($rest:tt)*) => {
+        $crate::__ini_schema_constraints!(($schema.range($min as f64, $max as f64)); $($rest)*)
+    };
+    (($schema:expr); default $value:literal $($rest:tt)*) => {
+        $crate::__ini_schema_constraints!(($schema.default_value($value.to_string())); $($rest)*)
+    };
+    (($schema:expr); one_of [$($value:literal),* $(,)?] $($rest:tt)*) => {
+        $crate::__ini_schema_constraints!(($schema.allowed(vec![$($value.to_string()),*])); $($rest)*)
+    };
+    (($schema:expr); $($rest:tt)*) => {
+        $crate::__ini_schema_keys!(($schema); $($rest)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ini_schema_type {
+    (str) => { $crate::schema::ValueType::Str };
+    (int) => { $crate::schema::ValueType::Int };
+    (real) => { $crate::schema::ValueType::Real };
+    (bool) => { $crate::schema::ValueType::Bool };
+    (duration) => { $crate::schema::ValueType::Duration };
+}
@@ -0,0 +1,112 @@
+//! Accessors scoped to one section
+//!
+//! [`IniReader::section`] returns a [`SectionView`] for code that reads many
+//! keys from the same section, so the section name is checked once and not
+//! repeated on every call:
+//!
+//! ```rust
+//! use inih::IniReader;
+//!
+//! let reader = IniReader::from_string("[db]\nhost = db.local\nport = 5432\n").unwrap();
+//! let Some(db) = reader.section("db") else {
+//!     panic!("no [db] section");
+//! };
+//! assert_eq!(db.get("host", ""), "db.local");
+//! assert_eq!(db.get_integer("port", 0), 5432);
+//! assert!(reader.section("cache").is_none());
+//! ```
+
+use crate::reader::{IniReader, KeyName};
+
+/// The values of one section of an [`IniReader`]
+///
+/// The getters behave as the reader's getters of the same name with the
+/// section filled in. A view sees every block of its section, however often
+/// and in whatever case the header is repeated.
+#[derive(Debug, Clone, Copy)]
+pub struct SectionView<'a> {
+    reader: &'a IniReader,
+    name: &'a str,
+}
+
+impl<'a> SectionView<'a> {
+    /// Section name as first written; `""` for the global section
+    pub fn name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Get a string value, returning the default if not found
+    pub fn get(&self, name: impl KeyName, default_value: &str) -> String {
+        self.reader.get(self.name, name, default_value)
+    }
+
+    /// Get a string value, returning the default if not found or empty
+    pub fn get_nonempty_or(&self, name: impl KeyName, default_value: &str) -> String {
+        self.reader.get_nonempty_or(self.name, name, default_value)
+    }
+
+    /// Get an integer value, returning the default if not found, empty or invalid
+    pub fn get_integer(&self, name: impl KeyName, default_value: i64) -> i64 {
+        self.reader.get_integer(self.name, name, default_value)
+    }
+
+    /// Get a floating point value, returning the default if not found, empty or invalid
+    pub fn get_real(&self, name: impl KeyName, default_value: f64) -> f64 {
+        self.reader.get_real(self.name, name, default_value)
+    }
+
+    /// Get a boolean value, returning the default if not found, empty or invalid
+    pub fn get_boolean(&self, name: impl KeyName, default_value: bool) -> bool {
+        self.reader.get_boolean(self.name, name, default_value)
+    }
+
+    /// Check if a value exists
+    pub fn has_value(&self, name: impl KeyName) -> bool {
+        self.reader.has_value(self.name, name)
+    }
+
+    /// Normalized keys in order of first appearance, as [`IniReader::keys`] lists them
+    pub fn keys(&self) -> Vec<String> {
+        self.reader.keys(self.name)
+    }
+
+    /// Iterate over (key, value), as [`IniReader::iter_section`] does
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        self.reader.iter_section(self.name)
+    }
+
+    /// Number of keys
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Whether the section has no keys, as a section declared without any
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl IniReader {
+    /// A view of one section, or None if it is neither declared nor has values
+    ///
+    /// The name matches as in lookups; `""` is the global section, present
+    /// once it has a value.
+    pub fn section(&self, name: &str) -> Option<SectionView<'_>> {
+        let name = if name.is_empty() {
+            ""
+        } else {
+            let folded = self.fold(name);
+            self.section_names().find(|section| self.fold(section) == folded)?
+        };
+        self.section_present(name).then_some(SectionView { reader: self, name })
+    }
+
+    /// Iterate over the declared sections as (name, view), in document order
+    ///
+    /// The global section is left out, as in [`sections`](Self::sections).
+    /// Unlike `sections`, nothing is copied: the names are borrowed from the
+    /// reader, which keeps them in order until it next changes.
+    pub fn sections_iter(&self) -> impl Iterator<Item = (&str, SectionView<'_>)> + '_ {
+        self.section_names().map(move |name| (name, SectionView { reader: self, name }))
+    }
+}
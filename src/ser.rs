@@ -0,0 +1,410 @@
+//! Writing your own types out as INI with serde (feature `serde`)
+//!
+//! The mirror image of [`de`](crate::de): fields holding structs or maps
+//! become sections, and the other top-level fields go into the global
+//! section, before the first header:
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Config {
+//!     name: String,
+//!     server: Server,
+//! }
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Server {
+//!     port: u16,
+//!     tls: bool,
+//!     proxy: Option<String>,
+//! }
+//!
+//! let config = Config { name: "demo".to_string(), server: Server { port: 8080, tls: true, proxy: None } };
+//! let text = inih::to_string(&config).unwrap();
+//! assert_eq!(text, "name = demo\n\n[server]\nport = 8080\ntls = true\n");
+//! assert_eq!(inih::from_str::<Config>(&text).unwrap(), config);
+//! ```
+//!
+//! Values are written as the typed setters write them, following
+//! [`WriteOptions`]: booleans as `true`/`false` by default, reals in their
+//! shortest exact form, sequences as comma-separated lists and unit enum
+//! variants by name. A `None` leaves the key (or section) out.
+//!
+//! Output goes through [`IniWriter`], so anything that would not read back
+//! unchanged is an error rather than a corrupt file. INI has only one level
+//! of sections: a struct or map inside a section is an error, unless
+//! [`SerializeOptions::dotted_sections`] writes it as a section of its own.
+
+use serde::ser::{self, Impossible, Serialize};
+
+use crate::error::IniSerError;
+use crate::writer::{format_boolean, format_real, IniWriter, WriteOptions};
+
+/// Options for [`to_string_with`] and [`to_ini_writer`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SerializeOptions {
+    /// Write a struct or map nested in a section as the section `[parent.child]`
+    ///
+    /// It comes after the keys of its parent. [`de`](crate::de) reads it back
+    /// as a section named `parent.child`, not as a nested struct.
+    pub dotted_sections: bool,
+    /// How entries and values are written
+    pub write_options: WriteOptions,
+}
+
+/// Write `value` as INI text with the default options
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, IniSerError> {
+    to_string_with(value, &SerializeOptions::default())
+}
+
+/// Write `value` as INI text with `options`
+pub fn to_string_with<T: Serialize + ?Sized>(value: &T, options: &SerializeOptions) -> Result<String, IniSerError> {
+    Ok(to_ini_writer(value, options)?.to_string())
+}
+
+/// Lay `value` out in an [`IniWriter`], to add to or save with [`IniWriter::write_to_file`]
+pub fn to_ini_writer<T: Serialize + ?Sized>(value: &T, options: &SerializeOptions) -> Result<IniWriter, IniSerError> {
+    let entries = match value.serialize(NodeSerializer { options: &options.write_options })? {
+        Node::Table(entries) => entries,
+        Node::Missing => Vec::new(),
+        Node::Value(_) => return Err(unsupported("only a struct or map can be written as INI")),
+    };
+
+    let mut writer = IniWriter::with_options(options.write_options.clone());
+    let mut sections = Vec::new();
+    for (name, node) in entries {
+        match node {
+            Node::Value(value) => writer.set("", &name, &value)?,
+            Node::Table(entries) => sections.push((name, entries)),
+            Node::Missing => {}
+        }
+    }
+    for (section, entries) in sections {
+        write_section(&mut writer, section, entries, options)?;
+    }
+    Ok(writer)
+}
+
+impl ser::Error for IniSerError {
+    fn custom<T: std::fmt::Display>(message: T) -> Self {
+        unsupported(&message.to_string())
+    }
+}
+
+fn unsupported(message: &str) -> IniSerError {
+    IniSerError::Unsupported { path: Vec::new(), message: message.to_string() }
+}
+
+/// Write the keys of a section, then any sections nested in it
+fn write_section(
+    writer: &mut IniWriter,
+    section: String,
+    entries: Vec<(String, Node)>,
+    options: &SerializeOptions,
+) -> Result<(), IniSerError> {
+    writer.set_section(&section)?;
+    let mut nested = Vec::new();
+    for (name, node) in entries {
+        match node {
+            Node::Value(value) => writer.set(&section, &name, &value)?,
+            Node::Table(entries) if options.dotted_sections => nested.push((format!("{}.{}", section, name), entries)),
+            Node::Table(_) => {
+                let message = "INI cannot nest a struct or map inside a section (see SerializeOptions::dotted_sections)";
+                return Err(unsupported(message).within(&name).within(&section));
+            }
+            Node::Missing => {}
+        }
+    }
+    for (section, entries) in nested {
+        write_section(writer, section, entries, options)?;
+    }
+    Ok(())
+}
+
+/// A serialized value, before it is laid out as sections and keys
+enum Node {
+    /// A single value
+    Value(String),
+    /// Named values of a struct or map, in order
+    Table(Vec<(String, Node)>),
+    /// `None`: nothing to write
+    Missing,
+}
+
+/// Serializer of any value into a [`Node`]
+#[derive(Clone, Copy)]
+struct NodeSerializer<'o> {
+    options: &'o WriteOptions,
+}
+
+impl<'o> ser::Serializer for NodeSerializer<'o> {
+    type Ok = Node;
+    type Error = IniSerError;
+    type SerializeSeq = ListSerializer<'o>;
+    type SerializeTuple = ListSerializer<'o>;
+    type SerializeTupleStruct = ListSerializer<'o>;
+    type SerializeTupleVariant = Impossible<Node, IniSerError>;
+    type SerializeMap = TableSerializer<'o>;
+    type SerializeStruct = TableSerializer<'o>;
+    type SerializeStructVariant = Impossible<Node, IniSerError>;
+
+    fn serialize_bool(self, v: bool) -> Result<Node, IniSerError> {
+        Ok(Node::Value(format_boolean(v, self.options).to_string()))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Node, IniSerError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Node, IniSerError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Node, IniSerError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Node, IniSerError> {
+        Ok(Node::Value(v.to_string()))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Node, IniSerError> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Node, IniSerError> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Node, IniSerError> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Node, IniSerError> {
+        Ok(Node::Value(v.to_string()))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Node, IniSerError> {
+        // Widening first would write 0.1 as 0.10000000149011612
+        match self.options.real_precision {
+            None => Ok(Node::Value(format!("{:?}", v))),
+            Some(_) => self.serialize_f64(v.into()),
+        }
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Node, IniSerError> {
+        Ok(Node::Value(format_real(v, self.options)))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Node, IniSerError> {
+        Ok(Node::Value(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Node, IniSerError> {
+        Ok(Node::Value(v.to_string()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Node, IniSerError> {
+        match std::str::from_utf8(v) {
+            Ok(text) => self.serialize_str(text),
+            Err(_) => Err(unsupported("bytes that are not UTF-8 cannot be written")),
+        }
+    }
+
+    fn serialize_none(self) -> Result<Node, IniSerError> {
+        Ok(Node::Missing)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Node, IniSerError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Node, IniSerError> {
+        Ok(Node::Value(String::new()))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Node, IniSerError> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Node, IniSerError> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<Node, IniSerError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _value: &T,
+    ) -> Result<Node, IniSerError> {
+        Err(variant_with_data(variant))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ListSerializer<'o>, IniSerError> {
+        Ok(ListSerializer { serializer: self, items: Vec::with_capacity(len.unwrap_or(0)) })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ListSerializer<'o>, IniSerError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<ListSerializer<'o>, IniSerError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, IniSerError> {
+        Err(variant_with_data(variant))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<TableSerializer<'o>, IniSerError> {
+        Ok(TableSerializer { serializer: self, entries: Vec::with_capacity(len.unwrap_or(0)), key: None })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<TableSerializer<'o>, IniSerError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, IniSerError> {
+        Err(variant_with_data(variant))
+    }
+}
+
+fn variant_with_data(variant: &str) -> IniSerError {
+    unsupported(&format!("enum variant {} has data, only unit variants can be written", variant))
+}
+
+/// Items of a sequence, written as a comma-separated list
+struct ListSerializer<'o> {
+    serializer: NodeSerializer<'o>,
+    items: Vec<String>,
+}
+
+impl ListSerializer<'_> {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), IniSerError> {
+        let item = match value.serialize(self.serializer)? {
+            Node::Value(item) => item,
+            Node::Table(_) => return Err(unsupported("a list of structs or maps cannot be written")),
+            Node::Missing => return Err(unsupported("a list item that is None cannot be written")),
+        };
+        // parse_list splits on commas and line breaks, trims items and drops empty ones
+        if item.is_empty() || item.trim() != item || item.contains([',', '\n', '\r']) {
+            return Err(unsupported(&format!("list item {:?} would not read back unchanged", item)));
+        }
+        self.items.push(item);
+        Ok(())
+    }
+
+    fn finish(self) -> Node {
+        Node::Value(self.items.join(", "))
+    }
+}
+
+impl ser::SerializeSeq for ListSerializer<'_> {
+    type Ok = Node;
+    type Error = IniSerError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), IniSerError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Node, IniSerError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTuple for ListSerializer<'_> {
+    type Ok = Node;
+    type Error = IniSerError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), IniSerError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Node, IniSerError> {
+        Ok(self.finish())
+    }
+}
+
+impl ser::SerializeTupleStruct for ListSerializer<'_> {
+    type Ok = Node;
+    type Error = IniSerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), IniSerError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Node, IniSerError> {
+        Ok(self.finish())
+    }
+}
+
+/// Fields of a struct or entries of a map
+struct TableSerializer<'o> {
+    serializer: NodeSerializer<'o>,
+    entries: Vec<(String, Node)>,
+    /// Key of a map entry whose value is still to come
+    key: Option<String>,
+}
+
+impl TableSerializer<'_> {
+    fn push<T: Serialize + ?Sized>(&mut self, name: String, value: &T) -> Result<(), IniSerError> {
+        let node = value.serialize(self.serializer).map_err(|error| error.within(&name))?;
+        self.entries.push((name, node));
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for TableSerializer<'_> {
+    type Ok = Node;
+    type Error = IniSerError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), IniSerError> {
+        match key.serialize(self.serializer)? {
+            Node::Value(key) => {
+                self.key = Some(key);
+                Ok(())
+            }
+            _ => Err(unsupported("map keys must be single values")),
+        }
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), IniSerError> {
+        let name = self.key.take().expect("serialize_value is called after serialize_key");
+        self.push(name, value)
+    }
+
+    fn end(self) -> Result<Node, IniSerError> {
+        Ok(Node::Table(self.entries))
+    }
+}
+
+impl ser::SerializeStruct for TableSerializer<'_> {
+    type Ok = Node;
+    type Error = IniSerError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, name: &'static str, value: &T) -> Result<(), IniSerError> {
+        self.push(name.to_string(), value)
+    }
+
+    fn end(self) -> Result<Node, IniSerError> {
+        Ok(Node::Table(self.entries))
+    }
+}
@@ -0,0 +1,85 @@
+//! Orderings for listing sections and keys
+//!
+//! [`IniReader::sections_sorted_by`] and [`IniReader::keys_sorted_by`] take
+//! any comparator; [`natural_cmp`] is the one people expect in a listing,
+//! with numbers in names compared by value:
+//!
+//! ```rust
+//! use inih::sort::natural_cmp;
+//! use inih::IniReader;
+//!
+//! let reader = IniReader::from_string("[worker10]\n[worker2]\n[worker1]\n").unwrap();
+//! assert_eq!(reader.sections_sorted_by(natural_cmp), vec!["worker1", "worker2", "worker10"]);
+//! ```
+
+use std::cmp::Ordering;
+
+use crate::reader::IniReader;
+
+/// Compare two names with runs of ASCII digits compared as numbers
+///
+/// Text between the numbers is compared as by `str::cmp`, and a digit run
+/// against text as text, so digits sort first. Numbers equal in value but
+/// written with different leading zeros are told apart only when the names
+/// are otherwise equal, and then the one with fewer zeros comes first:
+/// `a1 < a01 < a2`.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    // Decides between names that differ only in leading zeros
+    let mut zeros = Ordering::Equal;
+    loop {
+        if a.is_empty() || b.is_empty() {
+            return a.len().cmp(&b.len()).then(zeros);
+        }
+        let (a_run, a_rest) = split_run(a);
+        let (b_run, b_rest) = split_run(b);
+        let order = if is_number(a_run) && is_number(b_run) {
+            let (a_value, b_value) = (a_run.trim_start_matches('0'), b_run.trim_start_matches('0'));
+            zeros = zeros.then(a_run.len().cmp(&b_run.len()));
+            a_value.len().cmp(&b_value.len()).then_with(|| a_value.cmp(b_value))
+        } else {
+            a_run.cmp(b_run)
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+        (a, b) = (a_rest, b_rest);
+    }
+}
+
+/// Split off the leading run of digits or of other characters
+fn split_run(text: &str) -> (&str, &str) {
+    let digits = is_number(text);
+    let end = text.find(|c: char| c.is_ascii_digit() != digits).unwrap_or(text.len());
+    text.split_at(end)
+}
+
+fn is_number(run: &str) -> bool {
+    run.starts_with(|c: char| c.is_ascii_digit())
+}
+
+impl IniReader {
+    /// Section names as [`sections`](Self::sections) lists them, sorted with `compare`
+    ///
+    /// Names the comparator finds equal stay in document order.
+    pub fn sections_sorted_by<F>(&self, mut compare: F) -> Vec<String>
+    where
+        F: FnMut(&str, &str) -> Ordering,
+    {
+        let mut sections = self.sections();
+        sections.sort_by(|a, b| compare(a, b));
+        sections
+    }
+
+    /// Keys of a section as [`keys`](Self::keys) lists them, sorted with `compare`
+    ///
+    /// Keys the comparator finds equal stay in document order.
+    pub fn keys_sorted_by<F>(&self, section: &str, mut compare: F) -> Vec<String>
+    where
+        F: FnMut(&str, &str) -> Ordering,
+    {
+        let mut keys = self.keys(section);
+        keys.sort_by(|a, b| compare(a, b));
+        keys
+    }
+}
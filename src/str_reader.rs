@@ -0,0 +1,252 @@
+//! Reader borrowing its keys and values from the parsed string
+//!
+//! [`IniStrReader`] offers the lookups of [`IniReader`](crate::IniReader) for
+//! an in-memory string without copying it: names and values that appear in
+//! the input as written are kept as `&str` slices of it. Only text the parser
+//! has to build is owned: multi-line and concatenated values, section names,
+//! and names or sections that need case folding.
+//!
+//! ```rust
+//! use std::borrow::Cow;
+//! use inih::str_reader::IniStrReader;
+//! use inih::ParseOptions;
+//!
+//! let data = String::from("[server]\nhost = example.com\nport = 8080\n");
+//! let reader = IniStrReader::from_str(&data, &ParseOptions::default()).unwrap();
+//! assert_eq!(reader.get_integer("server", "port", 0), 8080);
+//!
+//! // The value outlives the reader: it points into `data`
+//! let host = match reader.get_cow("Server", "HOST") {
+//!     Some(Cow::Borrowed(host)) => host,
+//!     _ => unreachable!(),
+//! };
+//! drop(reader);
+//! assert_eq!(host, "example.com");
+//! ```
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::error::IniParseError;
+use crate::parser::{ini_parse_string_with_options, DuplicateKeyPolicy, EntryInfo, IniHandler, NewlineStyle, ParseOptions, SectionHeader};
+use crate::reader::{KeyName, SectionName};
+use crate::tokens::offset_in;
+use crate::value;
+
+/// Values of one section, by case-folded key
+#[derive(Clone, Default)]
+struct Section<'a> {
+    /// Name as first written
+    name: Cow<'a, str>,
+    values: HashMap<Cow<'a, str>, Cow<'a, str>>,
+}
+
+/// INI reader whose keys and values borrow from the input where possible
+///
+/// Lookups are case-insensitive, as with [`IniReader`](crate::IniReader), and
+/// follow the duplicate key policy and value newline style of the options it
+/// was built with. See the [module documentation](self) for an example.
+/// `Debug` prints sections and keys sorted.
+#[derive(Clone)]
+pub struct IniStrReader<'a> {
+    /// By case-folded section name; the global section is `""`
+    sections: HashMap<Cow<'a, str>, Section<'a>>,
+}
+
+impl<'a> IniStrReader<'a> {
+    /// Parse `data`, keeping slices of it for names and values
+    ///
+    /// Fails only with `ParseError`.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(data: &'a str, options: &ParseOptions) -> Result<Self, IniParseError> {
+        let mut builder = Builder {
+            data,
+            reader: IniStrReader { sections: HashMap::new() },
+            duplicate_policy: &options.duplicate_key_policy,
+            value_newline: options.value_newline,
+            last_line_ending: "",
+        };
+        ini_parse_string_with_options(data, &mut builder, options)?;
+        Ok(builder.reader)
+    }
+
+    /// The stored value of a key, if present
+    ///
+    /// `Cow::Borrowed` values are slices of the input and can be kept after
+    /// the reader is dropped.
+    pub fn get_cow(&self, section: impl SectionName, name: impl KeyName) -> Option<Cow<'a, str>> {
+        self.raw(section.section_name(), name.key_name()).cloned()
+    }
+
+    /// Get a string value, returning the default if not found
+    pub fn get<'s>(&'s self, section: impl SectionName, name: impl KeyName, default_value: &'s str) -> &'s str {
+        self.raw(section.section_name(), name.key_name()).map_or(default_value, |value| value)
+    }
+
+    /// Get a string value, returning the default if not found or empty
+    pub fn get_nonempty_or<'s>(&'s self, section: impl SectionName, name: impl KeyName, default_value: &'s str) -> &'s str {
+        match self.raw(section.section_name(), name.key_name()) {
+            Some(value) if !value.is_empty() => value,
+            _ => default_value,
+        }
+    }
+
+    /// Get an integer value, returning the default if not found, empty or invalid
+    pub fn get_integer(&self, section: impl SectionName, name: impl KeyName, default_value: i64) -> i64 {
+        self.parsed(section, name, value::parse_integer).unwrap_or(default_value)
+    }
+
+    /// Get an unsigned integer value, returning the default if not found, empty or invalid
+    pub fn get_unsigned(&self, section: impl SectionName, name: impl KeyName, default_value: u64) -> u64 {
+        self.parsed(section, name, value::parse_unsigned).unwrap_or(default_value)
+    }
+
+    /// Get a floating-point value, returning the default if not found, empty or invalid
+    pub fn get_real(&self, section: impl SectionName, name: impl KeyName, default_value: f64) -> f64 {
+        self.parsed(section, name, value::parse_real).unwrap_or(default_value)
+    }
+
+    /// Get a boolean value, returning the default if not found, empty or invalid
+    pub fn get_boolean(&self, section: impl SectionName, name: impl KeyName, default_value: bool) -> bool {
+        self.parsed(section, name, value::parse_bool).unwrap_or(default_value)
+    }
+
+    /// Check if a section exists (case-insensitive)
+    pub fn has_section(&self, section: impl SectionName) -> bool {
+        let section = section.section_name();
+        !section.is_empty() && self.sections.contains_key(fold(section).as_ref())
+    }
+
+    /// Check if a value exists
+    pub fn has_value(&self, section: impl SectionName, name: impl KeyName) -> bool {
+        self.raw(section.section_name(), name.key_name()).is_some()
+    }
+
+    /// Section names as first written, sorted, without the global section
+    pub fn sections(&self) -> Vec<&str> {
+        let mut sections: Vec<&str> =
+            self.sections.values().map(|section| section.name.as_ref()).filter(|name| !name.is_empty()).collect();
+        sections.sort_unstable();
+        sections
+    }
+
+    /// Normalized keys of a section, sorted
+    pub fn keys(&self, section: impl SectionName) -> Vec<&str> {
+        let mut keys: Vec<&str> = match self.sections.get(fold(section.section_name()).as_ref()) {
+            Some(section) => section.values.keys().map(AsRef::as_ref).collect(),
+            None => Vec::new(),
+        };
+        keys.sort_unstable();
+        keys
+    }
+
+    fn raw(&self, section: &str, name: &str) -> Option<&Cow<'a, str>> {
+        self.sections.get(fold(section).as_ref())?.values.get(fold(name.trim()).as_ref())
+    }
+
+    fn parsed<T>(&self, section: impl SectionName, name: impl KeyName, parse: fn(&str) -> Option<T>) -> Option<T> {
+        self.raw(section.section_name(), name.key_name()).and_then(|value| parse(value))
+    }
+}
+
+impl fmt::Debug for IniStrReader<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sections: BTreeMap<_, BTreeMap<_, _>> = self
+            .sections
+            .iter()
+            .map(|(key, section)| (key, section.values.iter().collect()))
+            .collect();
+        f.debug_struct("IniStrReader").field("sections", &sections).finish()
+    }
+}
+
+/// Lowercase `text`, borrowing it when it already is
+fn fold(text: &str) -> Cow<'_, str> {
+    let lowercase = if text.is_ascii() {
+        !text.bytes().any(|b| b.is_ascii_uppercase())
+    } else {
+        text.chars().all(|c| c.to_lowercase().eq([c]))
+    };
+    if lowercase {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(text.to_lowercase())
+    }
+}
+
+/// Borrow `text` from `data` if it is a slice of it, otherwise copy it
+fn borrow_or_own<'a>(data: &'a str, text: &str) -> Cow<'a, str> {
+    match offset_in(data, text) {
+        Some(start) => Cow::Borrowed(&data[start..start + text.len()]),
+        None => Cow::Owned(text.to_string()),
+    }
+}
+
+/// Fold text that may borrow from the input, keeping the borrow if possible
+fn fold_cow(text: Cow<'_, str>) -> Cow<'_, str> {
+    match fold(&text) {
+        Cow::Borrowed(_) => text,
+        Cow::Owned(folded) => Cow::Owned(folded),
+    }
+}
+
+/// Handler filling an [`IniStrReader`]
+struct Builder<'a, 'o> {
+    data: &'a str,
+    reader: IniStrReader<'a>,
+    duplicate_policy: &'o DuplicateKeyPolicy,
+    value_newline: NewlineStyle,
+    /// Terminator of the previous entry line, for joining continuations
+    last_line_ending: &'static str,
+}
+
+impl<'a> Builder<'a, '_> {
+    fn section(&mut self, section: &str) -> &mut Section<'a> {
+        let folded = fold(section);
+        if !self.reader.sections.contains_key(folded.as_ref()) {
+            let name = borrow_or_own(self.data, section);
+            let key: Cow<'a, str> = Cow::Owned(folded.into_owned());
+            return self.reader.sections.entry(key).or_insert(Section { name, values: HashMap::new() });
+        }
+        self.reader.sections.get_mut(folded.as_ref()).expect("section was just checked")
+    }
+}
+
+impl IniHandler for Builder<'_, '_> {
+    fn handle(&mut self, _section: &str, _name: &str, _value: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn start_section(&mut self, header: &SectionHeader) -> Result<(), String> {
+        self.section(&header.flattened_name());
+        Ok(())
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        let newline = self.value_newline.terminator(self.last_line_ending);
+        self.last_line_ending = info.line_ending;
+        let data = self.data;
+        let policy = self.duplicate_policy;
+        let values = &mut self.section(section).values;
+
+        if let Some(existing) = values.get_mut(fold(name).as_ref()) {
+            if info.continuation {
+                let existing = existing.to_mut();
+                existing.push_str(newline);
+                existing.push_str(value);
+                return Ok(());
+            }
+            if let DuplicateKeyPolicy::Concatenate { separator } = policy {
+                let existing = existing.to_mut();
+                existing.push_str(separator);
+                existing.push_str(value);
+                return Ok(());
+            }
+            *existing = borrow_or_own(data, value);
+            return Ok(());
+        }
+        values.insert(fold_cow(borrow_or_own(data, name)), borrow_or_own(data, value));
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+//! Compact human-readable dump of a reader for logging
+
+use crate::reader::IniReader;
+
+/// Options for [`IniReader::summary_with`]
+#[derive(Debug, Clone)]
+pub struct SummaryOptions {
+    /// Values longer than this many bytes are elided
+    pub max_value_len: usize,
+    /// Key patterns whose values are replaced by `<redacted>`
+    ///
+    /// Patterns are matched case-insensitively against the key name and may
+    /// contain `*` wildcards, e.g. `*password*` or `api_*`.
+    pub redact_patterns: Vec<String>,
+    /// List sections and keys in document order (preserving readers only)
+    /// instead of sorted order
+    pub document_order: bool,
+}
+
+impl Default for SummaryOptions {
+    fn default() -> Self {
+        Self {
+            max_value_len: 80,
+            redact_patterns: Vec::new(),
+            document_order: false,
+        }
+    }
+}
+
+impl IniReader {
+    /// Produce a compact dump of all values, eliding those longer than `max_value_len` bytes
+    pub fn summary(&self, max_value_len: usize) -> String {
+        self.summary_with(&SummaryOptions { max_value_len, ..SummaryOptions::default() })
+    }
+
+    /// Produce a compact dump of all values
+    ///
+    /// Each value is written on one line with newlines escaped as `\n`. Long
+    /// values are cut at a character boundary and followed by `…(size)` giving
+    /// the full size.
+    pub fn summary_with(&self, options: &SummaryOptions) -> String {
+        let layout: Vec<(String, Vec<String>)> = match (options.document_order, self.document()) {
+            (true, Some(document)) => std::iter::once("")
+                .chain(document.sections())
+                .map(|section| {
+                    let keys = document.keys(section).into_iter().map(str::to_string).collect();
+                    (section.to_string(), keys)
+                })
+                .collect(),
+            _ => {
+                let mut sections = self.sections();
+                sections.sort();
+                std::iter::once(String::new())
+                    .chain(sections)
+                    .map(|section| {
+                        let mut keys = self.keys(&section);
+                        keys.sort();
+                        (section, keys)
+                    })
+                    .collect()
+            }
+        };
+
+        let mut out = String::new();
+        for (section, keys) in &layout {
+            if !section.is_empty() {
+                out.push_str(&format!("[{}]\n", section));
+            }
+            for key in keys {
+                let value = if is_redacted(key, &options.redact_patterns) {
+                    "<redacted>".to_string()
+                } else {
+                    elide(&self.get(section, key, ""), options.max_value_len)
+                };
+                out.push_str(&format!("{} = {}\n", key, value));
+            }
+        }
+        out
+    }
+}
+
+/// Escape newlines and cut a value to at most `max_len` bytes
+fn elide(value: &str, max_len: usize) -> String {
+    let escaped = value.replace('\n', "\\n");
+    if value.len() <= max_len {
+        return escaped;
+    }
+    let mut cut = max_len.min(escaped.len());
+    while !escaped.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    format!("{}…({})", &escaped[..cut], format_size(value.len()))
+}
+
+/// Format a byte count as B, KiB, MiB or GiB
+fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Check a key against the redaction patterns
+fn is_redacted(key: &str, patterns: &[String]) -> bool {
+    let key = key.to_lowercase();
+    patterns.iter().any(|pattern| glob_match(&pattern.to_lowercase(), &key))
+}
+
+/// Match `text` against a pattern where `*` matches any run of characters
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*password*", "db_password_file"));
+        assert!(glob_match("api_*", "api_key"));
+        assert!(glob_match("token", "token"));
+        assert!(!glob_match("token", "tokens"));
+        assert!(!glob_match("a*b*c", "acb"));
+    }
+
+    #[test]
+    fn test_elide_char_boundary() {
+        assert_eq!(elide("ééé", 3), "é…(6 B)");
+        assert_eq!(format_size(1229), "1.2 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
@@ -0,0 +1,151 @@
+//! Tables keyed by normalized key, stored one section at a time
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A table of normalized keys (see [`IniReader`](crate::IniReader)) split by section
+///
+/// Each section's entries live behind their own `Arc`, so a clone shares
+/// every section with the original and a change copies only the section it
+/// touches. Lookups that find nothing to change never copy.
+#[derive(Debug, Clone)]
+pub(crate) struct SectionTables<V> {
+    /// Entries by key prefix, the folded section name followed by `=`
+    sections: HashMap<String, Arc<HashMap<String, V>>>,
+    /// Room reserved in the table of each new section
+    keys_per_section: usize,
+}
+
+impl<V> Default for SectionTables<V> {
+    fn default() -> Self {
+        Self { sections: HashMap::new(), keys_per_section: 0 }
+    }
+}
+
+impl<V: Clone> SectionTables<V> {
+    /// An empty table with room for `sections` sections and `keys` keys spread over them
+    pub(crate) fn with_capacity(sections: usize, keys: usize) -> Self {
+        Self { sections: HashMap::with_capacity(sections), keys_per_section: keys / sections.max(1) }
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<&V> {
+        self.sections.get(prefix(key))?.get(key)
+    }
+
+    pub(crate) fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// The entry of `key` for changing, copying its section first if it is shared
+    pub(crate) fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        let table = self.sections.get_mut(prefix(key))?;
+        if !table.contains_key(key) {
+            return None;
+        }
+        Arc::make_mut(table).get_mut(key)
+    }
+
+    pub(crate) fn insert(&mut self, key: String, value: V) -> Option<V> {
+        Arc::make_mut(self.table(&key)).insert(key, value)
+    }
+
+    /// The entry of `key`, inserting `V::default()` if there is none
+    pub(crate) fn entry_or_default(&mut self, key: String) -> &mut V
+    where
+        V: Default,
+    {
+        Arc::make_mut(self.table(&key)).entry(key).or_default()
+    }
+
+    /// The table of the section of `key`, created if it has none
+    fn table(&mut self, key: &str) -> &mut Arc<HashMap<String, V>> {
+        let capacity = self.keys_per_section;
+        self.sections.entry(prefix(key).to_string()).or_insert_with(|| Arc::new(HashMap::with_capacity(capacity)))
+    }
+
+    /// Remove the entry of `key`, dropping its section once it is empty
+    pub(crate) fn remove(&mut self, key: &str) -> Option<V> {
+        let section = prefix(key);
+        let table = self.sections.get_mut(section)?;
+        if !table.contains_key(key) {
+            return None;
+        }
+        let removed = Arc::make_mut(table).remove(key);
+        if table.is_empty() {
+            self.sections.remove(section);
+        }
+        removed
+    }
+
+    /// Remove every entry of the sections `prefixes` start
+    pub(crate) fn remove_sections(&mut self, prefixes: &[String]) {
+        self.sections.retain(|section, _| !prefixes.contains(section));
+    }
+
+    /// Entries of the section with key prefix `section`, in no particular order
+    pub(crate) fn section(&self, section: &str) -> impl Iterator<Item = (&String, &V)> {
+        self.sections.get(section).into_iter().flat_map(|table| table.iter())
+    }
+
+    /// Every entry, in no particular order
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&String, &V)> {
+        self.sections.values().flat_map(|table| table.iter())
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = &String> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.sections.values().map(|table| table.len()).sum()
+    }
+
+    /// Number of tables sharing the entries of the section with key prefix `section`, 0 if it has none
+    pub(crate) fn section_strong_count(&self, section: &str) -> usize {
+        self.sections.get(section).map_or(0, Arc::strong_count)
+    }
+
+    /// Number of tables sharing every section, 1 if none has entries
+    pub(crate) fn strong_count(&self) -> usize {
+        self.sections.values().map(Arc::strong_count).min().unwrap_or(1)
+    }
+
+    /// Apply `shrink` to the sections no clone shares
+    pub(crate) fn shrink_unshared(&mut self, mut shrink: impl FnMut(&mut HashMap<String, V>)) {
+        for table in self.sections.values_mut() {
+            if let Some(table) = Arc::get_mut(table) {
+                shrink(table);
+            }
+        }
+        self.sections.shrink_to_fit();
+    }
+
+    /// Capacity of the tables and of their keys, plus what `heap` says each value holds
+    pub(crate) fn approx_memory_bytes(&self, heap: impl Fn(&V) -> usize) -> usize {
+        self.sections.capacity() * std::mem::size_of::<(String, Arc<HashMap<String, V>>)>()
+            + self
+                .sections
+                .iter()
+                .map(|(section, table)| {
+                    section.capacity()
+                        + table.capacity() * std::mem::size_of::<(String, V)>()
+                        + table.iter().map(|(key, value)| key.capacity() + heap(value)).sum::<usize>()
+                })
+                .sum::<usize>()
+    }
+}
+
+/// Key prefix of a normalized key: up to and including the first `=` that is not escaped
+pub(crate) fn prefix(key: &str) -> &str {
+    let mut bytes = key.bytes().enumerate();
+    while let Some((i, byte)) = bytes.next() {
+        match byte {
+            b'\\' => {
+                bytes.next();
+            }
+            b'=' => return &key[..i + 1],
+            _ => {}
+        }
+    }
+    key
+}
@@ -0,0 +1,327 @@
+//! Pre-tokenized input for parsing the same text with several handlers
+//!
+//! [`IniTokens::scan`] runs the parser once and records every handler call
+//! it makes. [`IniTokens::replay`] then repeats those calls into any other
+//! handler without looking at the text again, which skips line splitting,
+//! trimming, comment and separator scanning and duplicate tracking:
+//!
+//! ```rust
+//! use inih::handlers::{MapHandler, VecHandler};
+//! use inih::tokens::IniTokens;
+//! use inih::ParseOptions;
+//!
+//! let data = "[server]\nhost = example.com\nport = 8080\n";
+//! let tokens = IniTokens::scan(data, &ParseOptions::default());
+//!
+//! let mut entries = VecHandler::new();
+//! tokens.replay(&mut entries).unwrap();
+//! let mut map = MapHandler::new();
+//! tokens.replay(&mut map).unwrap();
+//! assert_eq!(map.map()["server"]["port"], "8080");
+//! ```
+//!
+//! Names and values are stored as byte ranges into the scanned string, so
+//! the tokens borrow it and hold no copies of the values.
+
+use std::collections::HashMap;
+
+use crate::error::{IniParseError, IniWarning};
+use crate::parser::{ini_parse_string_with_options, EntryInfo, IniHandler, ParseOptions, SectionHeader};
+
+/// Text passed to a handler: a range of the scanned string when the parser
+/// handed out a slice of it, otherwise a copy
+#[derive(Debug, Clone)]
+enum Text {
+    Span(usize, usize),
+    Owned(String),
+}
+
+/// One recorded handler call
+#[derive(Debug, Clone)]
+enum Token {
+    /// `handle_at(line, section, "", "")` for a section header
+    Section { section: usize },
+    /// `start_section(header)`
+    StartSection(SectionHeader),
+    /// `end_section(section)`
+    EndSection { section: usize },
+    /// `handle_entry(info, section, name, value)`, with the raw line kept apart from `info`
+    Entry { info: EntryInfo<'static>, raw_line: Text, section: usize, name: Text, value: Text },
+    /// `end_value(section, name)`
+    EndValue { section: usize, name: Text },
+    /// `warning(warning)`
+    Warning(IniWarning),
+    /// `start_document()`
+    StartDocument,
+    /// `end_document()`
+    EndDocument,
+}
+
+/// The handler calls of one parse, ready to be replayed
+///
+/// See the [module documentation](self) for an example.
+#[derive(Debug, Clone)]
+pub struct IniTokens<'a> {
+    data: &'a str,
+    /// Calls in order, each with the line it belongs to
+    tokens: Vec<(usize, Token)>,
+    /// Distinct section names, referenced by index
+    sections: Vec<String>,
+    stop_on_first_error: bool,
+    /// Outcome of the scan, without handler errors (the recorder never fails)
+    result: Result<(), IniParseError>,
+}
+
+impl<'a> IniTokens<'a> {
+    /// Parse `data` with `options`, recording the calls a handler would receive
+    ///
+    /// Parse errors are not returned here: they are kept and returned by every
+    /// [`replay`](Self::replay), as a direct parse would return them.
+    pub fn scan(data: &'a str, options: &ParseOptions) -> Self {
+        let mut recorder = Recorder {
+            data,
+            tokens: Vec::new(),
+            sections: Vec::new(),
+            section_index: HashMap::new(),
+            unplaced: 0,
+        };
+        // Header calls place the tokens before them on their line, and are
+        // dropped again unless the options asked for them
+        let recording = ParseOptions { call_handler_on_new_section: true, ..options.clone() };
+        let result = ini_parse_string_with_options(data, &mut recorder, &recording);
+        recorder.place(usize::MAX);
+        if !options.call_handler_on_new_section {
+            recorder.tokens.retain(|(_, token)| !matches!(token, Token::Section { .. }));
+        }
+
+        IniTokens {
+            data,
+            tokens: recorder.tokens,
+            sections: recorder.sections,
+            stop_on_first_error: options.stop_on_first_error,
+            result,
+        }
+    }
+
+    /// Number of recorded handler calls
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Whether no handler calls were recorded
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Repeat the recorded calls into `handler`
+    ///
+    /// A handler that accepts every call receives exactly the calls, with the
+    /// same arguments and in the same order, that a direct parse of the text
+    /// with the same options would make, and the result is the same.
+    ///
+    /// When the handler returns an error the replay behaves like the parser:
+    /// with `stop_on_first_error` it stops at once, otherwise the remaining
+    /// calls for that line are skipped and the first error is returned at the
+    /// end. The recorded calls do not change, so a direct parse can diverge
+    /// after a failed call (a header whose `end_value` fails is never entered,
+    /// for instance) where a replay carries on as if it had succeeded.
+    pub fn replay(&self, handler: &mut dyn IniHandler) -> Result<(), IniParseError> {
+        // First handler error and its line
+        let mut failed: Option<(usize, IniParseError)> = None;
+        // Line of the latest handler error, whose remaining calls are skipped
+        let mut skipped_line = None;
+
+        for (line, token) in &self.tokens {
+            let line = *line;
+            let call = match token {
+                Token::StartDocument => {
+                    handler.start_document();
+                    continue;
+                }
+                Token::EndDocument => {
+                    if let Err(message) = handler.end_document() {
+                        failed.get_or_insert((usize::MAX, IniParseError::HandlerError(message)));
+                    }
+                    continue;
+                }
+                _ if skipped_line == Some(line) => continue,
+                Token::Entry { info, raw_line, section, name, value } => {
+                    let info = EntryInfo { raw_line: self.text(raw_line), ..*info };
+                    handler
+                        .handle_entry(&info, &self.sections[*section], self.text(name), self.text(value))
+                        .map_err(|message| IniParseError::rejected_entry(&info, message))
+                }
+                Token::Section { section } => {
+                    handler.handle_at(line, &self.sections[*section], "", "").map_err(IniParseError::HandlerError)
+                }
+                Token::StartSection(header) => handler.start_section(header).map_err(IniParseError::HandlerError),
+                Token::EndSection { section } => {
+                    handler.end_section(&self.sections[*section]).map_err(IniParseError::HandlerError)
+                }
+                Token::EndValue { section, name } => {
+                    handler.end_value(&self.sections[*section], self.text(name)).map_err(IniParseError::HandlerError)
+                }
+                Token::Warning(warning) => handler.warning(warning).map_err(IniParseError::HandlerError),
+            };
+            if let Err(error) = call {
+                if self.stop_on_first_error {
+                    return Err(error);
+                }
+                skipped_line = Some(line);
+                failed.get_or_insert((line, error));
+            }
+        }
+
+        match (&self.result, failed) {
+            (Ok(()), None) => Ok(()),
+            (Ok(()), Some((_, failure))) => Err(failure),
+            (Err(error), None) => Err(error.clone()),
+            (Err(error), Some((line, failure))) => {
+                let error_line = match error {
+                    IniParseError::ParseError { line, .. } => *line,
+                    _ => usize::MAX,
+                };
+                if line <= error_line {
+                    Err(failure)
+                } else {
+                    Err(error.clone())
+                }
+            }
+        }
+    }
+
+    fn text<'t>(&'t self, text: &'t Text) -> &'t str {
+        match text {
+            Text::Span(start, end) => &self.data[*start..*end],
+            Text::Owned(text) => text,
+        }
+    }
+}
+
+/// Byte offset of `text` in `data`, if `text` is a slice of `data`
+///
+/// The parser hands handlers slices of the input wherever it can; this is how
+/// the borrowing consumers (`IniTokens`, `IniStrReader`) tell them apart from
+/// text the parser had to build.
+pub(crate) fn offset_in(data: &str, text: &str) -> Option<usize> {
+    let base = data.as_ptr() as usize;
+    let start = text.as_ptr() as usize;
+    (start >= base && start + text.len() <= base + data.len()).then(|| start - base)
+}
+
+/// Handler that records every call as a [`Token`]
+struct Recorder<'a> {
+    data: &'a str,
+    tokens: Vec<(usize, Token)>,
+    sections: Vec<String>,
+    section_index: HashMap<String, usize>,
+    /// Trailing tokens still waiting for a line number
+    unplaced: usize,
+}
+
+impl Recorder<'_> {
+    fn section(&mut self, section: &str) -> usize {
+        if let Some(last) = self.sections.last() {
+            if last == section {
+                return self.sections.len() - 1;
+            }
+        }
+        if let Some(&index) = self.section_index.get(section) {
+            return index;
+        }
+        self.sections.push(section.to_string());
+        self.section_index.insert(section.to_string(), self.sections.len() - 1);
+        self.sections.len() - 1
+    }
+
+    fn text(&self, text: &str) -> Text {
+        if let Some(start) = offset_in(self.data, text) {
+            return Text::Span(start, start + text.len());
+        }
+        // Continuations carry the name of the entry they continue; reuse its range
+        if let Some((_, Token::Entry { name: name @ Text::Span(from, to), .. })) = self.tokens.last() {
+            if &self.data[*from..*to] == text {
+                return name.clone();
+            }
+        }
+        Text::Owned(text.to_string())
+    }
+
+    /// Record a call whose line is only known once the next located call arrives
+    fn push_unplaced(&mut self, token: Token) {
+        self.tokens.push((0, token));
+        self.unplaced += 1;
+    }
+
+    /// Record a call made for `line`, which also places the calls before it
+    fn push(&mut self, line: usize, token: Token) {
+        self.place(line);
+        self.tokens.push((line, token));
+    }
+
+    fn place(&mut self, line: usize) {
+        let len = self.tokens.len();
+        for (token_line, _) in &mut self.tokens[len - self.unplaced..] {
+            *token_line = line;
+        }
+        self.unplaced = 0;
+    }
+}
+
+impl IniHandler for Recorder<'_> {
+    fn handle(&mut self, _section: &str, _name: &str, _value: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn handle_at(&mut self, line: usize, section: &str, _name: &str, _value: &str) -> Result<(), String> {
+        // Only section headers get here: entries arrive through handle_entry
+        let section = self.section(section);
+        self.push(line, Token::Section { section });
+        Ok(())
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        let section = self.section(section);
+        let name = self.text(name);
+        let value = self.text(value);
+        let raw_line = self.text(info.raw_line);
+        let info = EntryInfo { raw_line: "", ..*info };
+        self.push(info.line, Token::Entry { info, raw_line, section, name, value });
+        Ok(())
+    }
+
+    fn start_section(&mut self, header: &SectionHeader) -> Result<(), String> {
+        self.push_unplaced(Token::StartSection(header.clone()));
+        Ok(())
+    }
+
+    fn end_section(&mut self, section: &str) -> Result<(), String> {
+        let section = self.section(section);
+        self.push_unplaced(Token::EndSection { section });
+        Ok(())
+    }
+
+    fn end_value(&mut self, section: &str, name: &str) -> Result<(), String> {
+        let section = self.section(section);
+        let name = self.text(name);
+        self.push_unplaced(Token::EndValue { section, name });
+        Ok(())
+    }
+
+    fn warning(&mut self, warning: &IniWarning) -> Result<(), String> {
+        match warning.line {
+            Some(line) => self.push(line, Token::Warning(warning.clone())),
+            None => self.push_unplaced(Token::Warning(warning.clone())),
+        }
+        Ok(())
+    }
+
+    fn start_document(&mut self) {
+        self.push(0, Token::StartDocument);
+    }
+
+    fn end_document(&mut self) -> Result<(), String> {
+        self.push(usize::MAX, Token::EndDocument);
+        Ok(())
+    }
+}
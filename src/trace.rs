@@ -0,0 +1,102 @@
+//! Parse traces for asserting what a handler receives
+//!
+//! [`parse_trace`] parses a string and returns the handler calls it made as
+//! a list of [`TraceEvent`]s, so a test can check a whole parse with one
+//! `assert_eq!`:
+//!
+//! ```rust
+//! use inih::trace::{parse_trace, TraceEvent};
+//! use inih::{IniParseError, ParseOptions};
+//!
+//! let trace = parse_trace("[server]\nport = 80\n[]\n", &ParseOptions::default());
+//! assert_eq!(trace, vec![
+//!     TraceEvent::section("server"),
+//!     TraceEvent::pair("server", "port", "80"),
+//!     TraceEvent::Error(IniParseError::ParseError { line: 3, message: "Empty section name".to_string(), suggestion: None }),
+//! ]);
+//! ```
+//!
+//! The events are recorded by an ordinary [`IniHandler`], so they follow the
+//! parser wherever it goes. This module is a stable testing utility: the
+//! variants and their meaning only change together with the handler calls
+//! they mirror.
+
+use crate::error::{IniParseError, IniWarning};
+use crate::parser::{ini_parse_string_with_options, EntryInfo, IniHandler, ParseOptions, SectionHeader};
+
+/// One handler call of a parse, or the error it ended with
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceEvent {
+    /// A section header, by its flattened name
+    Section(String),
+    /// A name=value pair, or a bare name with an empty value
+    Pair { section: String, name: String, value: String },
+    /// A continuation line of the value of `name`
+    Continuation { section: String, name: String, value: String },
+    /// A warning from an opt-in lint
+    Warning(IniWarning),
+    /// The error the parse returned; always the last event
+    Error(IniParseError),
+}
+
+impl TraceEvent {
+    /// [`TraceEvent::Section`] from a borrowed name
+    pub fn section(name: &str) -> Self {
+        TraceEvent::Section(name.to_string())
+    }
+
+    /// [`TraceEvent::Pair`] from borrowed strings
+    pub fn pair(section: &str, name: &str, value: &str) -> Self {
+        TraceEvent::Pair { section: section.to_string(), name: name.to_string(), value: value.to_string() }
+    }
+
+    /// [`TraceEvent::Continuation`] from borrowed strings
+    pub fn continuation(section: &str, name: &str, value: &str) -> Self {
+        TraceEvent::Continuation { section: section.to_string(), name: name.to_string(), value: value.to_string() }
+    }
+}
+
+/// Parse `data` with `options` and return the handler calls, in order
+///
+/// Values are given as the handler receives them. Since parsing goes on
+/// after an error, events after the failing line are still recorded; the
+/// error itself comes last.
+pub fn parse_trace(data: &str, options: &ParseOptions) -> Vec<TraceEvent> {
+    let mut recorder = Recorder { events: Vec::new() };
+    if let Err(error) = ini_parse_string_with_options(data, &mut recorder, options) {
+        recorder.events.push(TraceEvent::Error(error));
+    }
+    recorder.events
+}
+
+/// Handler that turns its calls into events
+struct Recorder {
+    events: Vec<TraceEvent>,
+}
+
+impl IniHandler for Recorder {
+    fn handle(&mut self, _section: &str, _name: &str, _value: &str) -> Result<(), String> {
+        // Only reached for headers, with `call_handler_on_new_section`, which `start_section` already recorded
+        Ok(())
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        let event = if info.continuation {
+            TraceEvent::continuation(section, name, value)
+        } else {
+            TraceEvent::pair(section, name, value)
+        };
+        self.events.push(event);
+        Ok(())
+    }
+
+    fn start_section(&mut self, header: &SectionHeader) -> Result<(), String> {
+        self.events.push(TraceEvent::Section(header.flattened_name()));
+        Ok(())
+    }
+
+    fn warning(&mut self, warning: &IniWarning) -> Result<(), String> {
+        self.events.push(TraceEvent::Warning(warning.clone()));
+        Ok(())
+    }
+}
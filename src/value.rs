@@ -0,0 +1,386 @@
+//! Coercion of raw INI values into typed values
+//!
+//! These are the rules the [`IniReader`](crate::IniReader) getters and
+//! [`ini_validate`](crate::schema::ini_validate) use, exposed so that
+//! [`IniHandler`](crate::IniHandler) implementations can read values the same
+//! way:
+//!
+//! ```rust
+//! use inih::value;
+//!
+//! assert_eq!(value::parse_bool("Yes"), Some(true));
+//! assert_eq!(value::parse_integer("0x1F"), Some(31));
+//! assert_eq!(value::parse_size("4 KiB"), Some(4096));
+//! assert_eq!(value::parse_list("a, b,,c"), vec!["a", "b", "c"]);
+//! ```
+//!
+//! Every function returns `None` for input it does not accept and never panics.
+//! Surrounding whitespace is the caller's business, except where noted.
+
+use std::time::Duration;
+
+/// The words read as booleans, as (true, false) pairs of the same style
+pub(crate) const BOOLEAN_WORDS: [(&str, &str); 4] = [("true", "false"), ("yes", "no"), ("on", "off"), ("1", "0")];
+
+/// Parse a boolean: "true", "yes", "on", "1" or "false", "no", "off", "0" (case-insensitive)
+pub fn parse_bool(value: &str) -> Option<bool> {
+    let value = value.to_lowercase();
+    BOOLEAN_WORDS.iter().find_map(|&(yes, no)| {
+        if value == yes {
+            Some(true)
+        } else if value == no {
+            Some(false)
+        } else {
+            None
+        }
+    })
+}
+
+/// The opposite of a boolean in the same style: `yes` to `no`, `On` to `Off`, `1` to `0`
+///
+/// The case of the word is kept: all capitals stay capitals and a capital
+/// first letter stays one. Returns `None` for anything
+/// [`parse_bool`] does not accept.
+pub fn toggle_bool(value: &str) -> Option<String> {
+    let lower = value.to_lowercase();
+    let opposite = BOOLEAN_WORDS.iter().find_map(|&(yes, no)| match lower.as_str() {
+        word if word == yes => Some(no),
+        word if word == no => Some(yes),
+        _ => None,
+    })?;
+    let has_letters = value.chars().any(char::is_alphabetic);
+    Some(if has_letters && value.chars().all(|c| !c.is_lowercase()) {
+        opposite.to_uppercase()
+    } else if value.starts_with(char::is_uppercase) {
+        opposite[..1].to_uppercase() + &opposite[1..]
+    } else {
+        opposite.to_string()
+    })
+}
+
+/// Parse a signed integer
+///
+/// Accepts an optional sign, then decimal digits or a `0x` (hexadecimal),
+/// `0o` (octal) or `0b` (binary) prefix and digits; prefixes are
+/// case-insensitive. Single underscores may separate digits: `1_000_000`,
+/// `0xFF_FF`. A leading zero does not mean octal: `010` is ten.
+///
+/// A prefixed literal without `-` that is too large for `i64` but fits in
+/// `u64` is taken as a bit pattern, so that full-width masks read as written:
+/// `0xFFFF_FFFF_FFFF_FFFF` is -1 and `0x8000_0000_0000_0000` is `i64::MIN`.
+/// Decimal literals are never reinterpreted: `9223372036854775808` is rejected.
+pub fn parse_integer(value: &str) -> Option<i64> {
+    match value.strip_prefix('-') {
+        Some(magnitude) => 0i64.checked_sub_unsigned(parse_magnitude(magnitude)?),
+        None => {
+            let magnitude = value.strip_prefix('+').unwrap_or(value);
+            let bits = parse_magnitude(magnitude)?;
+            match split_radix(magnitude) {
+                Some((10, _)) => i64::try_from(bits).ok(),
+                _ => Some(bits as i64),
+            }
+        }
+    }
+}
+
+/// Parse an unsigned integer, with the same syntax as [`parse_integer`] but no `-`
+pub fn parse_unsigned(value: &str) -> Option<u64> {
+    parse_magnitude(value.strip_prefix('+').unwrap_or(value))
+}
+
+/// Whether `value` has the syntax [`parse_integer`] accepts, whatever its size
+///
+/// Tells a number that overflows `i64` or `u64` apart from one that is not a
+/// number at all.
+pub fn is_integer_literal(value: &str) -> bool {
+    let magnitude = value.strip_prefix(['-', '+']).unwrap_or(value);
+    split_radix(magnitude).is_some()
+}
+
+/// Parse a floating-point number, as Rust's `f64` parser does
+pub fn parse_real(value: &str) -> Option<f64> {
+    value.parse().ok()
+}
+
+/// Digits of an unsigned integer, after any sign
+fn parse_magnitude(value: &str) -> Option<u64> {
+    let (radix, digits) = split_radix(value)?;
+    u64::from_str_radix(&digits.replace('_', ""), radix).ok()
+}
+
+/// Radix and digits of an integer literal without sign, if its syntax is valid
+fn split_radix(value: &str) -> Option<(u32, &str)> {
+    let prefix = value.get(..2).map(str::to_ascii_lowercase);
+    let (radix, digits) = match prefix.as_deref() {
+        Some("0x") => (16, &value[2..]),
+        Some("0o") => (8, &value[2..]),
+        Some("0b") => (2, &value[2..]),
+        _ => (10, value),
+    };
+    if digits.is_empty() || digits.starts_with('_') || digits.ends_with('_') || digits.contains("__") {
+        return None;
+    }
+    if !digits.chars().all(|c| c == '_' || c.is_digit(radix)) {
+        return None;
+    }
+    Some((radix, digits))
+}
+
+/// Parse a duration such as `30s`, `5m`, `1h30m`, `2.5s` or `250ms`
+///
+/// A bare number is taken as seconds. Units are `ms`, `s`, `m`, `h` and `d`,
+/// optionally separated from the number by whitespace. The clock forms
+/// `HH:MM:SS` and `MM:SS` are accepted too, as in `03:30:00` for three and
+/// a half hours; there every field but the first has two digits and is
+/// below 60, and the seconds may have a fraction. Negative and overflowing
+/// durations are rejected. Surrounding whitespace is ignored.
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if value.is_empty() || value.starts_with('-') {
+        return None;
+    }
+    if let Ok(seconds) = value.parse::<f64>() {
+        return Duration::try_from_secs_f64(seconds).ok();
+    }
+    if value.contains(':') {
+        return parse_clock(value);
+    }
+
+    let mut total = 0.0;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let number_len = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+        let number: f64 = rest[..number_len].parse().ok()?;
+        rest = rest[number_len..].trim_start();
+
+        let unit_len = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        let scale = match &rest[..unit_len] {
+            "ms" => 0.001,
+            "s" => 1.0,
+            "m" => 60.0,
+            "h" => 3600.0,
+            "d" => 86400.0,
+            _ => return None,
+        };
+        rest = rest[unit_len..].trim_start();
+        total += number * scale;
+    }
+    Duration::try_from_secs_f64(total).ok()
+}
+
+/// Parse the `HH:MM:SS` and `MM:SS` forms of a duration
+fn parse_clock(value: &str) -> Option<Duration> {
+    let fields: Vec<&str> = value.split(':').collect();
+    if fields.len() > 3 {
+        return None;
+    }
+    let (seconds, larger) = fields.split_last()?;
+    let (whole, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+    let two_digits = |field: &str| field.len() == 2 && field.bytes().all(|b| b.is_ascii_digit());
+    if !two_digits(whole) || !fraction.bytes().all(|b| b.is_ascii_digit()) || seconds.ends_with('.') {
+        return None;
+    }
+
+    let mut total: u64 = 0;
+    for (i, field) in larger.iter().enumerate() {
+        // The first field may have any number of digits and no upper bound
+        let valid = if i == 0 { !field.is_empty() && field.bytes().all(|b| b.is_ascii_digit()) } else { two_digits(field) };
+        let number: u64 = field.parse().ok().filter(|_| valid)?;
+        if i > 0 && number >= 60 {
+            return None;
+        }
+        total = total.checked_mul(60)?.checked_add(number)?;
+    }
+    let seconds: f64 = seconds.parse().ok()?;
+    if seconds >= 60.0 {
+        return None;
+    }
+    Duration::from_secs(total.checked_mul(60)?).checked_add(Duration::try_from_secs_f64(seconds).ok()?)
+}
+
+/// Parse a size in bytes such as `512`, `64k`, `4 KiB`, `1.5MB` or `2G`
+///
+/// Units are case-insensitive and may be separated from the number by
+/// whitespace: `b` for bytes, `kb`/`mb`/`gb`/`tb` for powers of 1000,
+/// `kib`/`mib`/`gib`/`tib` and the bare letters `k`/`m`/`g`/`t` for powers of
+/// 1024. A fractional size is rounded to the nearest byte. Negative and
+/// overflowing sizes are rejected. Surrounding whitespace is ignored.
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let number_len = value.find(|c: char| !c.is_ascii_digit() && c != '.' && c != '_').unwrap_or(value.len());
+    let (number, unit) = value.split_at(number_len);
+    let scale: u64 = match unit.trim_start().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kib" => 1 << 10,
+        "m" | "mib" => 1 << 20,
+        "g" | "gib" => 1 << 30,
+        "t" | "tib" => 1 << 40,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        _ => return None,
+    };
+
+    if let Some(whole) = parse_magnitude(number) {
+        return whole.checked_mul(scale);
+    }
+    let fractional: f64 = number.parse().ok()?;
+    let bytes = (fractional * scale as f64).round();
+    // u64::MAX as f64 rounds up to 2^64, which is already out of range
+    if bytes.is_finite() && bytes < u64::MAX as f64 {
+        Some(bytes as u64)
+    } else {
+        None
+    }
+}
+
+/// Split a list on commas and newlines, trimming the items and dropping empty ones
+///
+/// Newlines count as separators so that a multi-line value can hold one item
+/// per line. Quotes are not special: `"a, b"` is the two items `"a` and `b"`.
+pub fn parse_list(value: &str) -> Vec<&str> {
+    parse_list_with(value, ',')
+}
+
+/// Split a list on `separator` and newlines, as [`parse_list`] does on commas
+///
+/// With `' '` as the separator, items may be separated by any number of spaces.
+pub fn parse_list_with(value: &str, separator: char) -> Vec<&str> {
+    value.split([separator, '\n']).map(str::trim).filter(|item| !item.is_empty()).collect()
+}
+
+/// Split a multi-line value into its lines, trimming them and dropping empty ones
+pub fn parse_lines(value: &str) -> Vec<&str> {
+    parse_list_with(value, '\n')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bool() {
+        for text in ["true", "YES", "On", "1"] {
+            assert_eq!(parse_bool(text), Some(true), "{}", text);
+        }
+        for text in ["false", "No", "OFF", "0"] {
+            assert_eq!(parse_bool(text), Some(false), "{}", text);
+        }
+        for text in ["", "2", "y", "enabled", " true"] {
+            assert_eq!(parse_bool(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_toggle_bool() {
+        for (text, toggled) in [("yes", "no"), ("Off", "On"), ("TRUE", "FALSE"), ("1", "0"), ("no", "yes")] {
+            assert_eq!(toggle_bool(text).as_deref(), Some(toggled), "{}", text);
+            assert_eq!(parse_bool(toggled), parse_bool(text).map(|value| !value));
+        }
+        assert_eq!(toggle_bool("enabled"), None);
+    }
+
+    #[test]
+    fn test_parse_integer() {
+        assert_eq!(parse_integer("42"), Some(42));
+        assert_eq!(parse_integer("+42"), Some(42));
+        assert_eq!(parse_integer("-42"), Some(-42));
+        assert_eq!(parse_integer("010"), Some(10));
+        assert_eq!(parse_integer("0x1F"), Some(31));
+        assert_eq!(parse_integer("0XfF"), Some(255));
+        assert_eq!(parse_integer("-0x10"), Some(-16));
+        assert_eq!(parse_integer("0o17"), Some(15));
+        assert_eq!(parse_integer("0b1010"), Some(10));
+        assert_eq!(parse_integer("1_000_000"), Some(1_000_000));
+        assert_eq!(parse_integer("0xFF_FF"), Some(0xFFFF));
+        assert_eq!(parse_integer("9223372036854775807"), Some(i64::MAX));
+        assert_eq!(parse_integer("-9223372036854775808"), Some(i64::MIN));
+        assert_eq!(parse_integer("-0x8000000000000000"), Some(i64::MIN));
+        assert_eq!(parse_integer("0x7FFF_FFFF_FFFF_FFFF"), Some(i64::MAX));
+        assert_eq!(parse_integer("0x8000_0000_0000_0000"), Some(i64::MIN));
+        assert_eq!(parse_integer("0xFFFF_FFFF_FFFF_FFFF"), Some(-1));
+        assert_eq!(parse_integer(&format!("+0b{}", "1".repeat(64))), Some(-1));
+
+        for text in ["", "-", "0x", "_1", "1_", "1__0", "0x_1", "0b102", "0o8", "1.5", " 1", "0x+5", "--1", "9223372036854775808"] {
+            assert_eq!(parse_integer(text), None, "{}", text);
+        }
+        for text in ["18446744073709551615", "0x1_0000_0000_0000_0000", "-0x8000000000000001", "-0xFFFF_FFFF_FFFF_FFFF"] {
+            assert_eq!(parse_integer(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_is_integer_literal() {
+        for text in ["0", "-1", "+0x1F", "1_000", "99999999999999999999999", "0b1111_0000", "-0o7"] {
+            assert!(is_integer_literal(text), "{}", text);
+        }
+        for text in ["", "-", "+-1", "1.5", "0x", "1__0", "0b2", " 1", "ten"] {
+            assert!(!is_integer_literal(text), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_unsigned_and_real() {
+        assert_eq!(parse_unsigned("18446744073709551615"), Some(u64::MAX));
+        assert_eq!(parse_unsigned("0xFFFF_FFFF"), Some(0xFFFF_FFFF));
+        assert_eq!(parse_unsigned("+7"), Some(7));
+        assert_eq!(parse_unsigned("-7"), None);
+        assert_eq!(parse_unsigned("18446744073709551616"), None);
+
+        assert_eq!(parse_real("1.5"), Some(1.5));
+        assert_eq!(parse_real("-2e3"), Some(-2000.0));
+        assert_eq!(parse_real("abc"), None);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_duration("2.5s"), Some(Duration::from_millis(2500)));
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration(" 1 d 2 h "), Some(Duration::from_secs(93600)));
+
+        for text in ["", "-1s", "5x", "s", "1e400"] {
+            assert_eq!(parse_duration(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_duration_clock() {
+        assert_eq!(parse_duration("03:30:00"), Some(Duration::from_secs(12600)));
+        assert_eq!(parse_duration("100:00:01"), Some(Duration::from_secs(360001)));
+        assert_eq!(parse_duration("5:07"), Some(Duration::from_secs(307)));
+        assert_eq!(parse_duration("90:00"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration(" 00:01.5 "), Some(Duration::from_millis(1500)));
+
+        for text in ["1:2:3:4", "1:2", "1:60", "1:60:00", ":30", "1:", "01:30.", "1:3a", "1h:30", "-1:00", "1::00"] {
+            assert_eq!(parse_duration(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("512B"), Some(512));
+        assert_eq!(parse_size("64k"), Some(65536));
+        assert_eq!(parse_size("4 KiB"), Some(4096));
+        assert_eq!(parse_size("4kb"), Some(4000));
+        assert_eq!(parse_size("1.5M"), Some(1_572_864));
+        assert_eq!(parse_size("2 GB"), Some(2_000_000_000));
+        assert_eq!(parse_size("1_024 t"), Some(1 << 50));
+        assert_eq!(parse_size("0.5b"), Some(1));
+
+        for text in ["", "k", "-1k", "1 xb", "16777216T", "1e30", "1..5k"] {
+            assert_eq!(parse_size(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(parse_list("a, b ,c"), vec!["a", "b", "c"]);
+        assert_eq!(parse_list("one\n  two,\n  three"), vec!["one", "two", "three"]);
+        assert_eq!(parse_list(" , ,"), Vec::<&str>::new());
+        assert_eq!(parse_list(""), Vec::<&str>::new());
+    }
+}
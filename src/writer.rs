@@ -0,0 +1,411 @@
+//! Serialization of configuration back to INI text
+//!
+//! [`IniWriter`] builds a configuration from scratch:
+//!
+//! ```rust
+//! use inih::writer::IniWriter;
+//! use inih::IniReader;
+//!
+//! let mut writer = IniWriter::new();
+//! writer.set("server", "host", "example.com").unwrap();
+//! writer.set("server", "port", "8080").unwrap();
+//! writer.set_section("empty").unwrap();
+//! assert_eq!(writer.to_string(), "[server]\nhost = example.com\nport = 8080\n\n[empty]\n");
+//!
+//! let reader = IniReader::from_string(&writer.to_string()).unwrap();
+//! assert_eq!(reader.get_integer("server", "port", 0), 8080);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::IniWriteError;
+
+/// Options controlling how INI text is written
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Write keys that had no value in the input (`key`) as `key = ` instead
+    ///
+    /// By default bare keys are written back bare, since some consumers treat
+    /// a bare flag differently from an empty value.
+    pub normalize_bare_keys: bool,
+    /// Separator written between names and values
+    pub separator: Separator,
+    /// Let [`IniWriter`] write values with line breaks as continuation lines
+    ///
+    /// Such output needs `allow_multiline` to be read back.
+    pub allow_multiline: bool,
+    /// Words used for booleans set with a typed setter
+    pub boolean_style: BooleanStyle,
+    /// Form of durations set with a typed setter
+    pub duration_style: DurationStyle,
+    /// Decimal places for reals set with a typed setter
+    ///
+    /// `None` writes the shortest text that reads back as the same `f64`.
+    /// With a precision the value is rounded and trailing zeros are dropped,
+    /// so `0.1 + 0.2` is written as `0.3` rather than `0.30000000000000004`.
+    pub real_precision: Option<usize>,
+}
+
+/// Separator between the name and value of an entry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Separator {
+    /// `name = value`
+    #[default]
+    Equals,
+    /// `name: value`
+    Colon,
+}
+
+/// An entry line, without its terminator
+pub(crate) fn entry_line(name: &str, value: &str, options: &WriteOptions) -> String {
+    match options.separator {
+        Separator::Equals => format!("{} = {}", name, value),
+        Separator::Colon => format!("{}: {}", name, value),
+    }
+}
+
+/// How [`format_boolean`] writes booleans
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BooleanStyle {
+    /// `true` and `false`
+    #[default]
+    TrueFalse,
+    /// `yes` and `no`
+    YesNo,
+}
+
+/// How [`format_duration`] writes durations
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DurationStyle {
+    /// One number of seconds, or of milliseconds below a second: `90s`, `1500ms`
+    #[default]
+    Seconds,
+    /// Days, hours, minutes and seconds: `1m30s`, `1d2h`
+    Units,
+}
+
+/// A boolean as written by the typed setters
+pub fn format_boolean(value: bool, options: &WriteOptions) -> &'static str {
+    match (options.boolean_style, value) {
+        (BooleanStyle::TrueFalse, true) => "true",
+        (BooleanStyle::TrueFalse, false) => "false",
+        (BooleanStyle::YesNo, true) => "yes",
+        (BooleanStyle::YesNo, false) => "no",
+    }
+}
+
+/// A real as written by the typed setters
+///
+/// Finite values always have a decimal point or an exponent (`2.0`, `1e300`),
+/// so they are not mistaken for integers. Infinities and NaN are written as
+/// `inf`, `-inf` and `NaN`, which [`parse_real`](crate::value::parse_real)
+/// reads back.
+pub fn format_real(value: f64, options: &WriteOptions) -> String {
+    let Some(precision) = options.real_precision.filter(|_| value.is_finite()) else {
+        return format!("{:?}", value);
+    };
+    let mut text = format!("{:.*}", precision, value);
+    if text.contains('.') {
+        text.truncate(text.trim_end_matches('0').len());
+    }
+    if text.ends_with('.') {
+        text.push('0');
+    } else if !text.contains('.') {
+        text.push_str(".0");
+    }
+    text
+}
+
+/// A duration as written by the typed setters
+///
+/// [`parse_duration`](crate::value::parse_duration) reads back durations in
+/// whole milliseconds exactly; finer durations are written as fractional
+/// seconds and read back to the precision of an `f64`.
+pub fn format_duration(value: Duration, options: &WriteOptions) -> String {
+    let seconds = value.as_secs();
+    let nanos = value.subsec_nanos();
+    let whole_millis = nanos.is_multiple_of(1_000_000);
+    match options.duration_style {
+        DurationStyle::Seconds if nanos == 0 => format!("{}s", seconds),
+        DurationStyle::Seconds if whole_millis => format!("{}ms", value.as_millis()),
+        DurationStyle::Seconds => format!("{}s", fractional_seconds(seconds, nanos)),
+        DurationStyle::Units => {
+            let mut text = String::new();
+            for (unit, count) in [("d", seconds / 86400), ("h", seconds / 3600 % 24), ("m", seconds / 60 % 60)] {
+                if count > 0 {
+                    text.push_str(&format!("{}{}", count, unit));
+                }
+            }
+            let rest = seconds % 60;
+            if nanos == 0 {
+                if rest > 0 || text.is_empty() {
+                    text.push_str(&format!("{}s", rest));
+                }
+            } else if whole_millis {
+                if rest > 0 {
+                    text.push_str(&format!("{}s", rest));
+                }
+                text.push_str(&format!("{}ms", nanos / 1_000_000));
+            } else {
+                text.push_str(&format!("{}s", fractional_seconds(rest, nanos)));
+            }
+            text
+        }
+    }
+}
+
+/// Seconds with a fraction written out in full, without trailing zeros
+fn fractional_seconds(seconds: u64, nanos: u32) -> String {
+    let text = format!("{}.{:09}", seconds, nanos);
+    text.trim_end_matches('0').to_string()
+}
+
+/// A list as written by the typed setters: the items joined by `delimiter`
+///
+/// [`parse_list`](crate::value::parse_list) splits on commas and newlines and
+/// trims the items, so the delimiter must be made of commas, newlines and
+/// whitespace, with at least one comma or newline, and no item may be empty,
+/// have surrounding whitespace or contain a comma or newline itself. Fails
+/// with `InvalidList` otherwise.
+pub fn format_list(items: &[&str], delimiter: &str) -> Result<String, IniWriteError> {
+    let reason = if !delimiter.contains([',', '\n']) || !delimiter.chars().all(|c| c == ',' || c.is_whitespace()) {
+        Some("delimiter is not a comma or newline with optional whitespace")
+    } else if items.iter().any(|item| item.is_empty()) {
+        Some("an item is empty")
+    } else if items.iter().any(|item| item.trim() != *item) {
+        Some("an item has leading or trailing whitespace")
+    } else if items.iter().any(|item| item.contains([',', '\n'])) {
+        Some("an item contains a comma or newline")
+    } else {
+        None
+    };
+    match reason {
+        Some(reason) => Err(IniWriteError::InvalidList { reason }),
+        None => Ok(items.join(delimiter)),
+    }
+}
+
+/// The header line for a section, without its terminator
+///
+/// Names come out as written, except that a line break or `]` would end the
+/// header early; those are written as `\n`, `\r` and `\]`. Such names cannot
+/// be read back unchanged, since the parser has no escapes in headers, but
+/// the output stays one header line.
+pub(crate) fn section_header(name: &str) -> String {
+    let mut header = String::with_capacity(name.len() + 2);
+    header.push('[');
+    for c in name.chars() {
+        match c {
+            '\n' => header.push_str("\\n"),
+            '\r' => header.push_str("\\r"),
+            ']' => header.push_str("\\]"),
+            _ => header.push(c),
+        }
+    }
+    header.push(']');
+    header
+}
+
+/// One section of an [`IniWriter`]
+#[derive(Debug, Clone)]
+struct WriterSection {
+    name: String,
+    /// Entries in insertion order; `None` is a bare key
+    entries: Vec<(String, Option<String>)>,
+    /// Index of each entry, by normalized name
+    index: HashMap<String, usize>,
+}
+
+/// Builder of INI text, checking that what it writes reads back unchanged
+///
+/// Sections and keys come out in the order they were first set, global
+/// entries (section `""`) first. As in [`IniReader`](crate::IniReader),
+/// sections match case-insensitively and keys after trimming and case folding;
+/// setting a key again replaces its value but keeps its place and spelling.
+///
+/// Anything the parser would read differently with default options is
+/// rejected: names that contain separators or start like a comment or
+/// header, values with surrounding whitespace or an inline comment, and
+/// values with line breaks unless `allow_multiline` is set. Lines longer
+/// than the reader's `max_line` are not checked.
+#[derive(Debug, Clone)]
+pub struct IniWriter {
+    options: WriteOptions,
+    sections: Vec<WriterSection>,
+    /// Index of each section, by folded name
+    section_index: HashMap<String, usize>,
+}
+
+impl Default for IniWriter {
+    fn default() -> Self {
+        Self::with_options(WriteOptions::default())
+    }
+}
+
+impl IniWriter {
+    /// Create an empty writer with default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty writer formatting entries according to `options`
+    pub fn with_options(options: WriteOptions) -> Self {
+        let global = WriterSection { name: String::new(), entries: Vec::new(), index: HashMap::new() };
+        Self { options, sections: vec![global], section_index: HashMap::from([(String::new(), 0)]) }
+    }
+
+    /// Add a section, if not already there, so that it is written even without keys
+    pub fn set_section(&mut self, section: &str) -> Result<(), IniWriteError> {
+        self.section(section).map(|_| ())
+    }
+
+    /// Set a value, adding the section and key if needed
+    pub fn set(&mut self, section: &str, name: &str, value: &str) -> Result<(), IniWriteError> {
+        check_value(section, name, value, &self.options)?;
+        self.entry(section, name, Some(value.to_string()))
+    }
+
+    /// Set a key without a value, written bare (`name`)
+    ///
+    /// Bare keys need `allow_no_value` to be read back; with
+    /// `normalize_bare_keys` they are written as `name = ` instead.
+    pub fn set_flag(&mut self, section: &str, name: &str) -> Result<(), IniWriteError> {
+        self.entry(section, name, None)
+    }
+
+    /// Write the text to `path`, replacing the file
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_to(File::create(path)?)
+    }
+
+    /// Write the text to `writer`
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_string().as_bytes())?;
+        writer.flush()
+    }
+
+    fn entry(&mut self, section: &str, name: &str, value: Option<String>) -> Result<(), IniWriteError> {
+        check_name(section, name)?;
+        let section = self.section(section)?;
+        let key = name.to_lowercase();
+        match section.index.get(&key) {
+            Some(&index) => section.entries[index].1 = value,
+            None => {
+                section.index.insert(key, section.entries.len());
+                section.entries.push((name.to_string(), value));
+            }
+        }
+        Ok(())
+    }
+
+    fn section(&mut self, section: &str) -> Result<&mut WriterSection, IniWriteError> {
+        let folded = section.to_lowercase();
+        let index = match self.section_index.get(&folded) {
+            Some(&index) => index,
+            None => {
+                check_section(section)?;
+                self.sections.push(WriterSection { name: section.to_string(), entries: Vec::new(), index: HashMap::new() });
+                self.section_index.insert(folded, self.sections.len() - 1);
+                self.sections.len() - 1
+            }
+        };
+        Ok(&mut self.sections[index])
+    }
+}
+
+impl fmt::Display for IniWriter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut wrote_anything = false;
+        for section in &self.sections {
+            if !section.name.is_empty() {
+                if wrote_anything {
+                    writeln!(f)?;
+                }
+                writeln!(f, "[{}]", section.name)?;
+            }
+            for (name, value) in &section.entries {
+                match value {
+                    None if !self.options.normalize_bare_keys => writeln!(f, "{}", name)?,
+                    value => writeln!(f, "{}", entry_line(name, value.as_deref().unwrap_or(""), &self.options))?,
+                }
+            }
+            wrote_anything |= !section.name.is_empty() || !section.entries.is_empty();
+        }
+        Ok(())
+    }
+}
+
+/// Check that a section name reads back from its header as written
+fn check_section(section: &str) -> Result<(), IniWriteError> {
+    let reason = if section.trim().is_empty() {
+        Some("name is blank")
+    } else if section.trim() != section {
+        Some("name has leading or trailing whitespace")
+    } else if section.contains(['\n', '\r']) {
+        Some("name contains a line break")
+    } else if section.contains(']') {
+        Some("name contains ']'")
+    } else {
+        None
+    };
+    match reason {
+        Some(reason) => Err(IniWriteError::InvalidSection { section: section.to_string(), reason }),
+        None => Ok(()),
+    }
+}
+
+/// Check that a key name reads back as the name of its entry
+fn check_name(section: &str, name: &str) -> Result<(), IniWriteError> {
+    let trimmed = name.trim();
+    let reason = if trimmed.is_empty() {
+        Some("name is blank")
+    } else if trimmed != name {
+        Some("name has leading or trailing whitespace")
+    } else if name.contains(['=', ':']) {
+        Some("name contains a separator")
+    } else if name.contains(['\n', '\r']) {
+        Some("name contains a line break")
+    } else if trimmed.starts_with(['[', ';', '#']) {
+        Some("name starts like a header or comment")
+    } else {
+        None
+    };
+    match reason {
+        Some(reason) => Err(IniWriteError::InvalidName { section: section.to_string(), name: name.to_string(), reason }),
+        None => Ok(()),
+    }
+}
+
+/// Check that a value reads back unchanged
+///
+/// Continuation lines keep their indentation when read, so with
+/// `allow_multiline` each line after the first must already be indented and
+/// not blank, and not start like a comment.
+fn check_value(section: &str, name: &str, value: &str, options: &WriteOptions) -> Result<(), IniWriteError> {
+    let mut lines = value.split('\n');
+    let first = lines.next().unwrap_or("");
+    let reason = if value.contains('\r') {
+        Some("value contains a carriage return")
+    } else if value.contains('\n') && !options.allow_multiline {
+        Some("value contains a line break and allow_multiline is off")
+    } else if first.trim() != first || value.trim_end() != value {
+        Some("value has leading or trailing whitespace")
+    } else if lines.any(|line| line.trim().is_empty() || !line.starts_with(char::is_whitespace)) {
+        Some("continuation line is blank or not indented")
+    } else if value.lines().skip(1).any(|line| line.trim_start().starts_with([';', '#'])) {
+        Some("continuation line starts like a comment")
+    } else if value.split(char::is_whitespace).skip(1).any(|word| word.starts_with(';')) {
+        Some("value contains an inline comment")
+    } else {
+        None
+    };
+    match reason {
+        Some(reason) => Err(IniWriteError::InvalidValue { section: section.to_string(), name: name.to_string(), reason }),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,33 @@
+//! Tests for the build capability report
+
+use inih::{capabilities, ParseOptions};
+
+#[test]
+fn test_capabilities_match_build() {
+    let report = capabilities();
+
+    assert_eq!(report.version, env!("CARGO_PKG_VERSION"));
+    let expected: &[&str] = if cfg!(feature = "serde") { &["serde"] } else { &[] };
+    assert_eq!(report.features, expected);
+    assert_eq!(report.default_options.max_line, ParseOptions::default().max_line);
+    assert_eq!(report.expansion_budget, ParseOptions::default().expansion_budget);
+}
+
+#[test]
+fn test_capabilities_output() {
+    let report = capabilities();
+
+    let (features, json_features) = if cfg!(feature = "serde") { ("serde", "\"serde\"") } else { ("none", "") };
+
+    let line = report.to_string();
+    assert!(line.starts_with(&format!("inih {} features={} max_line=200 ", env!("CARGO_PKG_VERSION"), features)));
+    assert!(!line.contains('\n'));
+
+    let json = report.to_json_string();
+    assert!(json.starts_with(&format!(
+        "{{\"version\":\"{}\",\"features\":[{}],\"defaults\":{{\"max_line\":200,",
+        env!("CARGO_PKG_VERSION"),
+        json_features
+    )));
+    assert!(json.ends_with("\"limits\":{\"max_depth\":32,\"max_total_expansions\":10000,\"max_expanded_len\":1048576}}"));
+}
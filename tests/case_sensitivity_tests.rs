@@ -0,0 +1,139 @@
+//! Tests for case-sensitive readers (`ReaderOptions::case_sensitive`)
+
+use inih::migrate::{apply_migrations, Migration, MigrationStatus};
+use inih::{DuplicateKeyPolicy, IniParseError, IniReader, ParseOptions, ReaderOptions};
+
+const HEADERS: &str = "[Headers]\nX-Token = a\nx-token = b\n[headers]\nAccept = */*\n";
+
+fn sensitive(data: &str) -> IniReader {
+    let options = ReaderOptions { case_sensitive: true, ..ReaderOptions::default() };
+    IniReader::from_string_with_reader_options(data, &ParseOptions::default(), options).unwrap()
+}
+
+#[test]
+fn test_mixed_case_names_collide_by_default() {
+    let reader = IniReader::from_string(HEADERS).unwrap();
+
+    assert_eq!(reader.get("HEADERS", "X-TOKEN", ""), "a\nb");
+    assert_eq!(reader.keys("headers"), vec!["x-token", "accept"]);
+    assert_eq!(reader.get("Headers", "accept", ""), "*/*");
+}
+
+#[test]
+fn test_mixed_case_names_are_distinct_when_sensitive() {
+    let reader = sensitive(HEADERS);
+
+    assert_eq!(reader.get("Headers", "X-Token", ""), "a");
+    assert_eq!(reader.get("Headers", "x-token", ""), "b");
+    assert!(!reader.has_value("Headers", "X-TOKEN"));
+    assert!(!reader.has_value("Headers", "Accept"));
+    assert_eq!(reader.get("headers", "Accept", ""), "*/*");
+    assert_eq!(reader.get_opt("HEADERS", "Accept"), None);
+
+    assert_eq!(reader.keys("Headers"), vec!["X-Token", "x-token"]);
+    assert_eq!(reader.keys("headers"), vec!["Accept"]);
+    assert_eq!(reader.sections(), vec!["Headers", "headers"]);
+    assert!(reader.has_section("headers"));
+    assert!(!reader.has_section("HEADERS"));
+    assert_eq!(
+        reader.iter().map(|(section, key, value)| format!("[{}] {}={}", section, key, value)).collect::<Vec<_>>(),
+        vec!["[Headers] X-Token=a", "[Headers] x-token=b", "[headers] Accept=*/*"]
+    );
+
+    // Names are still trimmed
+    assert_eq!(reader.get("Headers", " X-Token\t", ""), "a");
+}
+
+#[test]
+fn test_sensitive_typed_getters_and_mutators() {
+    let mut reader = sensitive("[Pool]\nSize = 8\nsize = 2\n");
+    assert_eq!(reader.get_integer("Pool", "Size", 0), 8);
+    assert_eq!(reader.try_get_integer("Pool", "size"), Ok(2));
+    assert!(reader.try_get_integer("pool", "size").is_err());
+
+    reader.set("Pool", "SIZE", "4");
+    assert_eq!(reader.keys("Pool"), vec!["Size", "size", "SIZE"]);
+    assert_eq!(reader.remove("Pool", "size").as_deref(), Some("2"));
+    assert_eq!(reader.get("Pool", "Size", ""), "8");
+
+    // Copies keep the setting
+    let copy = reader.clone();
+    assert!(!copy.has_value("Pool", "sIZE"));
+    let extracted = reader.extract(&["Pool"]);
+    assert_eq!(extracted.keys("Pool"), vec!["Size", "SIZE"]);
+    assert!(reader.extract(&["pool"]).sections().is_empty());
+}
+
+#[test]
+fn test_sensitive_migrations() {
+    let mut reader = sensitive("[App]\nName = x\nname = y\n");
+    let report = apply_migrations(&mut reader, &[
+        Migration::rename_key("App", "Name", "name"),
+        Migration::rename_key("App", "NAME", "title"),
+    ]);
+
+    assert_eq!(report.statuses, vec![MigrationStatus::Conflicted { overwritten: false }, MigrationStatus::Skipped]);
+    assert_eq!(reader.get("App", "Name", ""), "x");
+}
+
+const CASE_VARIANTS: &str = "[Server]\nKey = 1\nother = x\nKEY = 2\n";
+
+fn with_policy(data: &str, policy: DuplicateKeyPolicy, case_sensitive: bool) -> Result<IniReader, IniParseError> {
+    let options = ParseOptions { duplicate_key_policy: policy, ..ParseOptions::default() };
+    let reader_options = ReaderOptions { case_sensitive, ..ReaderOptions::default() };
+    IniReader::from_string_with_reader_options(data, &options, reader_options)
+}
+
+#[test]
+fn test_case_variant_duplicates_are_reported() {
+    let error = with_policy(CASE_VARIANTS, DuplicateKeyPolicy::Error, false).unwrap_err();
+    assert_eq!(error, IniParseError::ParseError {
+        line: 4,
+        message: "Duplicate key 'KEY' in section 'Server': first set as 'Key' on line 2 with '=', again on line 4 with '='"
+            .to_string(),
+        suggestion: None,
+    });
+
+    let expected = "Duplicate key 'KEY' in section 'Server': first set as 'Key' on line 2; names differing only in case are the same key";
+    for (policy, value) in [(DuplicateKeyPolicy::LastWins, "2"), (DuplicateKeyPolicy::FirstWins, "1"), (DuplicateKeyPolicy::default(), "1\n2")] {
+        let reader = with_policy(CASE_VARIANTS, policy.clone(), false).unwrap();
+        assert_eq!(reader.get("server", "key", ""), value, "{:?}", policy);
+        let warnings = reader.warnings();
+        assert_eq!(warnings.len(), 1, "{:?}", policy);
+        assert_eq!((warnings[0].line, warnings[0].name.as_str()), (Some(4), "KEY"));
+        assert_eq!(warnings[0].message, expected);
+    }
+
+    // Exact repeats are combined silently, as before
+    let reader = with_policy("[Server]\nKey = 1\nKey = 2\n", DuplicateKeyPolicy::LastWins, false).unwrap();
+    assert!(reader.warnings().is_empty());
+}
+
+#[test]
+fn test_case_variant_sections_are_named() {
+    let data = "[Server]\nport = 1\n[SERVER]\nport = 2\n";
+    let error = with_policy(data, DuplicateKeyPolicy::Error, false).unwrap_err();
+    assert_eq!(error, IniParseError::ParseError {
+        line: 4,
+        message: "Duplicate key 'port' in section 'SERVER': first set in section 'Server' on line 2 with '=', again on line 4 with '='"
+            .to_string(),
+        suggestion: None,
+    });
+}
+
+#[test]
+fn test_case_variants_are_distinct_keys_when_sensitive() {
+    for policy in [DuplicateKeyPolicy::Error, DuplicateKeyPolicy::FirstWins, DuplicateKeyPolicy::LastWins] {
+        let reader = with_policy(CASE_VARIANTS, policy.clone(), true).unwrap();
+        assert_eq!(reader.get("Server", "Key", ""), "1", "{:?}", policy);
+        assert_eq!(reader.get("Server", "KEY", ""), "2", "{:?}", policy);
+        assert!(reader.warnings().is_empty(), "{:?}", policy);
+    }
+
+    let reader = with_policy("[Server]\nport = 1\n[SERVER]\nport = 2\n", DuplicateKeyPolicy::Error, true).unwrap();
+    assert_eq!(reader.sections(), vec!["Server", "SERVER"]);
+
+    // Exact repeats are still duplicates
+    let error = with_policy("[Server]\nKey = 1\nKey = 2\n", DuplicateKeyPolicy::Error, true).unwrap_err();
+    assert!(matches!(error, IniParseError::ParseError { line: 3, .. }));
+}
@@ -0,0 +1,77 @@
+//! Tests for parsing on a background thread into a channel
+
+use std::sync::mpsc::sync_channel;
+
+use inih::channel::{ini_parse_to_channel, ChannelHandler, IniEvent};
+use inih::{ini_parse_string_with_options, IniParseError, ParseOptions};
+
+#[test]
+fn test_parse_to_channel() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/multi_line.ini");
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+
+    let (receiver, worker) = ini_parse_to_channel(path, options, 2);
+    let events: Vec<IniEvent> = receiver.iter().collect();
+
+    assert_eq!(worker.join().unwrap(), Ok(()));
+    assert_eq!(events.len(), 9);
+    assert_eq!(
+        events[1],
+        IniEvent::Entry {
+            section: "section1".to_string(),
+            name: "multi".to_string(),
+            value: "this is a\n        multi-line value".to_string(),
+            line: 3,
+        }
+    );
+    assert_eq!(events[8], IniEvent::Finished(Ok(())));
+}
+
+#[test]
+fn test_parse_to_channel_missing_file() {
+    let (receiver, worker) = ini_parse_to_channel("/nonexistent/inih.ini", ParseOptions::default(), 1);
+
+    let events: Vec<IniEvent> = receiver.iter().collect();
+    assert!(matches!(
+        &events[..],
+        [IniEvent::Finished(Err(IniParseError::FileOpenContext { path, .. }))] if path.as_os_str() == "/nonexistent/inih.ini"
+    ));
+    assert!(worker.join().unwrap().is_err());
+}
+
+#[test]
+fn test_parse_to_channel_receiver_dropped() {
+    let path = std::env::temp_dir().join(format!("inih_channel_{}.ini", std::process::id()));
+    let mut data = String::from("[big]\n");
+    for i in 0..100_000 {
+        data.push_str(&format!("key{} = {}\n", i, i));
+    }
+    std::fs::write(&path, data).unwrap();
+
+    let (receiver, worker) = ini_parse_to_channel(&path, ParseOptions::default(), 1);
+    let first: Vec<IniEvent> = receiver.iter().take(3).collect();
+    drop(receiver);
+    let result = worker.join().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(first.len(), 3);
+    assert_eq!(result, Err(IniParseError::HandlerError("receiver dropped".to_string())));
+}
+
+#[test]
+fn test_channel_handler_direct() {
+    let (sender, receiver) = sync_channel(16);
+    let mut handler = ChannelHandler::new(sender);
+
+    let result = ini_parse_string_with_options("[a]\nx = 1\n[b]\ny = 2\n", &mut handler, &ParseOptions::default());
+    handler.finish(result);
+
+    let names: Vec<String> = receiver
+        .iter()
+        .filter_map(|event| match event {
+            IniEvent::Entry { section, name, .. } => Some(format!("{}.{}", section, name)),
+            IniEvent::Finished(_) => None,
+        })
+        .collect();
+    assert_eq!(names, ["a.x", "b.y"]);
+}
@@ -0,0 +1,96 @@
+//! Tests for cloning readers that share their storage until changed
+
+use inih::migrate::{apply_migrations, Migration};
+use inih::IniReader;
+
+const DATA: &str = "[net]\nhost = example.com\nport = 80\n[server]\nname = web\n";
+
+fn reader() -> IniReader {
+    IniReader::from_string(DATA).unwrap()
+}
+
+#[test]
+fn test_clone_shares_storage() {
+    let original = reader();
+    assert_eq!(original.shared_storage_count(), 1);
+
+    let clone = original.clone();
+    assert_eq!(original.shared_storage_count(), 2);
+    assert_eq!(clone.to_ini_string(), original.to_ini_string());
+
+    drop(clone);
+    assert_eq!(original.shared_storage_count(), 1);
+}
+
+#[test]
+fn test_mutating_clone_leaves_original() {
+    let original = reader();
+    let mut clone = original.clone();
+    clone.extend([("net".to_string(), "port".to_string(), "8080".to_string())]);
+
+    assert_eq!(clone.get_integer("net", "port", 0), 8080);
+    assert_eq!(original.get_integer("net", "port", 0), 80);
+    assert_eq!(original.shared_storage_count(), 1);
+    assert_eq!(clone.shared_storage_count(), 1);
+}
+
+#[test]
+fn test_mutation_copies_only_its_section() {
+    let original = reader();
+    let mut clone = original.clone();
+    clone.set("net", "port", "8080");
+
+    assert_eq!(clone.shared_section_count("net"), 1);
+    assert_eq!(original.shared_section_count("net"), 1);
+    assert_eq!(clone.shared_section_count("SERVER"), 2);
+    assert_eq!(original.shared_section_count("missing"), 0);
+    assert_eq!(original.get_integer("net", "port", 0), 80);
+
+    clone.remove("server", "name");
+    assert_eq!(original.shared_section_count("server"), 1);
+    assert_eq!(original.get("server", "name", ""), "web");
+}
+
+#[test]
+fn test_mutating_original_leaves_clone() {
+    let mut original = reader();
+    let clone = original.clone();
+    assert!(original.split_off("server").is_some());
+    original.clear();
+
+    assert!(!original.has_value("server", "name"));
+    assert_eq!(clone.get("server", "name", ""), "web");
+    assert_eq!(clone.to_ini_string(), reader().to_ini_string());
+}
+
+#[test]
+fn test_migrations_on_clone() {
+    let original = reader();
+    let mut clone = original.clone();
+    apply_migrations(&mut clone, &[Migration::rename_key("net", "host", "address"), Migration::move_key("server", "name", "net", "name")]);
+
+    assert_eq!(clone.get("net", "address", ""), "example.com");
+    assert_eq!(original.get("net", "host", ""), "example.com");
+    assert_eq!(original.get("server", "name", ""), "web");
+    assert!(!original.has_value("net", "address"));
+}
+
+#[test]
+fn test_skipped_change_keeps_sharing() {
+    let original = reader();
+    let mut clone = original.clone();
+    let report = apply_migrations(&mut clone, &[Migration::DeleteKey { section: "net".to_string(), name: "missing".to_string() }]);
+
+    assert_eq!(report.skipped(), vec![0]);
+    assert_eq!(original.shared_storage_count(), 2);
+}
+
+#[test]
+fn test_parsing_into_clone() {
+    let original = reader();
+    let mut clone = original.clone();
+    inih::parser::ini_parse_string("[net]\nport = 443\n", &mut clone).unwrap();
+
+    assert_eq!(clone.get_integer("net", "port", 0), 443);
+    assert_eq!(original.get_integer("net", "port", 0), 80);
+}
@@ -0,0 +1,64 @@
+//! Tests for the fallback section of lookups (`ReaderOptions::default_section`)
+
+use std::time::Duration;
+
+use inih::{IniReader, ParseOptions, ReaderOptions};
+
+const CONFIG: &str = "[DEFAULT]\ntimeout = 30\nhost = localhost\nverbose = yes\n\n[server]\nhost = example.org\nport = 8080\n\n[client]\n";
+
+fn with_default(data: &str, section: &str) -> IniReader {
+    let options = ReaderOptions { default_section: Some(section.to_string()), ..ReaderOptions::default() };
+    IniReader::from_string_with_reader_options(data, &ParseOptions::default(), options).unwrap()
+}
+
+#[test]
+fn test_section_value_wins() {
+    let reader = with_default(CONFIG, "DEFAULT");
+    assert_eq!(reader.get("server", "host", ""), "example.org");
+    assert_eq!(reader.get("client", "host", ""), "localhost");
+}
+
+#[test]
+fn test_only_in_default() {
+    let reader = with_default(CONFIG, "DEFAULT");
+    assert_eq!(reader.get("server", "timeout", ""), "30");
+    assert_eq!(reader.get_integer("client", "timeout", 0), 30);
+    assert_eq!(reader.get_duration("server", "timeout", Duration::ZERO), Duration::from_secs(30));
+    assert!(reader.get_boolean("server", "verbose", false));
+    assert_eq!(reader.get_opt("nosuch", "timeout"), Some("30"));
+    assert_eq!(reader.get("server", "missing", "fallback"), "fallback");
+
+    // Strict checks and listings are unchanged
+    assert!(!reader.has_value("server", "timeout"));
+    assert!(reader.has_value("DEFAULT", "timeout"));
+    assert_eq!(reader.keys("server"), ["host", "port"]);
+    assert_eq!(reader.keys_with_defaults("server"), ["host", "port", "timeout", "verbose"]);
+    assert_eq!(reader.keys_with_defaults("DEFAULT"), ["timeout", "host", "verbose"]);
+}
+
+#[test]
+fn test_default_section_name_matches_case_insensitively() {
+    let reader = with_default("[default]\ntimeout = 30\n[server]\n", "DEFAULT");
+    assert_eq!(reader.get("server", "timeout", ""), "30");
+    assert_eq!(reader.keys_with_defaults("server"), ["timeout"]);
+}
+
+#[test]
+fn test_off_by_default() {
+    let reader = IniReader::from_string(CONFIG).unwrap();
+    assert_eq!(reader.get("server", "timeout", "none"), "none");
+    assert_eq!(reader.keys_with_defaults("server"), reader.keys("server"));
+}
+
+#[test]
+fn test_explain_shows_fallback() {
+    let reader = with_default(CONFIG, "DEFAULT");
+    let explanation = reader.explain("server", "timeout");
+    assert_eq!(explanation.value.as_deref(), Some("30"));
+    assert_eq!(explanation.steps.iter().map(|step| (step.section.as_str(), step.found)).collect::<Vec<_>>(), [("server", false), ("DEFAULT", true)]);
+    assert_eq!(explanation.occurrences[0].line, 2);
+
+    // Cloned and extracted readers keep the fallback
+    assert_eq!(reader.clone().get("client", "host", ""), "localhost");
+    assert_eq!(reader.extract(&["DEFAULT", "client"]).get("client", "timeout", ""), "30");
+}
@@ -0,0 +1,91 @@
+//! Tests for the duplicate key policies
+
+use inih::{DuplicateKeyPolicy, IniParseError, IniReader, ParseOptions};
+
+const MIXED_SEPARATORS: &str = r#"[colon_tests]
+foo: bar
+other = 1
+FOO = baz
+"#;
+
+fn options(policy: DuplicateKeyPolicy) -> ParseOptions {
+    ParseOptions { duplicate_key_policy: policy, ..ParseOptions::default() }
+}
+
+#[test]
+fn test_mixed_separators_are_duplicates() {
+    let reader = IniReader::from_string_with_options(MIXED_SEPARATORS, &options(DuplicateKeyPolicy::LastWins)).unwrap();
+    assert_eq!(reader.get("colon_tests", "foo", ""), "baz");
+
+    let reader = IniReader::from_string_with_options(MIXED_SEPARATORS, &options(DuplicateKeyPolicy::FirstWins)).unwrap();
+    assert_eq!(reader.get("colon_tests", "foo", ""), "bar");
+}
+
+#[test]
+fn test_mixed_separators_error_message() {
+    let result = IniReader::from_string_with_options(MIXED_SEPARATORS, &options(DuplicateKeyPolicy::Error));
+
+    assert_eq!(result.unwrap_err(), IniParseError::ParseError {
+        line: 4,
+        message: "Duplicate key 'FOO' in section 'colon_tests': first set as 'foo' on line 2 with ':', again on line 4 with '='"
+            .to_string(),
+        suggestion: None,
+    });
+}
+
+#[test]
+fn test_default_policy_concatenates() {
+    let reader = IniReader::from_string(MIXED_SEPARATORS).unwrap();
+
+    assert_eq!(reader.get("colon_tests", "foo", ""), "bar\nbaz");
+}
+
+const PADDED_KEYS: &str = "[padding]\nkey = 1\n\tTabbed\t= 2\nspaced   = 3\n";
+
+#[test]
+fn test_padded_keys_are_normalized() {
+    let reader = IniReader::from_string(PADDED_KEYS).unwrap();
+
+    assert_eq!(reader.keys("padding"), vec!["key", "tabbed", "spaced"]);
+    assert_eq!(reader.get("padding", "tabbed", ""), "2");
+    assert_eq!(reader.get("padding", " spaced\t", ""), "3");
+    assert_eq!(reader.occurrences("padding", "TABBED")[0].name, "Tabbed");
+}
+
+#[test]
+fn test_keys_differing_only_in_padding_collide() {
+    let data = "[padding]\nname = a\n\t name  \t= b\n";
+    let result = IniReader::from_string_with_options(data, &options(DuplicateKeyPolicy::Error));
+
+    assert_eq!(result.unwrap_err(), IniParseError::ParseError {
+        line: 3,
+        message: "Duplicate key 'name' in section 'padding': first set on line 2 with '=', again on line 3 with '='"
+            .to_string(),
+        suggestion: None,
+    });
+
+    let reader = IniReader::from_string_with_options(data, &options(DuplicateKeyPolicy::LastWins)).unwrap();
+    assert_eq!(reader.get("padding", "name", ""), "b");
+    assert_eq!(reader.occurrences("padding", "name").len(), 2);
+}
+
+const REPEATED_MULTILINE: &str = "[motd]\ntext = first\n  more\nother = 1\ntext = second\n  again\n";
+
+#[test]
+fn test_every_policy_keeps_continuations() {
+    let read = |policy| {
+        let options = ParseOptions { allow_multiline: true, ..options(policy) };
+        IniReader::from_string_with_options(REPEATED_MULTILINE, &options)
+    };
+
+    assert_eq!(read(DuplicateKeyPolicy::LastWins).unwrap().get("motd", "text", ""), "second\n  again");
+    assert_eq!(read(DuplicateKeyPolicy::FirstWins).unwrap().get("motd", "text", ""), "first\n  more");
+    let concatenated = read(DuplicateKeyPolicy::Concatenate { separator: " | ".to_string() }).unwrap();
+    assert_eq!(concatenated.get("motd", "text", ""), "first\n  more | second\n  again");
+    assert_eq!(concatenated.get("motd", "other", ""), "1");
+    assert_eq!(read(DuplicateKeyPolicy::Error).unwrap_err(), IniParseError::ParseError {
+        line: 5,
+        message: "Duplicate key 'text' in section 'motd': first set on line 2 with '=', again on line 5 with '='".to_string(),
+        suggestion: None,
+    });
+}
@@ -0,0 +1,181 @@
+//! Tests for byte encodings on the read and write paths
+
+use inih::prelude::*;
+
+const DATA: &str = "[user]\nname = Jürgen\ncity = Zürich ✓\n";
+
+#[test]
+fn test_write_encodings_round_trip() {
+    let reader = IniReader::from_string(DATA).unwrap();
+
+    for encoding in [WriteEncoding::Utf8, WriteEncoding::Utf8WithBom, WriteEncoding::Utf16Le] {
+        let bytes = reader.to_ini_bytes(encoding);
+        let reread = IniReader::from_bytes(&bytes, &ParseOptions::default()).unwrap();
+
+        assert_eq!(reread.get("user", "name", ""), "Jürgen", "{:?}", encoding);
+        assert_eq!(reread.get("user", "city", ""), "Zürich ✓", "{:?}", encoding);
+        assert_eq!(reread.sections(), vec!["user"], "{:?}", encoding);
+    }
+}
+
+#[test]
+fn test_write_encoding_bytes() {
+    let reader = IniReader::from_string("[a]\nk = v\n").unwrap();
+
+    assert_eq!(reader.to_ini_bytes(WriteEncoding::Utf8), b"[a]\nk = v\n");
+    assert!(reader.to_ini_bytes(WriteEncoding::Utf8WithBom).starts_with(&[0xEF, 0xBB, 0xBF, b'[']));
+    assert_eq!(&reader.to_ini_bytes(WriteEncoding::Utf16Le)[..4], &[0xFF, 0xFE, b'[', 0]);
+    assert_eq!(WriteEncoding::default(), WriteEncoding::Utf8);
+}
+
+#[test]
+fn test_from_bytes_rejects_invalid_input() {
+    let result = IniReader::from_bytes(b"[a]\nk = \xFF\n", &ParseOptions::default());
+    assert_eq!(result.unwrap_err(), IniParseError::Encoding("invalid UTF-8 at byte 8".to_string()));
+
+    let result = IniReader::from_bytes(&[0xFF, 0xFE, b'['], &ParseOptions::default());
+    assert!(matches!(result, Err(IniParseError::Encoding(_))));
+}
+
+const CRLF_MULTI_LINE: &str = "[motd]\r\ntext = first\r\n  second\n  third\r\n";
+
+fn stored_with(style: NewlineStyle) -> String {
+    let options = ParseOptions { allow_multiline: true, value_newline: style, ..ParseOptions::default() };
+    IniReader::from_string_with_options(CRLF_MULTI_LINE, &options).unwrap().get("motd", "text", "")
+}
+
+#[test]
+fn test_value_newline_styles() {
+    assert_eq!(stored_with(NewlineStyle::Lf), "first\n  second\n  third");
+    assert_eq!(stored_with(NewlineStyle::CrLf), "first\r\n  second\r\n  third");
+    assert_eq!(stored_with(NewlineStyle::Preserve), "first\r\n  second\n  third");
+    let platform = if cfg!(windows) { "\r\n" } else { "\n" };
+    assert_eq!(stored_with(NewlineStyle::Platform), format!("first{0}  second{0}  third", platform));
+}
+
+#[test]
+fn test_line_endings_reported_from_stream() {
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    let mut writer = WriterHandler::new(Vec::new()).newline(NewlineStyle::Preserve);
+    inih::parser::ini_parse_reader_with_options(CRLF_MULTI_LINE.as_bytes(), &mut writer, &options).unwrap();
+    assert_eq!(String::from_utf8(writer.into_inner()).unwrap(), "[motd]\ntext = first\r\n  second\n  third\r\n");
+}
+
+#[test]
+fn test_writer_handler_newline() {
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    for (style, expected) in [
+        (NewlineStyle::Lf, "[motd]\ntext = first\n  second\n  third\n"),
+        (NewlineStyle::CrLf, "[motd]\r\ntext = first\r\n  second\r\n  third\r\n"),
+        (NewlineStyle::Preserve, "[motd]\ntext = first\r\n  second\n  third\r\n"),
+    ] {
+        let mut writer = WriterHandler::new(Vec::new()).newline(style);
+        ini_parse_string_with_options(CRLF_MULTI_LINE, &mut writer, &options).unwrap();
+        assert_eq!(String::from_utf8(writer.into_inner()).unwrap(), expected, "{:?}", style);
+    }
+}
+
+#[test]
+fn test_ascii_only() {
+    let strict = ParseOptions { ascii_only: true, stop_on_first_error: true, ..ParseOptions::default() };
+
+    assert_eq!(IniReader::from_string(DATA).unwrap().get("user", "name", ""), "Jürgen");
+    assert_eq!(
+        IniReader::from_string_with_options(DATA, &strict).unwrap_err(),
+        IniParseError::ParseError { line: 2, message: "Non-ASCII byte 0xC3 at byte 8".to_string(), suggestion: None }
+    );
+
+    let reader = IniReader::from_string_with_options("[user]\nname = Juergen\n", &strict).unwrap();
+    assert_eq!(reader.get("USER", "name", ""), "Juergen");
+}
+
+#[test]
+fn test_ascii_only_rejects_bom() {
+    let data = "\u{FEFF}[user]\nname = x\n";
+    let strict = ParseOptions { ascii_only: true, stop_on_first_error: true, ..ParseOptions::default() };
+
+    assert!(IniReader::from_string(data).is_ok());
+    match IniReader::from_string_with_options(data, &strict) {
+        Err(IniParseError::ParseError { line: 1, message, .. }) => {
+            assert!(message.contains("byte order mark"), "{}", message);
+            assert!(message.contains("allow_bom"), "{}", message);
+        }
+        other => panic!("expected a BOM error, got {:?}", other),
+    }
+}
+
+const LATIN1: &[u8] = b"[user]\nname = J\xFCrgen\n\n; caf\xE9\ncity = Z\xFCrich\n";
+
+fn read_as(bytes: &[u8], encoding: EncodingMode) -> IniReader {
+    IniReader::from_bytes(bytes, &ParseOptions { encoding, ..ParseOptions::default() }).unwrap()
+}
+
+#[test]
+fn test_auto_keeps_ascii_and_utf8() {
+    let reader = read_as(b"[user]\nname = Juergen\n", EncodingMode::Auto);
+    assert_eq!(reader.get("user", "name", ""), "Juergen");
+    assert!(reader.warnings().is_empty());
+
+    let reader = read_as(DATA.as_bytes(), EncodingMode::Auto);
+    assert_eq!(reader.get("user", "name", ""), "Jürgen");
+    assert_eq!(reader.get("user", "city", ""), "Zürich ✓");
+    assert!(reader.warnings().is_empty());
+}
+
+#[test]
+fn test_auto_falls_back_to_latin1_for_the_whole_document() {
+    let reader = read_as(LATIN1, EncodingMode::Auto);
+    assert_eq!(reader.get("user", "name", ""), "Jürgen");
+    assert_eq!(reader.get("user", "city", ""), "Zürich");
+
+    let warnings = reader.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].line, Some(2));
+    assert_eq!(warnings[0].to_string(), "line 2: invalid UTF-8, the document was read as Latin-1");
+
+    // Valid UTF-8 before the first invalid byte is read as Latin-1 too
+    let reader = read_as(b"[user]\nname = J\xC3\xBCrgen\ncity = Z\xFCrich\n", EncodingMode::Auto);
+    assert_eq!(reader.get("user", "name", ""), "J\u{C3}\u{BC}rgen");
+    assert_eq!(reader.warnings()[0].line, Some(3));
+}
+
+#[test]
+fn test_explicit_encoding_modes() {
+    assert_eq!(
+        IniReader::from_bytes(LATIN1, &ParseOptions::default()).unwrap_err(),
+        IniParseError::Encoding("invalid UTF-8 at byte 15".to_string())
+    );
+
+    let reader = read_as(LATIN1, EncodingMode::Latin1);
+    assert_eq!(reader.get("user", "name", ""), "Jürgen");
+    assert!(reader.warnings().is_empty());
+    assert_eq!(read_as(DATA.as_bytes(), EncodingMode::Latin1).get("user", "name", ""), "J\u{C3}\u{BC}rgen");
+
+    let reader = read_as(LATIN1, EncodingMode::Utf8Lossy);
+    assert_eq!(reader.get("user", "name", ""), "J\u{FFFD}rgen");
+    assert!(reader.warnings().is_empty());
+}
+
+#[test]
+fn test_encoding_modes_on_streams_and_files() {
+    for encoding in [EncodingMode::Utf8Lossy, EncodingMode::Latin1, EncodingMode::Auto] {
+        let options = ParseOptions { encoding, ..ParseOptions::default() };
+        let from_bytes = IniReader::from_bytes(LATIN1, &options).unwrap();
+        let from_reader = IniReader::from_reader_with_options(LATIN1, &options).unwrap();
+        assert_eq!(from_reader.iter().collect::<Vec<_>>(), from_bytes.iter().collect::<Vec<_>>(), "{:?}", encoding);
+        assert_eq!(from_reader.warnings(), from_bytes.warnings(), "{:?}", encoding);
+    }
+
+    let path = std::env::temp_dir().join(format!("inih_latin1_{}.ini", std::process::id()));
+    std::fs::write(&path, LATIN1).unwrap();
+
+    let options = ParseOptions { encoding: EncodingMode::Auto, ..ParseOptions::default() };
+    let report = inih::report::ini_check_file(&path, &options);
+    assert!(!report.is_fatal());
+    assert_eq!(report.warning_count(), 1);
+    let (reader, report) = IniReader::from_file_lossy(&path, &options);
+    assert_eq!(reader.get("user", "city", ""), "Zürich");
+    assert_eq!(report.findings[0].line, Some(2));
+
+    std::fs::remove_file(&path).unwrap();
+}
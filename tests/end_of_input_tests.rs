@@ -0,0 +1,151 @@
+//! Input without a trailing newline and input ending in whitespace lines
+//!
+//! Every entry point must parse the last line the same way whether or not it
+//! is terminated, and treat lines holding only whitespace as blank.
+
+use std::io::{BufReader, Cursor};
+
+use inih::parser::ini_parse_reader_with_options;
+use inih::str_reader::IniStrReader;
+use inih::{ini_parse_string_with_options, EntryInfo, IniHandler, IniReader, ParseOptions};
+
+/// Inputs as they would end in a file written without a final newline
+const ENDINGS: &[&str] = &[
+    "[s]\nkey = value",
+    "[s]\nkey = value  ",
+    "[s]\nkey = value\n   \n\t",
+    "[s]\nkey = value\n  more",
+    "[s]\nkey = value\n  more\n   ",
+    "[s]\r\nkey = value\r\n  more\r",
+    "[s]\nkey = value\n[t]",
+    "[s]\nkey = value\nflag",
+    "[s]\nkey = value ; note\r",
+];
+
+/// Handler recording every call with the line details it was given
+#[derive(Default)]
+struct Recorder {
+    calls: Vec<String>,
+}
+
+impl IniHandler for Recorder {
+    fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.calls.push(format!("handle [{}] {}={:?}", section, name, value));
+        Ok(())
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.calls.push(format!(
+            "entry {} {:?} {:?} [{}] {}={:?} continuation={}",
+            info.line, info.line_ending, info.raw_line, section, name, value, info.continuation
+        ));
+        Ok(())
+    }
+
+    fn end_value(&mut self, section: &str, name: &str) -> Result<(), String> {
+        self.calls.push(format!("end [{}] {}", section, name));
+        Ok(())
+    }
+}
+
+fn option_sets() -> Vec<ParseOptions> {
+    [false, true]
+        .into_iter()
+        .flat_map(|allow_multiline| {
+            [true, false].into_iter().map(move |trim_values| ParseOptions {
+                allow_multiline,
+                trim_values,
+                allow_no_value: true,
+                allow_inline_comments: true,
+                ..ParseOptions::default()
+            })
+        })
+        .collect()
+}
+
+fn calls_from_string(data: &str, options: &ParseOptions) -> Vec<String> {
+    let mut recorder = Recorder::default();
+    ini_parse_string_with_options(data, &mut recorder, options).unwrap();
+    recorder.calls
+}
+
+fn calls_from_stream(data: &str, options: &ParseOptions) -> Vec<String> {
+    // A one-byte buffer splits every line, and every CRLF, across reads
+    let mut recorder = Recorder::default();
+    ini_parse_reader_with_options(BufReader::with_capacity(1, data.as_bytes()), &mut recorder, options).unwrap();
+    recorder.calls
+}
+
+/// Entries of a reader with the value of each and the sections it declared
+fn contents(reader: &IniReader) -> (Vec<String>, Vec<(&str, &str, &str)>) {
+    (reader.sections(), reader.iter().collect())
+}
+
+#[test]
+fn test_entry_points_agree_on_unterminated_input() {
+    for options in option_sets() {
+        for &data in ENDINGS {
+            let context = format!("{:?} multiline={} trim={}", data, options.allow_multiline, options.trim_values);
+            let terminated = format!("{}\n", data);
+
+            let expected = calls_from_string(&terminated, &options);
+            let calls = calls_from_string(data, &options);
+            // Only the terminator of the last line may differ
+            let last_line = format!("entry {} ", data.split('\n').count());
+            let unterminated: Vec<String> = expected
+                .iter()
+                .map(|call| match call.strip_prefix(&last_line) {
+                    Some(rest) => format!("{}\"\"{}", last_line, &rest[rest.find(' ').unwrap()..]),
+                    None => call.clone(),
+                })
+                .collect();
+            assert_eq!(calls, unterminated, "{}", context);
+            assert_eq!(calls_from_stream(data, &options), calls, "{}", context);
+            assert_eq!(calls_from_stream(&terminated, &options), expected, "{}", context);
+
+            let reader = IniReader::from_string_with_options(&terminated, &options).unwrap();
+            let from_string = IniReader::from_string_with_options(data, &options).unwrap();
+            let from_reader = IniReader::from_reader_with_options(Cursor::new(data.as_bytes()), &options).unwrap();
+            let from_bytes = IniReader::from_bytes(data.as_bytes(), &options).unwrap();
+            for other in [&from_string, &from_reader, &from_bytes] {
+                assert_eq!(contents(other), contents(&reader), "{}", context);
+            }
+
+            let borrowed = IniStrReader::from_str(data, &options).unwrap();
+            for (section, key, value) in reader.iter() {
+                assert_eq!(borrowed.get(section, key, "<missing>"), value, "{}", context);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_last_line_without_newline() {
+    let reader = IniReader::from_string("[s]\r\nkey = value\r").unwrap();
+    assert_eq!(reader.get("s", "key", ""), "value");
+
+    let options = ParseOptions { trim_values: false, ..ParseOptions::default() };
+    let reader = IniReader::from_reader_with_options(Cursor::new("[s]\nkey = value  "), &options).unwrap();
+    assert_eq!(reader.get("s", "key", ""), " value  ");
+}
+
+#[test]
+fn test_whitespace_lines_at_the_end_are_blank() {
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    for data in ["[s]\nkey = value\n   \n\t\n", "[s]\nkey = value\n \t ", "[s]\nkey = value\r\n  \r\n  \r"] {
+        let reader = IniReader::from_reader_with_options(Cursor::new(data), &options).unwrap();
+        assert_eq!(reader.get("s", "key", ""), "value", "{:?}", data);
+        assert_eq!(reader.keys("s"), vec!["key"], "{:?}", data);
+    }
+}
+
+#[test]
+fn test_final_continuation_joins() {
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    for data in ["[s]\nkey = first\n  second", "[s]\r\nkey = first\r\n  second\r", "[s]\nkey = first\n  second\n  "] {
+        let from_string = IniReader::from_string_with_options(data, &options).unwrap();
+        let from_reader = IniReader::from_reader_with_options(Cursor::new(data), &options).unwrap();
+        assert_eq!(from_string.get("s", "key", ""), "first\n  second", "{:?}", data);
+        assert_eq!(from_reader.get("s", "key", ""), "first\n  second", "{:?}", data);
+    }
+}
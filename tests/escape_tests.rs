@@ -0,0 +1,79 @@
+//! Tests for escape sequence decoding in values (`ParseOptions::parse_escapes`)
+
+use inih::{IniParseError, IniReader, ParseOptions};
+
+fn escapes() -> ParseOptions {
+    ParseOptions { parse_escapes: true, ..ParseOptions::default() }
+}
+
+fn error(line: usize, message: &str) -> IniParseError {
+    IniParseError::ParseError { line, message: message.to_string(), suggestion: None }
+}
+
+#[test]
+fn test_every_escape_decoded() {
+    let data = r"[s]
+k = tab\there\nnew\r\\ \; \# \= \: \x41\x7e
+";
+    let reader = IniReader::from_string_with_options(data, &escapes()).unwrap();
+    assert_eq!(reader.get("s", "k", ""), "tab\there\nnew\r\\ ; # = : A~");
+}
+
+#[test]
+fn test_escaped_comment_character_is_kept() {
+    let data = r"k = x \;y ; a comment
+j = \\ ; cut
+";
+    let reader = IniReader::from_string_with_options(data, &escapes()).unwrap();
+    assert_eq!(reader.get("", "k", ""), "x ;y");
+    assert_eq!(reader.get("", "j", ""), "\\");
+}
+
+#[test]
+fn test_bad_escapes_name_line_and_offset() {
+    let parse = |data: &str| IniReader::from_string_with_options(data, &escapes()).err();
+    assert_eq!(parse("[s]\nk = ab\\q\n"), Some(error(2, "Invalid escape sequence '\\q' at byte 6")));
+    assert_eq!(parse("k = \\x4\n"), Some(error(1, "Truncated escape sequence '\\x4' at byte 4")));
+    assert_eq!(parse("k = \\x4g\n"), Some(error(1, "Invalid escape sequence '\\x4g' at byte 4")));
+    assert_eq!(parse("k = end\\\n"), Some(error(1, "Backslash at end of value at byte 7")));
+
+    // Parsing goes on after the bad entry
+    let (reader, report) = IniReader::from_string_lossy("a = \\q\nb = \\t\n", &escapes());
+    assert_eq!(report.errors().len(), 1);
+    assert!(!reader.has_value("", "a"));
+    assert_eq!(reader.get("", "b", ""), "\t");
+}
+
+#[test]
+fn test_continuations_decoded_per_line() {
+    let options = ParseOptions { allow_multiline: true, ..escapes() };
+    let reader = IniReader::from_string_with_options("k = a\\tb\n  c\\x41\n", &options).unwrap();
+    assert_eq!(reader.get("", "k", ""), "a\tb\n  cA");
+
+    // The error is on the continuation line, at its offset there
+    let result = IniReader::from_string_with_options("k = a\n  b\\z\n", &options);
+    assert_eq!(result.err(), Some(error(2, "Invalid escape sequence '\\z' at byte 3")));
+
+    // A bad first line leaves out its continuations too
+    let (reader, _) = IniReader::from_string_lossy("k = \\q\n  more\nj = 1\n", &options);
+    assert_eq!(reader.keys(""), ["j"]);
+}
+
+#[test]
+fn test_off_by_default_backslashes_round_trip() {
+    let data = r"[paths]
+win = C:\dir\new\table
+regex = \d+\.\q
+end = tail\
+";
+    let reader = IniReader::from_string(data).unwrap();
+    assert_eq!(reader.get("paths", "win", ""), r"C:\dir\new\table");
+    assert_eq!(reader.get("paths", "regex", ""), r"\d+\.\q");
+    assert_eq!(reader.get("paths", "end", ""), r"tail\");
+
+    let written = reader.to_ini_string();
+    let reread = IniReader::from_string(&written).unwrap();
+    for key in ["win", "regex", "end"] {
+        assert_eq!(reread.get("paths", key, ""), reader.get("paths", key, ""));
+    }
+}
@@ -0,0 +1,122 @@
+//! Tests for explanations of how a key resolves
+
+use inih::explain::{Source, Step};
+use inih::fallback::FallbackChain;
+use inih::layered::LayeredReader;
+use inih::interpolation::Interpolation;
+use inih::{DuplicateKeyPolicy, IniReader, ParseOptions, ReaderOptions};
+
+const FILE: &str = "[server]\nhost = example.com\ntimeout = 30\n[defaults]\nretries = 3\n";
+
+fn step(source: Source, section: &str, found: bool) -> Step {
+    Step { source, section: section.to_string(), found }
+}
+
+/// A layer built from environment variables, as an overlay would
+fn env_layer() -> IniReader {
+    let mut env = IniReader::from_string("").unwrap();
+    env.extend([("server".to_string(), "timeout".to_string(), "60".to_string())]);
+    env
+}
+
+#[test]
+fn test_env_override_shadows_file() {
+    let file = IniReader::from_string(FILE).unwrap();
+    let env = env_layer();
+    let layered = LayeredReader::new(vec![&file, &env]);
+
+    let explanation = layered.explain("server", "timeout");
+    assert_eq!(explanation.value.as_deref(), Some("60"));
+    assert_eq!(explanation.steps, [step(Source::Layer(1), "server", true)]);
+    assert_eq!(explanation.occurrences[0].line, 0);
+    assert_eq!(explanation.to_string(), "[server] timeout = \"60\"\n  layer 1: [server] timeout found, set\n");
+    assert_eq!(layered.get_integer("server", "timeout", 0), 60);
+
+    // A key the overlay does not have falls through to the file
+    let explanation = layered.explain("server", "host");
+    assert_eq!(explanation.steps, [step(Source::Layer(1), "server", false), step(Source::Layer(0), "server", true)]);
+    assert_eq!(
+        explanation.to_string(),
+        "[server] host = \"example.com\"\n  layer 1: [server] host not found\n  layer 0: [server] host found, line 2\n"
+    );
+}
+
+#[test]
+fn test_pure_default() {
+    let file = IniReader::from_string(FILE).unwrap();
+    let env = env_layer();
+    let layered = LayeredReader::new(vec![&file, &env]);
+
+    let explanation = layered.explain("server", "port");
+    assert_eq!(explanation.value, None);
+    assert!(explanation.occurrences.is_empty());
+    assert_eq!(
+        explanation.to_string(),
+        "[server] port is not set, getters return their default\n  layer 1: [server] port not found\n  layer 0: [server] port not found\n"
+    );
+    assert_eq!(layered.get_integer("server", "port", 8080), 8080);
+
+    let explanation = file.explain("server", "port");
+    assert_eq!(explanation.steps, [step(Source::Reader, "server", false)]);
+}
+
+#[test]
+fn test_reader_explain_matches_getters() {
+    let reader = IniReader::from_string(FILE).unwrap();
+    for (section, name) in [("server", "host"), ("SERVER", " Timeout "), ("defaults", "retries"), ("server", "missing"), ("none", "host")] {
+        let explanation = reader.explain(section, name);
+        assert_eq!(explanation.value.is_some(), reader.has_value(section, name));
+        assert_eq!(explanation.value.unwrap_or_default(), reader.get(section, name, ""));
+    }
+}
+
+#[test]
+fn test_reader_explain_name_types() {
+    let reader = IniReader::from_string(FILE).unwrap();
+    let section = String::from("Server");
+    let explanation = reader.explain(&section, String::from("HOST"));
+    assert_eq!(explanation.value.as_deref(), Some("example.com"));
+    assert_eq!(explanation.steps, [step(Source::Reader, "Server", true)]);
+}
+
+#[test]
+fn test_duplicates_show_every_line() {
+    let options = ParseOptions {
+        duplicate_key_policy: DuplicateKeyPolicy::Concatenate { separator: ",".to_string() },
+        ..ParseOptions::default()
+    };
+    let reader = IniReader::from_string_with_options("[s]\nk = a\nk = b\n", &options).unwrap();
+
+    assert_eq!(reader.explain("s", "k").to_string(), "[s] k = \"a,b\"\n  reader: [s] k found, line 2, line 3\n");
+}
+
+#[test]
+fn test_fallback_chain() {
+    let reader = IniReader::from_string(FILE).unwrap();
+    let chain = FallbackChain::new(&["server", "defaults"]);
+
+    let explanation = chain.explain(&reader, "retries");
+    assert_eq!(explanation.value.as_deref(), Some("3"));
+    assert_eq!(explanation.steps, [step(Source::Reader, "server", false), step(Source::Reader, "defaults", true)]);
+    assert_eq!(
+        explanation.to_string(),
+        "[server] retries = \"3\"\n  reader: [server] retries not found\n  reader: [defaults] retries found, line 5\n"
+    );
+    assert_eq!(chain.resolve_origin(&reader, "retries"), Some("defaults"));
+}
+
+#[test]
+fn test_interpolated_value_shows_raw_value() {
+    let options = ReaderOptions { interpolation: Interpolation::Extended, ..ReaderOptions::default() };
+    let mut reader = IniReader::from_string_with_reader_options("[s]\nroot = /srv\nlog = ${root}/log\n", &ParseOptions::default(), options).unwrap();
+
+    let explanation = reader.explain("s", "log");
+    assert_eq!(explanation.value.as_deref(), Some("/srv/log"));
+    assert_eq!(explanation.raw_value.as_deref(), Some("${root}/log"));
+    assert_eq!(explanation.to_string(), "[s] log = \"/srv/log\"\n  reader: [s] log found, line 3\n  interpolated from \"${root}/log\"\n");
+    assert_eq!(reader.explain("s", "root").raw_value, None);
+
+    // A value set afterwards was not interpolated
+    reader.set("s", "log", "/var/log");
+    assert_eq!(reader.explain("s", "log").raw_value, None);
+}
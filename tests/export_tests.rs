@@ -0,0 +1,198 @@
+//! Tests for exporting reader contents to other formats
+
+use inih::args::{key_value_pair, long_flag, shell_join, skip_empty};
+use inih::env::EnvExportOptions;
+use inih::error::{ArgsError, EnvExportError};
+use inih::summary::SummaryOptions;
+use inih::{IniParseError, IniReader, ParseOptions};
+
+#[test]
+fn test_export_env_transform() {
+    let data = "[database]\nhost = db.local\nmax-connections = 10\nuser.name = admin\n";
+    let reader = IniReader::from_string(data).unwrap();
+
+    let vars = reader.export_env("database", "DB_").unwrap();
+
+    assert_eq!(vars, vec![
+        ("DB_HOST".to_string(), "db.local".to_string()),
+        ("DB_MAX_CONNECTIONS".to_string(), "10".to_string()),
+        ("DB_USER_NAME".to_string(), "admin".to_string()),
+    ]);
+
+    let options = EnvExportOptions { key_transform: |key| key.replace('-', ""), ..EnvExportOptions::default() };
+    let vars = reader.export_env_with("database", "", &options).unwrap();
+    assert_eq!(vars[1].0, "maxconnections");
+}
+
+#[test]
+fn test_export_env_collision() {
+    let reader = IniReader::from_string("[cache]\nmax-size = 1\nmax_size = 2\n").unwrap();
+
+    let result = reader.export_env("cache", "");
+
+    assert_eq!(result, Err(EnvExportError::Collision {
+        name: "MAX_SIZE".to_string(),
+        first_key: "max-size".to_string(),
+        second_key: "max_size".to_string(),
+    }));
+}
+
+#[test]
+fn test_export_env_multiline_value() {
+    let data = "[motd]\ntext = hello\n  world\n";
+    let options = inih::ParseOptions { allow_multiline: true, ..inih::ParseOptions::default() };
+    let reader = IniReader::from_string_with_options(data, &options).unwrap();
+
+    let vars = reader.export_env("motd", "").unwrap();
+    assert_eq!(vars, vec![("TEXT".to_string(), "hello\n  world".to_string())]);
+
+    let strict = EnvExportOptions { reject_unsafe_values: true, ..EnvExportOptions::default() };
+    let result = reader.export_env_with("motd", "", &strict);
+    assert_eq!(result, Err(EnvExportError::UnsafeValue { key: "text".to_string() }));
+}
+
+#[test]
+fn test_apply_to_command() {
+    let reader = IniReader::from_string("[server]\nport = 8080\n").unwrap();
+    let mut command = std::process::Command::new("true");
+
+    reader.apply_to_command(&mut command, "server", "APP_").unwrap();
+
+    let envs: Vec<_> = command.get_envs().collect();
+    assert_eq!(envs, vec![(std::ffi::OsStr::new("APP_PORT"), Some(std::ffi::OsStr::new("8080")))]);
+}
+
+#[test]
+fn test_to_args_mapping_closure() {
+    let reader = IniReader::from_string("[ffmpeg]\ninput = in.mp4\nCRF = 23\n[other]\nx = 1\n").unwrap();
+
+    let args = reader.to_args("ffmpeg", |_section, key, value| Some(format!("--{}={}", key, value))).unwrap();
+    assert_eq!(args, vec!["--input=in.mp4", "--crf=23"]);
+
+    // Keys left out by the closure give no arguments; several arguments per key are kept in order
+    let args = reader
+        .to_args("ffmpeg", |section, key, value| if key == "crf" { vec![] } else { vec![format!("[{}]", section), value.to_string()] })
+        .unwrap();
+    assert_eq!(args, vec!["[ffmpeg]", "in.mp4"]);
+    assert!(reader.to_args("missing", long_flag).unwrap().is_empty());
+}
+
+#[test]
+fn test_to_args_booleans_and_empty_values() {
+    let data = "[tool]\nverbose = yes\nquiet = off\nthreads = 0\nlevel = 1\nlabel =\n";
+    let reader = IniReader::from_string(data).unwrap();
+
+    assert_eq!(reader.to_args("tool", long_flag).unwrap(), vec!["--verbose", "--threads=0", "--level=1", "--label="]);
+    assert_eq!(reader.to_args("tool", skip_empty(long_flag)).unwrap(), vec!["--verbose", "--threads=0", "--level=1"]);
+    assert_eq!(
+        reader.to_args("tool", skip_empty(key_value_pair)).unwrap(),
+        vec!["-verbose", "-threads", "0", "-level", "1"]
+    );
+}
+
+#[test]
+fn test_to_args_rejects_nul() {
+    let mut reader = IniReader::from_string("[tool]\nname = a\n").unwrap();
+    reader.set("tool", "name", "a\0b");
+
+    let error = reader.to_args("tool", long_flag).unwrap_err();
+    assert_eq!(error, ArgsError::NulByte { section: "tool".to_string(), key: "name".to_string() });
+    assert_eq!(error.to_string(), "Argument for 'name' in [tool] contains a NUL byte");
+}
+
+#[test]
+fn test_args_shell_join() {
+    let reader = IniReader::from_string("[tool]\ntitle = it's \"big\" now\nout = /tmp/a.txt\n").unwrap();
+    let args = reader.to_args("tool", long_flag).unwrap();
+
+    // The arguments themselves are never quoted
+    assert_eq!(args, vec!["--title=it's \"big\" now", "--out=/tmp/a.txt"]);
+    assert_eq!(shell_join(&args), "--title='it'\\''s \"big\" now' --out=/tmp/a.txt");
+    assert_eq!(shell_join(&["a b", "", "-x", "$HOME"]), "'a b' '' -x '$HOME'");
+
+    let mut command = std::process::Command::new("true");
+    command.args(&args);
+    assert_eq!(command.get_args().count(), 2);
+}
+
+#[test]
+fn test_section_maps() {
+    let reader = IniReader::from_string("top = 1\n[DB]\nHost = db.local\nport = 5432\n[empty]\n[db]\nsslmode = require\n").unwrap();
+
+    let map = reader.section_map("db");
+    assert_eq!(map.len(), 3);
+    assert_eq!(map["host"], "db.local");
+    assert_eq!(map["sslmode"], "require");
+    let sorted: Vec<(String, String)> = reader.section_btree("Db").into_iter().collect();
+    assert_eq!(sorted, [("host", "db.local"), ("port", "5432"), ("sslmode", "require")].map(|(k, v)| (k.to_string(), v.to_string())));
+    assert_eq!(reader.section_map("")["top"], "1");
+
+    // Declared without values and absent look the same; has_section tells them apart
+    assert!(reader.section_map("empty").is_empty());
+    assert!(reader.section_btree("missing").is_empty());
+    assert!(reader.has_section("empty") && !reader.has_section("missing"));
+}
+
+#[test]
+fn test_summary_snapshot() {
+    let blob = "x".repeat(1229);
+    let data = format!("name = demo\n[database]\nhost = db.local\npassword = hunter2\n[motd]\ntext = {}\n", blob);
+    let parse_options = inih::ParseOptions { max_line: 2000, ..inih::ParseOptions::default() };
+    let reader = IniReader::from_string_with_options(&data, &parse_options).unwrap();
+
+    let options = SummaryOptions { max_value_len: 8, redact_patterns: vec!["*password*".to_string()], ..SummaryOptions::default() };
+    let summary = reader.summary_with(&options);
+
+    assert_eq!(summary, "\
+name = demo
+[database]
+host = db.local
+password = <redacted>
+[motd]
+text = xxxxxxxx…(1.2 KiB)
+");
+}
+
+#[test]
+fn test_summary_document_order() {
+    let data = "[zeta]\nb = 2\na = 1\n[alpha]\nkey = multi\n  line\n";
+    let options = inih::ParseOptions { allow_multiline: true, ..inih::ParseOptions::default() };
+    let reader = IniReader::from_string_preserving(data, &options).unwrap();
+
+    let sorted = reader.summary(80);
+    let ordered = reader.summary_with(&SummaryOptions { document_order: true, ..SummaryOptions::default() });
+
+    assert_eq!(sorted, "[alpha]\nkey = multi\\n  line\n[zeta]\na = 1\nb = 2\n");
+    assert_eq!(ordered, "[zeta]\nb = 2\na = 1\n[alpha]\nkey = multi\\n  line\n");
+}
+
+#[test]
+fn test_canonical_round_trip() {
+    let data = "zeta = last\n[Web]\nPort = 80\nbanner = a\\tb\n[empty]\n[app]\nmotd = first\n  second\tcol\nname = demo\n";
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    let reader = IniReader::from_string_with_options(data, &options).unwrap();
+
+    let canonical = reader.to_canonical_string();
+    assert_eq!(
+        canonical,
+        "\tzeta\tlast\napp\tmotd\tfirst\\n  second\\tcol\napp\tname\tdemo\nempty\nWeb\tbanner\ta\\\\tb\nWeb\tport\t80\n"
+    );
+
+    let reread = IniReader::from_canonical_string(&canonical).unwrap();
+    assert_eq!(reread.get("app", "motd", ""), "first\n  second\tcol");
+    assert_eq!(reread.get("web", "banner", ""), "a\\tb");
+    assert!(reread.has_section("empty"));
+    assert_eq!(reread.to_canonical_string(), canonical);
+}
+
+#[test]
+fn test_canonical_errors() {
+    assert_eq!(
+        IniReader::from_canonical_string("a\tb\tc\na\tb\n").unwrap_err(),
+        IniParseError::ParseError { line: 2, message: "Expected 1 or 3 tab-separated fields".to_string(), suggestion: None }
+    );
+    assert_eq!(
+        IniReader::from_canonical_string("a\tb\tc\\q\n").unwrap_err(),
+        IniParseError::ParseError { line: 1, message: "Invalid escape sequence".to_string(), suggestion: None }
+    );
+}
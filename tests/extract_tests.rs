@@ -0,0 +1,102 @@
+//! Tests for batch extraction with error accumulation (`Extractor`)
+
+use inih::error::FieldError;
+use inih::extract::Extractor;
+use inih::{IniReader, SectionName};
+
+const SETTINGS: &str = "[server]\nhost = example.com\nport = 70000\nworkers = many\ndebug = yes\nratio = 0.5\n";
+
+#[test]
+fn test_two_invalid_fields_both_reported() {
+    let reader = IniReader::from_string(SETTINGS).unwrap();
+    let ex = Extractor::new(&reader);
+    let host = ex.string("server", "host").non_empty();
+    let port = ex.integer("server", "port").range(1..=65535);
+    let workers = ex.unsigned("server", "workers").range(1..=64);
+    let debug = ex.boolean("server", "debug");
+
+    assert!(host.is_valid() && debug.is_valid());
+    assert!(!port.is_valid() && !workers.is_valid());
+    let errors = ex.finish().unwrap_err();
+    assert_eq!(
+        errors,
+        [
+            FieldError {
+                section: "server".to_string(),
+                name: "port".to_string(),
+                value: Some("70000".to_string()),
+                line: Some(3),
+                constraint: "expected 1..=65535".to_string(),
+            },
+            FieldError {
+                section: "server".to_string(),
+                name: "workers".to_string(),
+                value: Some("many".to_string()),
+                line: Some(4),
+                constraint: "expected unsigned integer".to_string(),
+            },
+        ]
+    );
+    assert_eq!(errors[1].to_string(), "line 4: invalid value \"many\" for server.workers: expected unsigned integer");
+}
+
+#[test]
+fn test_values_after_successful_finish() {
+    let reader = IniReader::from_string(SETTINGS).unwrap();
+    let ex = Extractor::new(&reader);
+    let host = ex.string("server", "host").non_empty();
+    let ratio = ex.real("server", "ratio").range(0.0..=1.0);
+    let addr = ex.parse::<std::net::IpAddr>("server", "bind");
+
+    // A missing key is an error as well
+    assert_eq!(ex.finish().unwrap_err()[0].to_string(), "missing key server.bind");
+    assert!(!addr.is_valid());
+
+    let ex = Extractor::new(&reader);
+    let port = ex.integer("server", "port").range(1..=99999);
+    let domain = ex.string("server", "host").check("expected a domain name", |host| host.contains('.'));
+    ex.finish().unwrap();
+    assert_eq!((host.get(), ratio.get(), port.get(), domain.get()), ("example.com".to_string(), 0.5, 70000, "example.com".to_string()));
+}
+
+#[test]
+fn test_first_failed_check_only() {
+    let mut reader = IniReader::from_string("[a]\nname =   \n").unwrap();
+    reader.set("a", "level", "-3");
+    let ex = Extractor::new(&reader);
+    ex.string("a", "name").non_empty().check("expected lowercase", |name| name == &name.to_lowercase());
+    ex.integer("a", "level").range(0..=10).check("expected even", |level| level % 2 == 0);
+
+    let errors = ex.finish().unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].constraint, "expected non-empty value");
+    assert_eq!(errors[0].line, Some(2));
+    // Values that were set rather than read have no line
+    assert_eq!(errors[1].to_string(), "invalid value \"-3\" for a.level: expected 0..=10");
+}
+
+#[test]
+#[should_panic(expected = "a.b is invalid")]
+fn test_get_of_invalid_field_panics() {
+    let reader = IniReader::from_string("[a]\nb = x\n").unwrap();
+    let ex = Extractor::new(&reader);
+    ex.integer("a", "b").get();
+}
+
+#[test]
+fn test_field_name_types() {
+    struct Server;
+    impl SectionName for Server {
+        fn section_name(&self) -> &str {
+            "server"
+        }
+    }
+
+    let reader = IniReader::from_string(SETTINGS).unwrap();
+    let ex = Extractor::new(&reader);
+    let section = String::from("Server");
+    assert_eq!(ex.string(&section, String::from("HOST")).get(), "example.com");
+    assert_eq!(ex.real(Server, "ratio").get(), 0.5);
+    assert!(!ex.integer(Server, "port").range(1..=65535).is_valid());
+    assert_eq!(ex.finish().unwrap_err()[0].line, Some(3));
+}
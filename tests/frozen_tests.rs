@@ -0,0 +1,85 @@
+//! Tests for freezing a reader so it cannot be changed
+
+use std::sync::Arc;
+use std::thread;
+
+use inih::frozen::FrozenIniReader;
+use inih::layered::LayeredReader;
+use inih::{IniReader, InsertDecision, ParseOptions, ReaderOptions};
+
+const DATA: &str = "[server]\nhost = example.com\nport = 8080\n";
+
+#[test]
+fn test_frozen_reader_reads() {
+    let config = IniReader::from_string(DATA).unwrap().freeze();
+    assert_eq!(config.get("server", "host", ""), "example.com");
+    assert_eq!(config.get_integer("Server", "PORT", 0), 8080);
+    assert_eq!(config.sections(), ["server"]);
+    assert_eq!(config.to_string(), IniReader::from_string(DATA).unwrap().to_string());
+}
+
+#[test]
+fn test_thaw_allows_changes() {
+    let config = IniReader::from_string(DATA).unwrap().freeze();
+    let mut reader = config.thaw();
+    reader.set("server", "port", "9090");
+    assert!(reader.remove("server", "host").is_some());
+    assert_eq!(reader.get_integer("server", "port", 0), 9090);
+    assert!(!reader.has_value("server", "host"));
+}
+
+#[test]
+fn test_snapshot_ignores_later_changes() {
+    let mut reader = IniReader::from_string(DATA).unwrap();
+    let snapshot = reader.snapshot();
+    assert_eq!(snapshot.shared_storage_count(), 2);
+
+    reader.set("server", "port", "9090");
+    reader.remove_section("server");
+    assert_eq!(snapshot.get_integer("server", "port", 0), 8080);
+    assert_eq!(snapshot.get("server", "host", ""), "example.com");
+    assert_eq!(snapshot.shared_storage_count(), 1);
+}
+
+#[test]
+fn test_thawed_copy_does_not_change_others() {
+    let config = IniReader::from_string(DATA).unwrap().freeze();
+    let mut copy = config.clone().thaw();
+    copy.set("server", "port", "1");
+    assert_eq!(config.get_integer("server", "port", 0), 8080);
+}
+
+#[test]
+fn test_frozen_shared_across_threads() {
+    let config = Arc::new(IniReader::from_string(DATA).unwrap().freeze());
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let config = Arc::clone(&config);
+            thread::spawn(move || config.get_integer("server", "port", 0))
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), 8080);
+    }
+}
+
+#[test]
+fn test_frozen_layers() {
+    let file = IniReader::from_string(DATA).unwrap().freeze();
+    let env = FrozenIniReader::from(IniReader::from_string("[server]\nport = 9090\n").unwrap());
+    let layered = LayeredReader::new(vec![&*file, &*env]);
+    assert_eq!(layered.get_integer("server", "port", 0), 9090);
+    assert_eq!(layered.get("server", "host", ""), "example.com");
+}
+
+#[test]
+fn test_freeze_drops_insert_hook() {
+    let on_insert = Box::new(|_: &str, _: &str, value: &str| InsertDecision::AcceptRewritten(value.to_uppercase()));
+    let options = ReaderOptions { on_insert: Some(on_insert), ..ReaderOptions::default() };
+    let reader = IniReader::from_string_with_reader_options(DATA, &ParseOptions::default(), options).unwrap();
+
+    let mut reader = reader.freeze().thaw();
+    assert_eq!(reader.get("server", "host", ""), "EXAMPLE.COM");
+    reader.set("server", "host", "lower");
+    assert_eq!(reader.get("server", "host", ""), "lower");
+}
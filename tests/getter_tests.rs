@@ -0,0 +1,585 @@
+//! Tests for the typed and bounded getters of `IniReader`
+
+use inih::prelude::*;
+
+const LIMITS: &str = "[pool]\nlow = 0\nhigh = 100\nok = 8\nratio = 1.5\nbad = lots\n";
+
+fn reader() -> IniReader {
+    IniReader::from_string(LIMITS).unwrap()
+}
+
+#[test]
+fn test_clamped_in_range() {
+    let reader = reader();
+    assert_eq!(reader.get_integer_clamped("pool", "ok", 4, 1..=64), 8);
+    assert_eq!(reader.get_unsigned_clamped("pool", "ok", 4, 1..=64), 8);
+    assert_eq!(reader.get_real_clamped("pool", "ratio", 1.0, 0.0..=2.0), 1.5);
+    assert!(reader.warnings().is_empty());
+}
+
+#[test]
+fn test_clamped_below_and_above_range() {
+    let reader = reader();
+    assert_eq!(reader.get_integer_clamped("pool", "low", 4, 1..=64), 1);
+    assert_eq!(reader.get_unsigned_clamped("pool", "high", 4, 1..=64), 64);
+    assert_eq!(reader.get_real_clamped("pool", "ratio", 1.0, 0.0..=1.0), 1.0);
+
+    let warnings = reader.warnings();
+    assert_eq!(warnings.len(), 3);
+    assert_eq!(warnings[0].line, Some(2));
+    assert_eq!((warnings[0].section.as_str(), warnings[0].name.as_str()), ("pool", "low"));
+    assert_eq!(warnings[0].to_string(), "line 2: value 0 for pool.low is outside 1..=64, clamped to 1");
+    assert_eq!(warnings[1].to_string(), "line 3: value 100 for pool.high is outside 1..=64, clamped to 64");
+}
+
+#[test]
+fn test_clamped_missing_or_invalid_uses_default() {
+    let reader = reader();
+    assert_eq!(reader.get_integer_clamped("pool", "missing", 4, 1..=64), 4);
+    assert_eq!(reader.get_integer_clamped("pool", "bad", 4, 1..=64), 4);
+    assert!(reader.warnings().is_empty());
+}
+
+#[test]
+fn test_try_get_in_range() {
+    let reader = reader();
+    assert_eq!(reader.try_get_integer_in("pool", "ok", 1..=64), Ok(8));
+    assert_eq!(reader.try_get_unsigned_in("pool", "ok", 1..=64), Ok(8));
+    assert_eq!(reader.try_get_real_in("pool", "ratio", 0.5..=2.5), Ok(1.5));
+}
+
+#[test]
+fn test_try_get_in_errors() {
+    let reader = reader();
+
+    let error = reader.try_get_integer_in("pool", "high", 1..=64).unwrap_err();
+    assert_eq!(error.to_string(), "value 100 for pool.high is outside 1..=64 (line 3)");
+    let error = reader.try_get_real_in("pool", "ratio", 0.0..=1.0).unwrap_err();
+    assert_eq!(error.to_string(), "value 1.5 for pool.ratio is outside 0..=1 (line 5)");
+
+    assert_eq!(
+        reader.try_get_unsigned_in("pool", "bad", 1..=64),
+        Err(IniGetError::InvalidValue {
+            section: "pool".to_string(),
+            name: "bad".to_string(),
+            value: "lots".to_string(),
+            expected: "unsigned integer",
+        })
+    );
+    assert_eq!(
+        reader.try_get_integer_in("pool", "missing", 1..=64).unwrap_err().to_string(),
+        "missing key pool.missing"
+    );
+}
+
+#[test]
+fn test_try_get_real_boolean_and_string() {
+    let reader = IniReader::from_string("[server]\nport = abc\nratio = 1.5\ntls = yes\nname =\n").unwrap();
+    assert_eq!(reader.try_get_real("server", "ratio"), Ok(1.5));
+    assert_eq!(reader.try_get_boolean("server", "tls"), Ok(true));
+    assert_eq!(reader.try_get_string("server", "port"), Ok("abc".to_string()));
+    assert_eq!(reader.try_get_string("server", "name"), Ok(String::new()));
+
+    assert_eq!(
+        reader.try_get_integer("server", "port").unwrap_err().to_string(),
+        "invalid value \"abc\" for server.port: expected integer"
+    );
+    assert_eq!(
+        reader.try_get_real("server", "port").unwrap_err().to_string(),
+        "invalid value \"abc\" for server.port: expected real"
+    );
+    assert_eq!(
+        reader.try_get_boolean("server", "ratio"),
+        Err(IniGetError::InvalidValue {
+            section: "server".to_string(),
+            name: "ratio".to_string(),
+            value: "1.5".to_string(),
+            expected: "boolean",
+        })
+    );
+    assert!(matches!(reader.try_get_boolean("server", "name"), Err(IniGetError::Empty { .. })));
+    assert_eq!(
+        reader.try_get_string("server", "host"),
+        Err(IniGetError::MissingKey { section: "server".to_string(), name: "host".to_string() })
+    );
+    assert_eq!(reader.try_get_real("server", "host").unwrap_err().to_string(), "missing key server.host");
+}
+
+#[test]
+fn test_get_and_parse_errors_box_together() {
+    fn port(data: &str) -> Result<i64, Box<dyn std::error::Error + Send + Sync>> {
+        let reader = IniReader::from_string(data)?;
+        Ok(reader.try_get_integer("server", "port")?)
+    }
+
+    assert_eq!(port("[server]\nport = 8080\n").unwrap(), 8080);
+    let error = port("[server]\nport = abc\n").unwrap_err();
+    assert_eq!(error.to_string(), "invalid value \"abc\" for server.port: expected integer");
+    assert!(error.downcast_ref::<IniGetError>().is_some());
+    let error = port("[server\nport = 8080\n").unwrap_err();
+    assert!(error.downcast_ref::<IniParseError>().is_some());
+}
+
+#[derive(Debug, PartialEq)]
+enum LogLevel {
+    Debug,
+    Info,
+}
+
+impl std::str::FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            other => Err(format!("unknown log level {:?}", other)),
+        }
+    }
+}
+
+#[test]
+fn test_get_parse_custom_types() {
+    let data = "[log]\nlevel = debug\nother = info\nbad = loud\n[net]\nlisten = 127.0.0.1:8080\n";
+    let reader = IniReader::from_string(data).unwrap();
+    assert_eq!(reader.get_parse("log", "level"), Some(LogLevel::Debug));
+    assert_eq!(reader.try_get_parse::<LogLevel>("log", "other"), Ok(LogLevel::Info));
+    assert_eq!(reader.get_parse::<LogLevel>("log", "bad"), None);
+    assert_eq!(reader.get_parse::<LogLevel>("log", "missing"), None);
+
+    let listen: std::net::SocketAddr = reader.try_get_parse("net", "listen").unwrap();
+    assert_eq!(listen.port(), 8080);
+
+    assert_eq!(
+        reader.try_get_parse::<LogLevel>("log", "bad"),
+        Err(IniGetError::Unparsable {
+            section: "log".to_string(),
+            name: "bad".to_string(),
+            value: "loud".to_string(),
+            message: "unknown log level \"loud\"".to_string(),
+        })
+    );
+    assert_eq!(
+        reader.try_get_parse::<std::net::SocketAddr>("log", "bad").unwrap_err().to_string(),
+        "invalid value \"loud\" for log.bad: invalid socket address syntax"
+    );
+    assert_eq!(reader.try_get_parse::<u16>("net", "port").unwrap_err().to_string(), "missing key net.port");
+}
+
+#[test]
+fn test_get_parse_trims_and_leaves_typed_getters_alone() {
+    let options = ParseOptions { trim_values: false, ..ParseOptions::default() };
+    let reader = IniReader::from_string_with_options("[v]\nn= 42 \nhex=0x10\nflag=yes\n", &options).unwrap();
+    assert_eq!(reader.get_parse::<i64>("v", "n"), Some(42));
+    assert_eq!(reader.try_get_parse::<u8>("v", "n"), Ok(42));
+
+    // The typed getters keep their own syntax
+    assert_eq!(reader.get_integer("v", "hex", 0), 16);
+    assert_eq!(reader.get_parse::<i64>("v", "hex"), None);
+    assert_eq!(
+        reader.try_get_parse::<i64>("v", "hex").unwrap_err().to_string(),
+        "invalid value \"0x10\" for v.hex: invalid digit found in string"
+    );
+    assert!(reader.get_boolean("v", "flag", false));
+    assert_eq!(reader.get_parse::<bool>("v", "flag"), None);
+    assert_eq!(reader.get_parse::<String>("v", "flag"), Some("yes".to_string()));
+}
+
+const REPEATED: &str = "[mirror]\nurl = a\nurl = b\n[other]\nurl = x\n[mirror]\nURL = c\n  continued\nurl = d\n";
+
+#[test]
+fn test_occurrences_across_section_blocks() {
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    let reader = IniReader::from_string_with_options(REPEATED, &options).unwrap();
+
+    let occurrences = reader.occurrences("mirror", "url");
+    let summary: Vec<_> = occurrences.iter().map(|o| (o.value.as_str(), o.line)).collect();
+    assert_eq!(summary, vec![("a", 2), ("b", 3), ("c\n  continued", 7), ("d", 9)]);
+
+    assert_eq!(reader.get_all("mirror", "url"), vec!["a", "b", "c\n  continued", "d"]);
+    assert_eq!(reader.get_nth("mirror", "url", 1), Some("b"));
+    assert_eq!(reader.get_nth("MIRROR", "Url", 2), Some("c\n  continued"));
+    assert_eq!(reader.get_nth("mirror", "url", 4), None);
+    assert_eq!(reader.get_nth("other", "url", 0), Some("x"));
+    assert_eq!(reader.get("mirror", "url", ""), "a\nb\nc\n  continued\nd");
+}
+
+#[test]
+fn test_occurrences_first_wins_keeps_only_first() {
+    let options = ParseOptions { duplicate_key_policy: DuplicateKeyPolicy::FirstWins, ..ParseOptions::default() };
+    let reader = IniReader::from_string_with_options(REPEATED, &options).unwrap();
+
+    assert_eq!(reader.get_all("mirror", "url"), vec!["a"]);
+    assert!(reader.occurrences("mirror", "missing").is_empty());
+}
+
+const CHAIN: &str = "[defaults]\ntimeout = 30\nretries = 3\nverbose = no\n[Handler]\ntimeout = 10\n[handler.upload]\nchunk = 4096\n";
+
+#[test]
+fn test_get_chain() {
+    let reader = IniReader::from_string(CHAIN).unwrap();
+    let sections = ["handler.upload", "handler", "defaults"];
+
+    assert_eq!(reader.get_chain(&sections, "timeout", "0"), "10");
+    assert_eq!(reader.get_chain(&sections, "chunk", "0"), "4096");
+    assert_eq!(reader.get_chain(&sections, "missing", "none"), "none");
+}
+
+#[test]
+fn test_fallback_chain() {
+    let reader = IniReader::from_string(CHAIN).unwrap();
+    let chain = FallbackChain::new(&["handler.upload", "HANDLER", "defaults"]);
+
+    assert_eq!(chain.get_integer(&reader, "timeout", 0), 10);
+    assert_eq!(chain.resolve_origin(&reader, "timeout"), Some("HANDLER"));
+    assert_eq!(chain.get_unsigned(&reader, "retries", 0), 3);
+    assert_eq!(chain.resolve_origin(&reader, "retries"), Some("defaults"));
+    assert!(!chain.get_boolean(&reader, "verbose", true));
+
+    assert_eq!(chain.get_real(&reader, "missing", 1.5), 1.5);
+    assert_eq!(chain.get_nonempty_or(&reader, "missing", "dflt"), "dflt");
+    assert_eq!(chain.resolve_origin(&reader, "missing"), None);
+}
+
+#[derive(Clone, Copy)]
+enum Section {
+    Database,
+}
+
+impl SectionName for Section {
+    fn section_name(&self) -> &str {
+        match self {
+            Section::Database => "database",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Key {
+    Host,
+    Port,
+}
+
+impl KeyName for Key {
+    fn key_name(&self) -> &str {
+        match self {
+            Key::Host => "host",
+            Key::Port => "port",
+        }
+    }
+}
+
+#[test]
+fn test_getter_name_types() {
+    use std::borrow::Cow;
+
+    let reader = IniReader::from_string("[Database]\nhost = db.local\nport = 5432\n").unwrap();
+    let section = String::from("database");
+    let name: Cow<str> = Cow::Owned("port".to_string());
+
+    assert_eq!(reader.get(&section, "host", ""), "db.local");
+    assert_eq!(reader.get_integer(section.clone(), &name, 0), 5432);
+    assert_eq!(reader.get_unsigned(Cow::Borrowed("DATABASE"), String::from("port"), 0), 5432);
+    assert!(reader.has_value(&section, name));
+
+    assert_eq!(reader.get(Section::Database, Key::Host, ""), "db.local");
+    assert_eq!(reader.get_integer(Section::Database, Key::Port, 0), 5432);
+    assert!(reader.has_value(Section::Database, Key::Port));
+    assert!(reader.has_section("Database"));
+
+    assert_eq!(reader.try_get_integer(Section::Database, Key::Port), Ok(5432));
+    assert_eq!(reader.try_get_integer_in(&section, Key::Port, 1..=65535), Ok(5432));
+    assert_eq!(reader.try_get_unsigned(Section::Database, String::from("port")), Ok(5432));
+    assert_eq!(reader.try_get_unsigned_in(Section::Database, Key::Port, 0..=1024).map_err(|e| e.to_string()).unwrap_err(), "value 5432 for database.port is outside 0..=1024 (line 3)");
+    assert_eq!(reader.get_integer_clamped(Section::Database, Key::Port, 0, 1..=1024), 1024);
+    assert_eq!(reader.get_unsigned_clamped(Section::Database, Key::Port, 0, 1..=65535), 5432);
+    assert_eq!(reader.get_real_clamped(Section::Database, Key::Port, 0.0, 0.0..=1.0), 1.0);
+
+    assert_eq!(reader.try_get_real(Section::Database, Key::Port), Ok(5432.0));
+    assert_eq!(reader.try_get_real_in(&section, Key::Port, 0.0..=1e4), Ok(5432.0));
+    assert!(reader.try_get_boolean(Section::Database, Key::Host).is_err());
+    assert_eq!(reader.try_get_string(Section::Database, Key::Host).as_deref(), Ok("db.local"));
+    assert_eq!(reader.try_get_parse::<u16>(Section::Database, Key::Port), Ok(5432));
+    assert_eq!(reader.try_get_duration(Section::Database, Key::Port), Ok(std::time::Duration::from_secs(5432)));
+}
+
+/// What every getter gives for one key, with "D"/-1/true as defaults
+#[derive(Debug, PartialEq)]
+struct Row {
+    get: String,
+    nonempty: String,
+    integer: i64,
+    unsigned: u64,
+    real: f64,
+    boolean: bool,
+    clamped: i64,
+    present: bool,
+    strict: Result<i64, &'static str>,
+}
+
+fn row(reader: &IniReader, name: &str) -> Row {
+    #[allow(deprecated)]
+    let deprecated = reader.get_string("m", name, "D");
+    let nonempty = reader.get_nonempty_or("m", name, "D");
+    assert_eq!(deprecated, nonempty);
+    assert_eq!(reader.get_integer64("m", name, -1), reader.get_integer("m", name, -1));
+    assert_eq!(reader.get_unsigned64("m", name, 7), reader.get_unsigned("m", name, 7));
+
+    Row {
+        get: reader.get("m", name, "D"),
+        nonempty,
+        integer: reader.get_integer("m", name, -1),
+        unsigned: reader.get_unsigned("m", name, 7),
+        real: reader.get_real("m", name, 0.5),
+        boolean: reader.get_boolean("m", name, true),
+        clamped: reader.get_integer_clamped("m", name, -1, 0..=10),
+        present: reader.has_value("m", name),
+        strict: reader.try_get_integer_in("m", name, 0..=10).map_err(|error| match error {
+            IniGetError::MissingKey { .. } => "missing",
+            IniGetError::Empty { .. } => "empty",
+            IniGetError::InvalidValue { .. } => "invalid",
+            IniGetError::Overflow { .. } => "overflow",
+            IniGetError::Unparsable { .. } => "unparsable",
+            IniGetError::OutOfRange { .. } => "range",
+        }),
+    }
+}
+
+#[test]
+fn test_empty_missing_and_blank_matrix() {
+    let data = "[m]\nempty =\nblank =    \nset = 3\n";
+    let trimmed = IniReader::from_string(data).unwrap();
+    let untrimmed =
+        IniReader::from_string_with_options(data, &ParseOptions { trim_values: false, ..ParseOptions::default() }).unwrap();
+
+    let defaults = |get: &str, present, strict| Row {
+        get: get.to_string(),
+        nonempty: "D".to_string(),
+        integer: -1,
+        unsigned: 7,
+        real: 0.5,
+        boolean: true,
+        clamped: -1,
+        present,
+        strict,
+    };
+    for reader in [&trimmed, &untrimmed] {
+        assert_eq!(row(reader, "missing"), defaults("D", false, Err("missing")));
+        assert_eq!(row(reader, "empty"), defaults("", true, Err("empty")));
+        assert_eq!(reader.get("m", "set", "D"), reader.get_nonempty_or("m", "set", "D"));
+        assert_eq!(reader.try_get_unsigned_in("m", "empty", 0..=10).unwrap_err().to_string(), "empty value for m.empty");
+        assert!(matches!(reader.try_get_real_in("m", "blank", 0.0..=1.0), Err(IniGetError::Empty { .. })));
+    }
+    assert_eq!(row(&trimmed, "blank"), defaults("", true, Err("empty")));
+    assert_eq!(
+        row(&untrimmed, "blank"),
+        Row { get: "    ".to_string(), nonempty: "    ".to_string(), ..defaults("", true, Err("empty")) }
+    );
+    assert!(trimmed.warnings().is_empty());
+}
+
+#[test]
+fn test_nonempty_or_in_chains_and_layers() {
+    let reader = IniReader::from_string("[a]\nname =\n[b]\nname = bob\n").unwrap();
+    let chain = FallbackChain::new(&["a", "b"]);
+    assert_eq!(chain.get(&reader, "name", "D"), "");
+    assert_eq!(chain.get_nonempty_or(&reader, "name", "D"), "D");
+
+    let layered = inih::layered::LayeredReader::new(vec![&reader]);
+    assert_eq!(layered.get("a", "name", "D"), "");
+    assert_eq!(layered.get_nonempty_or("a", "name", "D"), "D");
+    assert_eq!(layered.get_nonempty_or("b", "name", "D"), "bob");
+}
+
+const WIDE: &str = "[w]
+i64_max = 9223372036854775807
+past_i64 = 9223372036854775808
+u64_max = 18446744073709551615
+past_u64 = 18446744073709551616
+hex_i64_max = 0x7FFFFFFFFFFFFFFF
+hex_past_i64 = 0x8000000000000000
+hex_u64_max = 0xFFFFFFFFFFFFFFFF
+hex_past_u64 = 0x1_0000_0000_0000_0000
+below_i64 = -9223372036854775809
+negative = -1
+";
+
+#[test]
+fn test_integer_width_limits() {
+    let reader = IniReader::from_string(WIDE).unwrap();
+    let overflow = |name: &str, expected| IniGetError::Overflow {
+        section: "w".to_string(),
+        name: name.to_string(),
+        value: reader.get("w", name, ""),
+        expected,
+    };
+
+    assert_eq!(reader.get_integer("w", "i64_max", 0), i64::MAX);
+    assert_eq!(reader.try_get_integer("w", "i64_max"), Ok(i64::MAX));
+    assert_eq!(reader.get_integer("w", "past_i64", 0), 0);
+    assert_eq!(reader.try_get_integer("w", "past_i64"), Err(overflow("past_i64", "integer")));
+    assert_eq!(reader.try_get_integer("w", "u64_max"), Err(overflow("u64_max", "integer")));
+    assert_eq!(reader.try_get_integer("w", "below_i64"), Err(overflow("below_i64", "integer")));
+
+    // Prefixed masks are bit patterns
+    assert_eq!(reader.get_integer("w", "hex_i64_max", 0), i64::MAX);
+    assert_eq!(reader.get_integer("w", "hex_past_i64", 0), i64::MIN);
+    assert_eq!(reader.get_integer("w", "hex_u64_max", 0), -1);
+    assert_eq!(reader.try_get_integer("w", "hex_u64_max"), Ok(-1));
+    assert_eq!(reader.get_integer("w", "hex_past_u64", 0), 0);
+    assert_eq!(reader.try_get_integer("w", "hex_past_u64"), Err(overflow("hex_past_u64", "integer")));
+
+    assert_eq!(reader.get_unsigned("w", "past_i64", 0), 1 << 63);
+    assert_eq!(reader.get_unsigned("w", "u64_max", 0), u64::MAX);
+    assert_eq!(reader.get_unsigned("w", "hex_u64_max", 0), u64::MAX);
+    assert_eq!(reader.try_get_unsigned("w", "hex_u64_max"), Ok(u64::MAX));
+    assert_eq!(reader.get_unsigned("w", "past_u64", 0), 0);
+    assert_eq!(reader.try_get_unsigned("w", "past_u64"), Err(overflow("past_u64", "unsigned integer")));
+    assert_eq!(reader.try_get_unsigned("w", "hex_past_u64"), Err(overflow("hex_past_u64", "unsigned integer")));
+    assert_eq!(reader.try_get_unsigned("w", "negative"), Err(overflow("negative", "unsigned integer")));
+
+    assert_eq!(
+        reader.try_get_integer("w", "past_u64").unwrap_err().to_string(),
+        "value 18446744073709551616 for w.past_u64 does not fit in a 64-bit integer"
+    );
+    assert!(matches!(reader.try_get_integer_in("w", "past_i64", 0..=10), Err(IniGetError::Overflow { .. })));
+    assert!(matches!(reader.try_get_integer("pool", "bad"), Err(IniGetError::MissingKey { .. })));
+    assert!(matches!(IniReader::from_string(LIMITS).unwrap().try_get_integer("pool", "bad"), Err(IniGetError::InvalidValue { .. })));
+}
+
+#[test]
+fn test_opt_getters_tell_missing_from_malformed() {
+    let data = "[db]\nport=5432\nratio=0.75\ntls=on\nhost=localhost\nempty=\nblank=    \nnegative=-3\n";
+    let trimmed = IniReader::from_string(data).unwrap();
+    let untrimmed =
+        IniReader::from_string_with_options(data, &ParseOptions { trim_values: false, ..ParseOptions::default() }).unwrap();
+
+    for reader in [&trimmed, &untrimmed] {
+        assert_eq!(reader.get_opt("db", "port"), Some("5432"));
+        assert_eq!(reader.get_integer_opt("DB", "Port"), Some(5432));
+        assert_eq!(reader.get_unsigned_opt("db", "port"), Some(5432));
+        assert_eq!(reader.get_real_opt("db", "ratio"), Some(0.75));
+        assert_eq!(reader.get_boolean_opt("db", "tls"), Some(true));
+
+        // Missing: None everywhere
+        assert_eq!(reader.get_opt("db", "missing"), None);
+        assert_eq!(reader.get_integer_opt("db", "missing"), None);
+        assert_eq!(reader.get_boolean_opt("nosection", "tls"), None);
+
+        // Present but malformed: the raw value is there, the typed one is not
+        assert_eq!(reader.get_opt("db", "host"), Some("localhost"));
+        assert_eq!(reader.get_integer_opt("db", "host"), None);
+        assert_eq!(reader.get_real_opt("db", "host"), None);
+        assert_eq!(reader.get_boolean_opt("db", "host"), None);
+        assert_eq!(reader.get_unsigned_opt("db", "negative"), None);
+        assert_eq!(reader.get_integer_opt("db", "negative"), Some(-3));
+
+        // Empty: present, but no typed value
+        assert_eq!(reader.get_opt("db", "empty"), Some(""));
+        assert_eq!(reader.get_integer_opt("db", "empty"), None);
+        assert_eq!(reader.get_boolean_opt("db", "empty"), None);
+        assert_eq!(reader.get_real_opt("db", "blank"), None);
+        assert_eq!(reader.get_unsigned_opt("db", "blank"), None);
+    }
+    assert_eq!(trimmed.get_opt("db", "blank"), Some(""));
+    assert_eq!(untrimmed.get_opt("db", "blank"), Some("    "));
+}
+
+#[test]
+fn test_default_getters_match_opt_getters() {
+    let reader = IniReader::from_string("[m]\nint = 12\nreal = 2.5\nflag = no\nword = abc\nempty =\n").unwrap();
+    for name in ["int", "real", "flag", "word", "empty", "missing"] {
+        assert_eq!(reader.get("m", name, "D"), reader.get_opt("m", name).unwrap_or("D"));
+        assert_eq!(reader.get_integer("m", name, -1), reader.get_integer_opt("m", name).unwrap_or(-1));
+        assert_eq!(reader.get_unsigned("m", name, 7), reader.get_unsigned_opt("m", name).unwrap_or(7));
+        assert_eq!(reader.get_real("m", name, 0.5), reader.get_real_opt("m", name).unwrap_or(0.5));
+        assert_eq!(reader.get_boolean("m", name, true), reader.get_boolean_opt("m", name).unwrap_or(true));
+    }
+    assert_eq!(reader.get_integer("m", "word", 9), 9);
+    assert!(!reader.get_boolean("m", "flag", true));
+}
+
+#[test]
+fn test_clock_durations() {
+    use std::time::Duration;
+
+    // The first ':' separates the key; the rest of the line is the value
+    let data = "[job]\ntimeout: 03:30:00\ngrace = 05:30\nspaced : 1:00:00\nodd = 1:2:3:4\n";
+    let reader = IniReader::from_string(data).unwrap();
+    assert_eq!(reader.get_opt("job", "timeout"), Some("03:30:00"));
+    assert_eq!(reader.get_duration("job", "timeout", Duration::ZERO), Duration::from_secs(12600));
+    assert_eq!(reader.get_duration("job", "grace", Duration::ZERO), Duration::from_secs(330));
+    assert_eq!(reader.get_duration("job", "spaced", Duration::ZERO), Duration::from_secs(3600));
+    assert_eq!(reader.get_duration("job", "odd", Duration::MAX), Duration::MAX);
+}
+
+#[test]
+fn test_lists() {
+    let input = "[net]\nhosts = a.example.com, b.example.com,, c.example.com\npath = /bin:/usr/bin\nwords = one  two three\nempty =\nquoted = \"a, b\", c\n";
+    let reader = IniReader::from_string(input).unwrap();
+    assert_eq!(reader.get_list("net", "hosts"), ["a.example.com", "b.example.com", "c.example.com"]);
+    assert_eq!(reader.get_list_with("net", "path", ':'), ["/bin", "/usr/bin"]);
+    assert_eq!(reader.get_list_with("net", "words", ' '), ["one", "two", "three"]);
+    assert!(reader.get_list("net", "empty").is_empty());
+    assert!(reader.get_list_with("net", "missing", ';').is_empty());
+    // Quotes are not interpreted yet
+    assert_eq!(reader.get_list("net", "quoted"), ["\"a", "b\"", "c"]);
+}
+
+#[test]
+fn test_typed_lists_skip_invalid_items() {
+    let reader = IniReader::from_string("[n]\nports = 80, 0x1BB, http, 8080\nratios = 0.5,1e3,,x\n").unwrap();
+    assert_eq!(reader.get_integer_list("n", "ports"), [80, 443, 8080]);
+    assert_eq!(reader.get_real_list("n", "ratios"), [0.5, 1000.0]);
+    assert!(reader.get_integer_list("n", "missing").is_empty());
+
+    let warnings = reader.warnings();
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(warnings[0].line, Some(2));
+    assert_eq!(warnings[0].message, "item 'http' of n.ports is not an integer, skipped");
+    assert_eq!(warnings[1].message, "item 'x' of n.ratios is not a number, skipped");
+}
+
+#[test]
+fn test_multiline_lists_and_lines() {
+    let options = ParseOptions { allow_multiline: true, duplicate_key_policy: DuplicateKeyPolicy::LastWins, ..ParseOptions::default() };
+    let reader = IniReader::from_string_with_options("[s]\nhosts = a, b\n  c\n\nmotd = line1\n  line2, still line2\n", &options).unwrap();
+    assert_eq!(reader.get_list("s", "hosts"), ["a", "b", "c"]);
+    assert_eq!(reader.get_lines("s", "motd"), ["line1", "line2, still line2"]);
+    assert_eq!(reader.get_lines("s", "hosts"), ["a, b", "c"]);
+    assert!(reader.get_lines("s", "missing").is_empty());
+}
+
+#[test]
+fn test_duration_units() {
+    use std::time::Duration;
+
+    let input = "[t]\nms = 250ms\ns = 30s\nm = 5m\nh = 2h\nd = 7d\nbare = 45\nspaced = 10 m\nfraction = 2.5s\ncompound = 1h30m\nnegative = -5s\nbad = soon\nhuge = 999999999999999h\nhuger = 99999999999999999999999d\nempty =\n";
+    let reader = IniReader::from_string(input).unwrap();
+    let cases = [
+        ("ms", Duration::from_millis(250)),
+        ("s", Duration::from_secs(30)),
+        ("m", Duration::from_secs(300)),
+        ("h", Duration::from_secs(7200)),
+        ("d", Duration::from_secs(7 * 86400)),
+        ("bare", Duration::from_secs(45)),
+        ("spaced", Duration::from_secs(600)),
+        ("fraction", Duration::from_millis(2500)),
+        ("compound", Duration::from_secs(5400)),
+    ];
+    for (name, expected) in cases {
+        assert_eq!(reader.get_duration("t", name, Duration::ZERO), expected, "{}", name);
+        assert_eq!(reader.try_get_duration("t", name), Ok(expected), "{}", name);
+    }
+
+    // Overflow is handled without panicking: rejected once it no longer fits
+    assert_eq!(reader.try_get_duration("t", "huge").map(|d| d.as_secs() / 3600), Ok(999_999_999_999_999));
+    let default = Duration::from_secs(1);
+    for name in ["negative", "bad", "huger", "empty", "missing"] {
+        assert_eq!(reader.get_duration("t", name, default), default, "{}", name);
+        assert!(reader.try_get_duration("t", name).is_err(), "{}", name);
+    }
+    assert!(matches!(reader.try_get_duration("t", "missing"), Err(IniGetError::MissingKey { .. })));
+    assert!(matches!(reader.try_get_duration("t", "empty"), Err(IniGetError::Empty { .. })));
+    assert_eq!(reader.try_get_duration("t", "bad").unwrap_err().to_string(), "invalid value \"soon\" for t.bad: expected duration");
+}
@@ -0,0 +1,470 @@
+//! Tests for the handler adapters shipped with the crate
+
+use std::collections::BTreeMap;
+
+use inih::handlers::{
+    BTreeHandler, BatchingHandler, JsonWriterHandler, MapHandler, SectionFilter, SectionRouter, VecHandler, WriterHandler,
+};
+use inih::tokens::IniTokens;
+use inih::writer::WriteOptions;
+use inih::{ini_parse_string, ini_parse_string_with_options, EntryInfo, IniHandler, IniParseError, ParseOptions};
+
+const FIVE_ENTRIES: &str = r#"
+[section1]
+key1 = value1
+key2 = value2
+
+[section2]
+key3 = value3
+key4 = value4
+key5 = value5
+"#;
+
+#[test]
+fn test_batching_handler_batches() {
+    let mut batches: Vec<Vec<(String, String, String)>> = Vec::new();
+    let mut handler = BatchingHandler::new(2, |batch: &[(String, String, String)]| {
+        batches.push(batch.to_vec());
+        Ok(())
+    });
+    ini_parse_string(FIVE_ENTRIES, &mut handler).unwrap();
+    drop(handler);
+
+    assert_eq!(batches.len(), 3);
+    assert_eq!(batches[0].len(), 2);
+    assert_eq!(batches[1].len(), 2);
+    assert_eq!(batches[1][0], ("section2".to_string(), "key3".to_string(), "value3".to_string()));
+    assert_eq!(batches[2], vec![("section2".to_string(), "key5".to_string(), "value5".to_string())]);
+}
+
+#[test]
+fn test_batching_handler_flush_on_section_change() {
+    let mut sizes = Vec::new();
+    let mut handler = BatchingHandler::new(2, |batch: &[(String, String, String)]| {
+        sizes.push(batch.len());
+        Ok(())
+    })
+    .flush_on_section_change(true);
+    ini_parse_string("[a]\nx=1\n[b]\ny=2\nz=3\n", &mut handler).unwrap();
+    drop(handler);
+
+    assert_eq!(sizes, vec![1, 2]);
+}
+
+#[test]
+fn test_batching_handler_error_line() {
+    let mut handler = BatchingHandler::new(2, |batch: &[(String, String, String)]| {
+        if batch.iter().any(|(_, name, _)| name == "key3") {
+            Err("insert failed".to_string())
+        } else {
+            Ok(())
+        }
+    });
+    let result = ini_parse_string(FIVE_ENTRIES, &mut handler);
+
+    assert_eq!(
+        result,
+        Err(IniParseError::RejectedEntry {
+            line: 8,
+            raw_line: "key4 = value4".to_string(),
+            message: "batch starting at line 7: insert failed".to_string(),
+        })
+    );
+}
+
+#[derive(Debug, Default)]
+struct Collector {
+    entries: Vec<(String, String)>,
+    reject: Option<String>,
+}
+
+impl IniHandler for Collector {
+    fn handle(&mut self, _section: &str, name: &str, value: &str) -> Result<(), String> {
+        if self.reject.as_deref() == Some(name) {
+            return Err(format!("invalid {}", name));
+        }
+        self.entries.push((name.to_string(), value.to_string()));
+        Ok(())
+    }
+}
+
+const ROUTED: &str = r#"[Database]
+host = db.local
+port = 5432
+
+[server]
+port = 80
+threads = many
+
+[cache]
+ttl = 60
+"#;
+
+#[test]
+fn test_section_router_dispatch() {
+    let mut database = Collector::default();
+    let mut server = Collector::default();
+    let mut fallback = Collector::default();
+
+    let mut router = SectionRouter::new()
+        .route("database", &mut database)
+        .route("server", &mut server)
+        .route_default(&mut fallback);
+    ini_parse_string(ROUTED, &mut router).unwrap();
+    assert!(router.warnings().is_empty());
+    drop(router);
+
+    assert_eq!(database.entries.len(), 2);
+    assert_eq!(server.entries, vec![("port".to_string(), "80".to_string()), ("threads".to_string(), "many".to_string())]);
+    assert_eq!(fallback.entries, vec![("ttl".to_string(), "60".to_string())]);
+}
+
+#[test]
+fn test_section_router_errors_and_warnings() {
+    let mut database = Collector::default();
+    let mut server = Collector { reject: Some("threads".to_string()), ..Collector::default() };
+
+    let mut router = SectionRouter::new()
+        .route("database", &mut database)
+        .route("server", &mut server);
+    let result = ini_parse_string(ROUTED, &mut router);
+
+    assert_eq!(
+        result,
+        Err(IniParseError::RejectedEntry {
+            line: 7,
+            raw_line: "threads = many".to_string(),
+            message: "[server] line 7: invalid threads".to_string(),
+        })
+    );
+    assert_eq!(router.errors().len(), 1);
+    assert_eq!(router.errors()[0].section, "server");
+    assert_eq!(router.errors()[0].line, 7);
+    assert_eq!(router.warnings(), &["No handler for section 'cache'".to_string()]);
+}
+
+#[test]
+fn test_section_router_case_sensitive() {
+    let mut database = Collector::default();
+
+    let mut router = SectionRouter::new().case_sensitive(true).route("database", &mut database);
+    ini_parse_string(ROUTED, &mut router).unwrap();
+    assert!(router.warnings().contains(&"No handler for section 'Database'".to_string()));
+    drop(router);
+
+    assert!(database.entries.is_empty());
+}
+
+const INTERLEAVED: &str = "top = 1\n[a]\nx = 1\n[b]\ny = 2\n[A]\nz = 3\n";
+
+#[test]
+fn test_writer_handler_streaming() {
+    let mut writer = WriterHandler::new(Vec::new());
+    ini_parse_string(INTERLEAVED, &mut writer).unwrap();
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+
+    assert_eq!(output, "top = 1\n\n[a]\nx = 1\n\n[b]\ny = 2\n\n[A]\nz = 3\n");
+}
+
+#[test]
+fn test_writer_handler_grouping() {
+    let mut writer = WriterHandler::new(Vec::new()).group_sections(true);
+    ini_parse_string(INTERLEAVED, &mut writer).unwrap();
+    assert_eq!(writer.buffered_bytes(), 0);
+    assert_eq!(writer.peak_buffered_bytes(), "top = 1\nx = 1\ny = 2\nz = 3\n".len());
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+
+    assert_eq!(output, "top = 1\n\n[a]\nx = 1\nz = 3\n\n[b]\ny = 2\n");
+}
+
+#[test]
+fn test_writer_handler_grouping_two_documents() {
+    let mut writer = WriterHandler::new(Vec::new()).group_sections(true);
+    ini_parse_string("top = 1\n[a]\nx = 1\n", &mut writer).unwrap();
+    ini_parse_string("[b]\ny = 2\n[A]\nz = 3\n", &mut writer).unwrap();
+    assert_eq!(writer.buffered_bytes(), 0);
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+
+    assert_eq!(output, "top = 1\n\n[a]\nx = 1\n\n[b]\ny = 2\n\n[A]\nz = 3\n");
+}
+
+#[test]
+fn test_writer_handler_buffer_limit() {
+    let mut writer = WriterHandler::new(Vec::new()).group_sections(true).buffer_limit(16);
+    let result = ini_parse_string(INTERLEAVED, &mut writer);
+
+    assert!(matches!(
+        result,
+        Err(IniParseError::RejectedEntry { line: 5, message, .. }) if message == "grouping buffer exceeded 16 bytes"
+    ));
+}
+
+#[test]
+fn test_section_filter() {
+    let mut collector = Collector::default();
+
+    let mut filter = SectionFilter::new(&mut collector, &["SERVER"]).record_skipped(true);
+    ini_parse_string(ROUTED, &mut filter).unwrap();
+    assert_eq!(filter.skipped_sections(), &["Database".to_string(), "cache".to_string()]);
+    drop(filter);
+
+    assert_eq!(collector.entries, vec![("port".to_string(), "80".to_string()), ("threads".to_string(), "many".to_string())]);
+}
+
+#[test]
+fn test_section_filter_without_recording() {
+    let mut collector = Collector::default();
+
+    let mut filter = SectionFilter::new(&mut collector, &["database"]);
+    ini_parse_string(ROUTED, &mut filter).unwrap();
+    assert!(filter.skipped_sections().is_empty());
+    drop(filter);
+
+    assert_eq!(collector.entries.len(), 2);
+}
+
+#[test]
+fn test_section_filter_folding() {
+    let data = "[Ärger]\na = 1\n[äRGER]\nb = 2\n[Keep]\nc = 3\n";
+
+    let mut collector = Collector::default();
+    let mut filter = SectionFilter::new(&mut collector, &["keep"]).record_skipped(true);
+    ini_parse_string(data, &mut filter).unwrap();
+    assert_eq!(filter.skipped_sections(), &["Ärger".to_string()]);
+    drop(filter);
+    assert_eq!(collector.entries, vec![("c".to_string(), "3".to_string())]);
+
+    let mut collector = Collector::default();
+    let mut filter = SectionFilter::new(&mut collector, &["keep"]).case_sensitive(true).record_skipped(true);
+    ini_parse_string(data, &mut filter).unwrap();
+    assert_eq!(filter.skipped_sections(), &["Ärger".to_string(), "äRGER".to_string(), "Keep".to_string()]);
+    drop(filter);
+    assert!(collector.entries.is_empty());
+}
+
+#[test]
+fn test_writer_handler_bare_keys() {
+    let options = ParseOptions { allow_no_value: true, ..ParseOptions::default() };
+    let data = "[flags]\nverbose\nlevel =\n";
+
+    let mut writer = WriterHandler::new(Vec::new());
+    ini_parse_string_with_options(data, &mut writer, &options).unwrap();
+    assert_eq!(String::from_utf8(writer.into_inner()).unwrap(), "[flags]\nverbose\nlevel = \n");
+
+    let normalize = WriteOptions { normalize_bare_keys: true, ..WriteOptions::default() };
+    let mut writer = WriterHandler::new(Vec::new()).write_options(normalize);
+    ini_parse_string_with_options(data, &mut writer, &options).unwrap();
+    assert_eq!(String::from_utf8(writer.into_inner()).unwrap(), "[flags]\nverbose = \nlevel = \n");
+}
+
+fn parse_multi_line(handler: &mut dyn IniHandler) {
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    ini_parse_string_with_options(include_str!("multi_line.ini"), handler, &options).unwrap();
+}
+
+fn entry(section: &str, name: &str, value: &str) -> (String, String, String) {
+    (section.to_string(), name.to_string(), value.to_string())
+}
+
+#[test]
+fn test_vec_handler_shape() {
+    let mut handler = VecHandler::new();
+    parse_multi_line(&mut handler);
+
+    let entries = handler.into_entries();
+    assert_eq!(entries.len(), 8);
+    assert_eq!(entries[0], entry("section1", "single1", "abc"));
+    assert_eq!(entries[1], entry("section1", "multi", "this is a\n        multi-line value"));
+    assert_eq!(entries[3], entry("section2", "multi", "a\n        b\n        c"));
+    assert_eq!(entries[7], entry("section3", "foo", "bar\n      Hi World"));
+}
+
+#[test]
+fn test_map_handlers_shape() {
+    let mut map = MapHandler::new();
+    parse_multi_line(&mut map);
+    let mut btree = BTreeHandler::new();
+    parse_multi_line(&mut btree);
+
+    let map = map.into_map();
+    assert_eq!(map.len(), 3);
+    assert_eq!(map["section2"]["multi"], "a\n        b\n        c");
+    assert_eq!(map["section3"]["name"], "bob smith");
+
+    let btree = btree.into_map();
+    assert_eq!(btree.keys().collect::<Vec<_>>(), ["section1", "section2", "section3"]);
+    assert_eq!(btree["section3"].keys().collect::<Vec<_>>(), ["foo", "multi", "name", "single"]);
+    for (section, entries) in &btree {
+        let sorted: BTreeMap<_, _> = map[section].iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        assert_eq!(&sorted, entries);
+    }
+}
+
+#[test]
+fn test_map_handler_keeps_empty_sections_and_last_value() {
+    let mut handler = MapHandler::new();
+    ini_parse_string("[empty]\n[a]\nx = 1\nx = 2\n", &mut handler).unwrap();
+
+    assert!(handler.map()["empty"].is_empty());
+    assert_eq!(handler.map()["a"]["x"], "2");
+}
+
+#[test]
+fn test_json_writer_handler_shape() {
+    let mut handler = JsonWriterHandler::new(Vec::new());
+    parse_multi_line(&mut handler);
+
+    let json = String::from_utf8(handler.into_inner()).unwrap();
+    assert_eq!(
+        json,
+        concat!(
+            r#"{"section1":{"single1":"abc","multi":"this is a\n        multi-line value","single2":"xyz"},"#,
+            r#""section2":{"multi":"a\n        b\n        c"},"#,
+            r#""section3":{"single":"ghi","multi":"the quick\n       brown fox","name":"bob smith","foo":"bar\n      Hi World"}}"#,
+        )
+    );
+}
+
+#[test]
+fn test_json_writer_handler_escaping_and_edges() {
+    let mut handler = JsonWriterHandler::new(Vec::new());
+    ini_parse_string("", &mut handler).unwrap();
+    assert_eq!(handler.into_inner(), b"{}");
+
+    let mut handler = JsonWriterHandler::new(Vec::new());
+    ini_parse_string("top = \"q\" \\ \t\u{1}\n[empty]\n", &mut handler).unwrap();
+    assert_eq!(String::from_utf8(handler.into_inner()).unwrap(), r#"{"":{"top":"\"q\" \\ \t\u0001"},"empty":{}}"#);
+}
+
+#[test]
+fn test_json_writer_handler_grouping() {
+    let data = "[a]\nx = 1\n[b]\ny = 2\n[a]\nz = 3\n";
+    let mut streaming = JsonWriterHandler::new(Vec::new());
+    ini_parse_string(data, &mut streaming).unwrap();
+    assert_eq!(streaming.into_inner(), br#"{"a":{"x":"1"},"b":{"y":"2"},"a":{"z":"3"}}"#);
+
+    let mut grouped = JsonWriterHandler::new(Vec::new()).group_sections(true);
+    ini_parse_string(data, &mut grouped).unwrap();
+    assert_eq!(grouped.into_inner(), br#"{"a":{"x":"1","z":"3"},"b":{"y":"2"}}"#);
+}
+
+#[test]
+fn test_json_writer_handler_two_documents() {
+    for grouped in [false, true] {
+        let mut writer = JsonWriterHandler::new(Vec::new()).group_sections(grouped);
+        ini_parse_string("[a]\nx = 1\n", &mut writer).unwrap();
+        ini_parse_string("top = 0\n[b]\ny = 2\n", &mut writer).unwrap();
+        assert_eq!(writer.into_inner(), br#"{"a":{"x":"1"}}{"":{"top":"0"},"b":{"y":"2"}}"#);
+    }
+}
+
+/// Handler rejecting values that are not numbers, quoting the line it saw
+#[derive(Default)]
+struct NumbersOnly {
+    seen: Vec<String>,
+}
+
+impl IniHandler for NumbersOnly {
+    fn handle(&mut self, _section: &str, _name: &str, _value: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, _section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.seen.push(info.raw_line.to_string());
+        match value.trim().parse::<i64>() {
+            Ok(_) => Ok(()),
+            Err(_) => Err(format!("'{}' is not a number", name)),
+        }
+    }
+}
+
+const NUMBERS: &str = "[limits]\n  max  =   10   ; upper bound\r\nmin\t= low ; lower bound\nstep = 1\n    two\n";
+
+#[test]
+fn test_raw_line_in_rejected_entry() {
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    let mut handler = NumbersOnly::default();
+    let result = ini_parse_string_with_options(NUMBERS, &mut handler, &options);
+
+    assert_eq!(handler.seen, vec!["  max  =   10   ; upper bound", "min\t= low ; lower bound", "step = 1", "    two"]);
+    let error = result.unwrap_err();
+    assert_eq!(
+        error,
+        IniParseError::RejectedEntry {
+            line: 3,
+            raw_line: "min\t= low ; lower bound".to_string(),
+            message: "'min' is not a number".to_string(),
+        }
+    );
+    assert_eq!(error.to_string(), "Handler error on line 3: 'min' is not a number\n    min\t= low ; lower bound");
+
+    let options = ParseOptions { stop_on_first_error: true, ..options };
+    let result = ini_parse_string_with_options("[s]\na = 1\n\t  b ; c\n", &mut NumbersOnly::default(), &options);
+    assert!(matches!(result, Err(IniParseError::RejectedEntry { line: 3, raw_line, .. }) if raw_line == "\t  b ; c"));
+}
+
+#[test]
+fn test_raw_line_in_replay() {
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    let tokens = IniTokens::scan(NUMBERS, &options);
+    let mut replayed = NumbersOnly::default();
+    let replay_result = tokens.replay(&mut replayed);
+
+    let mut parsed = NumbersOnly::default();
+    assert_eq!(replay_result, ini_parse_string_with_options(NUMBERS, &mut parsed, &options));
+    assert_eq!(replayed.seen, parsed.seen);
+}
+
+/// Handler that only knows where each entry was
+#[derive(Default)]
+struct LineRecorder {
+    lines: Vec<(usize, String, String)>,
+}
+
+impl IniHandler for LineRecorder {
+    fn handle(&mut self, _section: &str, _name: &str, _value: &str) -> Result<(), String> {
+        unreachable!("handle_at is overridden")
+    }
+
+    fn handle_at(&mut self, line: usize, section: &str, name: &str, value: &str) -> Result<(), String> {
+        if value == "maybe" {
+            return Err(format!("line {}: invalid boolean '{}'", line, value));
+        }
+        self.lines.push((line, section.to_string(), name.to_string()));
+        Ok(())
+    }
+}
+
+const COMMENTED: &str = "; header comment\n\n[server]\nhost = a\n\n# note\nport = 80\n[motd]\ntext = first\n\n  second\n; between\n  third\n";
+
+#[test]
+fn test_handle_at_lines() {
+    let options = ParseOptions { allow_multiline: true, call_handler_on_new_section: true, ..ParseOptions::default() };
+    let mut recorder = LineRecorder::default();
+    ini_parse_string_with_options(COMMENTED, &mut recorder, &options).unwrap();
+
+    let lines: Vec<(usize, &str, &str)> = recorder.lines.iter().map(|(line, s, n)| (*line, s.as_str(), n.as_str())).collect();
+    assert_eq!(
+        lines,
+        vec![
+            (3, "server", ""),
+            (4, "server", "host"),
+            (7, "server", "port"),
+            (8, "motd", ""),
+            (9, "motd", "text"),
+            (11, "motd", "text"),
+            (13, "motd", "text"),
+        ]
+    );
+
+    let mut recorder = LineRecorder::default();
+    let error = ini_parse_string("[flags]\n\n; verbose output\nverbose = maybe\n", &mut recorder).unwrap_err();
+    assert_eq!(
+        error,
+        IniParseError::RejectedEntry {
+            line: 4,
+            raw_line: "verbose = maybe".to_string(),
+            message: "line 4: invalid boolean 'maybe'".to_string()
+        }
+    );
+}
@@ -0,0 +1,260 @@
+//! Tests for `@include` lines (`ParseOptions::allow_includes`)
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use inih::error::InterpolationError;
+use inih::expansion::ExpansionBudget;
+use inih::handlers::{MapHandler, VecHandler};
+use inih::interpolation::Interpolation;
+use inih::{ini_parse_string_with_includes, DuplicateKeyPolicy, IniParseError, IniReader, ParseOptions, ReaderOptions};
+
+fn includes() -> ParseOptions {
+    ParseOptions { allow_includes: true, duplicate_key_policy: DuplicateKeyPolicy::LastWins, ..ParseOptions::default() }
+}
+
+/// A temp directory unique to this process and `name`, removed on drop
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("inih_include_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    fn write(&self, name: &str, data: &str) -> PathBuf {
+        let path = self.0.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// The error inside an `InFile`, with the file it names
+fn in_file(error: IniParseError) -> (PathBuf, IniParseError) {
+    match error {
+        IniParseError::InFile { path, error } => (path, *error),
+        other => panic!("expected InFile, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_override_after_include() {
+    let dir = TempDir::new("override");
+    dir.write("base.ini", "[db]\nhost = localhost\nport = 5432\n");
+    let main = dir.write("prod.ini", "@include base.ini\n[db]\nhost = db.example.org\n");
+
+    let reader = IniReader::from_file_with_options(&main, &includes()).unwrap();
+    assert_eq!(reader.get("db", "host", ""), "db.example.org");
+    assert_eq!(reader.get_integer("db", "port", 0), 5432);
+}
+
+#[test]
+fn test_paths_relative_to_including_file() {
+    let dir = TempDir::new("relative");
+    dir.write("base.ini", "[a]\nfrom = base\n");
+    dir.write("conf.d/extra.ini", "@include ../base.ini ; shared settings\n[b]\nfrom = extra\n");
+    let main = dir.write("main.ini", "@include conf.d/extra.ini\n[c]\nfrom = main\n");
+
+    let reader = IniReader::from_file_with_options(&main, &includes()).unwrap();
+    assert_eq!(reader.sections(), ["a", "b", "c"]);
+    assert_eq!(reader.get("a", "from", ""), "base");
+    assert_eq!(reader.get("b", "from", ""), "extra");
+}
+
+#[test]
+fn test_recursive_include_is_an_error() {
+    let dir = TempDir::new("recursive");
+    let a = dir.write("a.ini", "[a]\nk = 1\n@include b.ini\n");
+    let b = dir.write("b.ini", "@include a.ini\n");
+
+    let (path, error) = in_file(IniReader::from_file_with_options(&a, &includes()).unwrap_err());
+    assert_eq!(path, b);
+    let IniParseError::ParseError { line, message, .. } = error else { panic!("{:?}", error) };
+    assert_eq!(line, 1);
+    assert!(message.starts_with("Recursive include of 'a.ini' ("), "{}", message);
+    assert!(message.ends_with(&format!("{} -> {} -> {})", a.display(), b.display(), b.with_file_name("a.ini").display())), "{}", message);
+}
+
+#[test]
+fn test_missing_include_names_file_and_line() {
+    let dir = TempDir::new("missing");
+    let main = dir.write("main.ini", "[s]\nk = 1\n@include nope.ini\n");
+
+    let error = IniReader::from_file_with_options(&main, &includes()).unwrap_err();
+    assert!(error.to_string().starts_with(&format!("{}: Parse error on line 3: Cannot include 'nope.ini': ", main.display())), "{}", error);
+    let (path, error) = in_file(error);
+    assert_eq!(path, main);
+    assert!(matches!(error, IniParseError::ParseError { line: 3, .. }));
+}
+
+#[test]
+fn test_error_in_included_file_names_that_file() {
+    let dir = TempDir::new("nested_error");
+    let broken = dir.write("broken.ini", "[ok]\n[broken\n");
+    let main = dir.write("main.ini", "[s]\n@include broken.ini\n[t]\nk = 1\n");
+
+    let (path, error) = in_file(IniReader::from_file_with_options(&main, &includes()).unwrap_err());
+    assert_eq!(path, broken);
+    assert!(matches!(error, IniParseError::ParseError { line: 2, .. }));
+}
+
+#[test]
+fn test_string_with_resolver() {
+    let files: HashMap<PathBuf, &str> = [
+        (PathBuf::from("conf/a.ini"), "[a]\nk = 1\n@include b.ini\n"),
+        (PathBuf::from("conf/b.ini"), "[b]\nk = 2\n"),
+    ]
+    .into_iter()
+    .collect();
+    let mut requested = Vec::new();
+    let mut read = |path: &Path| {
+        requested.push(path.to_path_buf());
+        files.get(path).map(|data| data.as_bytes().to_vec()).ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    };
+
+    let mut handler = MapHandler::default();
+    ini_parse_string_with_includes("@include conf/a.ini\n[main]\nk = 0\n", &mut handler, &includes(), &mut read).unwrap();
+    assert_eq!(requested, [PathBuf::from("conf/a.ini"), PathBuf::from("conf/b.ini")]);
+    assert_eq!(handler.map()["b"]["k"], "2");
+    assert_eq!(handler.map()["main"]["k"], "0");
+}
+
+#[test]
+fn test_string_without_resolver_rejects_includes() {
+    let error = IniReader::from_string_with_options("[s]\n@include other.ini\n", &includes()).unwrap_err();
+    let IniParseError::ParseError { line, message, .. } = error else { panic!("{:?}", error) };
+    assert_eq!(line, 2);
+    assert!(message.starts_with("Cannot include 'other.ini'"), "{}", message);
+}
+
+#[test]
+fn test_off_by_default() {
+    let dir = TempDir::new("off");
+    dir.write("base.ini", "[a]\nk = 1\n");
+    let main = dir.write("main.ini", "@include base.ini\n[b]\nk = 2\n");
+
+    let reader = IniReader::from_file_with_options(&main, &ParseOptions::default()).unwrap();
+    assert!(!reader.has_section("a"));
+    assert_eq!(reader.get("b", "k", ""), "2");
+}
+
+/// `a.ini` including `b.ini` and `c.ini`, which both include `common.ini` under different paths
+fn diamond(dir: &TempDir, c_includes: &str) -> PathBuf {
+    dir.write("common.ini", "[s]\nk = v\n");
+    dir.write("b.ini", "@include common.ini\n[b]\nx = 1\n");
+    dir.write("c.ini", &format!("@include {}\n[c]\ny = 2\n", c_includes));
+    dir.write("a.ini", "@include b.ini\n@include c.ini\n")
+}
+
+fn entries(path: &Path, options: &ParseOptions) -> Vec<(String, String, String)> {
+    let mut handler = VecHandler::new();
+    inih::parser::ini_parse_with_options(path, &mut handler, options).unwrap();
+    handler.into_entries()
+}
+
+fn entry(section: &str, name: &str, value: &str) -> (String, String, String) {
+    (section.to_string(), name.to_string(), value.to_string())
+}
+
+#[test]
+fn test_diamond_include_applied_once() {
+    let dir = TempDir::new("diamond");
+    let a = diamond(&dir, "./common.ini");
+
+    assert_eq!(entries(&a, &includes()), [entry("s", "k", "v"), entry("b", "x", "1"), entry("c", "y", "2")]);
+
+    let reader = IniReader::from_file_with_options(&a, &includes()).unwrap();
+    let warnings = reader.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!((warnings[0].line, warnings[0].message.as_str()), (Some(1), "Skipping './common.ini': already included"));
+}
+
+#[test]
+fn test_diamond_include_is_not_a_duplicate() {
+    let dir = TempDir::new("diamond_error");
+    let a = diamond(&dir, "./common.ini");
+
+    let options = ParseOptions { duplicate_key_policy: DuplicateKeyPolicy::Error, ..includes() };
+    let reader = IniReader::from_file_with_options(&a, &options).unwrap();
+    assert_eq!(reader.get("s", "k", ""), "v");
+    assert_eq!(reader.occurrences("s", "k").len(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_diamond_include_through_symlink() {
+    let dir = TempDir::new("diamond_link");
+    std::fs::create_dir_all(dir.0.join("linked")).unwrap();
+    let a = diamond(&dir, "linked/common.ini");
+    std::os::unix::fs::symlink(dir.0.join("common.ini"), dir.0.join("linked/common.ini")).unwrap();
+
+    let found = entries(&a, &includes());
+    assert_eq!(found.iter().filter(|found| **found == entry("s", "k", "v")).count(), 1);
+}
+
+#[test]
+fn test_repeated_includes_when_allowed() {
+    let dir = TempDir::new("repeat");
+    let a = diamond(&dir, "./common.ini");
+
+    let options = ParseOptions { allow_repeated_includes: true, ..includes() };
+    let found = entries(&a, &options);
+    assert_eq!(found.iter().filter(|found| **found == entry("s", "k", "v")).count(), 2);
+}
+
+#[test]
+fn test_string_resolver_skips_repeats() {
+    let mut read = |path: &Path| match path.to_str() {
+        Some("common.ini") => Ok(b"[s]\nk = v\n".to_vec()),
+        _ => Err(io::Error::from(io::ErrorKind::NotFound)),
+    };
+    let mut handler = VecHandler::new();
+    ini_parse_string_with_includes("@include common.ini\n@include ./common.ini\n", &mut handler, &includes(), &mut read).unwrap();
+    assert_eq!(handler.entries(), [entry("s", "k", "v")]);
+}
+
+#[test]
+fn test_includes_and_interpolation_share_one_budget() {
+    let dir = TempDir::new("budget");
+    dir.write("one.ini", "[one]\nk = 1\n");
+    dir.write("two.ini", "[two]\nk = 2\n");
+    let main = dir.write("main.ini", "@include one.ini\n@include two.ini\n[s]\nx = ${one:k}${two:k}\n");
+    let load = |max_total_expansions| {
+        let reader_options = ReaderOptions { interpolation: Interpolation::Extended, ..ReaderOptions::default() };
+        let budget = ExpansionBudget { max_total_expansions, ..ExpansionBudget::default() };
+        let options = ParseOptions { expansion_budget: budget, ..includes() };
+        let mut reader = IniReader::with_reader_options(reader_options);
+        inih::parser::ini_parse_with_options(&main, &mut reader, &options).unwrap();
+        reader
+    };
+
+    let reader = load(10);
+    assert_eq!(reader.get("s", "x", ""), "12");
+    assert!(reader.interpolation_errors().is_empty());
+
+    // Enough for either feature alone, not for both
+    let reader = load(3);
+    assert_eq!(reader.get("s", "x", ""), "${one:k}${two:k}");
+    assert!(matches!(reader.interpolation_errors()[0], InterpolationError::LimitExceeded { .. }));
+}
+
+#[test]
+fn test_budget_from_parse_options() {
+    let dir = TempDir::new("budget_options");
+    let a = diamond(&dir, "./common.ini");
+
+    let budget = ExpansionBudget { max_total_expansions: 2, ..ExpansionBudget::default() };
+    let options = ParseOptions { expansion_budget: budget, ..includes() };
+    let error = IniReader::from_file_with_options(&a, &options).unwrap_err();
+    assert!(matches!(in_file(error).1, IniParseError::LimitExceeded { .. }));
+}
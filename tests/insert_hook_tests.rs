@@ -0,0 +1,146 @@
+//! Tests for the reader's insert hook
+
+use std::sync::{Arc, Mutex};
+
+use inih::parser::ini_parse_string;
+use inih::{IniParseError, IniReader, InsertDecision, ParseOptions, ReaderOptions};
+
+const DATA: &str = "[server]\nhost = example.com\nroot = /srv//www/\nsecret = hunter2\n[client]\nname = app\n";
+
+fn load(data: &str, on_insert: impl FnMut(&str, &str, &str) -> InsertDecision + Send + Sync + 'static) -> Result<IniReader, IniParseError> {
+    let options = ReaderOptions { on_insert: Some(Box::new(on_insert)), ..ReaderOptions::default() };
+    let parse_options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    IniReader::from_string_with_reader_options(data, &parse_options, options)
+}
+
+#[test]
+fn test_accept() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let record = Arc::clone(&seen);
+    let reader = load(DATA, move |section, name, value| {
+        record.lock().unwrap().push(format!("{}.{}={}", section, name, value));
+        InsertDecision::Accept
+    })
+    .unwrap();
+
+    assert_eq!(reader.to_ini_string(), IniReader::from_string(DATA).unwrap().to_ini_string());
+    assert_eq!(
+        *seen.lock().unwrap(),
+        ["server.host=example.com", "server.root=/srv//www/", "server.secret=hunter2", "client.name=app"]
+    );
+}
+
+#[test]
+fn test_accept_rewritten() {
+    let reader = load(DATA, |_, name, value| {
+        if name == "root" {
+            InsertDecision::AcceptRewritten(value.replace("//", "/").trim_end_matches('/').to_string())
+        } else {
+            InsertDecision::Accept
+        }
+    })
+    .unwrap();
+
+    assert_eq!(reader.get("server", "root", ""), "/srv/www");
+    assert_eq!(reader.occurrences("server", "root")[0].value, "/srv/www");
+}
+
+#[test]
+fn test_skip() {
+    let reader = load(DATA, |_, name, _| if name == "secret" { InsertDecision::Skip } else { InsertDecision::Accept }).unwrap();
+
+    assert!(!reader.has_value("server", "secret"));
+    assert_eq!(reader.keys("server"), ["host", "root"]);
+    assert!(reader.occurrences("server", "secret").is_empty());
+    assert_eq!(reader.parse_error(), None);
+}
+
+#[test]
+fn test_skip_allowlist() {
+    let allowed = ["host", "name"];
+    let reader = load(DATA, move |_, name, _| {
+        if allowed.contains(&name) {
+            InsertDecision::Accept
+        } else {
+            InsertDecision::Skip
+        }
+    })
+    .unwrap();
+
+    assert_eq!(reader.to_ini_string(), "[server]\nhost = example.com\n\n[client]\nname = app\n");
+}
+
+#[test]
+fn test_reject() {
+    let data = "[server]\nhost = example.com\nbanner = x\n  long continuation\nport = 80\n";
+    let result = load(data, |_, _, value| {
+        if value.len() > 16 {
+            InsertDecision::Reject(format!("value is {} bytes long", value.len()))
+        } else {
+            InsertDecision::Accept
+        }
+    });
+
+    let expected = IniParseError::RejectedEntry {
+        line: 3,
+        raw_line: "banner = x".to_string(),
+        message: "value is 21 bytes long".to_string(),
+    };
+    assert_eq!(result.unwrap_err(), expected);
+}
+
+#[test]
+fn test_reject_recorded_when_used_as_handler() {
+    let options = ReaderOptions {
+        on_insert: Some(Box::new(|_, name, _| {
+            if name.starts_with('_') {
+                InsertDecision::Reject("reserved name".to_string())
+            } else {
+                InsertDecision::Accept
+            }
+        })),
+        ..ReaderOptions::default()
+    };
+    let mut reader = IniReader::with_reader_options(options);
+    ini_parse_string("a = 1\n_b = 2\nc = 3\n_d = 4\n", &mut reader).unwrap();
+
+    assert_eq!(reader.keys(""), ["a", "c"]);
+    match reader.parse_error() {
+        Some(IniParseError::RejectedEntry { line, message, .. }) => {
+            assert_eq!(*line, 2);
+            assert_eq!(message, "reserved name");
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_earlier_parse_error_wins() {
+    let result = load("[bad\nkey = value\n", |_, _, _| InsertDecision::Reject("no".to_string()));
+    assert!(matches!(result, Err(IniParseError::ParseError { line: 1, .. })));
+
+    let result = load("key = value\n[bad\n", |_, _, _| InsertDecision::Reject("no".to_string()));
+    assert!(matches!(result, Err(IniParseError::RejectedEntry { line: 1, .. })));
+}
+
+#[test]
+fn test_hook_sees_complete_multiline_value() {
+    let reader = load("text = first\n  second\nnext = 1\n", |_, name, value| {
+        if name == "text" {
+            InsertDecision::AcceptRewritten(value.to_uppercase())
+        } else {
+            InsertDecision::Accept
+        }
+    })
+    .unwrap();
+
+    assert_eq!(reader.get("", "text", ""), "FIRST\n  SECOND");
+    assert_eq!(reader.get("", "next", ""), "1");
+}
+
+#[test]
+fn test_setters_bypass_hook() {
+    let mut reader = load("", |_, _, _| InsertDecision::Skip).unwrap();
+    reader.set_integer("server", "port", 80);
+    assert_eq!(reader.get_integer("server", "port", 0), 80);
+}
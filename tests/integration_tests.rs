@@ -1,6 +1,16 @@
 //! Integration tests for inih library
 
-use inih::{IniReader, ini_parse_string, IniHandler, ParseOptions, IniParseError};
+use std::collections::{BTreeMap, HashMap};
+
+use inih::error::Suggestion;
+use inih::handlers::VecHandler;
+use inih::layered::LayeredReader;
+use inih::multi::ini_parse_multi;
+use inih::trace::{parse_trace, TraceEvent};
+use inih::{
+    ini_parse_string, ini_parse_string_with_options, CommentPrefixes, IniHandler, IniParseError, IniReader,
+    LineLengthPolicy, ParseOptions, ParseOptionsDelta, SectionAnnotation, SectionHeader, SectionPattern,
+};
 
 #[derive(Debug, Default)]
 struct TestHandler {
@@ -28,10 +38,10 @@ key4=value4
 
     let reader = IniReader::from_string(data).unwrap();
     
-    assert_eq!(reader.get_string("section1", "key1", ""), "value1");
-    assert_eq!(reader.get_string("section1", "key2", ""), "value2");
-    assert_eq!(reader.get_string("section1", "key3", ""), "value3");
-    assert_eq!(reader.get_string("section2", "key4", ""), "value4");
+    assert_eq!(reader.get("section1", "key1", ""), "value1");
+    assert_eq!(reader.get("section1", "key2", ""), "value2");
+    assert_eq!(reader.get("section1", "key3", ""), "value3");
+    assert_eq!(reader.get("section2", "key4", ""), "value4");
 }
 
 #[test]
@@ -46,8 +56,8 @@ key2=value2
 
     let reader = IniReader::from_string(data).unwrap();
     
-    assert_eq!(reader.get_string("section1", "key1", ""), "value1");
-    assert_eq!(reader.get_string("section1", "key2", ""), "value2");
+    assert_eq!(reader.get("section1", "key1", ""), "value1");
+    assert_eq!(reader.get("section1", "key2", ""), "value2");
 }
 
 #[test]
@@ -60,12 +70,11 @@ key1=line1
 key2=value2
 "#;
 
-    let mut options = ParseOptions::default();
-    options.allow_multiline = true;
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
     let reader = IniReader::from_string_with_options(data, &options).unwrap();
     
-    assert_eq!(reader.get_string("section1", "key1", ""), "line1\n    line2\n    line3");
-    assert_eq!(reader.get_string("section1", "key2", ""), "value2");
+    assert_eq!(reader.get("section1", "key1", ""), "line1\n    line2\n    line3");
+    assert_eq!(reader.get("section1", "key2", ""), "value2");
 }
 
 #[test]
@@ -82,8 +91,8 @@ key2=value2
 
     let reader = IniReader::from_string(data).unwrap();
     
-    assert_eq!(reader.get_string("section1", "key1", ""), "value1");
-    assert_eq!(reader.get_string("section2", "key2", ""), "value2");
+    assert_eq!(reader.get("section1", "key1", ""), "value1");
+    assert_eq!(reader.get("section2", "key2", ""), "value2");
     assert!(reader.has_section("empty_section"));
 }
 
@@ -99,12 +108,13 @@ key3=value3
 
     let reader = IniReader::from_string(data).unwrap();
     
-    assert_eq!(reader.get_string("", "key1", ""), "value1");
-    assert_eq!(reader.get_string("", "key2", ""), "value2");
-    assert_eq!(reader.get_string("section1", "key3", ""), "value3");
+    assert_eq!(reader.get("", "key1", ""), "value1");
+    assert_eq!(reader.get("", "key2", ""), "value2");
+    assert_eq!(reader.get("section1", "key3", ""), "value3");
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_types() {
     let data = r#"
 [types]
@@ -127,14 +137,14 @@ hex = 0x1A
     assert_eq!(reader.get_integer("types", "integer", 0), 42);
     assert_eq!(reader.get_integer("types", "negative", 0), -123);
     assert_eq!(reader.get_real("types", "float", 0.0), 3.14159);
-    assert_eq!(reader.get_boolean("types", "boolean_true", false), true);
-    assert_eq!(reader.get_boolean("types", "boolean_false", true), false);
-    assert_eq!(reader.get_boolean("types", "boolean_yes", false), true);
-    assert_eq!(reader.get_boolean("types", "boolean_no", true), false);
-    assert_eq!(reader.get_boolean("types", "boolean_on", false), true);
-    assert_eq!(reader.get_boolean("types", "boolean_off", true), false);
-    assert_eq!(reader.get_boolean("types", "boolean_1", false), true);
-    assert_eq!(reader.get_boolean("types", "boolean_0", true), false);
+    assert!(reader.get_boolean("types", "boolean_true", false));
+    assert!(!reader.get_boolean("types", "boolean_false", true));
+    assert!(reader.get_boolean("types", "boolean_yes", false));
+    assert!(!reader.get_boolean("types", "boolean_no", true));
+    assert!(reader.get_boolean("types", "boolean_on", false));
+    assert!(!reader.get_boolean("types", "boolean_off", true));
+    assert!(reader.get_boolean("types", "boolean_1", false));
+    assert!(!reader.get_boolean("types", "boolean_0", true));
     assert_eq!(reader.get_integer("types", "hex", 0), 0x1A);
 }
 
@@ -203,6 +213,39 @@ key3=value3
     assert_eq!(key_value_calls[2], &("section2".to_string(), "key3".to_string(), "value3".to_string()));
 }
 
+#[test]
+fn test_vec_handler_api() {
+    let data = "[section1]\nkey1=value1\nkey2=value2\n\n[section2]\nkey3=value3\n";
+
+    let mut handler = VecHandler::new();
+    ini_parse_string(data, &mut handler).unwrap();
+
+    assert_eq!(
+        handler.entries(),
+        &[
+            ("section1".to_string(), "key1".to_string(), "value1".to_string()),
+            ("section1".to_string(), "key2".to_string(), "value2".to_string()),
+            ("section2".to_string(), "key3".to_string(), "value3".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_callback_trace() {
+    let data = "[section1]\nkey1=value1\nkey2=value2\n\n[section2]\nkey3=value3\n";
+
+    assert_eq!(
+        parse_trace(data, &ParseOptions::default()),
+        vec![
+            TraceEvent::section("section1"),
+            TraceEvent::pair("section1", "key1", "value1"),
+            TraceEvent::pair("section1", "key2", "value2"),
+            TraceEvent::section("section2"),
+            TraceEvent::pair("section2", "key3", "value3"),
+        ]
+    );
+}
+
 #[test]
 fn test_parse_error() {
     let data = r#"
@@ -212,6 +255,21 @@ key1=value1
 key2=value2
 "#;
 
+    // Parsing goes on after the unclosed header, which leaves the section unchanged
+    assert_eq!(
+        parse_trace(data, &ParseOptions::default()),
+        vec![
+            TraceEvent::section("section1"),
+            TraceEvent::pair("section1", "key1", "value1"),
+            TraceEvent::pair("section1", "key2", "value2"),
+            TraceEvent::Error(IniParseError::ParseError {
+                line: 4,
+                message: "Missing ']' in section header".to_string(),
+                suggestion: Some(Suggestion { replacement_line: "[unclosed_section]".to_string(), description: "insert ']' at byte 17".to_string() }),
+            }),
+        ]
+    );
+
     let result = IniReader::from_string(data);
     assert!(result.is_err());
     
@@ -231,11 +289,150 @@ key1=value1
 key2=value2
 "#;
 
-    let mut options = ParseOptions::default();
-    options.allow_multiline = true;
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
     
     let reader = IniReader::from_string_with_options(data, &options).unwrap();
-    assert_eq!(reader.get_string("section1", "key1", ""), "value1\n    continuation");
+    assert_eq!(reader.get("section1", "key1", ""), "value1\n    continuation");
+}
+
+#[test]
+fn test_comment_prefixes_construction() {
+    assert_eq!(CommentPrefixes::chars(";#"), CommentPrefixes::from(";#"));
+    assert_eq!(CommentPrefixes::chars(";;#"), CommentPrefixes::strings([";", "#"]));
+    assert_eq!(CommentPrefixes::strings([";", "//"]).as_slice(), ["//", ";"]);
+    assert_eq!(CommentPrefixes::strings(["", ";"]), CommentPrefixes::chars(";"));
+    assert!(CommentPrefixes::strings([""]).is_empty());
+    assert_eq!(CommentPrefixes::from("//").as_slice(), ["/"]);
+    assert!(CommentPrefixes::none().is_empty());
+}
+
+#[test]
+fn test_comment_prefixes_old_defaults() {
+    let data = "; comment\n# comment\n[s] ; note\na = 1 ; note\nb = 2 # kept\nc = x;y\n";
+    let old_style = ParseOptions {
+        inline_comment_prefixes: ";".into(),
+        start_comment_prefixes: ";#".into(),
+        ..ParseOptions::default()
+    };
+
+    let with_defaults = IniReader::from_string(data).unwrap();
+    let with_old_style = IniReader::from_string_with_options(data, &old_style).unwrap();
+    for key in ["a", "b", "c"] {
+        assert_eq!(with_defaults.get("s", key, "?"), with_old_style.get("s", key, "?"));
+    }
+    assert_eq!(with_defaults.get("s", "b", ""), "2 # kept");
+}
+
+#[test]
+fn test_multi_char_comment_prefixes() {
+    let data = "// comment\n[s]\nurl = http://example.com // note\npath = a/b\n";
+    let options = ParseOptions {
+        inline_comment_prefixes: CommentPrefixes::strings(["//"]),
+        start_comment_prefixes: CommentPrefixes::strings(["//", "#"]),
+        ..ParseOptions::default()
+    };
+
+    let reader = IniReader::from_string_with_options(data, &options).unwrap();
+    assert_eq!(reader.get("s", "url", ""), "http://example.com");
+    assert_eq!(reader.get("s", "path", ""), "a/b");
+}
+
+#[test]
+fn test_section_overrides() {
+    let data = "[main]\nname = app ; comment\n[script]\nrun = echo # not a comment ; nor this\n# still a comment\n[main.extra]\nx = 1 ; c\n";
+    let verbatim = ParseOptionsDelta {
+        allow_inline_comments: Some(false),
+        start_comment_prefixes: Some(CommentPrefixes::chars("#")),
+        trim_values: Some(false),
+        ..ParseOptionsDelta::default()
+    };
+    let options = ParseOptions {
+        section_overrides: vec![(SectionPattern::Exact("SCRIPT".to_string()), verbatim)],
+        ..ParseOptions::default()
+    };
+
+    let reader = IniReader::from_string_with_options(data, &options).unwrap();
+    assert_eq!(reader.get("script", "run", ""), " echo # not a comment ; nor this");
+    assert_eq!(reader.keys("script"), vec!["run"]);
+    assert_eq!(reader.get("main", "name", ""), "app");
+    assert_eq!(reader.get("main.extra", "x", ""), "1");
+}
+
+#[test]
+fn test_section_overrides_glob() {
+    let data = "[a]\nk = v\n  more\n[text.one]\nk = v\n  more\n";
+    let multiline = ParseOptionsDelta { allow_multiline: Some(true), ..ParseOptionsDelta::default() };
+    let options = ParseOptions {
+        allow_multiline: false,
+        section_overrides: vec![(SectionPattern::Glob("text.*".to_string()), multiline)],
+        ..ParseOptions::default()
+    };
+
+    let reader = IniReader::from_string_with_options(data, &options).unwrap();
+    assert_eq!(reader.get("a", "k", ""), "v");
+    assert_eq!(reader.get("text.one", "k", ""), "v\n  more");
+}
+
+const LONG_CONTINUATION: &str = "[s]\nkey = first\n  second line that is far too long\n  third\nnext = 1\n";
+
+fn long_continuation_options(policy: LineLengthPolicy) -> ParseOptions {
+    ParseOptions { allow_multiline: true, max_line: 20, line_length_policy: policy, ..ParseOptions::default() }
+}
+
+#[test]
+fn test_long_continuation_truncate() {
+    let options = long_continuation_options(LineLengthPolicy::Truncate);
+    let reader = IniReader::from_string_with_options(LONG_CONTINUATION, &options).unwrap();
+    assert_eq!(reader.get("s", "key", ""), "first\n  second line that i\n  third");
+    assert_eq!(reader.get("s", "next", ""), "1");
+}
+
+#[test]
+fn test_long_continuation_skip() {
+    let options = long_continuation_options(LineLengthPolicy::Skip);
+    let reader = IniReader::from_string_with_options(LONG_CONTINUATION, &options).unwrap();
+    assert_eq!(reader.get("s", "key", ""), "first\n  third");
+    assert_eq!(reader.get("s", "next", ""), "1");
+}
+
+#[test]
+fn test_long_continuation_error() {
+    let options = long_continuation_options(LineLengthPolicy::Error);
+    let mut handler = TestHandler::default();
+    let result = ini_parse_string_with_options(LONG_CONTINUATION, &mut handler, &options);
+    assert_eq!(result, Err(IniParseError::ParseError {
+        line: 3,
+        message: "Line too long (continuation of 'key')".to_string(),
+        suggestion: None,
+    }));
+
+    let options = ParseOptions { stop_on_first_error: true, ..options };
+    let result = IniReader::from_string_with_options("[s]\nthis line is also far too long = 1\n", &options);
+    assert_eq!(result.unwrap_err(), IniParseError::ParseError { line: 2, message: "Line too long".to_string(), suggestion: None });
+}
+
+const SUSPICIOUS: &str = "[server]\nhost = example.com\n\tport = 8080\nmotd = Welcome\n  to the server, enjoy\n  \"a=b\" in quotes ; or = in comment\n";
+
+#[test]
+fn test_suspicious_continuation_warning() {
+    let options = ParseOptions { allow_multiline: true, warn_suspicious_continuation: true, ..ParseOptions::default() };
+    let reader = IniReader::from_string_with_options(SUSPICIOUS, &options).unwrap();
+
+    let warnings = reader.warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].line, Some(3));
+    assert_eq!((warnings[0].section.as_str(), warnings[0].name.as_str()), ("server", "host"));
+    assert_eq!(
+        warnings[0].to_string(),
+        "line 3: Indented line 'port = 8080' looks like an entry but continues the value of 'host'"
+    );
+
+    // The parse result is unchanged by the lint
+    let plain = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    let unlinted = IniReader::from_string_with_options(SUSPICIOUS, &plain).unwrap();
+    assert_eq!(reader.get("server", "host", ""), unlinted.get("server", "host", ""));
+    assert_eq!(reader.get("server", "host", ""), "example.com\n\tport = 8080");
+    assert!(unlinted.warnings().is_empty());
 }
 
 #[test]
@@ -243,7 +440,7 @@ fn test_utf8_bom() {
     let data = "\u{FEFF}[section1]\nkey1=value1\n";
     
     let reader = IniReader::from_string(data).unwrap();
-    assert_eq!(reader.get_string("section1", "key1", ""), "value1");
+    assert_eq!(reader.get("section1", "key1", ""), "value1");
 }
 
 #[test]
@@ -257,8 +454,326 @@ KEY2=value2
     let reader = IniReader::from_string(data).unwrap();
     
     // Should be case-insensitive
-    assert_eq!(reader.get_string("section1", "key1", ""), "value1");
-    assert_eq!(reader.get_string("Section1", "Key1", ""), "value1");
-    assert_eq!(reader.get_string("SECTION1", "KEY1", ""), "value1");
-    assert_eq!(reader.get_string("section1", "key2", ""), "value2");
+    assert_eq!(reader.get("section1", "key1", ""), "value1");
+    assert_eq!(reader.get("Section1", "Key1", ""), "value1");
+    assert_eq!(reader.get("SECTION1", "KEY1", ""), "value1");
+    assert_eq!(reader.get("section1", "key2", ""), "value2");
+}
+
+#[test]
+fn test_multi_document_stream() {
+    let data = "[a]\nkey=1\n%%%\n[b]\n[broken\nkey=2\n%%%\n[c]\nkey=3\n";
+
+    let mut readers = Vec::new();
+    let result = ini_parse_multi(data.as_bytes(), &ParseOptions::default(), "%%%", |reader| {
+        readers.push(reader);
+        Ok(())
+    });
+
+    assert_eq!(readers.len(), 2);
+    assert_eq!(readers[0].get_integer("a", "key", 0), 1);
+    assert!(!readers[1].has_section("a"));
+    assert_eq!(readers[1].get_integer("c", "key", 0), 3);
+    match result {
+        Err(IniParseError::Document { index, error }) => {
+            assert_eq!(index, 1);
+            assert!(matches!(*error, IniParseError::ParseError { line: 2, .. }));
+        }
+        other => panic!("Expected document error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_multi_document_empty_documents() {
+    let data = "[a]\nkey=1\n%%%\n\n%%%\n[b]\nkey=2\n";
+
+    let mut count = 0;
+    ini_parse_multi(data.as_bytes(), &ParseOptions::default(), "%%%", |_| {
+        count += 1;
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(count, 3);
+
+    let options = ParseOptions { skip_empty_documents: true, ..ParseOptions::default() };
+    let mut count = 0;
+    ini_parse_multi(data.as_bytes(), &options, "%%%", |_| {
+        count += 1;
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(count, 2);
+}
+
+#[derive(Debug, Default)]
+struct HeaderRecorder {
+    headers: Vec<SectionHeader>,
+    sections: Vec<String>,
+}
+
+impl IniHandler for HeaderRecorder {
+    fn handle(&mut self, section: &str, name: &str, _value: &str) -> Result<(), String> {
+        if name.is_empty() {
+            self.sections.push(section.to_string());
+        }
+        Ok(())
+    }
+
+    fn start_section(&mut self, header: &SectionHeader) -> Result<(), String> {
+        self.headers.push(header.clone());
+        Ok(())
+    }
+}
+
+#[test]
+fn test_section_header_annotations() {
+    let data = r#"
+[remote "origin"]
+[staging : production]
+[server @ dev, prod]
+[plain]
+"#;
+
+    let options = ParseOptions {
+        allow_quoted_subsections: true,
+        allow_section_inheritance: true,
+        allow_section_profiles: true,
+        call_handler_on_new_section: true,
+        ..ParseOptions::default()
+    };
+    let mut handler = HeaderRecorder::default();
+    ini_parse_string_with_options(data, &mut handler, &options).unwrap();
+
+    let header = |name: &str, annotation| SectionHeader { name: name.to_string(), annotation };
+    assert_eq!(handler.headers, vec![
+        header("remote", Some(SectionAnnotation::QuotedSubsection("origin".to_string()))),
+        header("staging", Some(SectionAnnotation::Parent("production".to_string()))),
+        header("server", Some(SectionAnnotation::Profiles(vec!["dev".to_string(), "prod".to_string()]))),
+        header("plain", None),
+    ]);
+    assert_eq!(handler.sections, vec!["remote.origin", "staging", "server", "plain"]);
+
+    let options = ParseOptions { allow_raw_section_annotations: true, ..ParseOptions::default() };
+    let mut handler = HeaderRecorder::default();
+    ini_parse_string_with_options("[name some extra text]\n", &mut handler, &options).unwrap();
+    assert_eq!(handler.headers, vec![header("name", Some(SectionAnnotation::Raw("some extra text".to_string())))]);
+}
+
+#[test]
+fn test_section_header_plain_flattening() {
+    let data = "[ section 2 ]\n[remote \"origin\"]\n[a : b]\n";
+
+    let options = ParseOptions { call_handler_on_new_section: true, ..ParseOptions::default() };
+    let mut handler = HeaderRecorder::default();
+    ini_parse_string_with_options(data, &mut handler, &options).unwrap();
+
+    assert!(handler.headers.iter().all(|h| h.annotation.is_none()));
+    assert_eq!(handler.sections, vec![" section 2 ", "remote \"origin\"", "a : b"]);
+}
+
+#[derive(Debug, Default)]
+struct ValueCompletion {
+    events: Vec<String>,
+}
+
+impl IniHandler for ValueCompletion {
+    fn handle(&mut self, _section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.events.push(format!("{}={}", name, value.trim()));
+        Ok(())
+    }
+
+    fn start_section(&mut self, header: &SectionHeader) -> Result<(), String> {
+        self.events.push(format!("start {}", header.name));
+        Ok(())
+    }
+
+    fn end_section(&mut self, section: &str) -> Result<(), String> {
+        self.events.push(format!("close {}", section));
+        Ok(())
+    }
+
+    fn end_value(&mut self, section: &str, name: &str) -> Result<(), String> {
+        self.events.push(format!("end {}.{}", section, name));
+        Ok(())
+    }
+}
+
+#[test]
+fn test_end_value_signal() {
+    let data = "[s]\nkey1=line1\n  line2\n\nkey2=value2\n[t]\nkey3=line1\n  line2";
+
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    let mut handler = ValueCompletion::default();
+    ini_parse_string_with_options(data, &mut handler, &options).unwrap();
+
+    assert_eq!(handler.events, vec![
+        "start s",
+        "key1=line1", "key1=line2", "end s.key1",
+        "key2=value2", "end s.key2",
+        "close s", "start t",
+        "key3=line1", "key3=line2", "end t.key3",
+        "close t",
+    ]);
+}
+
+#[test]
+fn test_section_calls_follow_option() {
+    let data = "top = 1
+[a]
+[b]
+key = value
+";
+
+    let mut handler = ValueCompletion::default();
+    ini_parse_string(data, &mut handler).unwrap();
+    assert_eq!(handler.events, vec![
+        "top=1", "end .top",
+        "start a", "close a",
+        "start b", "key=value", "end b.key", "close b",
+    ]);
+
+    let options = ParseOptions { call_handler_on_new_section: true, ..ParseOptions::default() };
+    let mut handler = ValueCompletion::default();
+    ini_parse_string_with_options(data, &mut handler, &options).unwrap();
+    assert_eq!(handler.events, vec![
+        "top=1", "end .top",
+        "start a", "=", "close a",
+        "start b", "=", "key=value", "end b.key", "close b",
+    ]);
+
+    // Empty sections are still registered without the option
+    let reader = IniReader::from_string(data).unwrap();
+    assert_eq!(reader.sections(), vec!["a", "b"]);
+    assert!(reader.section("a").is_some());
+}
+
+#[test]
+fn test_layered_reader() {
+    let base = IniReader::from_string("[server]\nport = 80\nhost = base.local\n[log]\nlevel = warn\n").unwrap();
+    let site = IniReader::from_string("[server]\nport = 8080\n[cache]\nttl = 60\n").unwrap();
+    let user = IniReader::from_string("[log]\nlevel = debug\n[server]\nworkers = 4\n").unwrap();
+
+    let view = LayeredReader::new(vec![&base, &site, &user]);
+
+    assert_eq!(view.get_integer("server", "port", 0), 8080);
+    assert_eq!(view.get("server", "host", ""), "base.local");
+    assert_eq!(view.get("log", "level", ""), "debug");
+    assert_eq!(view.get_integer("server", "workers", 0), 4);
+    assert_eq!(view.get_integer("server", "missing", 7), 7);
+
+    assert_eq!(view.layer_of("server", "port"), Some(1));
+    assert_eq!(view.layer_of("server", "host"), Some(0));
+    assert_eq!(view.layer_of("log", "level"), Some(2));
+    assert_eq!(view.layer_of("server", "missing"), None);
+
+    assert_eq!(view.sections(), vec!["cache", "log", "server"]);
+    assert_eq!(view.keys("server"), vec!["host", "port", "workers"]);
+    assert!(view.has_value("cache", "ttl"));
+    assert!(!view.has_value("cache", "size"));
+}
+
+#[test]
+fn test_reader_accumulates_documents() {
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    let mut reader = IniReader::from_string_with_options("[a]\nx = 1\ny = first\n  more\n[b]\nz = 3\n", &options).unwrap();
+
+    ini_parse_string_with_options("[a]\ny = second\nw = 4\n  cont\n[c]\nv = 5\n", &mut reader, &options).unwrap();
+
+    assert_eq!(reader.get("a", "x", ""), "1");
+    assert_eq!(reader.get("a", "y", ""), "second");
+    assert_eq!(reader.get_all("a", "y"), vec!["second"]);
+    assert_eq!(reader.get("a", "w", ""), "4\n  cont");
+    assert_eq!(reader.get("b", "z", ""), "3");
+    assert_eq!(reader.get("c", "v", ""), "5");
+    assert_eq!(reader.to_ini_string(), "[a]\nx = 1\ny = second\nw = 4\n  cont\n\n[b]\nz = 3\n\n[c]\nv = 5\n");
+
+    // Duplicates inside one document still follow the policy
+    ini_parse_string("[a]\nx = 7\nx = 8\n", &mut reader).unwrap();
+    assert_eq!(reader.get("a", "x", ""), "7\n8");
+}
+
+#[test]
+fn test_reader_document_after_stopped_parse() {
+    let mut reader = IniReader::from_string("").unwrap();
+    let stopping = ParseOptions { stop_on_first_error: true, ..ParseOptions::default() };
+    assert!(ini_parse_string_with_options("[a]\nx = 1\n[broken\n", &mut reader, &stopping).is_err());
+    assert_eq!(reader.get("a", "x", ""), "1");
+
+    // The next document replaces the value instead of taking it for a duplicate
+    ini_parse_string("[a]\nx = 2\n", &mut reader).unwrap();
+    assert_eq!(reader.get("a", "x", ""), "2");
+}
+
+#[test]
+fn test_reader_clear() {
+    let mut reader = IniReader::from_string("[a]\nx = 1\n").unwrap();
+    reader.clear();
+
+    assert!(reader.sections().is_empty());
+    assert!(!reader.has_value("a", "x"));
+    assert_eq!(reader.to_ini_string(), "");
+    assert!(reader.parse_error().is_none());
+
+    ini_parse_string("[b]\ny = 2\n", &mut reader).unwrap();
+    assert_eq!(reader.sections(), vec!["b"]);
+}
+
+const CONVERTED: &str = "top = 1\n[Zebra]\nStripes = many\n[empty]\n[apple]\nkind = fuji\n";
+
+#[test]
+fn test_try_from_str() {
+    let reader = IniReader::try_from(CONVERTED).unwrap();
+    assert_eq!(reader.get("zebra", "stripes", ""), "many");
+
+    let error = IniReader::try_from("[ok]\n[broken\n").unwrap_err();
+    assert_eq!(error, IniParseError::ParseError {
+        line: 2,
+        message: "Missing ']' in section header".to_string(),
+        suggestion: Some(Suggestion { replacement_line: "[broken]".to_string(), description: "insert ']' at byte 7".to_string() }),
+    });
+    let parsed: Result<IniReader, _> = "a = 1".try_into();
+    assert!(parsed.is_ok());
+}
+
+#[test]
+fn test_into_hash_map() {
+    let map: HashMap<String, HashMap<String, String>> = IniReader::try_from(CONVERTED).unwrap().into();
+
+    assert_eq!(map.len(), 4);
+    assert_eq!(map[""]["top"], "1");
+    assert_eq!(map["Zebra"]["stripes"], "many");
+    assert!(map["empty"].is_empty());
+    assert_eq!(map["apple"]["kind"], "fuji");
+}
+
+#[test]
+fn test_into_btree_map() {
+    let map = BTreeMap::from(IniReader::try_from(CONVERTED).unwrap());
+
+    assert_eq!(map.keys().collect::<Vec<_>>(), ["", "Zebra", "apple", "empty"]);
+    assert_eq!(map[""]["top"], "1");
+    assert_eq!(map["Zebra"].keys().collect::<Vec<_>>(), ["stripes"]);
+
+    let without_global = BTreeMap::from(IniReader::try_from("[a]\nx = 1\n").unwrap());
+    assert_eq!(without_global.keys().collect::<Vec<_>>(), ["a"]);
+}
+
+#[test]
+fn test_extend_reader() {
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    let mut reader = IniReader::from_string_preserving("[a]\nx = 1\n  more\n", &options).unwrap();
+    reader.extend([
+        ("a".to_string(), "X".to_string(), "2".to_string()),
+        (String::new(), "top".to_string(), "3".to_string()),
+        ("b".to_string(), "y".to_string(), "4".to_string()),
+        ("b".to_string(), "y".to_string(), "5".to_string()),
+    ]);
+
+    assert!(reader.document().is_none());
+    assert_eq!(reader.get("a", "x", ""), "2");
+    assert_eq!(reader.get_all("a", "x"), vec!["2"]);
+    assert_eq!(reader.occurrences("a", "x")[0].line, 0);
+    assert_eq!(reader.get("b", "y", ""), "5");
+    assert!(reader.has_section("b"));
+    assert_eq!(reader.to_ini_string(), "top = 3\n\n[a]\nx = 2\n\n[b]\ny = 5\n");
 }
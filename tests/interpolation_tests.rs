@@ -0,0 +1,96 @@
+//! Tests for value interpolation (`ReaderOptions::interpolation`)
+
+use inih::error::InterpolationError;
+use inih::expansion::ExpansionBudget;
+use inih::interpolation::Interpolation;
+use inih::{ini_parse_string, IniReader, ParseOptions, ReaderOptions};
+
+fn load(data: &str, interpolation: Interpolation, strict: bool) -> IniReader {
+    let options = ReaderOptions { interpolation, strict_interpolation: strict, ..ReaderOptions::default() };
+    IniReader::from_string_with_reader_options(data, &ParseOptions::default(), options).unwrap()
+}
+
+#[test]
+fn test_chained_references() {
+    let data = "[s]\na = ${b}/a\nb = ${c}/b\nc = /root\n[t]\nx = ${s:a} and ${S:C}\n";
+    let reader = load(data, Interpolation::Extended, true);
+    assert_eq!(reader.get("s", "a", ""), "/root/b/a");
+    assert_eq!(reader.get("s", "b", ""), "/root/b");
+    assert_eq!(reader.get("t", "x", ""), "/root/b/a and /root");
+    assert!(reader.interpolation_errors().is_empty());
+}
+
+#[test]
+fn test_global_section_and_escapes() {
+    let data = "home = /home/ann\n[s]\ndir = ${:home}/x\nprice = $$5 for $${dir}\nlone = a $ b ${unclosed\n";
+    let reader = load(data, Interpolation::Extended, false);
+    assert_eq!(reader.get("s", "dir", ""), "/home/ann/x");
+    assert_eq!(reader.get("s", "price", ""), "$5 for ${dir}");
+    assert_eq!(reader.get("s", "lone", ""), "a $ b ${unclosed");
+}
+
+#[test]
+fn test_basic_style() {
+    let data = "[s]\nbase = /srv\ndata = %(base)s/data\nrate = 50%% of %(data)s\nother = ${base}\n";
+    let reader = load(data, Interpolation::Basic, false);
+    assert_eq!(reader.get("s", "data", ""), "/srv/data");
+    assert_eq!(reader.get("s", "rate", ""), "50% of /srv/data");
+    assert_eq!(reader.get("s", "other", ""), "${base}");
+}
+
+#[test]
+fn test_unknown_references() {
+    let data = "[s]\na = ${missing}/x\nb = ${nowhere:a}\n";
+    let lenient = load(data, Interpolation::Extended, false);
+    assert_eq!(lenient.get("s", "a", ""), "${missing}/x");
+    assert!(lenient.interpolation_errors().is_empty());
+
+    let strict = load(data, Interpolation::Extended, true);
+    assert_eq!(strict.get("s", "b", ""), "${nowhere:a}");
+    assert_eq!(
+        strict.interpolation_errors()[0],
+        InterpolationError::UnknownReference { section: "s".to_string(), name: "a".to_string(), reference: "${missing}".to_string() }
+    );
+    assert_eq!(strict.interpolation_errors()[1].to_string(), "unknown reference ${nowhere:a} in s.b");
+}
+
+#[test]
+fn test_cycle_reported_once() {
+    let data = "[s]\na = ${b}\nb = ${c}\nc = x${a}\nd = ${a}\nself = ${self}\nok = fine\n";
+    let reader = load(data, Interpolation::Extended, false);
+    assert_eq!(reader.get("s", "a", ""), "${b}");
+    assert_eq!(reader.get("s", "c", ""), "x${a}");
+    assert_eq!(reader.get("s", "d", ""), "${a}");
+    assert_eq!(reader.get("s", "ok", ""), "fine");
+
+    let errors: Vec<String> = reader.interpolation_errors().iter().map(ToString::to_string).collect();
+    assert_eq!(errors, ["reference cycle in s.a: s:a -> s:b -> s:c -> s:a", "reference cycle in s.self: s:self -> s:self"]);
+}
+
+#[test]
+fn test_budget_applies() {
+    // Each level doubles the value
+    let data: String = (0..20).map(|i| format!("v{} = ${{v{}}}${{v{}}}\n", i, i + 1, i + 1)).collect::<String>() + "v20 = xx\n";
+    let budget = ExpansionBudget { max_expanded_len: 1000, ..ExpansionBudget::default() };
+    let options = ReaderOptions { interpolation: Interpolation::Extended, ..ReaderOptions::default() };
+    let parse_options = ParseOptions { expansion_budget: budget, ..ParseOptions::default() };
+    let reader = IniReader::from_string_with_reader_options(&data, &parse_options, options).unwrap();
+    assert!(reader.get("", "v0", "").starts_with("${"));
+    assert_eq!(reader.get("", "v15", "").len(), 64);
+    assert!(matches!(reader.interpolation_errors()[0], InterpolationError::LimitExceeded { .. }));
+}
+
+#[test]
+fn test_later_documents_see_resolved_values() {
+    let options = ReaderOptions { interpolation: Interpolation::Extended, ..ReaderOptions::default() };
+    let mut reader = IniReader::with_reader_options(options);
+    ini_parse_string("root = /srv\nliteral = $${root}\n", &mut reader).unwrap();
+    ini_parse_string("logs = ${root}/logs\ncopy = ${literal}\n", &mut reader).unwrap();
+    assert_eq!(reader.get("", "logs", ""), "/srv/logs");
+    // Interpolated once: the escaped reference stays literal
+    assert_eq!(reader.get("", "copy", ""), "${root}");
+
+    // Off by default
+    let plain = IniReader::from_string("a = ${b}\nb = 1\n").unwrap();
+    assert_eq!(plain.get("", "a", ""), "${b}");
+}
@@ -0,0 +1,55 @@
+//! Tests for walking every entry of a reader (`IniReader::iter`, `iter_section`)
+
+use inih::{IniReader, ParseOptions};
+
+fn multi_line() -> IniReader {
+    let options = ParseOptions { allow_multiline: true, allow_inline_comments: true, ..ParseOptions::default() };
+    IniReader::from_string_with_options(include_str!("multi_line.ini"), &options).unwrap()
+}
+
+#[test]
+fn test_iter_matches_fixture() {
+    let reader = multi_line();
+    let entries: Vec<(&str, &str, &str)> = reader.iter().collect();
+    assert_eq!(
+        entries,
+        vec![
+            ("section1", "single1", "abc"),
+            ("section1", "multi", "this is a\n        multi-line value"),
+            ("section1", "single2", "xyz"),
+            ("section2", "multi", "a\n        b\n        c"),
+            ("section3", "single", "ghi"),
+            ("section3", "multi", "the quick\n       brown fox"),
+            ("section3", "name", "bob smith"),
+            ("section3", "foo", "bar\n      Hi World"),
+        ]
+    );
+    assert_eq!(reader.iter().len(), 8);
+
+    let mut visited = 0;
+    for (section, key, value) in &reader {
+        assert_eq!(reader.get(section, key, ""), value);
+        visited += 1;
+    }
+    assert_eq!(visited, 8);
+}
+
+#[test]
+fn test_iter_section() {
+    let reader = multi_line();
+    let section2: Vec<(&str, &str)> = reader.iter_section("SECTION2").collect();
+    assert_eq!(section2, vec![("multi", "a\n        b\n        c")]);
+    assert_eq!(reader.iter_section("missing").count(), 0);
+
+    let reader = IniReader::from_string("top = 1\n[s]\nk = 2\n").unwrap();
+    assert_eq!(reader.iter_section("").collect::<Vec<_>>(), vec![("top", "1")]);
+}
+
+#[test]
+fn test_keys_keep_their_spelling_and_empty_sections_are_skipped() {
+    let reader = IniReader::from_string("[Server]\nHost = a\nPORT = 80\n[Empty]\n[server]\nhost = b\n").unwrap();
+
+    assert_eq!(reader.iter().collect::<Vec<_>>(), vec![("Server", "Host", "a\nb"), ("Server", "PORT", "80")]);
+    assert_eq!(reader.keys("server"), vec!["host", "port"]);
+    assert_eq!(reader.sections(), vec!["Server", "Empty"]);
+}
@@ -0,0 +1,116 @@
+//! Tests for loading a file or falling back to built-in defaults
+
+use std::path::PathBuf;
+
+use inih::{ConfigSource, IniParseError, IniReader, ParseOptions};
+
+const DEFAULTS: &str = "[server]\nport = 8080\nhost = localhost\n";
+
+/// A path in the temp directory unique to this process and `name`
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("inih_load_or_default_{}_{}.ini", name, std::process::id()))
+}
+
+#[test]
+fn test_missing_file_uses_defaults() {
+    let path = temp_path("missing");
+    let _ = std::fs::remove_file(&path);
+
+    let (reader, source) = IniReader::from_file_or_default(&path, DEFAULTS, &ParseOptions::default()).unwrap();
+    assert_eq!(source, ConfigSource::BuiltinDefaults);
+    assert_eq!(reader.get_integer("server", "port", 0), 8080);
+    assert_eq!(reader.get("server", "host", ""), "localhost");
+}
+
+#[test]
+fn test_defaults_parsed_with_options() {
+    let path = temp_path("missing_options");
+    let _ = std::fs::remove_file(&path);
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+
+    let (reader, _) = IniReader::from_file_or_default(&path, "[a]\nlist = one\n  two\n", &options).unwrap();
+    assert_eq!(reader.get("a", "list", ""), "one\n  two");
+}
+
+#[test]
+fn test_present_file_is_used() {
+    let path = temp_path("valid");
+    std::fs::write(&path, "[server]\nport = 9090\n").unwrap();
+
+    let result = IniReader::from_file_or_default(&path, DEFAULTS, &ParseOptions::default());
+    std::fs::remove_file(&path).unwrap();
+    let (reader, source) = result.unwrap();
+    assert_eq!(source, ConfigSource::File(path));
+    assert_eq!(reader.get_integer("server", "port", 0), 9090);
+    // Defaults are not merged in
+    assert!(!reader.has_value("server", "host"));
+}
+
+#[test]
+fn test_invalid_file_does_not_fall_back() {
+    let path = temp_path("invalid");
+    std::fs::write(&path, "[server]\nport = 9090\n[broken\n").unwrap();
+
+    let result = IniReader::from_file_or_default(&path, DEFAULTS, &ParseOptions::default());
+    std::fs::remove_file(&path).unwrap();
+    match result {
+        Err(IniParseError::InFile { path: error_path, error }) => {
+            assert_eq!(error_path, path);
+            assert!(matches!(*error, IniParseError::ParseError { line: 3, .. }));
+        }
+        other => panic!("expected InFile, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_error_message_names_file() {
+    let path = temp_path("message");
+    std::fs::write(&path, "[broken\n").unwrap();
+
+    let result = IniReader::from_file_or_default(&path, DEFAULTS, &ParseOptions::default());
+    std::fs::remove_file(&path).unwrap();
+    let message = result.unwrap_err().to_string();
+    assert!(message.starts_with(&format!("{}: ", path.display())), "{}", message);
+}
+
+#[test]
+fn test_first_existing_search_order() {
+    let missing = temp_path("order_missing");
+    let second = temp_path("order_second");
+    let third = temp_path("order_third");
+    let _ = std::fs::remove_file(&missing);
+    std::fs::write(&second, "[server]\nport = 2\n").unwrap();
+    std::fs::write(&third, "[server]\nport = 3\n").unwrap();
+
+    let result = IniReader::from_first_existing(&[&missing, &second, &third], DEFAULTS, &ParseOptions::default());
+    std::fs::remove_file(&second).unwrap();
+    std::fs::remove_file(&third).unwrap();
+    let (reader, source) = result.unwrap();
+    assert_eq!(source, ConfigSource::File(second));
+    assert_eq!(reader.get_integer("server", "port", 0), 2);
+}
+
+#[test]
+fn test_first_existing_stops_at_invalid_file() {
+    let invalid = temp_path("stop_invalid");
+    let valid = temp_path("stop_valid");
+    std::fs::write(&invalid, "[broken\n").unwrap();
+    std::fs::write(&valid, "[server]\nport = 2\n").unwrap();
+
+    let result = IniReader::from_first_existing(&[&invalid, &valid], DEFAULTS, &ParseOptions::default());
+    std::fs::remove_file(&invalid).unwrap();
+    std::fs::remove_file(&valid).unwrap();
+    assert!(matches!(result, Err(IniParseError::InFile { .. })));
+}
+
+#[test]
+fn test_first_existing_none_found() {
+    let paths = [temp_path("none_a"), temp_path("none_b")];
+    for path in &paths {
+        let _ = std::fs::remove_file(path);
+    }
+
+    let (reader, source) = IniReader::from_first_existing(&paths, DEFAULTS, &ParseOptions::default()).unwrap();
+    assert_eq!(source, ConfigSource::BuiltinDefaults);
+    assert_eq!(reader.get_integer("server", "port", 0), 8080);
+}
@@ -0,0 +1,100 @@
+//! Tests for loading an application's configuration from a search order
+
+use std::path::PathBuf;
+
+use inih::load::{load_all_with, load_with, SearchOrder, SystemSearchOrder};
+use inih::{IniParseError, ParseOptions};
+
+/// Three scopes under a temp directory unique to this process and `name`
+struct TempScopes {
+    root: PathBuf,
+}
+
+impl TempScopes {
+    fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("inih_load_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for scope in ["local", "user", "system"] {
+            std::fs::create_dir_all(root.join(scope)).unwrap();
+        }
+        Self { root }
+    }
+
+    fn write(&self, scope: &str, app_name: &str, data: &str) -> PathBuf {
+        let path = self.root.join(scope).join(format!("{}.ini", app_name));
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+}
+
+impl SearchOrder for TempScopes {
+    fn paths(&self, app_name: &str) -> Vec<PathBuf> {
+        ["local", "user", "system"].iter().map(|scope| self.root.join(scope).join(format!("{}.ini", app_name))).collect()
+    }
+}
+
+impl Drop for TempScopes {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+#[test]
+fn test_found_in_second_location() {
+    let scopes = TempScopes::new("second");
+    let user = scopes.write("user", "tool", "[server]\nport = 9090\n");
+    scopes.write("system", "tool", "[server]\nport = 80\n");
+
+    let (reader, path) = load_with("tool", &scopes, &ParseOptions::default()).unwrap();
+    assert_eq!(path, user);
+    assert_eq!(reader.get_integer("server", "port", 0), 9090);
+}
+
+#[test]
+fn test_nothing_found_lists_paths_tried() {
+    let scopes = TempScopes::new("nothing");
+    let tried = scopes.paths("tool");
+
+    let error = load_with("tool", &scopes, &ParseOptions::default()).unwrap_err();
+    assert_eq!(error, IniParseError::NotFound { tried: tried.clone() });
+    let message = error.to_string();
+    assert!(message.starts_with("No configuration file found; tried: "));
+    for path in &tried {
+        assert!(message.contains(&path.display().to_string()));
+    }
+    assert!(matches!(load_all_with("tool", &scopes, &ParseOptions::default()), Err(IniParseError::NotFound { .. })));
+}
+
+#[test]
+fn test_invalid_file_stops_the_search() {
+    let scopes = TempScopes::new("invalid");
+    let local = scopes.write("local", "tool", "[broken\n");
+    scopes.write("user", "tool", "[server]\nport = 9090\n");
+
+    let error = load_with("tool", &scopes, &ParseOptions::default()).unwrap_err();
+    assert!(matches!(error, IniParseError::InFile { ref path, .. } if *path == local));
+}
+
+#[test]
+fn test_load_all_narrower_scope_wins() {
+    let scopes = TempScopes::new("all");
+    let system = scopes.write("system", "tool", "[server]\nhost = example.org\nport = 80\n[log]\nlevel = warn\n");
+    let local = scopes.write("local", "tool", "[server]\nport = 8080\n");
+
+    let (reader, used) = load_all_with("tool", &scopes, &ParseOptions::default()).unwrap();
+    assert_eq!(used, [system, local]);
+    assert_eq!(reader.get_integer("server", "port", 0), 8080);
+    assert_eq!(reader.get("server", "host", ""), "example.org");
+    assert_eq!(reader.get("log", "level", ""), "warn");
+}
+
+#[test]
+fn test_system_order_names_the_app() {
+    let paths = SystemSearchOrder.paths("tool");
+    assert_eq!(paths[0], std::env::current_dir().unwrap().join("tool.ini"));
+    assert!(paths.iter().all(|path| path.file_name().unwrap() == "tool.ini"));
+    assert!(paths.iter().enumerate().all(|(i, path)| !paths[..i].contains(path)));
+    if cfg!(unix) {
+        assert_eq!(paths.last().unwrap(), &PathBuf::from("/etc/tool/tool.ini"));
+    }
+}
@@ -0,0 +1,55 @@
+//! Tests for `IniReader::shrink_to_fit` and `approx_memory_bytes`
+
+use inih::{DuplicateKeyPolicy, IniReader, ParseOptions};
+
+/// A reader whose tables grew for many entries that were removed again
+fn churned() -> IniReader {
+    let data: String = (0..500).map(|i| format!("[s{}]\nkey{} = value {}\n", i, i, i)).collect();
+    let mut reader = IniReader::from_string(&data).unwrap();
+    for i in 10..500 {
+        reader.remove_section(&format!("s{}", i));
+    }
+    reader
+}
+
+#[test]
+fn test_shrink_reduces_estimate() {
+    let mut reader = churned();
+    let before = reader.approx_memory_bytes();
+    reader.shrink_to_fit();
+    let after = reader.approx_memory_bytes();
+    assert!(after < before / 4, "{} bytes before, {} after", before, after);
+
+    // Nothing is lost and a second shrink changes nothing
+    assert_eq!(reader.sections().len(), 10);
+    assert_eq!(reader.get("s3", "key3", ""), "value 3");
+    reader.shrink_to_fit();
+    assert_eq!(reader.approx_memory_bytes(), after);
+}
+
+#[test]
+fn test_estimate_counts_occurrences_and_document() {
+    let data: String = (0..100).map(|i| format!("k = {}\n", i)).collect();
+    let options = ParseOptions { duplicate_key_policy: DuplicateKeyPolicy::LastWins, ..ParseOptions::default() };
+    let single = IniReader::from_string_with_options("k = 99\n", &options).unwrap();
+    let repeated = IniReader::from_string_with_options(&data, &options).unwrap();
+    assert_eq!(repeated.get("", "k", ""), single.get("", "k", ""));
+    assert!(repeated.approx_memory_bytes() > single.approx_memory_bytes() + 100 * 2);
+
+    let kept = IniReader::from_string_preserving(&data, &options).unwrap();
+    assert!(kept.approx_memory_bytes() > repeated.approx_memory_bytes() + data.len());
+}
+
+#[test]
+fn test_shared_tables_left_alone() {
+    let mut reader = churned();
+    let clone = reader.clone();
+    let before = reader.approx_memory_bytes();
+    reader.shrink_to_fit();
+    assert_eq!(reader.shared_storage_count(), 2);
+    assert_eq!(clone.approx_memory_bytes(), before);
+    assert!(reader.approx_memory_bytes() <= before);
+
+    let empty = IniReader::with_capacity(0, 0);
+    assert_eq!(empty.approx_memory_bytes(), 0);
+}
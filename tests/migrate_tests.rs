@@ -0,0 +1,185 @@
+//! Tests for configuration migrations
+
+use inih::migrate::{apply_migrations, apply_migrations_with, ConflictPolicy, Migration, MigrationStatus};
+use inih::IniReader;
+
+const OLD: &str = "[net]\nhost = example.com\nPort = 80\ndebug = true\n[server]\nname = web\n[legacy]\nold = 1\n";
+
+fn reader() -> IniReader {
+    IniReader::from_string(OLD).unwrap()
+}
+
+fn entries(reader: &IniReader) -> Vec<String> {
+    reader.iter().map(|(section, key, value)| format!("[{}] {} = {}", section, key, value)).collect()
+}
+
+#[test]
+fn test_move_key() {
+    let mut reader = reader();
+    let report = apply_migrations(&mut reader, &[Migration::move_key("NET", "port", "server", "listen_port")]);
+
+    assert_eq!(report.statuses, vec![MigrationStatus::Applied]);
+    assert!(!reader.has_value("net", "port"));
+    assert_eq!(reader.get_integer("server", "listen_port", 0), 80);
+    // The occurrence keeps its line and takes the new spelling
+    let occurrences = reader.occurrences("server", "listen_port");
+    assert_eq!((occurrences[0].name.as_str(), occurrences[0].line), ("listen_port", 3));
+}
+
+#[test]
+fn test_move_key_into_new_section() {
+    let mut reader = reader();
+    apply_migrations(&mut reader, &[Migration::move_key("net", "host", "upstream", "host")]);
+
+    assert_eq!(reader.sections(), vec!["net", "server", "legacy", "upstream"]);
+    assert_eq!(reader.get("upstream", "host", ""), "example.com");
+    assert!(reader.to_ini_string().ends_with("[upstream]\nhost = example.com\n"));
+}
+
+#[test]
+fn test_rename_key_keeps_position() {
+    let mut reader = reader();
+    apply_migrations(&mut reader, &[Migration::rename_key("net", "host", "hostname")]);
+
+    assert_eq!(
+        entries(&reader)[..3],
+        ["[net] hostname = example.com", "[net] Port = 80", "[net] debug = true"]
+    );
+}
+
+#[test]
+fn test_rename_section() {
+    let mut reader = reader();
+    let report = apply_migrations(
+        &mut reader,
+        &[Migration::RenameSection { section: "Net".to_string(), to_section: "network".to_string() }],
+    );
+
+    assert_eq!(report.applied(), vec![0]);
+    assert!(!reader.has_section("net"));
+    assert_eq!(reader.sections(), vec!["network", "server", "legacy"]);
+    assert_eq!(reader.keys("network"), vec!["host", "port", "debug"]);
+    assert_eq!(entries(&reader)[0], "[network] host = example.com");
+}
+
+#[test]
+fn test_delete_key_and_section() {
+    let mut reader = reader();
+    let report = apply_migrations(
+        &mut reader,
+        &[
+            Migration::DeleteKey { section: "net".to_string(), name: "DEBUG".to_string() },
+            Migration::DeleteSection { section: "legacy".to_string() },
+        ],
+    );
+
+    assert_eq!(report.applied(), vec![0, 1]);
+    assert!(!reader.has_value("net", "debug"));
+    assert!(!reader.has_section("legacy"));
+    assert!(reader.occurrences("net", "debug").is_empty());
+}
+
+#[test]
+fn test_map_value_after_rename() {
+    let mut reader = reader();
+    let report = apply_migrations(
+        &mut reader,
+        &[
+            Migration::rename_key("net", "debug", "log_level"),
+            Migration::map_value("net", "log_level", |value| match value {
+                "true" => "debug".to_string(),
+                _ => "info".to_string(),
+            }),
+        ],
+    );
+
+    assert_eq!(report.applied(), vec![0, 1]);
+    assert_eq!(reader.get("net", "log_level", ""), "debug");
+    assert_eq!(reader.get_all("net", "log_level"), vec!["debug"]);
+    assert_eq!(reader.occurrences("net", "log_level")[0].line, 4);
+}
+
+#[test]
+fn test_missing_sources_are_skipped() {
+    let mut reader = reader();
+    let before = entries(&reader);
+    let migrations = [
+        Migration::move_key("net", "missing", "server", "x"),
+        Migration::rename_key("nowhere", "a", "b"),
+        Migration::RenameSection { section: "nowhere".to_string(), to_section: "x".to_string() },
+        Migration::DeleteKey { section: "net".to_string(), name: "missing".to_string() },
+        Migration::DeleteSection { section: "nowhere".to_string() },
+        Migration::map_value("net", "missing", |_| unreachable!()),
+    ];
+    let report = apply_migrations(&mut reader, &migrations);
+
+    assert_eq!(report.skipped(), vec![0, 1, 2, 3, 4, 5]);
+    assert_eq!(entries(&reader), before);
+}
+
+const CONFLICTING: &str = "[a]\nport = 1\nonly_a = x\n[b]\nport = 2\nonly_b = y\n";
+
+#[test]
+fn test_key_conflict_keep_existing() {
+    let mut reader = IniReader::from_string(CONFLICTING).unwrap();
+    let report = apply_migrations(&mut reader, &[Migration::move_key("a", "port", "b", "port")]);
+
+    assert_eq!(report.statuses, vec![MigrationStatus::Conflicted { overwritten: false }]);
+    assert_eq!(report.conflicted(), vec![0]);
+    assert_eq!(reader.get("a", "port", ""), "1");
+    assert_eq!(reader.get("b", "port", ""), "2");
+}
+
+#[test]
+fn test_key_conflict_overwrite() {
+    let mut reader = IniReader::from_string(CONFLICTING).unwrap();
+    let report = apply_migrations_with(&mut reader, &[Migration::move_key("a", "port", "b", "port")], ConflictPolicy::Overwrite);
+
+    assert_eq!(report.statuses, vec![MigrationStatus::Conflicted { overwritten: true }]);
+    assert!(!reader.has_value("a", "port"));
+    assert_eq!(reader.get("b", "port", ""), "1");
+    assert_eq!(reader.occurrences("b", "port")[0].line, 2);
+}
+
+#[test]
+fn test_section_conflict_under_both_policies() {
+    let rename = [Migration::RenameSection { section: "a".to_string(), to_section: "B".to_string() }];
+
+    let mut kept = IniReader::from_string(CONFLICTING).unwrap();
+    let report = apply_migrations_with(&mut kept, &rename, ConflictPolicy::KeepExisting);
+    assert_eq!(report.statuses, vec![MigrationStatus::Conflicted { overwritten: false }]);
+    assert_eq!(kept.sections(), vec!["a", "b"]);
+
+    let mut merged = IniReader::from_string(CONFLICTING).unwrap();
+    let report = apply_migrations_with(&mut merged, &rename, ConflictPolicy::Overwrite);
+    assert_eq!(report.statuses, vec![MigrationStatus::Conflicted { overwritten: true }]);
+    assert_eq!(merged.sections(), vec!["b"]);
+    // Merged keys keep their places; `port` keeps the one it had in [b]
+    assert_eq!(entries(&merged), vec!["[b] only_a = x", "[b] port = 1", "[b] only_b = y"]);
+}
+
+#[test]
+fn test_renaming_onto_itself_is_not_a_conflict() {
+    let mut reader = reader();
+    let report = apply_migrations(
+        &mut reader,
+        &[
+            Migration::rename_key("net", "Port", "PORT"),
+            Migration::RenameSection { section: "server".to_string(), to_section: "Server".to_string() },
+        ],
+    );
+
+    assert_eq!(report.applied(), vec![0, 1]);
+    assert_eq!(reader.occurrences("net", "port")[0].name, "PORT");
+    assert_eq!(reader.sections(), vec!["net", "Server", "legacy"]);
+    assert_eq!(reader.get("server", "name", ""), "web");
+}
+
+#[test]
+fn test_preserved_document_is_dropped() {
+    let mut reader = IniReader::from_string_preserving(OLD, &Default::default()).unwrap();
+    apply_migrations(&mut reader, &[Migration::DeleteKey { section: "net".to_string(), name: "debug".to_string() }]);
+
+    assert!(reader.document().is_none());
+    assert_eq!(Migration::move_key("a", "b", "c", "d").to_string(), "move [a] b to [c] d");
+}
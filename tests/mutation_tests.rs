@@ -0,0 +1,194 @@
+//! Tests for changing a reader and saving the result
+
+use inih::document::IniDocument;
+use inih::interpolation::Interpolation;
+use inih::{DuplicateKeyPolicy, IniReader, ParseOptions, ReaderOptions};
+
+const DATA: &str = "[server]\nHost = example.com\nport = 80\n\n[cache]\nttl = 60\n";
+
+fn reader() -> IniReader {
+    IniReader::from_string(DATA).unwrap()
+}
+
+#[test]
+fn test_set_overwrites() {
+    let mut reader = reader();
+    reader.set("server", "port", "8080");
+    reader.set("server", "port", "9090");
+
+    assert_eq!(reader.get("server", "port", ""), "9090");
+    assert_eq!(reader.get_all("server", "port"), ["9090"]);
+    assert_eq!(reader.keys("server"), ["host", "port"]);
+}
+
+#[test]
+fn test_set_matches_case_insensitively() {
+    let mut reader = reader();
+    reader.set("SERVER", " HOST ", "example.org");
+
+    assert_eq!(reader.get("server", "host", ""), "example.org");
+    assert_eq!(reader.sections(), ["server", "cache"]);
+    assert_eq!(reader.keys("server"), ["host", "port"]);
+    assert_eq!(reader.to_ini_string(), "[server]\nhost = example.org\nport = 80\n\n[cache]\nttl = 60\n");
+}
+
+#[test]
+fn test_set_creates_section() {
+    let mut reader = reader();
+    assert!(!reader.has_section("log"));
+    reader.set("log", "level", "debug");
+
+    assert!(reader.has_section("log"));
+    assert_eq!(reader.sections(), ["server", "cache", "log"]);
+    assert_eq!(reader.keys("log"), ["level"]);
+    assert!(reader.to_ini_string().ends_with("[log]\nlevel = debug\n"));
+}
+
+#[test]
+fn test_remove() {
+    let mut reader = reader();
+    assert_eq!(reader.remove("Server", "HOST"), Some("example.com".to_string()));
+    assert_eq!(reader.remove("server", "host"), None);
+
+    assert!(!reader.has_value("server", "host"));
+    assert!(reader.occurrences("server", "host").is_empty());
+    assert_eq!(reader.keys("server"), ["port"]);
+}
+
+#[test]
+fn test_removing_last_key_keeps_section() {
+    let mut reader = reader();
+    assert_eq!(reader.remove("cache", "ttl"), Some("60".to_string()));
+
+    assert!(reader.has_section("cache"));
+    assert!(reader.keys("cache").is_empty());
+    assert_eq!(reader.sections(), ["server", "cache"]);
+    assert_eq!(reader.to_ini_string(), "[server]\nhost = example.com\nport = 80\n\n[cache]\n");
+}
+
+#[test]
+fn test_remove_section() {
+    let mut reader = reader();
+    assert!(reader.remove_section("CACHE"));
+    assert!(!reader.remove_section("cache"));
+
+    assert!(!reader.has_section("cache"));
+    assert!(!reader.has_value("cache", "ttl"));
+    assert_eq!(reader.sections(), ["server"]);
+}
+
+#[test]
+fn test_save_to() {
+    let mut reader = reader();
+    reader.set("server", "port", "8080");
+    reader.remove("server", "host");
+    reader.remove_section("cache");
+
+    let mut out = Vec::new();
+    reader.save_to(&mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "[server]\nport = 8080\n");
+}
+
+#[test]
+fn test_mutation_drops_preserved_document() {
+    let mut reader = IniReader::from_string_preserving("; comment\n[server]\nport = 80\n", &Default::default()).unwrap();
+    assert_eq!(reader.remove("server", "missing"), None);
+    assert!(reader.document().is_some());
+
+    reader.set("server", "port", "81");
+    assert!(reader.document().is_none());
+    assert_eq!(reader.to_ini_string(), "[server]\nport = 81\n");
+}
+
+/// Replace `old.example.com` in a value, None if it is not there
+fn rehost(value: &str) -> Option<String> {
+    value.contains("old.example.com").then(|| value.replace("old.example.com", "new.example.com"))
+}
+
+const HOSTS: &str = "[db]\nhost = old.example.com   ; primary\nport = 5432\n\n[mirrors]\nurl = https://old.example.com/a\nurl = https://other.org/b\nurl=https://old.example.com/c\n";
+
+#[test]
+fn test_map_values_each_occurrence() {
+    let mut reader = IniReader::from_string(HOSTS).unwrap();
+    let mut seen = Vec::new();
+    let changed = reader.map_values_in_place(|section, name, value| {
+        seen.push(format!("{}.{}", section, name));
+        rehost(value)
+    });
+    assert_eq!(changed, 3);
+    assert_eq!(seen, ["db.host", "db.port", "mirrors.url", "mirrors.url", "mirrors.url"]);
+    assert_eq!(reader.get("db", "host", ""), "new.example.com");
+    assert_eq!(reader.get("db", "port", ""), "5432");
+    assert_eq!(reader.get_all("mirrors", "url"), ["https://new.example.com/a", "https://other.org/b", "https://new.example.com/c"]);
+    assert_eq!(reader.get("mirrors", "url", ""), "https://new.example.com/a\nhttps://other.org/b\nhttps://new.example.com/c");
+    assert_eq!(reader.occurrences("mirrors", "url")[2].line, 8);
+
+    // Nothing matches any more
+    assert_eq!(reader.map_values_in_place(|_, _, value| rehost(value)), 0);
+
+    let options = ParseOptions { duplicate_key_policy: DuplicateKeyPolicy::LastWins, ..ParseOptions::default() };
+    let mut last = IniReader::from_string_with_options(HOSTS, &options).unwrap();
+    assert_eq!(last.map_values_in_place(|_, _, value| rehost(value)), 3);
+    assert_eq!(last.get("mirrors", "url", ""), "https://new.example.com/c");
+}
+
+#[test]
+fn test_map_values_interpolates_again() {
+    let options = ReaderOptions { interpolation: Interpolation::Extended, strict_interpolation: true, ..ReaderOptions::default() };
+    let data = "[db]\nhost = old.example.com\nurl = https://${host}/db\nmirror = ${url}\nbackup = ${host}/${nowhere}\n";
+    let mut reader = IniReader::from_string_with_reader_options(data, &ParseOptions::default(), options).unwrap();
+    let mut seen = Vec::new();
+    let changed = reader.map_values_in_place(|_, name, value| {
+        seen.push(value.to_string());
+        match name {
+            "host" => rehost(value),
+            _ => None,
+        }
+    });
+    assert_eq!(changed, 1);
+    // The closure gets the values as written
+    assert_eq!(seen, ["old.example.com", "https://${host}/db", "${url}", "${host}/${nowhere}"]);
+    assert_eq!(reader.get("db", "url", ""), "https://new.example.com/db");
+    assert_eq!(reader.get("db", "mirror", ""), "https://new.example.com/db");
+    assert_eq!(reader.explain("db", "url").raw_value.as_deref(), Some("https://${host}/db"));
+    // Errors already reported are not reported again
+    assert_eq!(reader.get("db", "backup", ""), "new.example.com/${nowhere}");
+    assert_eq!(reader.interpolation_errors().len(), 1);
+
+    // A rewritten value is interpolated itself
+    reader.map_values_in_place(|_, name, _| (name == "host").then(|| "${missing}.org".to_string()));
+    assert_eq!(reader.get("db", "host", ""), "${missing}.org");
+    assert_eq!(reader.interpolation_errors()[1].to_string(), "unknown reference ${missing} in db.host");
+    reader.map_values_in_place(|_, name, _| (name == "host").then(|| "$${missing}.org".to_string()));
+    assert_eq!(reader.get("db", "mirror", ""), "https://${missing}.org/db");
+    assert_eq!(reader.interpolation_errors().len(), 2);
+}
+
+#[test]
+fn test_map_values_keeps_document_formatting() {
+    let mut reader = IniReader::from_string_preserving(HOSTS, &ParseOptions::default()).unwrap();
+    assert_eq!(reader.map_values_in_place(|_, _, value| rehost(value)), 3);
+    assert!(reader.document().is_some());
+    let expected = HOSTS.replace("old.example.com", "new.example.com");
+    assert_eq!(reader.to_ini_string(), expected);
+
+    // A change the document cannot follow drops it
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    let mut reader = IniReader::from_string_preserving("[a]\nhosts = old.example.com\n  b\n", &options).unwrap();
+    assert_eq!(reader.map_values_in_place(|_, _, value| rehost(value)), 1);
+    assert!(reader.document().is_none());
+    assert_eq!(reader.get("a", "hosts", ""), "new.example.com\n  b");
+}
+
+#[test]
+fn test_document_map_values() {
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    let input = "; hosts\n[a]\nx=old.example.com ; first\nlist = one\n    old.example.com\nquoted = \"old.example.com\"\nempty:\nkeep = 1\n";
+    let mut document = IniDocument::parse(input, &options).unwrap();
+    let changed = document.map_values_in_place(|_, name, value| if name == "empty" { Some("set".to_string()) } else { rehost(value) });
+    assert_eq!(changed, 4);
+    assert_eq!(
+        document.to_string(),
+        "; hosts\n[a]\nx=new.example.com ; first\nlist = one\n    new.example.com\nquoted = \"new.example.com\"\nempty = set\nkeep = 1\n"
+    );
+}
@@ -0,0 +1,131 @@
+//! Deterministic output of the hash-backed types
+//!
+//! Every public output path gives the same result from run to run, whatever
+//! the hash order of the storage behind it:
+//!
+//! - `Debug` of `IniReader`, `MapHandler` and `IniStrReader` prints sections
+//!   and keys sorted;
+//! - `Display`, `to_ini_string` and `iter()` follow document order;
+//! - `sections()` and `keys()` follow document order, and `export_env` follows `keys()`;
+//! - `get_all`, `occurrences` and `warnings` are in the order things occurred.
+//!
+//! Only the `HashMap`s returned by `as_map` and `MapHandler::map` are
+//! unordered, as their type says.
+
+use inih::handlers::MapHandler;
+use inih::layered::LayeredReader;
+use inih::parser::ini_parse_string;
+use inih::str_reader::IniStrReader;
+use std::time::{Duration, Instant};
+
+use inih::{DuplicateKeyPolicy, IniHandler, IniReader, ParseOptions};
+
+const DATA: &str = "top = 0\n[beta]\nz = 1\ny = 2\nx = 3\n[Alpha]\nq = 4\np = 5\n[gamma]\n";
+const REORDERED: &str = "top = 0\n[gamma]\n[Alpha]\np = 5\nq = 4\n[beta]\nx = 3\ny = 2\nz = 1\n";
+
+fn debug<T: std::fmt::Debug>(value: &T) -> String {
+    format!("{:?}", value)
+}
+
+#[test]
+fn test_reader_debug_is_stable() {
+    // Each map gets its own random hash seed, so two fresh readers would
+    // disagree if Debug followed hash order
+    let first = debug(&IniReader::from_string(DATA).unwrap());
+    for _ in 0..10 {
+        assert_eq!(debug(&IniReader::from_string(DATA).unwrap()), first);
+    }
+    let values = first.find("values").unwrap();
+    assert!(first[values..].find("alpha=p").unwrap() < first[values..].find("beta=x").unwrap());
+    assert!(first.find("\"Alpha\"").unwrap() < first.find("\"beta\"").unwrap());
+}
+
+#[test]
+fn test_debug_does_not_depend_on_document_order() {
+    assert_eq!(debug(&IniReader::from_string(DATA).unwrap()), debug(&IniReader::from_string(REORDERED).unwrap()));
+
+    let mut first = MapHandler::new();
+    ini_parse_string(DATA, &mut first).unwrap();
+    let mut second = MapHandler::new();
+    ini_parse_string(REORDERED, &mut second).unwrap();
+    assert_eq!(debug(&first), debug(&second));
+    assert!(debug(&first).starts_with("MapHandler { map: {\"\": {\"top\": \"0\"}, \"Alpha\": {\"p\": \"5\", \"q\": \"4\"}"));
+
+    let options = ParseOptions::default();
+    let first = IniStrReader::from_str(DATA, &options).unwrap();
+    let second = IniStrReader::from_str(REORDERED, &options).unwrap();
+    assert_eq!(debug(&first), debug(&second));
+}
+
+#[test]
+fn test_vec_outputs_are_ordered() {
+    let reader = IniReader::from_string(DATA).unwrap();
+    let other = IniReader::from_string("[delta]\n[beta]\nw = 0\n").unwrap();
+
+    assert_eq!(reader.sections(), vec!["beta", "Alpha", "gamma"]);
+    assert_eq!(reader.keys("beta"), vec!["z", "y", "x"]);
+    let names: Vec<String> = reader.export_env("beta", "B_").unwrap().into_iter().map(|(name, _)| name).collect();
+    assert_eq!(names, vec!["B_Z", "B_Y", "B_X"]);
+
+    let layered = LayeredReader::new(vec![&other, &reader]);
+    assert_eq!(layered.sections(), vec!["Alpha", "beta", "delta", "gamma"]);
+    assert_eq!(layered.keys("beta"), vec!["w", "x", "y", "z"]);
+
+    let entries: Vec<_> = reader.iter().map(|(section, key, _)| format!("{}.{}", section, key)).collect();
+    assert_eq!(entries, vec![".top", "beta.z", "beta.y", "beta.x", "Alpha.q", "Alpha.p"]);
+}
+
+#[test]
+fn test_repeated_sections_and_keys_keep_first_position() {
+    let data = "[b]\nk2 = 1\nk1 = 2\n[a]\nx = 3\n[B]\nk3 = 4\nK1 = 5\n";
+    for policy in [
+        DuplicateKeyPolicy::LastWins,
+        DuplicateKeyPolicy::FirstWins,
+        DuplicateKeyPolicy::Concatenate { separator: ",".to_string() },
+    ] {
+        let options = ParseOptions { duplicate_key_policy: policy.clone(), ..ParseOptions::default() };
+        let reader = IniReader::from_string_with_options(data, &options).unwrap();
+
+        assert_eq!(reader.sections(), vec!["b", "a"], "{:?}", policy);
+        assert_eq!(reader.keys("b"), vec!["k2", "k1", "k3"], "{:?}", policy);
+    }
+}
+
+#[test]
+fn test_handler_calls_keep_document_order() {
+    let mut reader = IniReader::with_capacity(0, 0);
+    reader.handle("zeta", "b", "1").unwrap();
+    reader.handle("alpha", "a", "2").unwrap();
+    reader.handle("zeta", "a", "3").unwrap();
+    assert_eq!(reader.sections(), vec!["zeta", "alpha"]);
+    assert_eq!(reader.keys("zeta"), vec!["b", "a"]);
+
+    // A later document appends what is new after what is there
+    ini_parse_string("[mid]\nq = 4\n[zeta]\nc = 5\nb = 6\n", &mut reader).unwrap();
+    assert_eq!(reader.sections(), vec!["zeta", "alpha", "mid"]);
+    assert_eq!(reader.keys("zeta"), vec!["b", "a", "c"]);
+}
+
+#[test]
+#[ignore = "stress test, run with --ignored --nocapture"]
+fn stress_many_one_key_sections() {
+    const SECTIONS: usize = 100_000;
+    let data: String = (0..SECTIONS).map(|i| format!("[s{}]\nkey = {}\n", i, i)).collect();
+    let reader = IniReader::from_string(&data).unwrap();
+
+    // Listing every section's keys in a loop must not cost a scan of the whole reader each time
+    let start = Instant::now();
+    let mut keys = 0;
+    for (name, section) in reader.sections_iter() {
+        keys += reader.keys(name).len();
+        assert_eq!(section.len(), 1);
+    }
+    assert_eq!(reader.sections().len(), SECTIONS);
+    assert_eq!(reader.iter().count(), SECTIONS);
+    let elapsed = start.elapsed();
+
+    println!("{} sections listed in {:?}", SECTIONS, elapsed);
+    assert_eq!(keys, SECTIONS);
+    assert_eq!(reader.sections()[SECTIONS - 1], format!("s{}", SECTIONS - 1));
+    assert!(elapsed < Duration::from_secs(10), "{:?}", elapsed);
+}
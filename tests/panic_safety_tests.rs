@@ -0,0 +1,111 @@
+//! Tests that no input makes the parsers or readers panic
+
+use std::panic;
+
+use inih::document::IniDocument;
+use inih::{ini_parse_string_with_options, CommentPrefixes, IniHandler, IniReader, ParseOptions};
+
+/// Handler that accepts everything, so only the parser itself can fail
+struct Sink;
+
+impl IniHandler for Sink {
+    fn handle(&mut self, _section: &str, _name: &str, _value: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Option sets covering every branch that slices the line
+fn option_sets() -> Vec<ParseOptions> {
+    let base = ParseOptions { max_line: 10_000, ..ParseOptions::default() };
+    vec![
+        base.clone(),
+        ParseOptions { allow_inline_comments: true, inline_comment_prefixes: CommentPrefixes::chars("§;€"), ..base.clone() },
+        ParseOptions { allow_no_value: true, allow_inline_comments: true, inline_comment_prefixes: CommentPrefixes::strings(["😀", "//"]), ..base.clone() },
+        ParseOptions { start_comment_prefixes: CommentPrefixes::strings(["#", "é", "--"]), allow_bom: false, ..base.clone() },
+        ParseOptions {
+            allow_quoted_subsections: true,
+            allow_section_inheritance: true,
+            allow_section_profiles: true,
+            allow_raw_section_annotations: true,
+            ..base
+        },
+    ]
+}
+
+/// Run every parsing entry point on the input, failing with the input if any of them panics
+fn assert_no_panic(input: &str) {
+    for options in option_sets() {
+        let result = panic::catch_unwind(|| {
+            let _ = ini_parse_string_with_options(input, &mut Sink, &options);
+            let _ = IniReader::from_string_with_options(input, &options);
+            let _ = IniDocument::parse(input, &options);
+            let _ = IniReader::from_bytes(input.as_bytes(), &options);
+        });
+        assert!(result.is_ok(), "parser panicked on {:?} with {:?}", input, options);
+    }
+}
+
+#[test]
+fn test_corpus_of_nasty_inputs() {
+    let corpus = [
+        "é=ü\nñ:ß",
+        "name=€\nname€=x\n€=€",
+        "key §comment\nkey=value §€\nkey=value😀 😀",
+        "name §=value\nname 😀:value",
+        "\u{FEFF}é=1",
+        "\u{FEFF}[ß]\nx=y",
+        "\u{FEFF}\u{FEFF}[a]",
+        "[section]\nkey=value\n😀continued\n\u{3000}ideographic indent §x\n\u{2003}em-space indent ; note",
+        "[ß]\n[ü:ö]\n[é @ ñ, ø]\n[ä \"ö\"]\n[ñ extra ü]",
+        "[",
+        "[]",
+        "[😀",
+        "[😀]",
+        "[\u{FEFF}]",
+        "[a §]b]",
+        "=",
+        ":",
+        "==::",
+        "§",
+        "a=\n\u{3000}\n\u{2003}§",
+        " ",
+        "\u{0}=\u{0}",
+    ];
+    for input in corpus {
+        assert_no_panic(input);
+    }
+}
+
+#[test]
+fn test_lone_surrogates_are_errors() {
+    let options = ParseOptions::default();
+    let lone_high = [0xFF, 0xFE, b'a', 0x00, 0x00, 0xD8, b'=', 0x00];
+    let lone_low = [0xFE, 0xFF, 0x00, b'a', 0xDC, 0x00, 0x00, b'='];
+    for bytes in [&lone_high[..], &lone_low[..], &[0xFF, 0xFE, b'a'][..], &[0xC3][..]] {
+        let result = panic::catch_unwind(|| IniReader::from_bytes(bytes, &options));
+        assert!(result.expect("from_bytes panicked").is_err());
+    }
+}
+
+#[test]
+fn test_randomized_inputs() {
+    const ALPHABET: &[&str] = &[
+        "a", "Z", "0", " ", "\t", "\n", "=", ":", "[", "]", "\"", "@", ",", ";", "#", "§", "€", "é", "😀",
+        "\u{FEFF}", "\u{3000}", "\u{2003}", "\r\n",
+    ];
+
+    // xorshift64 with a fixed seed so failures are reproducible
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+
+    for _ in 0..500 {
+        let len = (next() % 40) as usize;
+        let input: String = (0..len).map(|_| ALPHABET[(next() % ALPHABET.len() as u64) as usize]).collect();
+        assert_no_panic(&input);
+    }
+}
@@ -0,0 +1,84 @@
+//! Tests for path values (`get_path`, `expand_path`, `BaseDirs`)
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use inih::path::{expand_path, BaseDirs};
+use inih::IniReader;
+
+/// Environment holding only the given variables
+struct FakeDirs(HashMap<&'static str, &'static str>);
+
+impl FakeDirs {
+    fn new(vars: &[(&'static str, &'static str)]) -> Self {
+        FakeDirs(vars.iter().copied().collect())
+    }
+}
+
+impl BaseDirs for FakeDirs {
+    fn var(&self, name: &str) -> Option<OsString> {
+        self.0.get(name).map(OsString::from)
+    }
+}
+
+#[test]
+fn test_xdg_unset_uses_fallbacks() {
+    let dirs = FakeDirs::new(&[("HOME", "/home/ann")]);
+    assert_eq!(expand_path("~", &dirs), PathBuf::from("/home/ann"));
+    assert_eq!(expand_path("~/notes", &dirs), PathBuf::from("/home/ann/notes"));
+    assert_eq!(expand_path("$XDG_CONFIG_HOME/app/app.ini", &dirs), PathBuf::from("/home/ann/.config/app/app.ini"));
+    assert_eq!(expand_path("${XDG_DATA_HOME}/app", &dirs), PathBuf::from("/home/ann/.local/share/app"));
+    assert_eq!(expand_path("$XDG_STATE_HOME/log", &dirs), PathBuf::from("/home/ann/.local/state/log"));
+    assert_eq!(expand_path("$XDG_CACHE_HOME", &dirs), PathBuf::from("/home/ann/.cache"));
+    // No fallback for the runtime directory
+    assert_eq!(expand_path("$XDG_RUNTIME_DIR/app.sock", &dirs), PathBuf::from("$XDG_RUNTIME_DIR/app.sock"));
+}
+
+#[test]
+fn test_xdg_set_wins_unless_relative_or_empty() {
+    let dirs = FakeDirs::new(&[
+        ("HOME", "/home/ann"),
+        ("XDG_CONFIG_HOME", "/etc/ann"),
+        ("XDG_DATA_HOME", "relative/data"),
+        ("XDG_CACHE_HOME", ""),
+        ("XDG_RUNTIME_DIR", "/run/user/1000"),
+    ]);
+    assert_eq!(expand_path("$XDG_CONFIG_HOME/app.ini", &dirs), PathBuf::from("/etc/ann/app.ini"));
+    assert_eq!(expand_path("$XDG_DATA_HOME/app", &dirs), PathBuf::from("/home/ann/.local/share/app"));
+    assert_eq!(expand_path("$XDG_CACHE_HOME/app", &dirs), PathBuf::from("/home/ann/.cache/app"));
+    assert_eq!(expand_path("$XDG_RUNTIME_DIR/app.sock", &dirs), PathBuf::from("/run/user/1000/app.sock"));
+}
+
+#[test]
+fn test_windows_names() {
+    let dirs = FakeDirs::new(&[("USERPROFILE", "C:/Users/ann"), ("APPDATA", "D:/Roaming")]);
+    assert_eq!(expand_path("%APPDATA%/app/app.ini", &dirs), PathBuf::from("D:/Roaming/app/app.ini"));
+    assert_eq!(expand_path("%LOCALAPPDATA%/app", &dirs), PathBuf::from("C:/Users/ann/AppData/Local/app"));
+    assert_eq!(expand_path("%USERPROFILE%/x", &dirs), PathBuf::from("C:/Users/ann/x"));
+    assert_eq!(expand_path("~/x", &dirs), PathBuf::from("C:/Users/ann/x"));
+}
+
+#[test]
+fn test_unknown_left_intact() {
+    let dirs = FakeDirs::new(&[("HOME", "/home/ann"), ("TMP", "/tmp")]);
+    for value in ["$TMP/x", "${TMP}/x", "%TMP%/x", "100% sure", "$", "cost $5", "~ann/x", "a~/b", "${XDG_CONFIG_HOME"] {
+        assert_eq!(expand_path(value, &dirs), PathBuf::from(value), "{}", value);
+    }
+
+    // Without a home directory the fallbacks cannot be found either
+    let homeless = FakeDirs::new(&[]);
+    for value in ["~/x", "$HOME/x", "$XDG_CONFIG_HOME/x", "%APPDATA%/x"] {
+        assert_eq!(expand_path(value, &homeless), PathBuf::from(value), "{}", value);
+    }
+}
+
+#[test]
+fn test_get_path() {
+    let reader = IniReader::from_string("[app]\nconfig = $XDG_CONFIG_HOME/app\nempty =\nplain = /srv/data\n").unwrap();
+    let dirs = FakeDirs::new(&[("HOME", "/home/ann")]);
+    assert_eq!(reader.get_path_with("app", "config", &dirs), Some(PathBuf::from("/home/ann/.config/app")));
+    assert_eq!(reader.get_path_with("app", "empty", &dirs), None);
+    assert_eq!(reader.get_path_with("app", "missing", &dirs), None);
+    assert_eq!(reader.get_path("app", "plain"), Some(PathBuf::from("/srv/data")));
+}
@@ -0,0 +1,34 @@
+//! The prelude alone is enough for common programs
+
+use inih::prelude::*;
+
+struct Collector {
+    entries: Vec<(String, String, String)>,
+}
+
+impl IniHandler for Collector {
+    fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.entries.push((section.to_string(), name.to_string(), value.to_string()));
+        Ok(())
+    }
+}
+
+#[test]
+fn test_prelude_reader() {
+    let options = ParseOptions { duplicate_key_policy: DuplicateKeyPolicy::FirstWins, ..ParseOptions::default() };
+    let reader = IniReader::from_string_with_options("[a]\nx = 1\nx = 2", &options).unwrap();
+    assert_eq!(reader.get_integer("a", "x", 0), 1);
+    assert_eq!(reader.to_ini_bytes(WriteEncoding::Utf8), b"[a]\nx = 1\n");
+}
+
+#[test]
+fn test_prelude_handlers() {
+    let mut collector = Collector { entries: Vec::new() };
+    ini_parse_string("[a]\nx = 1", &mut collector).unwrap();
+    assert_eq!(collector.entries, vec![("a".to_string(), "x".to_string(), "1".to_string())]);
+
+    let mut writer = WriterHandler::new(Vec::new());
+    let result: Result<(), IniParseError> = ini_parse_string_with_options("[a]\nx = 1", &mut writer, &ParseOptions::default());
+    result.unwrap();
+    assert_eq!(writer.into_inner(), b"[a]\nx = 1\n");
+}
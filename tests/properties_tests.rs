@@ -0,0 +1,159 @@
+//! Tests for reading and writing Java `.properties` files
+
+use inih::handlers::VecHandler;
+use inih::properties::{ini_parse_properties, ini_parse_properties_file};
+use inih::{EntryInfo, IniHandler, IniParseError, IniReader};
+
+/// Entries of `data` as (name, value) pairs, in input order
+fn entries(data: &str) -> Vec<(String, String)> {
+    let mut handler = VecHandler::new();
+    ini_parse_properties(data, &mut handler).unwrap();
+    handler
+        .entries()
+        .iter()
+        .map(|(section, name, value)| {
+            assert_eq!(section, "");
+            (name.clone(), value.clone())
+        })
+        .collect()
+}
+
+fn pair(name: &str, value: &str) -> (String, String) {
+    (name.to_string(), value.to_string())
+}
+
+#[test]
+fn test_separators() {
+    let data = "a=1\nb = 2\nc:3\nd : 4\ne 5\nf   =   6\ng\n  h = 8\n";
+    assert_eq!(
+        entries(data),
+        [pair("a", "1"), pair("b", "2"), pair("c", "3"), pair("d", "4"), pair("e", "5"), pair("f", "6"), pair("g", ""), pair("h", "8")]
+    );
+    // Only the first separator counts
+    assert_eq!(entries("url = http://example.com:80/a=b\n"), [pair("url", "http://example.com:80/a=b")]);
+}
+
+#[test]
+fn test_comments_and_blank_lines() {
+    let data = "# hash\n! bang\n   # indented\n\t\n\x0c\nkey = value # not a comment\n";
+    assert_eq!(entries(data), [pair("key", "value # not a comment")]);
+}
+
+#[test]
+fn test_escaped_key_characters() {
+    let data = "key\\ with\\ spaces = 1\nkey\\=equals = 2\nkey\\:colon = 3\nback\\\\slash = 4\n";
+    assert_eq!(entries(data), [pair("key with spaces", "1"), pair("key=equals", "2"), pair("key:colon", "3"), pair("back\\slash", "4")]);
+}
+
+#[test]
+fn test_continuation_lines() {
+    let data = "fruits = apple, banana, \\\n         pear, \\\n         cherry\nnext = x\n";
+    assert_eq!(entries(data), [pair("fruits", "apple, banana, pear, cherry"), pair("next", "x")]);
+
+    // An even number of backslashes is an escaped backslash, not a continuation
+    assert_eq!(entries("path = c:\\\\\nnext = x\n"), [pair("path", "c:\\"), pair("next", "x")]);
+    // A comment line cannot be continued, but a continuation can look like a comment
+    assert_eq!(entries("# comment \\\nkey = a\\\n  # b\n"), [pair("key", "a# b")]);
+    // A backslash on the last line is dropped
+    assert_eq!(entries("key = value\\"), [pair("key", "value")]);
+}
+
+#[test]
+fn test_escapes_in_values() {
+    let data = "tab = a\\tb\nnewline = a\\nb\nother = \\q\\=\\:\ntrailing = kept   \n";
+    assert_eq!(entries(data), [pair("tab", "a\tb"), pair("newline", "a\nb"), pair("other", "q=:"), pair("trailing", "kept   ")]);
+}
+
+#[test]
+fn test_unicode_escapes() {
+    let data = "copyright = \\u00A9 2024\nname\\u0020x = \\u00e9t\\u00E9\nemoji = \\uD83D\\uDE00\n";
+    assert_eq!(entries(data), [pair("copyright", "© 2024"), pair("name x", "été"), pair("emoji", "😀")]);
+
+    let mut handler = VecHandler::new();
+    let result = ini_parse_properties("good = 1\nbad = \\u12G4\nafter = 2\nlone = \\uDE00\n", &mut handler);
+    assert_eq!(result, Err(IniParseError::ParseError { line: 2, message: "Malformed \\uXXXX escape".to_string(), suggestion: None }));
+    // Parsing went on after the error
+    assert_eq!(handler.entries().len(), 2);
+}
+
+#[test]
+fn test_line_endings_and_entry_info() {
+    struct Infos(Vec<(usize, Option<char>, &'static str)>);
+
+    impl IniHandler for Infos {
+        fn handle(&mut self, _section: &str, _name: &str, _value: &str) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn handle_entry(&mut self, info: &EntryInfo, _section: &str, _name: &str, _value: &str) -> Result<(), String> {
+            self.0.push((info.line, info.separator, info.line_ending));
+            Ok(())
+        }
+    }
+
+    let mut infos = Infos(Vec::new());
+    ini_parse_properties("a = 1\rb: 2\r\n\nc \\\n 3\nd", &mut infos).unwrap();
+    assert_eq!(infos.0, [(1, Some('='), "\r"), (2, Some(':'), "\r\n"), (4, Some(' '), "\n"), (6, None, "")]);
+}
+
+#[test]
+fn test_reader_from_properties() {
+    let data = "# settings\nserver.host = example.com\nserver.port : 8080\ndebug = true\nserver.port = 9090\n";
+    let reader = IniReader::from_properties_str(data).unwrap();
+    assert_eq!(reader.get("", "server.host", ""), "example.com");
+    assert_eq!(reader.get_integer("", "server.port", 0), 9090);
+    assert!(reader.get_boolean("", "debug", false));
+    assert!(reader.sections().is_empty());
+}
+
+#[test]
+fn test_reader_from_properties_file() {
+    let path = std::env::temp_dir().join(format!("inih_properties_{}.properties", std::process::id()));
+    // ISO-8859-1, as Java writes it: 0xE9 is é
+    std::fs::write(&path, b"name = caf\xE9\n").unwrap();
+    let result = IniReader::from_properties_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(result.unwrap().get("", "name", ""), "café");
+
+    let mut handler = VecHandler::new();
+    ini_parse_properties_file("name = café\n".as_bytes(), &mut handler).unwrap();
+    assert_eq!(handler.entries(), [(String::new(), "name".to_string(), "café".to_string())]);
+
+    assert!(matches!(IniReader::from_properties_file(&path), Err(IniParseError::FileOpenContext { .. })));
+}
+
+#[test]
+fn test_to_properties_escapes() {
+    let mut reader = IniReader::from_string("top = 1\n[Server]\nhost = example.com\n[empty]\n").unwrap();
+    reader.set("server", "motd", "  two\nlines");
+    reader.set("server", "a=b:c", "#!\\");
+    reader.set("server", "unicode", "é😀");
+    assert_eq!(
+        reader.to_properties_string("."),
+        "top=1\nServer.host=example.com\nServer.motd=\\  two\\nlines\nServer.a\\=b\\:c=\\#\\!\\\\\nServer.unicode=\\u00E9\\uD83D\\uDE00\n"
+    );
+    assert_eq!(reader.to_properties_string("/").lines().nth(1), Some("Server/host=example.com"));
+    assert!(reader.to_properties_string(".").is_ascii());
+
+    // Only the first leading space needs escaping
+    let properties = IniReader::from_properties_str(&reader.to_properties_string(".")).unwrap();
+    assert_eq!(properties.get("", "server.motd", ""), "  two\nlines");
+}
+
+#[test]
+fn test_properties_round_trip() {
+    let reader = IniReader::from_string(include_str!("awkward.ini")).unwrap();
+    let text = reader.to_properties_string(".");
+    let properties = IniReader::from_properties_str(&text).unwrap();
+
+    let mut count = 0;
+    for (section, key, value) in reader.iter() {
+        let name = if section.is_empty() { key.to_string() } else { format!("{}.{}", section, key) };
+        assert_eq!(properties.get("", &name, "<missing>"), value, "{}", name);
+        count += 1;
+    }
+    assert_eq!(properties.keys("").len(), count);
+    assert_eq!(properties.get("", "server name.key with spaces", ""), "value with = and : signs");
+    assert_eq!(properties.get("", "server name.path", ""), "C:\\Program Files\\app\\");
+    assert_eq!(properties.get("", "server name.unicode", ""), "café ☕ 😀");
+}
@@ -0,0 +1,124 @@
+//! Tests for parse reports (`ParseReport`, `ini_check`, lossy constructors)
+
+use std::error::Error;
+
+use inih::report::{ini_check, ini_check_file, Severity};
+use inih::{ini_parse_string_collecting, IniHandler, IniParseError, IniReader, ParseOptions};
+
+const MIXED: &str = "[server]\nhost = a\n  port = 8080\n[broken\nname = x\n[]\n";
+
+fn options() -> ParseOptions {
+    ParseOptions { allow_multiline: true, warn_suspicious_continuation: true, ..ParseOptions::default() }
+}
+
+#[test]
+fn test_report_snapshot() {
+    let report = ini_check(MIXED, &options()).with_source("app.ini");
+    assert_eq!(
+        report.to_string(),
+        "app.ini:3: warning[lint]: Indented line 'port = 8080' looks like an entry but continues the value of 'host'\n\
+         app.ini:4: error[syntax]: Missing ']' in section header\n\
+         app.ini:4: help: insert ']' at byte 7: [broken]\n\
+         app.ini:6: error[syntax]: Empty section name\n"
+    );
+    assert_eq!((report.error_count(), report.warning_count()), (2, 1));
+    assert!(report.is_fatal());
+    assert!(!report.is_clean());
+}
+
+#[test]
+fn test_report_cap_and_counts() {
+    let report = ini_check(MIXED, &options()).with_max_findings(1);
+    assert_eq!(
+        report.to_string(),
+        "<input>:3: warning[lint]: Indented line 'port = 8080' looks like an entry but continues the value of 'host'\n\
+         ... and 2 more\n"
+    );
+
+    let clean = ini_check("[a]\nb = 1\n", &ParseOptions::default());
+    assert!(clean.is_clean() && !clean.is_fatal());
+    assert_eq!(clean.to_string(), "");
+
+    let warned = ini_check("[a]\nb = 1\n  c = 2\n", &options());
+    assert!(!warned.is_fatal());
+    assert_eq!(warned.findings[0].severity, Severity::Warning);
+}
+
+#[test]
+fn test_report_sorting_and_source() {
+    let mut report = ini_check(MIXED, &options());
+    assert_eq!(report.findings.iter().map(|finding| finding.line).collect::<Vec<_>>(), vec![Some(3), Some(4), Some(6)]);
+
+    report.findings.reverse();
+    report.sort_by_line();
+    assert_eq!(report.findings[0].line, Some(3));
+
+    // The error source is the first parse error, in input order
+    let first = IniParseError::ParseError { line: 4, message: "Missing ']' in section header".to_string(), suggestion: None };
+    assert_eq!(report.errors().len(), 2);
+    assert_eq!(report.source().unwrap().to_string(), first.to_string());
+    assert!(ini_check("a = 1\n", &options()).source().is_none());
+}
+
+#[test]
+fn test_lossy_constructors_keep_what_they_can() {
+    let (reader, report) = IniReader::from_string_lossy(MIXED, &options());
+    assert_eq!(reader.get("server", "host", ""), "a\n  port = 8080");
+    assert_eq!(reader.get("server", "name", ""), "x");
+    assert_eq!(report.error_count(), 2);
+    assert_eq!(reader.parse_error(), report.errors().first());
+
+    // With stop_on_first_error only the first error is reported
+    let stopping = ParseOptions { stop_on_first_error: true, ..options() };
+    let (_, report) = IniReader::from_string_lossy(MIXED, &stopping);
+    assert_eq!(report.error_count(), 1);
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/bad_section.ini");
+    let (_, report) = IniReader::from_file_lossy(path, &ParseOptions::default());
+    assert!(report.to_string().starts_with(&format!("{}:", path)));
+    assert_eq!(report, ini_check_file(path, &ParseOptions::default()));
+
+    let (reader, report) = IniReader::from_file_lossy("/nonexistent/app.ini", &ParseOptions::default());
+    assert!(reader.sections().is_empty());
+    assert_eq!(report.findings[0].code, "io");
+    assert!(report.to_string().starts_with("/nonexistent/app.ini: error[io]: Unable to open file"));
+}
+
+/// Rejects every entry named `bad`
+struct Picky;
+
+impl IniHandler for Picky {
+    fn handle(&mut self, _section: &str, name: &str, _value: &str) -> Result<(), String> {
+        if name == "bad" {
+            Err("not allowed".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[test]
+fn test_every_error_collected_once() {
+    let data = format!("[a]\nx = {}\n[broken\nbad = 1\n[]\ny = 2\nbad = 3\n", "v".repeat(300));
+    let options = ParseOptions { max_line: 40, ..ParseOptions::default() };
+
+    let errors = ini_parse_string_collecting(&data, &mut Picky, &options).unwrap_err();
+    let lines: Vec<Option<usize>> = errors.iter().map(|error| match error {
+        IniParseError::ParseError { line, .. } | IniParseError::RejectedEntry { line, .. } => Some(*line),
+        _ => None,
+    }).collect();
+    assert_eq!(lines, [Some(2), Some(3), Some(4), Some(5), Some(7)]);
+    assert!(matches!(&errors[0], IniParseError::ParseError { message, .. } if message == "Line too long"));
+    assert!(matches!(&errors[2], IniParseError::RejectedEntry { message, .. } if message == "not allowed"));
+    assert_eq!(ini_parse_string_collecting("[a]\nx = 1\n", &mut Picky, &options), Ok(()));
+
+    // The lossy reader keeps the syntax errors, in line order
+    let (reader, report) = IniReader::from_string_lossy(&data, &options);
+    let lines: Vec<usize> = reader.parse_errors().iter().map(|error| match error {
+        IniParseError::ParseError { line, .. } => *line,
+        other => panic!("unexpected error: {}", other),
+    }).collect();
+    assert_eq!(lines, [2, 3, 5]);
+    assert_eq!(reader.parse_errors(), report.errors());
+    assert_eq!(reader.get("a", "y", ""), "2");
+}
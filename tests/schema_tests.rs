@@ -0,0 +1,205 @@
+//! Tests for schema validation (`Schema`, `ini_schema!`)
+
+use inih::ini_schema;
+use inih::schema::{ini_validate, ini_validate_string, Schema, ValidationErrorKind, ValueType};
+
+fn server_schema() -> Schema {
+    ini_schema! {
+        [server]
+        host: str required,
+        port: int in 1..=65535,
+        mode: str one_of ["dev", "prod"],
+        debug: bool default false;
+        [cache]
+        ttl: duration in 1..=3600;
+    }
+}
+
+#[test]
+fn test_macro_matches_builder() {
+    let built = Schema::new()
+        .section("server")
+        .key("host", ValueType::Str)
+        .required()
+        .key("port", ValueType::Int)
+        .range(1.0, 65535.0)
+        .key("mode", ValueType::Str)
+        .allowed(vec!["dev".to_string(), "prod".to_string()])
+        .key("debug", ValueType::Bool)
+        .default_value("false")
+        .section("cache")
+        .key("ttl", ValueType::Duration)
+        .range(1.0, 3600.0);
+    assert_eq!(server_schema(), built);
+}
+
+#[test]
+fn test_valid_file() {
+    let ini = "[server]\nhost = example.com\nport = 8080\nmode = PROD\n\n[cache]\nttl = 5m\n";
+    assert_eq!(ini_validate_string(ini, &server_schema()), Ok(()));
+}
+
+#[test]
+fn test_all_errors_reported_with_lines() {
+    let ini = "[server]\nport = 70000\nmode = test\ndebug = maybe\n\n[logging]\nlevel = info\n\n[cache]\nttl = 2h\n";
+    let errors = ini_validate_string(ini, &server_schema()).unwrap_err();
+    let summary: Vec<_> = errors
+        .iter()
+        .map(|e| (e.line, e.section.as_str(), e.key.as_deref(), std::mem::discriminant(&e.kind)))
+        .collect();
+
+    let kind = |k: ValidationErrorKind| std::mem::discriminant(&k);
+    assert_eq!(
+        summary,
+        vec![
+            (Some(1), "server", Some("host"), kind(ValidationErrorKind::MissingRequired)),
+            (Some(2), "server", Some("port"), kind(ValidationErrorKind::OutOfRange { value: String::new(), min: 0.0, max: 0.0 })),
+            (Some(3), "server", Some("mode"), kind(ValidationErrorKind::NotAllowed { value: String::new(), allowed: Vec::new() })),
+            (Some(4), "server", Some("debug"), kind(ValidationErrorKind::InvalidType { value: String::new(), expected: ValueType::Bool })),
+            (Some(6), "logging", None, kind(ValidationErrorKind::UnknownSection)),
+            (Some(10), "cache", Some("ttl"), kind(ValidationErrorKind::OutOfRange { value: String::new(), min: 0.0, max: 0.0 })),
+        ]
+    );
+    assert_eq!(errors[1].to_string(), "line 2: value 70000 for server.port is outside 1..=65535");
+    assert_eq!(errors[4].to_string(), "line 6: unknown section [logging]");
+}
+
+#[test]
+fn test_missing_section_and_unknown_key() {
+    let ini = "[cache]\nttl = 30\nsize = 10\n";
+    let errors = ini_validate_string(ini, &server_schema()).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].to_string(), "line 3: unknown key cache.size");
+    assert_eq!(errors[1].line, None);
+    assert_eq!(errors[1].to_string(), "missing required key server.host");
+}
+
+#[test]
+fn test_validate_missing_file() {
+    let errors = ini_validate("does_not_exist.ini", &server_schema()).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0].kind, ValidationErrorKind::Parse(_)));
+}
+
+fn layout_schema() -> Schema {
+    Schema::new()
+        .section("general")
+        .key("name", ValueType::Str)
+        .section("advanced")
+        .key("level", ValueType::Int)
+        .section("sqlite")
+        .key("path", ValueType::Str)
+        .section("postgres")
+        .key("url", ValueType::Str)
+        .section("tls")
+        .key("cert", ValueType::Str)
+        .key("key", ValueType::Str)
+        .precedes("general", "advanced")
+        .exclusive(&["sqlite", "postgres"])
+        .requires_if("tls", "tls", &["cert", "key"])
+}
+
+#[test]
+fn test_sections_out_of_order() {
+    let ini = "[advanced]\nlevel = 2\n\n[general]\nname = app\n";
+    let errors = ini_validate_string(ini, &layout_schema()).unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, Some(1));
+    assert_eq!(errors[0].section, "advanced");
+    assert_eq!(errors[0].kind, ValidationErrorKind::OutOfOrder { first: "general".to_string(), first_line: 4 });
+    assert_eq!(errors[0].to_string(), "line 1: section [advanced] must come after [general] on line 4");
+
+    let ini = "[general]\nname = app\n[advanced]\nlevel = 2\n";
+    assert_eq!(ini_validate_string(ini, &layout_schema()), Ok(()));
+    assert_eq!(ini_validate_string("[advanced]\nlevel = 2\n", &layout_schema()), Ok(()));
+}
+
+#[test]
+fn test_exclusive_sections() {
+    let ini = "[postgres]\nurl = postgres://db\n[general]\n[SQLite]\npath = /tmp/db\n";
+    let errors = ini_validate_string(ini, &layout_schema()).unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, Some(4));
+    assert_eq!(errors[0].section, "sqlite");
+    assert_eq!(errors[0].kind, ValidationErrorKind::Exclusive { other: "postgres".to_string(), other_line: 1 });
+    assert_eq!(errors[0].to_string(), "line 4: section [sqlite] cannot be used together with [postgres] on line 1");
+}
+
+#[test]
+fn test_conditional_requirement() {
+    let schema = layout_schema();
+    assert_eq!(ini_validate_string("[tls]\ncert = a.pem\nkey = a.key\n", &schema), Ok(()));
+    assert_eq!(ini_validate_string("[general]\nname = app\n", &schema), Ok(()));
+
+    let errors = ini_validate_string("[general]\n\n[tls]\ncert = a.pem\n", &schema).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, Some(3));
+    assert_eq!(errors[0].key.as_deref(), Some("key"));
+    assert_eq!(errors[0].kind, ValidationErrorKind::ConditionallyRequired { condition: "tls".to_string(), condition_line: 3 });
+    assert_eq!(errors[0].to_string(), "line 3: missing key tls.key, required by [tls] on line 3");
+}
+
+#[test]
+fn test_conditional_requirement_in_other_section() {
+    let schema = layout_schema().requires_if("advanced", "general", &["name"]);
+    let errors = ini_validate_string("[general]\n[advanced]\nlevel = 1\n", &schema).unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, Some(1));
+    assert_eq!(errors[0].kind, ValidationErrorKind::ConditionallyRequired { condition: "advanced".to_string(), condition_line: 2 });
+}
+
+#[test]
+fn test_invalid_default_rejected() {
+    let schema = Schema::new().section("cache").key("ttl", ValueType::Duration).range(1.0, 3600.0).default_value("banana");
+    let errors = schema.check_defaults().unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].line, None);
+    assert_eq!(errors[0].key.as_deref(), Some("ttl"));
+    assert!(matches!(&errors[0].kind, ValidationErrorKind::InvalidDefault(kind) if matches!(**kind, ValidationErrorKind::InvalidType { .. })));
+    assert_eq!(errors[0].to_string(), "invalid default: invalid value \"banana\" for cache.ttl: expected duration");
+
+    assert_eq!(ini_validate_string("[cache]\nttl = 5m\n", &schema), Err(errors));
+    assert_eq!(server_schema().check_defaults(), Ok(()));
+}
+
+#[test]
+#[should_panic(expected = "invalid default")]
+fn test_macro_rejects_invalid_default() {
+    ini_schema! {
+        [cache]
+        ttl: duration default "banana";
+    };
+}
+
+#[test]
+fn test_apply_defaults() {
+    let schema = server_schema().section("cache").key("size", ValueType::Int).default_value("64");
+    let mut reader = inih::IniReader::from_string("[server]\nhost = example.com\n[cache]\nsize = 8\n").unwrap();
+
+    assert_eq!(schema.apply_defaults(&mut reader), 1);
+    assert_eq!(reader.get("server", "debug", ""), "false");
+    assert_eq!(reader.get("cache", "size", ""), "8");
+    assert_eq!(schema.apply_defaults(&mut reader), 0);
+}
+
+#[test]
+fn test_macro_quoted_section_names() {
+    let schema = ini_schema! {
+        [""]
+        name: str required;
+        ["my-section"]
+        level: int default 1;
+    };
+    let built = Schema::new()
+        .section("")
+        .key("name", ValueType::Str)
+        .required()
+        .section("my-section")
+        .key("level", ValueType::Int)
+        .default_value("1");
+    assert_eq!(schema, built);
+    assert_eq!(ini_validate_string("name = app\n[my-section]\nlevel = 2\n", &schema), Ok(()));
+}
@@ -0,0 +1,167 @@
+//! Acceptance tests for hostile section names
+//!
+//! Each test pins the defined behavior of one kind of name: what the parser
+//! makes of the header, how lookups see it, and whether the writer output
+//! reads back the same. `strict_section_names` rejects all of them.
+
+use inih::error::Suggestion;
+use inih::{IniParseError, IniReader, ParseOptions};
+
+/// Entries as (section, key, value), in document order
+fn entries(reader: &IniReader) -> Vec<(String, String, String)> {
+    reader.iter().map(|(section, key, value)| (section.to_string(), key.to_string(), value.to_string())).collect()
+}
+
+/// Parse, write and parse again, checking nothing changed
+fn assert_round_trip(data: &str) -> IniReader {
+    let reader = IniReader::from_string(data).unwrap();
+    let written = reader.to_ini_string();
+    let again = IniReader::from_string(&written).unwrap_or_else(|e| panic!("{:?} re-parsed with {}", written, e));
+    assert_eq!(entries(&again), entries(&reader), "{:?}", written);
+    assert_eq!(again.sections(), reader.sections(), "{:?}", written);
+    reader
+}
+
+fn strict_error(data: &str) -> String {
+    let options = ParseOptions { strict_section_names: true, ..ParseOptions::default() };
+    match IniReader::from_string_with_options(data, &options) {
+        Err(IniParseError::ParseError { message, .. }) => message,
+        other => panic!("{:?} was accepted: {:?}", data, other.map(|reader| reader.sections())),
+    }
+}
+
+#[test]
+fn test_equals_sign_does_not_leak_into_other_sections() {
+    let reader = assert_round_trip("[a=b]\nc = 1\n[a]\nx = 2\n[A\\]\ny = 3\n");
+
+    assert_eq!(reader.sections(), vec!["a=b", "a", "A\\"]);
+    assert_eq!(reader.keys("a"), vec!["x"]);
+    assert_eq!(reader.keys("a=b"), vec!["c"]);
+    assert_eq!(reader.keys("a\\"), vec!["y"]);
+    assert_eq!(reader.get("a=b", "c", ""), "1");
+    assert!(!reader.has_value("a", "b=c"));
+    assert!(reader.has_section("a=b"));
+
+    let mut reader = reader;
+    let split = reader.split_off("a").unwrap();
+    assert_eq!(entries(&split), vec![("a".to_string(), "x".to_string(), "2".to_string())]);
+    assert_eq!(reader.get("a=b", "c", ""), "1");
+    assert_eq!(strict_error("[a=b]\n"), "Section name 'a=b' contains '='");
+}
+
+#[test]
+fn test_colon_is_part_of_the_name_unless_inheritance_is_on() {
+    let reader = assert_round_trip("[a:b]\nk = 1\n");
+    assert_eq!(reader.sections(), vec!["a:b"]);
+    assert_eq!(reader.get("a:b", "k", ""), "1");
+
+    let options = ParseOptions { allow_section_inheritance: true, ..ParseOptions::default() };
+    let inherited = IniReader::from_string_with_options("[a:b]\nk = 1\n", &options).unwrap();
+    assert_eq!(inherited.sections(), vec!["a"]);
+    assert_eq!(strict_error("[a:b]\n"), "Section name 'a:b' contains ':'");
+}
+
+#[test]
+fn test_comment_prefixes_in_headers() {
+    // Without whitespace before it, a comment prefix is part of the name
+    let reader = assert_round_trip("[a;b]\nk = 1\n[c#d]\nk = 2\n[e #f]\nk = 3\n");
+    assert_eq!(reader.sections(), vec!["a;b", "c#d", "e #f"]);
+
+    // An inline comment before the ']' leaves the header unclosed
+    let error = IniReader::from_string("[a ;b]\nk = 1\n").unwrap_err();
+    assert_eq!(error, IniParseError::ParseError {
+        line: 1,
+        message: "Missing ']' in section header".to_string(),
+        suggestion: Some(Suggestion { replacement_line: "[a] ;b]".to_string(), description: "insert ']' at byte 2".to_string() }),
+    });
+
+    // After the ']' it is an ordinary comment
+    let reader = IniReader::from_string("[a] ; note\nk = 1\n").unwrap();
+    assert_eq!(reader.sections(), vec!["a"]);
+
+    assert_eq!(strict_error("[a;b]\n"), "Section name 'a;b' contains ';'");
+    assert_eq!(strict_error("[c#d]\n"), "Section name 'c#d' contains '#'");
+}
+
+#[test]
+fn test_brackets() {
+    let reader = assert_round_trip("[a[b]\nk = 1\n");
+    assert_eq!(reader.sections(), vec!["a[b"]);
+    assert_eq!(strict_error("[a[b]\n"), "Section name 'a[b' contains '['");
+
+    // The first ']' closes the header and the rest of the line is ignored
+    let reader = IniReader::from_string("[a]b]\nk = 1\n").unwrap();
+    assert_eq!(reader.sections(), vec!["a"]);
+    assert_eq!(reader.get("a", "k", ""), "1");
+}
+
+#[test]
+fn test_surrounding_whitespace_is_kept() {
+    let reader = assert_round_trip("[ a ]\nk = 1\n[a]\nk = 2\n");
+
+    assert_eq!(reader.sections(), vec![" a ", "a"]);
+    assert_eq!(reader.get(" a ", "k", ""), "1");
+    assert_eq!(reader.get("a", "k", ""), "2");
+    assert!(reader.has_section(" a "));
+
+    assert_eq!(strict_error("[ a ]\n"), "Section name ' a ' has leading or trailing whitespace");
+}
+
+#[test]
+fn test_empty_names_are_rejected_by_default() {
+    for data in ["k = 0\n[]\nk = 1\n", "k = 0\n[   ]\nk = 1\n"] {
+        let error = IniReader::from_string(data).unwrap_err();
+        assert_eq!(error, IniParseError::ParseError { line: 2, message: "Empty section name".to_string(), suggestion: None }, "{:?}", data);
+        assert_eq!(strict_error(data), "Empty section name");
+    }
+
+    let reader = IniReader::from_string("k = 0\n[a]\nk = 1\n").unwrap();
+    assert_eq!(reader.sections(), vec!["a"]);
+    assert_eq!(reader.get("a", "k", ""), "1");
+}
+
+#[test]
+fn test_empty_names_return_to_the_global_section_when_allowed() {
+    let options = ParseOptions { allow_empty_section_names: true, ..ParseOptions::default() };
+    for data in ["k = 0\n[a]\nx = 1\n[]\ny = 2\n", "k = 0\n[a]\nx = 1\n[   ]\ny = 2\n"] {
+        let reader = IniReader::from_string_with_options(data, &options).unwrap();
+        assert_eq!(reader.sections(), vec!["a"], "{:?}", data);
+        assert_eq!(reader.keys(""), vec!["k", "y"]);
+        assert_eq!(reader.get("", "y", ""), "2");
+        assert_eq!(reader.keys("a"), vec!["x"]);
+    }
+
+    let reader = IniReader::from_string_with_options("[a]\nk = 1\n", &options).unwrap();
+    assert_eq!(reader.sections(), vec!["a"]);
+    assert_eq!(reader.get("a", "k", ""), "1");
+}
+
+#[test]
+fn test_names_the_parser_cannot_produce() {
+    let mut reader = IniReader::from_string("").unwrap();
+    reader.extend([
+        ("line\nbreak".to_string(), "k".to_string(), "1".to_string()),
+        ("close]early".to_string(), "k".to_string(), "2".to_string()),
+    ]);
+    assert_eq!(reader.get("line\nbreak", "k", ""), "1");
+    assert_eq!(reader.keys("close]early"), vec!["k"]);
+
+    // The writer keeps each header on one line, at the cost of reading back differently
+    let written = reader.to_ini_string();
+    assert_eq!(written, "[line\\nbreak]\nk = 1\n\n[close\\]early]\nk = 2\n");
+    let again = IniReader::from_string(&written).unwrap();
+    assert_eq!(again.sections(), vec!["line\\nbreak", "close\\"]);
+
+    let control = format!("[a{}b]\n", '\u{7}');
+    assert_eq!(strict_error(&control), "Section name 'a\u{7}b' contains '\\u{7}'");
+}
+
+#[test]
+fn test_strict_names_accept_ordinary_headers() {
+    let options = ParseOptions { strict_section_names: true, allow_quoted_subsections: true, ..ParseOptions::default() };
+    let data = "[server]\n[server.http-1]\n[Über_Abschnitt]\n[remote \"origin\"]\nurl = x\n";
+    let reader = IniReader::from_string_with_options(data, &options).unwrap();
+
+    assert_eq!(reader.sections(), vec!["server", "server.http-1", "Über_Abschnitt", "remote.origin"]);
+    assert_eq!(strict_error("[quoted \"name\"]\n"), "Section name 'quoted \"name\"' contains '\"'");
+}
@@ -0,0 +1,47 @@
+//! Tests for section views (`IniReader::section`, `sections_iter`)
+
+use inih::IniReader;
+
+const DATA: &str = "top = 1\n[db]\nhost = db.local\nport = 5432\n[cache]\n[DB]\nssl = yes\nratio = 0.5\nport = 6432\n";
+
+#[test]
+fn test_view_reads_scoped_values() {
+    let reader = IniReader::from_string(DATA).unwrap();
+    let db = reader.section("Db").unwrap();
+
+    assert_eq!(db.name(), "db");
+    assert_eq!(db.get("host", ""), "db.local");
+    assert_eq!(db.get("missing", "none"), "none");
+    assert!(db.get_boolean("ssl", false));
+    assert_eq!(db.get_real("ratio", 0.0), 0.5);
+    assert!(db.has_value("HOST"));
+    assert!(!db.has_value("top"));
+}
+
+#[test]
+fn test_view_merges_repeated_blocks() {
+    let reader = IniReader::from_string(DATA).unwrap();
+    let db = reader.section("db").unwrap();
+
+    // Keys of the [DB] block come after those of [db], and the repeated port concatenates
+    assert_eq!(db.keys(), vec!["host", "port", "ssl", "ratio"]);
+    assert_eq!(db.len(), 4);
+    assert_eq!(db.get("port", ""), "5432\n6432");
+    assert_eq!(db.get_integer("port", -1), -1);
+    assert_eq!(db.iter().next(), Some(("host", "db.local")));
+}
+
+#[test]
+fn test_missing_empty_and_global_sections() {
+    let reader = IniReader::from_string(DATA).unwrap();
+
+    assert!(reader.section("missing").is_none());
+    let cache = reader.section("cache").unwrap();
+    assert!(cache.is_empty());
+    assert!(cache.keys().is_empty());
+    assert_eq!(reader.section("").unwrap().get_integer("top", 0), 1);
+    assert!(IniReader::from_string("[s]\n").unwrap().section("").is_none());
+
+    let names: Vec<(&str, usize)> = reader.sections_iter().map(|(name, view)| (name, view.len())).collect();
+    assert_eq!(names, vec![("db", 4), ("cache", 0)]);
+}
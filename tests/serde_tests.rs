@@ -0,0 +1,367 @@
+//! Tests for filling structs from INI and writing them out through serde (feature `serde`)
+
+#![cfg(feature = "serde")]
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use inih::de::{from_ini, from_str_with_options};
+use inih::error::{IniDeError, IniSerError, IniWriteError};
+use inih::ser::{to_string_with, SerializeOptions};
+use inih::writer::{BooleanStyle, WriteOptions};
+use inih::{from_str, to_string, IniReader, ParseOptions, ReaderOptions};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Config {
+    protocol: Protocol,
+    user: User,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Protocol {
+    version: u8,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct User {
+    name: String,
+    email: Option<String>,
+    active: bool,
+    ratio: f64,
+    #[serde(default)]
+    retries: u32,
+}
+
+const DATA: &str = "
+[Protocol]
+version = 6
+
+[User]
+Name = Bob Smith
+email = bob@smith.com
+active = yes
+ratio = 2.5
+";
+
+#[test]
+fn test_sections_to_nested_structs() {
+    let config: Config = from_str(DATA).unwrap();
+    assert_eq!(config.protocol, Protocol { version: 6 });
+    assert_eq!(config.user.name, "Bob Smith");
+    assert_eq!(config.user.email.as_deref(), Some("bob@smith.com"));
+    assert!(config.user.active);
+    assert_eq!(config.user.ratio, 2.5);
+    assert_eq!(config.user.retries, 0);
+}
+
+#[test]
+fn test_missing_option_and_default() {
+    let config: Config = from_str("[protocol]\nversion = 0x10\n[user]\nname = x\nactive = off\nratio = 1\nretries = 3\n").unwrap();
+    assert_eq!(config.protocol.version, 16);
+    assert_eq!(config.user.email, None);
+    assert!(!config.user.active);
+    assert_eq!(config.user.retries, 3);
+}
+
+#[test]
+fn test_boolean_tokens_match_get_boolean() {
+    #[derive(Debug, Deserialize)]
+    struct Flags {
+        flags: HashMap<String, bool>,
+    }
+
+    let data = "[flags]\na = true\nb = On\nc = 1\nd = NO\ne = false\nf = 0\n";
+    let reader = IniReader::from_string(data).unwrap();
+    let flags: Flags = from_str(data).unwrap();
+    for (name, value) in &flags.flags {
+        assert_eq!(*value, reader.get_boolean("flags", name, !value), "{}", name);
+    }
+    assert_eq!(flags.flags.len(), 6);
+
+    let error = from_str::<Flags>("[flags]\na = maybe\n").unwrap_err();
+    assert_eq!(error.to_string(), "flags.a: invalid value \"maybe\": expected a boolean");
+}
+
+#[test]
+fn test_global_keys_lists_and_enums() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Level {
+        Debug,
+        Info,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Top {
+        name: String,
+        level: Level,
+        ports: Vec<u16>,
+        server: Option<HashMap<String, String>>,
+    }
+
+    let top: Top = from_str("name = demo\nlevel = info\nports = 80, 443\n").unwrap();
+    assert_eq!(top.name, "demo");
+    assert_eq!(top.level, Level::Info);
+    assert_eq!(top.ports, [80, 443]);
+    assert!(top.server.is_none());
+}
+
+#[test]
+fn test_mixed_case_field_names() {
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Limits {
+        max_conn: u32,
+        #[serde(rename = "IdleTimeout")]
+        idle_timeout: u32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Top {
+        #[serde(rename = "Limits")]
+        limits: Limits,
+        #[serde(rename = "extra")]
+        extra: HashMap<String, String>,
+    }
+
+    let top: Top = from_str("[limits]
+maxConn = 5
+idletimeout = 30
+[Extra]
+KeepCase = 1
+").unwrap();
+    assert_eq!(top.limits.max_conn, 5);
+    assert_eq!(top.limits.idle_timeout, 30);
+    assert_eq!(top.extra, HashMap::from([("KeepCase".to_string(), "1".to_string())]));
+
+    let options = ParseOptions::default();
+    let sensitive = ReaderOptions { case_sensitive: true, ..ReaderOptions::default() };
+    let reader = IniReader::from_string_with_reader_options("[Limits]
+maxconn = 5
+IdleTimeout = 30
+", &options, sensitive).unwrap();
+    let error = from_ini::<Limits>(&reader).unwrap_err();
+    assert_eq!(error.to_string(), "missing field `maxConn`");
+}
+
+#[test]
+fn test_unknown_fields_ignored_by_default() {
+    let data = format!("{}\nextra = 1\n[other]\nx = y\n", DATA);
+    let config: Config = from_str(&data).unwrap();
+    assert_eq!(config.protocol.version, 6);
+}
+
+#[test]
+fn test_deny_unknown_fields_names_key() {
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Strict {
+        protocol: StrictProtocol,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct StrictProtocol {
+        version: u8,
+    }
+
+    let strict: Strict = from_str("[protocol]\nversion = 1\n").unwrap();
+    assert_eq!(strict.protocol.version, 1);
+
+    let error = from_str::<Strict>("[protocol]\nversion = 1\nverison = 2\n").unwrap_err();
+    match &error {
+        IniDeError::Message { section, name, .. } => {
+            assert_eq!(section.as_deref(), Some("protocol"));
+            assert_eq!(name.as_deref(), Some("verison"));
+        }
+        other => panic!("expected a message, got {:?}", other),
+    }
+    assert!(error.to_string().starts_with("protocol.verison: unknown field `verison`"), "{}", error);
+
+    let error = from_str::<Strict>("[protocol]\nversion = 1\n[protcol]\n").unwrap_err();
+    assert!(error.to_string().starts_with("section protcol: unknown field `protcol`"), "{}", error);
+}
+
+#[test]
+fn test_errors_carry_location() {
+    let error = from_str::<Config>("[protocol]\nversion = 300\n[user]\nname = x\nactive = 1\nratio = 1\n").unwrap_err();
+    assert_eq!(error.to_string(), "protocol.version: invalid value \"300\": expected an integer from 0 to 255");
+
+    let error = from_str::<Config>("[protocol]\nversion = 1\n[user]\nname = x\nratio = 1\n").unwrap_err();
+    assert_eq!(error.to_string(), "section user: missing field `active`");
+
+    let error = from_str::<Config>("[protocol]\nversion = 1\n").unwrap_err();
+    assert_eq!(error.to_string(), "missing field `user`");
+}
+
+#[test]
+fn test_parse_error_and_options() {
+    assert!(matches!(from_str::<Config>("[protocol\n"), Err(IniDeError::Parse(_))));
+
+    #[derive(Debug, Deserialize)]
+    struct Motd {
+        motd: Message,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Message {
+        text: String,
+    }
+
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    let motd: Motd = from_str_with_options("[motd]\ntext = hello\n  world\n", &options).unwrap();
+    assert_eq!(motd.motd.text, "hello\n  world");
+
+    let reader = IniReader::from_string("[motd]\ntext = from reader\n").unwrap();
+    let motd: Motd = from_ini(&reader).unwrap();
+    assert_eq!(motd.motd.text, "from reader");
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Mode {
+    Fast,
+    Safe,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct Settings {
+    name: String,
+    verbose: bool,
+    server: Server,
+    cache: Option<Cache>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct Server {
+    host: String,
+    port: u16,
+    ratio: f64,
+    scale: f32,
+    proxy: Option<String>,
+    tls: bool,
+    mode: Mode,
+    peers: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct Cache {
+    size: u64,
+}
+
+fn settings() -> Settings {
+    Settings {
+        name: "demo".to_string(),
+        verbose: false,
+        server: Server {
+            host: "example.com".to_string(),
+            port: 8080,
+            ratio: 0.1 + 0.2,
+            scale: 0.1,
+            proxy: None,
+            tls: true,
+            mode: Mode::Safe,
+            peers: vec!["a".to_string(), "b c".to_string()],
+        },
+        cache: None,
+    }
+}
+
+#[test]
+fn test_serialize_layout() {
+    let text = to_string(&settings()).unwrap();
+    assert_eq!(
+        text,
+        "name = demo\nverbose = false\n\n[server]\nhost = example.com\nport = 8080\nratio = 0.30000000000000004\nscale = 0.1\n\
+         tls = true\nmode = safe\npeers = a, b c\n"
+    );
+}
+
+#[test]
+fn test_serialize_round_trip() {
+    let original = settings();
+    let read_back: Settings = from_str(&to_string(&original).unwrap()).unwrap();
+    assert_eq!(read_back, original);
+
+    let mut with_cache = settings();
+    with_cache.cache = Some(Cache { size: 64 });
+    with_cache.server.proxy = Some("proxy:3128".to_string());
+    with_cache.server.peers.clear();
+    let text = to_string(&with_cache).unwrap();
+    assert!(text.ends_with("[cache]\nsize = 64\n"), "{}", text);
+    assert_eq!(from_str::<Settings>(&text).unwrap(), with_cache);
+}
+
+#[test]
+fn test_serialize_maps() {
+    let mut sections: BTreeMap<String, BTreeMap<String, i32>> = BTreeMap::new();
+    sections.entry("b".to_string()).or_default().insert("x".to_string(), 1);
+    sections.entry("a".to_string()).or_default().insert("y".to_string(), -2);
+    sections.entry("empty".to_string()).or_default();
+
+    let text = to_string(&sections).unwrap();
+    assert_eq!(text, "[a]\ny = -2\n\n[b]\nx = 1\n\n[empty]\n");
+    assert_eq!(from_str::<BTreeMap<String, BTreeMap<String, i32>>>(&text).unwrap(), sections);
+}
+
+#[test]
+fn test_serialize_write_options() {
+    let options = SerializeOptions {
+        write_options: WriteOptions { boolean_style: BooleanStyle::YesNo, real_precision: Some(2), ..WriteOptions::default() },
+        ..SerializeOptions::default()
+    };
+    let text = to_string_with(&settings(), &options).unwrap();
+    assert!(text.contains("verbose = no\n") && text.contains("tls = yes\n"), "{}", text);
+    assert!(text.contains("ratio = 0.3\n"), "{}", text);
+}
+
+#[test]
+fn test_serialize_nested_sections() {
+    #[derive(Serialize)]
+    struct Outer {
+        server: Inner,
+    }
+
+    #[derive(Serialize)]
+    struct Inner {
+        port: u16,
+        tls: Tls,
+    }
+
+    #[derive(Serialize)]
+    struct Tls {
+        cert: String,
+    }
+
+    let outer = Outer { server: Inner { port: 443, tls: Tls { cert: "a.pem".to_string() } } };
+    let error = to_string(&outer).unwrap_err();
+    assert!(matches!(&error, IniSerError::Unsupported { path, .. } if path == &["server", "tls"]));
+    assert!(error.to_string().starts_with("server.tls: INI cannot nest"), "{}", error);
+
+    let options = SerializeOptions { dotted_sections: true, ..SerializeOptions::default() };
+    let text = to_string_with(&outer, &options).unwrap();
+    assert_eq!(text, "[server]\nport = 443\n\n[server.tls]\ncert = a.pem\n");
+}
+
+#[test]
+fn test_serialize_errors() {
+    assert!(matches!(to_string(&42), Err(IniSerError::Unsupported { .. })));
+
+    #[derive(Serialize)]
+    struct Motd {
+        motd: HashMap<String, String>,
+    }
+
+    let motd = Motd { motd: HashMap::from([("text".to_string(), "two\nlines".to_string())]) };
+    assert!(matches!(to_string(&motd), Err(IniSerError::Write(IniWriteError::InvalidValue { .. }))));
+
+    #[derive(Serialize)]
+    struct List {
+        items: Vec<String>,
+    }
+
+    let list = List { items: vec!["a,b".to_string()] };
+    let error = to_string(&list).unwrap_err();
+    assert_eq!(error.to_string(), "items: list item \"a,b\" would not read back unchanged");
+}
@@ -0,0 +1,40 @@
+//! Tests for sorted listings of sections and keys (`sections_sorted_by`, `keys_sorted_by`, `natural_cmp`)
+
+use std::cmp::Ordering;
+
+use inih::sort::natural_cmp;
+use inih::IniReader;
+
+#[test]
+fn test_natural_order_of_sections() {
+    let reader = IniReader::from_string("[section10]\n[section2]\n[Section1]\n[section]\n").unwrap();
+
+    assert_eq!(reader.sections(), vec!["section10", "section2", "Section1", "section"]);
+    assert_eq!(reader.sections_sorted_by(str::cmp), vec!["Section1", "section", "section10", "section2"]);
+    assert_eq!(
+        reader.sections_sorted_by(|a, b| natural_cmp(&a.to_lowercase(), &b.to_lowercase())),
+        vec!["section", "Section1", "section2", "section10"]
+    );
+}
+
+#[test]
+fn test_natural_order_of_keys() {
+    let reader = IniReader::from_string("[disks]\ndisk10 = c\ndisk9 = b\ndisk1a = a2\ndisk1 = a\n").unwrap();
+
+    assert_eq!(reader.keys_sorted_by("DISKS", natural_cmp), vec!["disk1", "disk1a", "disk9", "disk10"]);
+    assert_eq!(reader.keys_sorted_by("DISKS", |a, b| natural_cmp(b, a)), vec!["disk10", "disk9", "disk1a", "disk1"]);
+    assert!(reader.keys_sorted_by("missing", natural_cmp).is_empty());
+}
+
+#[test]
+fn test_natural_cmp_leading_zeros_and_mixed_runs() {
+    let mut names = vec!["a2", "a01", "a1", "a001b", "a1b", "a", "a10", "b", "10", "9", "a1b2", "a1b10", "x0099", "x100"];
+    names.sort_by(|a, b| natural_cmp(a, b));
+    assert_eq!(names, vec!["9", "10", "a", "a1", "a01", "a1b", "a001b", "a1b2", "a1b10", "a2", "a10", "b", "x0099", "x100"]);
+
+    assert_eq!(natural_cmp("v1.10", "v1.9"), Ordering::Greater);
+    assert_eq!(natural_cmp("007", "7"), Ordering::Greater);
+    assert_eq!(natural_cmp("0", "00"), Ordering::Less);
+    assert_eq!(natural_cmp("item", "item"), Ordering::Equal);
+    assert_eq!(natural_cmp("99999999999999999999999", "100000000000000000000000"), Ordering::Less);
+}
@@ -0,0 +1,145 @@
+//! Tests for the borrowing reader (`IniStrReader`)
+
+use std::borrow::Cow;
+use std::fs;
+use std::time::Instant;
+
+use inih::str_reader::IniStrReader;
+use inih::{DuplicateKeyPolicy, IniReader, NewlineStyle, ParseOptions};
+
+/// Whether `text` points into `data`
+fn borrows_from(data: &str, text: &str) -> bool {
+    let range = data.as_bytes().as_ptr_range();
+    range.contains(&text.as_ptr()) && text.len() <= data.len()
+}
+
+#[test]
+fn test_same_values_as_ini_reader_on_corpus() {
+    let option_sets = [
+        ParseOptions::default(),
+        ParseOptions { allow_multiline: true, allow_no_value: true, ..ParseOptions::default() },
+        ParseOptions {
+            allow_multiline: true,
+            duplicate_key_policy: DuplicateKeyPolicy::Concatenate { separator: "|".to_string() },
+            value_newline: NewlineStyle::CrLf,
+            ..ParseOptions::default()
+        },
+        ParseOptions { duplicate_key_policy: DuplicateKeyPolicy::FirstWins, trim_values: false, ..ParseOptions::default() },
+    ];
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests");
+    let mut files = 0;
+
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "ini") {
+            continue;
+        }
+        files += 1;
+        let data = fs::read_to_string(&path).unwrap();
+        for options in &option_sets {
+            let owned = IniReader::from_string_with_options(&data, options);
+            let borrowed = IniStrReader::from_str(&data, options);
+            let (owned, borrowed) = match (owned, borrowed) {
+                (Ok(owned), Ok(borrowed)) => (owned, borrowed),
+                (Err(owned), Err(borrowed)) => {
+                    assert_eq!(owned, borrowed, "{}", path.display());
+                    continue;
+                }
+                (owned, borrowed) => panic!("{}: {:?} vs {:?}", path.display(), owned.err(), borrowed.err()),
+            };
+
+            let mut count = 0;
+            for (section, key, value) in owned.iter() {
+                assert_eq!(borrowed.get(section, key, "\u{0}missing"), value, "{} [{}] {}", path.display(), section, key);
+                count += 1;
+            }
+            let borrowed_count: usize = std::iter::once("")
+                .chain(borrowed.sections())
+                .map(|section| borrowed.keys(section).len())
+                .sum();
+            assert_eq!(borrowed_count, count, "{}", path.display());
+        }
+    }
+    assert!(files >= 10);
+}
+
+#[test]
+fn test_values_borrow_from_input() {
+    let data = String::from("top = 1\n[Server]\nHost = example.com\nport=8080\nmotd = hello\n  world\n");
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    let reader = IniStrReader::from_str(&data, &options).unwrap();
+
+    let host = reader.get_cow("server", "host").unwrap();
+    assert!(matches!(host, Cow::Borrowed(_)));
+    assert!(borrows_from(&data, &host));
+    assert!(borrows_from(&data, reader.get("", "top", "")));
+    assert!(matches!(reader.get_cow("SERVER", "motd"), Some(Cow::Owned(text)) if text == "hello\n  world"));
+    assert_eq!(reader.keys("server"), vec!["host", "motd", "port"]);
+    assert!(borrows_from(&data, reader.keys("server")[2]));
+    assert_eq!(reader.sections(), vec!["Server"]);
+    assert!(reader.has_section("SERVER"));
+    assert!(!reader.has_section(""));
+
+    // Borrowed values outlive the reader, though not the input
+    let kept: Vec<&str> = ["host", "port"]
+        .iter()
+        .filter_map(|name| match reader.get_cow("server", name) {
+            Some(Cow::Borrowed(value)) => Some(value),
+            _ => None,
+        })
+        .collect();
+    drop(reader);
+    assert_eq!(kept, vec!["example.com", "8080"]);
+}
+
+#[test]
+fn test_getters() {
+    let data = "[n]\nint = 0x10\nneg = -3\nreal = 2.5\nflag = on\nempty =\n";
+    let reader = IniStrReader::from_str(data, &ParseOptions::default()).unwrap();
+
+    assert_eq!(reader.get_integer("n", "int", 0), 16);
+    assert_eq!(reader.get_unsigned("n", "neg", 7), 7);
+    assert_eq!(reader.get_real("n", "real", 0.0), 2.5);
+    assert!(reader.get_boolean("n", "flag", false));
+    assert_eq!(reader.get("n", "empty", "D"), "");
+    assert_eq!(reader.get_nonempty_or("n", "empty", "D"), "D");
+    assert_eq!(reader.get("n", "missing", "D"), "D");
+    assert!(reader.has_value("N", " INT "));
+    assert!(!reader.has_value("n", "missing"));
+}
+
+#[test]
+fn test_parse_errors() {
+    let result = IniStrReader::from_str("[ok]\n[broken\n", &ParseOptions::default());
+    assert_eq!(result.unwrap_err(), IniReader::from_string("[ok]\n[broken\n").unwrap_err());
+}
+
+#[test]
+#[ignore = "timing comparison, run with --ignored --nocapture"]
+fn bench_str_reader_against_ini_reader() {
+    let mut data = String::new();
+    for section in 0..500 {
+        data.push_str(&format!("[section{}]\n", section));
+        for key in 0..40 {
+            data.push_str(&format!("key{} = some fairly long value number {}\n", key, key));
+        }
+    }
+    let options = ParseOptions::default();
+
+    let start = Instant::now();
+    for _ in 0..5 {
+        let reader = IniReader::from_string_with_options(&data, &options).unwrap();
+        assert_eq!(reader.get("section7", "key3", ""), "some fairly long value number 3");
+    }
+    let owned = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..5 {
+        let reader = IniStrReader::from_str(&data, &options).unwrap();
+        assert_eq!(reader.get("section7", "key3", ""), "some fairly long value number 3");
+    }
+    let borrowed = start.elapsed();
+
+    println!("5 x IniReader: {:?}; 5 x IniStrReader: {:?}", owned, borrowed);
+    assert!(borrowed < owned);
+}
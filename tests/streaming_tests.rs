@@ -0,0 +1,296 @@
+//! Memory bounds of the streaming reader on hostile input
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::{self, BufReader, Cursor, Read};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use inih::handlers::VecHandler;
+use inih::multi::ini_parse_multi;
+use inih::parser::{ini_parse_file_with_options, ini_parse_reader_with_options};
+use inih::{ini_parse_string_with_options, IniHandler, IniParseError, LineLengthPolicy, ParseOptions};
+
+/// Allocator that remembers the largest single allocation
+struct LargestAllocation;
+
+static LARGEST: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for LargestAllocation {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LARGEST.fetch_max(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        LARGEST.fetch_max(new_size, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: LargestAllocation = LargestAllocation;
+
+/// Tests share the allocation counter, so they take turns
+static SERIAL: Mutex<()> = Mutex::new(());
+
+const TEN_MB: usize = 10 * 1024 * 1024;
+
+/// Reader producing `prefix`, then `filler` repeated to `len` bytes, then
+/// `suffix`, without ever holding the long line in memory
+struct LongLine {
+    parts: Vec<Cursor<Vec<u8>>>,
+    filler: u8,
+    remaining: usize,
+    largest_read: usize,
+}
+
+impl LongLine {
+    fn new(prefix: &str, filler: u8, len: usize, suffix: &str) -> Self {
+        LongLine {
+            parts: vec![Cursor::new(prefix.as_bytes().to_vec()), Cursor::new(suffix.as_bytes().to_vec())],
+            filler,
+            remaining: len,
+            largest_read: 0,
+        }
+    }
+}
+
+impl Read for LongLine {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.largest_read = self.largest_read.max(buf.len());
+        let read = self.parts[0].read(buf)?;
+        if read > 0 {
+            return Ok(read);
+        }
+        if self.remaining > 0 {
+            let n = buf.len().min(self.remaining);
+            buf[..n].fill(self.filler);
+            self.remaining -= n;
+            return Ok(n);
+        }
+        self.parts[1].read(buf)
+    }
+}
+
+fn options(policy: LineLengthPolicy) -> ParseOptions {
+    ParseOptions { max_line: 200, line_length_policy: policy, ..ParseOptions::default() }
+}
+
+type Entries = Vec<(String, String, String)>;
+
+/// Parse `input` and return the entries, the result and the largest allocation made
+fn parse_measured(input: LongLine, options: &ParseOptions) -> (Entries, Result<(), IniParseError>, usize) {
+    let mut handler = VecHandler::new();
+    LARGEST.store(0, Ordering::Relaxed);
+    let result = ini_parse_file_with_options(input, &mut handler, options);
+    let largest = LARGEST.load(Ordering::Relaxed);
+    (handler.into_entries(), result, largest)
+}
+
+#[test]
+fn test_ten_mb_line_skip() {
+    let _serial = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let input = LongLine::new("[s]\nbig = ", b'x', TEN_MB, "\nafter = 1\n");
+    let (entries, result, largest) = parse_measured(input, &options(LineLengthPolicy::Skip));
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(entries, vec![("s".to_string(), "after".to_string(), "1".to_string())]);
+    assert!(largest < 64 * 1024, "largest allocation was {} bytes", largest);
+}
+
+#[test]
+fn test_ten_mb_line_truncate() {
+    let _serial = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let input = LongLine::new("[s]\nbig = ", b'x', TEN_MB, "\r\nafter = 1\r\n");
+    let (entries, result, largest) = parse_measured(input, &options(LineLengthPolicy::Truncate));
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].2, "x".repeat(200 - "big = ".len()));
+    assert_eq!(entries[1].1, "after");
+    assert!(largest < 64 * 1024, "largest allocation was {} bytes", largest);
+}
+
+#[test]
+fn test_ten_mb_line_error() {
+    let _serial = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let input = LongLine::new("[s]\nbig = ", b'x', TEN_MB, "\nafter = 1\n");
+    let (entries, result, largest) = parse_measured(input, &options(LineLengthPolicy::Error));
+
+    assert_eq!(result, Err(IniParseError::ParseError { line: 2, message: "Line too long".to_string(), suggestion: None }));
+    assert_eq!(entries.len(), 1);
+    assert!(largest < 64 * 1024, "largest allocation was {} bytes", largest);
+}
+
+#[test]
+fn test_long_whitespace_tail_is_not_too_long() {
+    let _serial = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let input = LongLine::new("[s]\nkey = value", b' ', TEN_MB, "\n");
+    let (entries, result, largest) = parse_measured(input, &options(LineLengthPolicy::Error));
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(entries, vec![("s".to_string(), "key".to_string(), "value".to_string())]);
+    assert!(largest < 64 * 1024, "largest allocation was {} bytes", largest);
+}
+
+#[test]
+fn test_content_after_long_whitespace_run_is_too_long() {
+    let _serial = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let input = LongLine::new("[s]\nkey = value", b' ', TEN_MB, "tail\n");
+    let (_, result, _) = parse_measured(input, &options(LineLengthPolicy::Error));
+    assert_eq!(result, Err(IniParseError::ParseError { line: 2, message: "Line too long".to_string(), suggestion: None }));
+
+    // In-memory input measures lines the same way
+    let data = format!("[s]\nkey = value{}\n", " ".repeat(1000));
+    let mut handler = VecHandler::new();
+    assert_eq!(ini_parse_string_with_options(&data, &mut handler, &options(LineLengthPolicy::Error)), Ok(()));
+    let data = format!("[s]\nkey = value{}tail\n", " ".repeat(1000));
+    assert!(ini_parse_string_with_options(&data, &mut handler, &options(LineLengthPolicy::Error)).is_err());
+}
+
+#[test]
+fn test_truncate_at_character_boundary() {
+    let _serial = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let data = format!("key = {}\n", "é".repeat(300));
+    let mut handler = VecHandler::new();
+    let options = ParseOptions { max_line: 21, ..options(LineLengthPolicy::Truncate) };
+    ini_parse_reader_with_options(BufReader::new(data.as_bytes()), &mut handler, &options).unwrap();
+
+    assert_eq!(handler.entries()[0].2, "é".repeat(7));
+}
+
+#[test]
+fn test_reads_stay_small() {
+    let _serial = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut input = LongLine::new("big = ", b'x', TEN_MB, "\n");
+    let mut handler = VecHandler::new();
+    ini_parse_file_with_options(&mut input, &mut handler, &options(LineLengthPolicy::Skip)).unwrap();
+
+    assert_eq!(input.remaining, 0);
+    assert!(input.largest_read <= 64 * 1024, "largest read was {} bytes", input.largest_read);
+}
+
+#[test]
+fn test_multi_document_long_line() {
+    let _serial = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let input = BufReader::new(LongLine::new("a = ", b'x', TEN_MB, "\nb = 2\n---\nc = 3\n"));
+    let mut documents = Vec::new();
+    LARGEST.store(0, Ordering::Relaxed);
+    ini_parse_multi(input, &options(LineLengthPolicy::Skip), "---", |reader| {
+        documents.push(reader.to_ini_string());
+        Ok(())
+    })
+    .unwrap();
+
+    assert!(LARGEST.load(Ordering::Relaxed) < 64 * 1024);
+    assert_eq!(documents, vec!["b = 2\n".to_string(), "c = 3\n".to_string()]);
+}
+
+/// Pipe-like reader generating `count` lines of a varied document, which can
+/// be read through only once
+struct Pipe {
+    next: usize,
+    count: usize,
+    pending: Cursor<Vec<u8>>,
+    finished: bool,
+}
+
+impl Pipe {
+    fn new(count: usize) -> Self {
+        Pipe { next: 0, count, pending: Cursor::new(Vec::new()), finished: false }
+    }
+
+    /// Line `i` with its terminator: headers, entries, continuations and broken lines
+    ///
+    /// Sections and keys repeat, so the parser has no more names to track
+    /// however long the document is.
+    fn line(i: usize) -> String {
+        let ending = if i % 2 == 1 { "\r\n" } else { "\n" };
+        if i.is_multiple_of(50) {
+            format!("[section{}]{}", i / 50 % 20, ending)
+        } else if i % 997 == 501 {
+            format!("[broken {}{}", i, ending)
+        } else if i % 7 == 3 {
+            format!("  continued {}{}", i, ending)
+        } else {
+            format!("key{} = value {}{}", i % 50, i, ending)
+        }
+    }
+
+    fn text(count: usize) -> String {
+        (0..count).map(Pipe::line).collect()
+    }
+}
+
+impl Read for Pipe {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        assert!(!self.finished, "pipe read again after its end");
+        loop {
+            let read = self.pending.read(buf)?;
+            if read > 0 || buf.is_empty() {
+                return Ok(read);
+            }
+            if self.next == self.count {
+                self.finished = true;
+                return Ok(0);
+            }
+            self.pending = Cursor::new(Pipe::line(self.next).into_bytes());
+            self.next += 1;
+        }
+    }
+}
+
+/// Handler counting entries without keeping them
+#[derive(Default)]
+struct Counter {
+    entries: usize,
+    bytes: usize,
+}
+
+impl IniHandler for Counter {
+    fn handle(&mut self, _section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.entries += 1;
+        self.bytes += name.len() + value.len();
+        Ok(())
+    }
+}
+
+fn multiline() -> ParseOptions {
+    ParseOptions { allow_multiline: true, ..ParseOptions::default() }
+}
+
+#[test]
+fn test_pipe_matches_string() {
+    let _serial = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    for options in [multiline(), ParseOptions { allow_multiline: false, ..ParseOptions::default() }] {
+        let mut from_pipe = VecHandler::new();
+        let piped = ini_parse_file_with_options(Pipe::new(3000), &mut from_pipe, &options);
+        let mut from_string = VecHandler::new();
+        let parsed = ini_parse_string_with_options(&Pipe::text(3000), &mut from_string, &options);
+
+        assert_eq!(piped, parsed);
+        assert!(matches!(piped, Err(IniParseError::ParseError { line: 502, .. })), "{:?}", piped);
+        assert_eq!(from_pipe.into_entries(), from_string.into_entries());
+    }
+}
+
+#[test]
+fn test_many_lines_not_buffered() {
+    let _serial = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    // About 4 MB of short lines
+    let count = 200_000;
+    let mut counter = Counter::default();
+    LARGEST.store(0, Ordering::Relaxed);
+    let result = ini_parse_reader_with_options(BufReader::new(Pipe::new(count)), &mut counter, &multiline());
+    let largest = LARGEST.load(Ordering::Relaxed);
+
+    assert!(matches!(result, Err(IniParseError::ParseError { line: 502, .. })));
+    assert!(counter.bytes > 2 * 1024 * 1024, "{} bytes of entries", counter.bytes);
+    // The table of names seen for duplicate detection is the largest
+    // allocation; a copy of the input would be several megabytes
+    assert!(largest < 1024 * 1024, "largest allocation was {} bytes", largest);
+}
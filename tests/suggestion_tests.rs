@@ -0,0 +1,62 @@
+//! Tests for fix suggestions attached to syntax errors
+
+use inih::error::Suggestion;
+use inih::report::ini_check;
+use inih::{IniParseError, IniReader, ParseOptions};
+
+fn strict() -> ParseOptions {
+    ParseOptions { stop_on_first_error: true, ..ParseOptions::default() }
+}
+
+/// The suggestion of the error `input` fails with
+fn suggestion(input: &str, options: &ParseOptions) -> (usize, Option<Suggestion>) {
+    match IniReader::from_string_with_options(input, options).unwrap_err() {
+        IniParseError::ParseError { line, suggestion, .. } => (line, suggestion),
+        other => panic!("expected ParseError, got {:?}", other),
+    }
+}
+
+/// `input` with its line `line` replaced by the suggested one
+fn apply(input: &str, line: usize, suggestion: &Suggestion) -> String {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, text)| if i + 1 == line { suggestion.replacement_line.as_str() } else { text })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_suggestions_fix_their_line() {
+    let cases = [
+        ("[server\nport = 80\n", "insert ']' at byte 7", "[server]"),
+        ("[a]\nk = 1\n  [b ; the b section\n", "insert ']' at byte 4", "  [b] ; the b section"),
+        ("[a]\nhost localhost\n", "insert '=' after 'host'", "host = localhost"),
+        ("[a]\nenabled\n", "insert '=' after 'enabled'", "enabled ="),
+    ];
+    for (input, description, replacement_line) in cases {
+        let (line, found) = suggestion(input, &strict());
+        let found = found.unwrap_or_else(|| panic!("no suggestion for {:?}", input));
+        assert_eq!((found.description.as_str(), found.replacement_line.as_str()), (description, replacement_line));
+        let fixed = apply(input, line, &found);
+        assert!(IniReader::from_string_with_options(&fixed, &strict()).is_ok(), "{:?} still fails", fixed);
+    }
+}
+
+#[test]
+fn test_no_suggestion_when_none_parses() {
+    // Closing the header leaves it empty, which is an error of its own
+    assert_eq!(suggestion("[\n", &ParseOptions::default()), (1, None));
+    assert_eq!(suggestion("[]\n", &ParseOptions::default()), (1, None));
+}
+
+#[test]
+fn test_report_prints_help_line() {
+    let report = ini_check("[a]\n[b\nk = 1\n", &ParseOptions::default()).with_source("app.ini");
+    assert_eq!(report.findings[0].suggestion.as_ref().map(|s| s.replacement_line.as_str()), Some("[b]"));
+    assert_eq!(
+        report.to_string(),
+        "app.ini:2: error[syntax]: Missing ']' in section header\n\
+         app.ini:2: help: insert ']' at byte 2: [b]\n"
+    );
+}
@@ -1,7 +1,9 @@
 //! Tests using actual INI files from the original project
 
-use inih::{IniReader, ParseOptions};
-use std::fs;
+use inih::document::LineKind;
+use inih::estimate::{ini_estimate, ini_estimate_file, Estimate};
+use inih::writer::WriteOptions;
+use inih::{DuplicateKeyPolicy, IniParseError, IniReader, ParseOptions, ReaderOptions};
 
 #[test]
 fn test_normal_ini() {
@@ -41,32 +43,32 @@ funny4 : two : colons
     let reader = IniReader::from_string(data).unwrap();
     
     // Test basic values
-    assert_eq!(reader.get_string("section1", "one", ""), "This is a test");
+    assert_eq!(reader.get("section1", "one", ""), "This is a test");
     assert_eq!(reader.get_integer("section1", "two", 0), 1234);
     
     // Test section with spaces
     assert_eq!(reader.get_integer(" section 2 ", "happy", 0), 4);
-    assert_eq!(reader.get_string(" section 2 ", "sad", ""), "");
+    assert_eq!(reader.get(" section 2 ", "sad", ""), "");
     
     // Test empty section
     assert!(reader.has_section("empty"));
     
     // Test comment handling
-    assert_eq!(reader.get_string("comment_test", "test1", ""), "1;2;3");
-    assert_eq!(reader.get_string("comment_test", "test2", ""), "2;3;4;this won't be a comment, needs whitespace before ';'");
-    assert_eq!(reader.get_string("comment_test", "test;3", ""), "345");
-    assert_eq!(reader.get_string("comment_test", "test4", ""), "4#5#6");
-    assert_eq!(reader.get_string("comment_test", "test7", ""), "");
-    assert_eq!(reader.get_string("comment_test", "test8", ""), "; not a comment, needs whitespace before ';'");
+    assert_eq!(reader.get("comment_test", "test1", ""), "1;2;3");
+    assert_eq!(reader.get("comment_test", "test2", ""), "2;3;4;this won't be a comment, needs whitespace before ';'");
+    assert_eq!(reader.get("comment_test", "test;3", ""), "345");
+    assert_eq!(reader.get("comment_test", "test4", ""), "4#5#6");
+    assert_eq!(reader.get("comment_test", "test7", ""), "");
+    assert_eq!(reader.get("comment_test", "test8", ""), "; not a comment, needs whitespace before ';'");
     
     // Test colon separators
-    assert_eq!(reader.get_string("colon_tests", "Content-Type", ""), "text/html");
-    assert_eq!(reader.get_string("colon_tests", "foo", ""), "bar");
+    assert_eq!(reader.get("colon_tests", "Content-Type", ""), "text/html");
+    assert_eq!(reader.get("colon_tests", "foo", ""), "bar");
     assert_eq!(reader.get_integer("colon_tests", "adams", 0), 42);
-    assert_eq!(reader.get_string("colon_tests", "funny1", ""), "with = equals");
-    assert_eq!(reader.get_string("colon_tests", "funny2", ""), "with : colons");
-    assert_eq!(reader.get_string("colon_tests", "funny3", ""), "two = equals");
-    assert_eq!(reader.get_string("colon_tests", "funny4", ""), "two : colons");
+    assert_eq!(reader.get("colon_tests", "funny1", ""), "with = equals");
+    assert_eq!(reader.get("colon_tests", "funny2", ""), "with : colons");
+    assert_eq!(reader.get("colon_tests", "funny3", ""), "two = equals");
+    assert_eq!(reader.get("colon_tests", "funny4", ""), "two : colons");
 }
 
 #[test]
@@ -80,13 +82,12 @@ key2 = value2
 key3 = value3
 "#;
 
-    let mut options = ParseOptions::default();
-    options.allow_multiline = true;
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
     let reader = IniReader::from_string_with_options(data, &options).unwrap();
     
-    assert_eq!(reader.get_string("section1", "key1", ""), "value1\n    continuation line 1\n    continuation line 2");
-    assert_eq!(reader.get_string("section1", "key2", ""), "value2\n    another continuation");
-    assert_eq!(reader.get_string("section1", "key3", ""), "value3");
+    assert_eq!(reader.get("section1", "key1", ""), "value1\n    continuation line 1\n    continuation line 2");
+    assert_eq!(reader.get("section1", "key2", ""), "value2\n    another continuation");
+    assert_eq!(reader.get("section1", "key3", ""), "value3");
 }
 
 #[test]
@@ -104,9 +105,9 @@ key3 = value3
     let reader = IniReader::from_string(data).unwrap();
     
     // Both keys should be present in section1
-    assert_eq!(reader.get_string("section1", "key1", ""), "value1");
-    assert_eq!(reader.get_string("section1", "key3", ""), "value3");
-    assert_eq!(reader.get_string("section2", "key2", ""), "value2");
+    assert_eq!(reader.get("section1", "key1", ""), "value1");
+    assert_eq!(reader.get("section1", "key3", ""), "value3");
+    assert_eq!(reader.get("section2", "key2", ""), "value2");
     
     // Keys should include both
     let keys = reader.keys("section1");
@@ -124,8 +125,8 @@ key2 = short
 
     let reader = IniReader::from_string(data).unwrap();
     
-    assert_eq!(reader.get_string("section1", "key1", ""), "this is a very long line that should be handled properly by the parser");
-    assert_eq!(reader.get_string("section1", "key2", ""), "short");
+    assert_eq!(reader.get("section1", "key1", ""), "this is a very long line that should be handled properly by the parser");
+    assert_eq!(reader.get("section1", "key2", ""), "short");
 }
 
 #[test]
@@ -133,7 +134,7 @@ fn test_bom_ini() {
     let data = "\u{FEFF}[section1]\nkey1=value1\n";
     
     let reader = IniReader::from_string(data).unwrap();
-    assert_eq!(reader.get_string("section1", "key1", ""), "value1");
+    assert_eq!(reader.get("section1", "key1", ""), "value1");
 }
 
 #[test]
@@ -145,8 +146,7 @@ key3 = value3
 "#;
 
     // This should fail with stop_on_first_error since allow_no_value is false
-    let mut options = ParseOptions::default();
-    options.stop_on_first_error = true;
+    let options = ParseOptions { stop_on_first_error: true, ..ParseOptions::default() };
     let result = IniReader::from_string_with_options(data, &options);
     assert!(result.is_err());
 }
@@ -160,8 +160,8 @@ key2 = value2
 "#;
 
     let reader = IniReader::from_string(data).unwrap();
-    assert_eq!(reader.get_string("section1", "key1", ""), "value1");
-    assert_eq!(reader.get_string("section1", "key2", ""), "value2");
+    assert_eq!(reader.get("section1", "key1", ""), "value1");
+    assert_eq!(reader.get("section1", "key2", ""), "value2");
 }
 
 #[test]
@@ -186,6 +186,301 @@ key2 = value2
 
     let reader = IniReader::from_string(data).unwrap();
     // The continuation should not be treated as a continuation
-    assert_eq!(reader.get_string("section1", "key1", ""), "value1");
-    assert_eq!(reader.get_string("section1", "key2", ""), "value2");
+    assert_eq!(reader.get("section1", "key1", ""), "value1");
+    assert_eq!(reader.get("section1", "key2", ""), "value2");
+}
+
+#[test]
+fn test_preserving_round_trip_normal_ini() {
+    let data = include_str!("normal.ini");
+
+    let reader = IniReader::from_string_preserving(data, &ParseOptions::default()).unwrap();
+
+    assert_eq!(reader.to_ini_string().trim_end(), data.replace("\r\n", "\n").trim_end());
+    assert_eq!(reader.get("comment_test", "test1", ""), "1;2;3");
+
+    let document = reader.document().unwrap();
+    assert_eq!(document.sections(), vec!["section1", " section 2 ", "empty", "comment_test", "colon_tests"]);
+    assert_eq!(document.keys("colon_tests")[0], "Content-Type");
+}
+
+#[test]
+fn test_preserving_round_trip_multi_line_ini() {
+    let data = include_str!("multi_line.ini");
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+
+    let reader = IniReader::from_string_preserving(data, &options).unwrap();
+
+    assert_eq!(reader.to_ini_string().trim_end(), data.replace("\r\n", "\n").trim_end());
+    assert_eq!(reader.get("section2", "multi", ""), "a\n        b\n        c");
+
+    let lines = reader.document().unwrap().lines();
+    assert!(matches!(lines[2].kind, LineKind::Entry { ref name, .. } if name == "multi"));
+    assert!(matches!(lines[3].kind, LineKind::Continuation { ref name, .. } if name == "multi"));
+}
+
+#[test]
+fn test_preserving_from_file() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/normal.ini");
+
+    let reader = IniReader::from_file_preserving(path, &ParseOptions::default()).unwrap();
+
+    assert_eq!(reader.to_ini_string().trim_end(), include_str!("normal.ini").replace("\r\n", "\n").trim_end());
+}
+
+#[test]
+fn test_estimate_normal_ini() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/normal.ini");
+
+    let estimate = ini_estimate(path, &ParseOptions::default()).unwrap();
+
+    assert_eq!(estimate, Estimate { sections: 5, keys: 17, max_value_len: 58, total_value_bytes: 198 });
+}
+
+#[test]
+fn test_estimate_multi_line_ini() {
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+
+    let estimate = ini_estimate_file(include_str!("multi_line.ini").as_bytes(), &options).unwrap();
+
+    assert_eq!(estimate.sections, 3);
+    assert_eq!(estimate.keys, 8);
+    assert_eq!(estimate.max_value_len, "this is a\n        multi-line value".len());
+}
+
+#[test]
+fn test_prereserved_reader_matches_from_file() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/normal.ini");
+
+    let prereserved = IniReader::from_file_prereserved(path, &ParseOptions::default()).unwrap();
+    let plain = IniReader::from_file(path).unwrap();
+
+    assert_eq!(prereserved.to_ini_string(), plain.to_ini_string());
+    assert_eq!(prereserved.sections(), plain.sections());
+}
+
+#[test]
+fn test_filtered_reader_normal_ini() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/normal.ini");
+
+    let filtered = IniReader::from_file_filtered(path, &ParseOptions::default(), &["Section1", "comment_test"]).unwrap();
+    let plain = IniReader::from_file(path).unwrap();
+
+    assert_eq!(filtered.sections(), vec!["section1", "comment_test"]);
+    assert_eq!(filtered.keys("section1"), plain.keys("section1"));
+    assert!(!filtered.has_value("section2", "name"));
+}
+
+#[test]
+fn test_filtered_reader_after_many_skipped_lines() {
+    let path = std::env::temp_dir().join(format!("inih_filtered_{}.ini", std::process::id()));
+    let mut data = String::from("[skipped]\n");
+    for i in 0..100_000 {
+        data.push_str(&format!("key{} = value {}\n", i, i));
+    }
+    data.push_str("[wanted]\nanswer = 42\n[also_skipped]\nx = 1\n");
+    std::fs::write(&path, data).unwrap();
+
+    let reader = IniReader::from_file_filtered(&path, &ParseOptions::default(), &["WANTED"]);
+    std::fs::remove_file(&path).unwrap();
+    let reader = reader.unwrap();
+
+    assert_eq!(reader.sections(), vec!["wanted"]);
+    assert_eq!(reader.get_integer("wanted", "answer", 0), 42);
+    assert!(reader.keys("skipped").is_empty());
+}
+
+#[test]
+fn test_filtered_reader_records_skipped_sections() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/normal.ini");
+
+    let reader_options = ReaderOptions { record_skipped_sections: true, ..ReaderOptions::default() };
+    let recorded = IniReader::from_file_filtered_with_reader_options(path, &ParseOptions::default(), reader_options, &["section1"]).unwrap();
+    let plain = IniReader::from_file(path).unwrap();
+    let expected: Vec<String> = plain.sections().into_iter().filter(|section| section != "section1").collect();
+    assert_eq!(recorded.skipped_sections(), expected);
+
+    let unrecorded = IniReader::from_file_filtered(path, &ParseOptions::default(), &["section1"]).unwrap();
+    assert!(unrecorded.skipped_sections().is_empty());
+}
+
+#[test]
+fn test_filtered_reader_case_sensitive() {
+    let path = std::env::temp_dir().join(format!("inih_filtered_case_{}.ini", std::process::id()));
+    std::fs::write(&path, "[Wanted]\nx = 1\n[wanted]\ny = 2\n").unwrap();
+
+    let reader_options = ReaderOptions { case_sensitive: true, ..ReaderOptions::default() };
+    let reader = IniReader::from_file_filtered_with_reader_options(&path, &ParseOptions::default(), reader_options, &["wanted"]);
+    std::fs::remove_file(&path).unwrap();
+    let reader = reader.unwrap();
+
+    assert_eq!(reader.sections(), vec!["wanted"]);
+    assert_eq!(reader.get_integer("wanted", "y", 0), 2);
+    assert!(!reader.has_value("Wanted", "x"));
+}
+
+#[test]
+fn test_bare_keys_round_trip() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/no_value.ini");
+    let options = ParseOptions { allow_no_value: true, ..ParseOptions::default() };
+
+    let reader = IniReader::from_file_filtered(path, &options, &["section_list", "section0"]).unwrap();
+    assert!(reader.is_flag("section_list", "section0"));
+    assert!(!reader.is_flag("section0", "key0"));
+
+    let written = reader.to_ini_string();
+    assert!(written.contains("[section_list]\nsection0\nsection1\n"));
+    let reread = IniReader::from_string_with_options(&written, &options).unwrap();
+    assert!(reread.is_flag("section_list", "section0"));
+    assert!(reread.is_flag("section_list", "section1"));
+    assert_eq!(reread.get("section0", "key0", ""), "val0");
+}
+
+#[test]
+fn test_bare_keys_normalized() {
+    let options = ParseOptions { allow_no_value: true, ..ParseOptions::default() };
+    let reader = IniReader::from_string_with_options("[flags]\nverbose\nlevel =\n", &options).unwrap();
+    assert!(reader.is_flag("flags", "verbose"));
+    assert!(!reader.is_flag("flags", "level"));
+
+    assert_eq!(reader.to_ini_string(), "[flags]\nverbose\nlevel = \n");
+    let normalize = WriteOptions { normalize_bare_keys: true, ..WriteOptions::default() };
+    assert_eq!(reader.to_ini_string_with(&normalize), "[flags]\nverbose = \nlevel = \n");
+}
+
+#[test]
+fn test_document_order_output() {
+    let data = include_str!("unordered.ini");
+    let reader = IniReader::from_string(data).unwrap();
+
+    let written = reader.to_ini_string();
+    assert_eq!(written, "name = demo\n\n[zebra]\nstripes = many\nage = 7\n\n[mango]\nripe = yes\ncolor = orange\n\n[apple]\nvariety = fuji\n");
+    assert_eq!(reader.to_string(), written);
+    assert_eq!(reader.sections(), vec!["zebra", "mango", "apple"]);
+
+    let order: Vec<(&str, &str)> = reader.iter().map(|(section, key, _)| (section, key)).collect();
+    assert_eq!(order[0], ("", "name"));
+    assert_eq!(order[1], ("zebra", "stripes"));
+    assert_eq!(order[3], ("mango", "ripe"));
+    assert_eq!(order.len(), 6);
+
+    let map = reader.as_map();
+    assert_eq!(map["mango"]["color"], "orange");
+    assert_eq!(map[""]["name"], "demo");
+}
+
+#[test]
+fn test_extract_sections() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/normal.ini");
+    let mut reader = IniReader::from_file(path).unwrap();
+
+    let extracted = reader.extract(&["SECTION1", "colon_tests", "missing"]);
+    reader.clear();
+
+    assert_eq!(extracted.sections(), vec!["section1", "colon_tests"]);
+    assert_eq!(extracted.get_integer("section1", "two", 0), 1234);
+    assert_eq!(extracted.get("colon_tests", "Content-Type", ""), "text/html");
+    assert!(!extracted.has_value("comment_test", "test1"));
+    assert_eq!(extracted.occurrences("section1", "two")[0].line, 4);
+    assert!(extracted.to_ini_string().starts_with("[section1]\none = This is a test\ntwo = 1234\n\n[colon_tests]\n"));
+}
+
+#[test]
+fn test_split_off_section() {
+    let mut reader = IniReader::from_string("top = 1\n[Database]\nhost = db\n[cache]\nttl = 5\n").unwrap();
+
+    let database = reader.split_off("database").unwrap();
+    assert_eq!(database.get("database", "host", ""), "db");
+    assert_eq!(reader.sections(), vec!["cache"]);
+    assert!(!reader.has_value("database", "host"));
+    assert!(reader.split_off("database").is_none());
+
+    let global = reader.split_off("").unwrap();
+    assert_eq!(global.get("", "top", ""), "1");
+    assert_eq!(reader.to_ini_string(), "[cache]\nttl = 5\n");
+}
+
+#[test]
+fn test_reader_never_fails_with_handler_error() {
+    let option_sets = [
+        ParseOptions::default(),
+        ParseOptions { allow_multiline: true, allow_no_value: true, ..ParseOptions::default() },
+        ParseOptions { stop_on_first_error: true, duplicate_key_policy: DuplicateKeyPolicy::Error, ..ParseOptions::default() },
+        ParseOptions { max_line: 20, ascii_only: true, ..ParseOptions::default() },
+    ];
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests");
+    let mut files = 0;
+
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "ini") {
+            continue;
+        }
+        files += 1;
+        let bytes = std::fs::read(&path).unwrap();
+        let data = String::from_utf8_lossy(&bytes);
+        for options in &option_sets {
+            let results = [
+                IniReader::from_file(&path).err(),
+                IniReader::from_string_with_options(&data, options).err(),
+                IniReader::from_bytes(&bytes, options).err(),
+                IniReader::from_file_prereserved(&path, options).err(),
+                IniReader::from_file_filtered(&path, options, &["section1", ""]).err(),
+            ];
+            for error in results.into_iter().flatten() {
+                assert!(
+                    !matches!(error, IniParseError::HandlerError(_) | IniParseError::RejectedEntry { .. }),
+                    "{}: {}",
+                    path.display(),
+                    error
+                );
+            }
+        }
+    }
+    assert!(files >= 10);
+}
+
+fn assert_open_error_path(error: &IniParseError, expected: &std::path::Path) {
+    match error {
+        IniParseError::FileOpenContext { path, kind, .. } => {
+            assert_eq!(path.as_os_str(), expected.as_os_str());
+            assert_eq!(*kind, std::io::ErrorKind::NotFound);
+        }
+        other => panic!("expected FileOpenContext, got {:?}", other),
+    }
+}
+
+fn assert_every_entry_point_keeps_path(path: &std::path::Path) {
+    let options = ParseOptions::default();
+    let mut handler = inih::handlers::VecHandler::new();
+    assert_open_error_path(&inih::ini_parse(path, &mut handler).unwrap_err(), path);
+    assert_open_error_path(&IniReader::from_file(path).unwrap_err(), path);
+    assert_open_error_path(&IniReader::from_file_prereserved(path, &options).unwrap_err(), path);
+    assert_open_error_path(&IniReader::from_file_filtered(path, &options, &[""]).unwrap_err(), path);
+    assert_open_error_path(&IniReader::from_file_preserving(path, &options).unwrap_err(), path);
+    assert_open_error_path(&ini_estimate(path, &options).unwrap_err(), path);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_non_utf8_path_in_open_error() {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+    let bytes = b"/nonexistent/inih-\xff\xfe-config.ini".to_vec();
+    let path = std::path::PathBuf::from(OsString::from_vec(bytes.clone()));
+    assert!(path.to_str().is_none());
+    assert_every_entry_point_keeps_path(&path);
+
+    match IniReader::from_file(OsString::from_vec(bytes.clone())).unwrap_err() {
+        IniParseError::FileOpenContext { path, .. } => assert_eq!(path.as_os_str().as_bytes(), &bytes[..]),
+        other => panic!("expected FileOpenContext, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_extended_length_path_passes_through() {
+    let path = std::path::Path::new(r"\\?\C:\nonexistent\inih\config.ini");
+    assert_every_entry_point_keeps_path(path);
+    assert!(IniReader::from_file(path).unwrap_err().to_string().contains(r"\\?\C:\nonexistent"));
 }
@@ -0,0 +1,177 @@
+//! Tests for pre-tokenized replay (`IniTokens`)
+
+use std::fs;
+use std::time::Instant;
+
+use inih::error::IniWarning;
+use inih::handlers::VecHandler;
+use inih::tokens::IniTokens;
+use inih::{ini_parse_string_with_options, DuplicateKeyPolicy, EntryInfo, IniHandler, IniParseError, ParseOptions, SectionHeader};
+
+/// Handler that logs every call it receives, failing on request
+#[derive(Debug, Default)]
+struct CallLog {
+    calls: Vec<String>,
+    fail_on: Option<&'static str>,
+}
+
+impl CallLog {
+    fn log(&mut self, call: String) -> Result<(), String> {
+        let fail = self.fail_on.is_some_and(|name| call.contains(name));
+        self.calls.push(call);
+        if fail {
+            Err(format!("rejected call {}", self.calls.len()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl IniHandler for CallLog {
+    fn handle(&mut self, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.log(format!("handle {:?} {:?} {:?}", section, name, value))
+    }
+
+    fn handle_at(&mut self, line: usize, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.log(format!("handle_at {} {:?} {:?} {:?}", line, section, name, value))
+    }
+
+    fn handle_entry(&mut self, info: &EntryInfo, section: &str, name: &str, value: &str) -> Result<(), String> {
+        self.log(format!("handle_entry {:?} {:?} {:?} {:?}", info, section, name, value))
+    }
+
+    fn start_section(&mut self, header: &SectionHeader) -> Result<(), String> {
+        self.log(format!("start_section {:?}", header))
+    }
+
+    fn end_section(&mut self, section: &str) -> Result<(), String> {
+        self.log(format!("end_section {:?}", section))
+    }
+
+    fn end_value(&mut self, section: &str, name: &str) -> Result<(), String> {
+        self.log(format!("end_value {:?} {:?}", section, name))
+    }
+
+    fn warning(&mut self, warning: &IniWarning) -> Result<(), String> {
+        self.log(format!("warning {:?}", warning))
+    }
+
+    fn end_document(&mut self) -> Result<(), String> {
+        self.log("end_document".to_string())
+    }
+}
+
+fn corpus() -> Vec<(String, String)> {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests");
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ini"))
+        .map(|path| (path.display().to_string(), fs::read_to_string(&path).unwrap()))
+        .collect();
+    files.sort();
+    assert!(files.len() >= 10);
+    files
+}
+
+fn option_sets() -> Vec<ParseOptions> {
+    vec![
+        ParseOptions::default(),
+        ParseOptions { allow_multiline: true, warn_suspicious_continuation: true, ..ParseOptions::default() },
+        ParseOptions { allow_no_value: true, allow_inline_comments: false, ..ParseOptions::default() },
+        ParseOptions { stop_on_first_error: true, allow_multiline: true, ..ParseOptions::default() },
+        ParseOptions { duplicate_key_policy: DuplicateKeyPolicy::FirstWins, trim_values: false, ..ParseOptions::default() },
+        ParseOptions { allow_quoted_subsections: true, allow_raw_section_annotations: true, ..ParseOptions::default() },
+        ParseOptions { call_handler_on_new_section: true, allow_multiline: true, ..ParseOptions::default() },
+    ]
+}
+
+fn assert_replay_matches(data: &str, options: &ParseOptions, fail_on: Option<&'static str>, context: &str) {
+    let mut direct = CallLog { fail_on, ..CallLog::default() };
+    let direct_result = ini_parse_string_with_options(data, &mut direct, options);
+
+    let tokens = IniTokens::scan(data, options);
+    let mut replayed = CallLog { fail_on, ..CallLog::default() };
+    let replay_result = tokens.replay(&mut replayed);
+
+    assert_eq!(replayed.calls, direct.calls, "{}", context);
+    assert_eq!(replay_result, direct_result, "{}", context);
+}
+
+#[test]
+fn test_replay_matches_direct_parse_on_corpus() {
+    for (path, data) in corpus() {
+        for (index, options) in option_sets().iter().enumerate() {
+            assert_replay_matches(&data, options, None, &format!("{} with option set {}", path, index));
+        }
+    }
+}
+
+#[test]
+fn test_replay_is_repeatable() {
+    let data = include_str!("multi_line.ini");
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    let tokens = IniTokens::scan(data, &options);
+    assert!(!tokens.is_empty());
+
+    let mut first = VecHandler::new();
+    tokens.replay(&mut first).unwrap();
+    let mut second = VecHandler::new();
+    tokens.replay(&mut second).unwrap();
+    assert_eq!(first.entries(), second.entries());
+    assert_eq!(first.entries().len(), 8);
+}
+
+#[test]
+fn test_replay_returns_parse_errors() {
+    let data = "[ok]\na = 1\n[broken\nb = 2\n";
+    for stop_on_first_error in [false, true] {
+        let options = ParseOptions { stop_on_first_error, ..ParseOptions::default() };
+        let tokens = IniTokens::scan(data, &options);
+        let mut handler = VecHandler::new();
+        assert!(matches!(tokens.replay(&mut handler), Err(IniParseError::ParseError { line: 3, .. })));
+        assert_replay_matches(data, &options, None, "broken header");
+    }
+}
+
+#[test]
+fn test_replay_handler_errors() {
+    let data = "[a]\nx = 1\ny = 2\n[b]\nz = 3\n";
+    for stop_on_first_error in [false, true] {
+        let options = ParseOptions { stop_on_first_error, ..ParseOptions::default() };
+        assert_replay_matches(data, &options, Some("\"y\" \"2\""), "failing entry");
+        assert_replay_matches(data, &options, Some("end_document"), "failing end_document");
+    }
+}
+
+#[test]
+#[ignore = "timing comparison, run with --ignored --nocapture"]
+fn bench_three_replays_against_three_parses() {
+    let mut data = String::new();
+    for section in 0..200 {
+        data.push_str(&format!("[section{}]\n; comment line\n", section));
+        for key in 0..50 {
+            data.push_str(&format!("key{} = some value {} ; trailing comment\n", key, key));
+        }
+    }
+    let options = ParseOptions::default();
+
+    let start = Instant::now();
+    for _ in 0..3 {
+        let mut handler = VecHandler::new();
+        ini_parse_string_with_options(&data, &mut handler, &options).unwrap();
+    }
+    let parsing = start.elapsed();
+
+    let start = Instant::now();
+    let tokens = IniTokens::scan(&data, &options);
+    let scanning = start.elapsed();
+    for _ in 0..3 {
+        let mut handler = VecHandler::new();
+        tokens.replay(&mut handler).unwrap();
+    }
+    let replaying = start.elapsed();
+
+    println!("3 parses: {:?}; scan + 3 replays: {:?} (scan alone {:?})", parsing, replaying, scanning);
+    assert!(replaying < parsing);
+}
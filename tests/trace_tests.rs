@@ -0,0 +1,72 @@
+//! Tests for parse traces (`parse_trace`)
+
+use std::fs;
+
+use inih::error::IniWarning;
+use inih::handlers::VecHandler;
+use inih::trace::{parse_trace, TraceEvent};
+use inih::{ini_parse_string_with_options, IniParseError, ParseOptions};
+
+#[test]
+fn test_continuations_and_warnings() {
+    let options = ParseOptions { allow_multiline: true, warn_suspicious_continuation: true, ..ParseOptions::default() };
+    let trace = parse_trace("[server]\nhost = a\n  port = 8080\n", &options);
+    assert_eq!(
+        trace,
+        vec![
+            TraceEvent::section("server"),
+            TraceEvent::pair("server", "host", "a"),
+            TraceEvent::Warning(IniWarning {
+                line: Some(3),
+                section: "server".to_string(),
+                name: "host".to_string(),
+                message: "Indented line 'port = 8080' looks like an entry but continues the value of 'host'".to_string(),
+            }),
+            TraceEvent::continuation("server", "host", "  port = 8080"),
+        ]
+    );
+}
+
+#[test]
+fn test_error_comes_last() {
+    let data = "a = 1\n[]\nb = 2\n";
+    let error = IniParseError::ParseError { line: 2, message: "Empty section name".to_string(), suggestion: None };
+    assert_eq!(
+        parse_trace(data, &ParseOptions::default()),
+        vec![TraceEvent::pair("", "a", "1"), TraceEvent::pair("", "b", "2"), TraceEvent::Error(error.clone())]
+    );
+
+    let options = ParseOptions { stop_on_first_error: true, ..ParseOptions::default() };
+    assert_eq!(parse_trace(data, &options), vec![TraceEvent::pair("", "a", "1"), TraceEvent::Error(error)]);
+}
+
+#[test]
+fn test_pairs_match_a_direct_parse() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests");
+    let options = ParseOptions::default();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "ini") {
+            continue;
+        }
+        let data = fs::read_to_string(&path).unwrap();
+
+        let mut handler = VecHandler::new();
+        let result = ini_parse_string_with_options(&data, &mut handler, &options);
+        let trace = parse_trace(&data, &options);
+
+        let entries: Vec<_> = trace
+            .iter()
+            .filter_map(|event| match event {
+                TraceEvent::Pair { section, name, value } => Some((section.clone(), name.clone(), value.clone())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(entries, handler.entries(), "{}", path.display());
+        let error = trace.last().and_then(|event| match event {
+            TraceEvent::Error(error) => Some(error.clone()),
+            _ => None,
+        });
+        assert_eq!(error, result.err(), "{}", path.display());
+    }
+}
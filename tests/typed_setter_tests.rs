@@ -0,0 +1,179 @@
+//! Round trips through the typed setters and getters of `IniReader`
+
+use std::time::Duration;
+
+use inih::writer::{BooleanStyle, DurationStyle, WriteOptions};
+use inih::error::IniWriteError;
+use inih::{DuplicateKeyPolicy, IniReader, ParseOptions};
+
+/// The reader after writing it out and reading it back
+fn reparsed(reader: &IniReader) -> IniReader {
+    IniReader::from_string(&reader.to_ini_string()).unwrap()
+}
+
+#[test]
+fn test_integer_round_trip() {
+    let mut reader = IniReader::from_string("").unwrap();
+    for value in [0, 42, -7, i64::MIN, i64::MAX] {
+        reader.set_integer("numbers", "value", value);
+        assert_eq!(reader.get("numbers", "value", ""), value.to_string());
+        assert_eq!(reparsed(&reader).get_integer("numbers", "value", 1), value);
+    }
+}
+
+#[test]
+fn test_boolean_styles() {
+    let mut reader = IniReader::from_string("").unwrap();
+    let yes_no = WriteOptions { boolean_style: BooleanStyle::YesNo, ..WriteOptions::default() };
+
+    reader.set_boolean("flags", "plain", true, &WriteOptions::default());
+    reader.set_boolean("flags", "worded", false, &yes_no);
+    assert_eq!(reader.to_ini_string(), "[flags]\nplain = true\nworded = no\n");
+
+    let reader = reparsed(&reader);
+    assert!(reader.get_boolean("flags", "plain", false));
+    assert!(!reader.get_boolean("flags", "worded", true));
+}
+
+#[test]
+fn test_real_round_trip() {
+    let mut reader = IniReader::from_string("").unwrap();
+    let options = WriteOptions::default();
+    for value in [0.1 + 0.2, 2.0, -1.5, 1e300, 5e-324, f64::INFINITY] {
+        reader.set_real("numbers", "value", value, &options);
+        assert_eq!(reparsed(&reader).get_real("numbers", "value", 0.0), value);
+    }
+
+    reader.set_real("numbers", "value", 2.0, &options);
+    assert_eq!(reader.get("numbers", "value", ""), "2.0");
+    reader.set_real("numbers", "value", f64::NAN, &options);
+    assert!(reparsed(&reader).get_real("numbers", "value", 0.0).is_nan());
+}
+
+#[test]
+fn test_real_precision() {
+    let mut reader = IniReader::from_string("").unwrap();
+    let options = WriteOptions { real_precision: Some(3), ..WriteOptions::default() };
+
+    reader.set_real("numbers", "sum", 0.1 + 0.2, &WriteOptions::default());
+    assert_eq!(reader.get("numbers", "sum", ""), "0.30000000000000004");
+
+    reader.set_real("numbers", "sum", 0.1 + 0.2, &options);
+    assert_eq!(reader.get("numbers", "sum", ""), "0.3");
+    assert_eq!(reparsed(&reader).get_real("numbers", "sum", 0.0), 0.3);
+
+    for (value, written) in [(2.0, "2.0"), (1.23456, "1.235"), (1234.5, "1234.5"), (-0.0004, "-0.0")] {
+        reader.set_real("numbers", "value", value, &options);
+        assert_eq!(reader.get("numbers", "value", ""), written);
+    }
+    reader.set_real("numbers", "value", 2.5001, &WriteOptions { real_precision: Some(0), ..options });
+    assert_eq!(reader.get("numbers", "value", ""), "3.0");
+}
+
+#[test]
+fn test_duration_styles() {
+    let mut reader = IniReader::from_string("").unwrap();
+    let units = WriteOptions { duration_style: DurationStyle::Units, ..WriteOptions::default() };
+    let cases = [
+        (Duration::from_secs(90), "90s", "1m30s"),
+        (Duration::from_secs(0), "0s", "0s"),
+        (Duration::from_secs(3600), "3600s", "1h"),
+        (Duration::from_secs(93_784), "93784s", "1d2h3m4s"),
+        (Duration::from_millis(1500), "1500ms", "1s500ms"),
+        (Duration::from_millis(250), "250ms", "250ms"),
+        (Duration::from_millis(3_600_001), "3600001ms", "1h1ms"),
+        (Duration::from_micros(1_500_250), "1.50025s", "1.50025s"),
+    ];
+    for (value, seconds, compound) in cases {
+        reader.set_duration("timing", "plain", value, &WriteOptions::default());
+        reader.set_duration("timing", "units", value, &units);
+        assert_eq!(reader.get("timing", "plain", ""), seconds);
+        assert_eq!(reader.get("timing", "units", ""), compound);
+
+        let reader = reparsed(&reader);
+        assert_eq!(reader.get_duration("timing", "plain", Duration::MAX), value);
+        assert_eq!(reader.get_duration("timing", "units", Duration::MAX), value);
+    }
+}
+
+#[test]
+fn test_list_round_trip() {
+    let mut reader = IniReader::from_string("").unwrap();
+    let items = ["alpha", "beta gamma", "delta"];
+    reader.set_list("lists", "spaced", &items, ", ").unwrap();
+    reader.set_list("lists", "tight", &items, ",").unwrap();
+    reader.set_list("lists", "empty", &[], ", ").unwrap();
+
+    assert_eq!(reader.get("lists", "spaced", ""), "alpha, beta gamma, delta");
+    let reader = reparsed(&reader);
+    assert_eq!(reader.get_list("lists", "spaced"), items);
+    assert_eq!(reader.get_list("lists", "tight"), items);
+    assert!(reader.get_list("lists", "empty").is_empty());
+    assert!(reader.get_list("lists", "missing").is_empty());
+}
+
+#[test]
+fn test_list_that_would_not_read_back() {
+    let mut reader = IniReader::from_string("").unwrap();
+    for (items, delimiter) in [(&["a", "b"][..], ";"), (&["a", "b"], " | "), (&["a,b", "c"], ", "), (&["a", ""], ", "), (&[" a", "b"], ", ")] {
+        let error = reader.set_list("lists", "bad", items, delimiter).unwrap_err();
+        assert!(matches!(error, IniWriteError::InvalidList { .. }), "{:?} {:?}", items, delimiter);
+    }
+    assert!(!reader.has_value("lists", "bad"));
+    assert_eq!(
+        reader.set_list("lists", "bad", &["a", "b"], ";").unwrap_err().to_string(),
+        "cannot write list: delimiter is not a comma or newline with optional whitespace"
+    );
+}
+
+#[test]
+fn test_setters_replace_and_order() {
+    let mut reader = IniReader::from_string("[server]\nport = 80\nhost = example.com\n").unwrap();
+    reader.set_integer("Server", "PORT", 8080);
+    reader.set_boolean("server", "tls", true, &WriteOptions::default());
+
+    assert_eq!(reader.to_ini_string(), "[server]\nport = 8080\nhost = example.com\ntls = true\n");
+    assert_eq!(reader.occurrences("server", "port").len(), 1);
+}
+
+#[test]
+fn test_toggle_boolean_keeps_style() {
+    let mut reader = IniReader::from_string("[flags]\na = yes\nb = on\nc = 1\nd = true\ne = Off\nf = maybe\n").unwrap();
+    for (key, toggled, value) in [("a", "no", false), ("b", "off", false), ("c", "0", false), ("d", "false", false), ("e", "On", true)] {
+        assert_eq!(reader.toggle_boolean("flags", key), Some(value), "{}", key);
+        assert_eq!(reader.get("flags", key, ""), toggled);
+    }
+    assert_eq!(reader.toggle_boolean("flags", "a"), Some(true));
+    assert_eq!(reader.get("flags", "a", ""), "yes");
+
+    // Not a boolean: left alone; missing: set in the plain style
+    assert_eq!(reader.toggle_boolean("flags", "f"), None);
+    assert_eq!(reader.get("flags", "f", ""), "maybe");
+    assert_eq!(reader.toggle_boolean("flags", "g"), Some(true));
+    assert_eq!(reader.get("flags", "g", ""), "true");
+}
+
+#[test]
+fn test_toggle_boolean_in_document() {
+    let input = "; features\n[flags]\ncache=yes   ; keep this\nDebug : ON\n";
+    let mut reader = IniReader::from_string_preserving(input, &ParseOptions::default()).unwrap();
+    assert_eq!(reader.toggle_boolean("flags", "cache"), Some(false));
+    assert_eq!(reader.toggle_boolean("FLAGS", "debug"), Some(false));
+    assert!(reader.document().is_some());
+    assert_eq!(reader.to_ini_string(), "; features\n[flags]\ncache=no   ; keep this\nDebug : OFF\n");
+    assert!(!IniReader::from_string(&reader.to_ini_string()).unwrap().get_boolean("flags", "debug", true));
+
+    // A key the document does not have cannot be toggled in place
+    assert_eq!(reader.toggle_boolean("flags", "verbose"), Some(true));
+    assert!(reader.document().is_none());
+}
+
+#[test]
+fn test_toggle_boolean_in_document_finds_the_entry_read() {
+    // The value comes from the first entry, which is the one toggled
+    let options = ParseOptions { duplicate_key_policy: DuplicateKeyPolicy::FirstWins, ..ParseOptions::default() };
+    let mut reader = IniReader::from_string_preserving("[f]\nx = yes\nx = on\n", &options).unwrap();
+    assert_eq!(reader.toggle_boolean("F", " X "), Some(false));
+    assert!(reader.document().is_some());
+    assert_eq!(reader.to_ini_string(), "[f]\nx = no\nx = on\n");
+}
@@ -0,0 +1,144 @@
+//! Tests for building INI text with `IniWriter`
+
+use inih::error::IniWriteError;
+use inih::writer::{IniWriter, Separator, WriteOptions};
+use inih::{IniReader, ParseOptions};
+
+/// Read the writer's output back with `options`
+fn reparse(writer: &IniWriter, options: &ParseOptions) -> IniReader {
+    IniReader::from_string_with_options(&writer.to_string(), options).unwrap()
+}
+
+#[test]
+fn test_sections_in_insertion_order() {
+    let mut writer = IniWriter::new();
+    writer.set("zeta", "b", "2").unwrap();
+    writer.set("alpha", "a", "1").unwrap();
+    writer.set("", "global", "yes").unwrap();
+    writer.set("Zeta", "A", "3").unwrap();
+    writer.set("zeta", "B", "4").unwrap();
+
+    assert_eq!(writer.to_string(), "global = yes\n\n[zeta]\nb = 4\nA = 3\n\n[alpha]\na = 1\n");
+    let reader = reparse(&writer, &ParseOptions::default());
+    assert_eq!(reader.get("zeta", "b", ""), "4");
+    assert_eq!(reader.get("", "global", ""), "yes");
+}
+
+#[test]
+fn test_empty_sections() {
+    let mut writer = IniWriter::new();
+    assert_eq!(writer.to_string(), "");
+
+    writer.set_section("first").unwrap();
+    writer.set_section("second").unwrap();
+    writer.set("second", "key", "value").unwrap();
+    writer.set_section("third").unwrap();
+    writer.set_section("FIRST").unwrap();
+
+    assert_eq!(writer.to_string(), "[first]\n\n[second]\nkey = value\n\n[third]\n");
+    let reader = reparse(&writer, &ParseOptions::default());
+    assert_eq!(reader.sections(), ["first", "second", "third"]);
+    assert!(reader.keys("first").is_empty());
+}
+
+#[test]
+fn test_colon_separator() {
+    let options = WriteOptions { separator: Separator::Colon, ..WriteOptions::default() };
+    let mut writer = IniWriter::with_options(options.clone());
+    writer.set("server", "url", "http://example.com:8080/").unwrap();
+
+    assert_eq!(writer.to_string(), "[server]\nurl: http://example.com:8080/\n");
+    let reader = reparse(&writer, &ParseOptions::default());
+    assert_eq!(reader.get("server", "url", ""), "http://example.com:8080/");
+    assert_eq!(reader.to_ini_string_with(&options), writer.to_string());
+}
+
+#[test]
+fn test_surrounding_whitespace_rejected() {
+    let mut writer = IniWriter::new();
+    for value in [" padded", "padded ", "\tpadded"] {
+        assert_eq!(
+            writer.set("s", "k", value),
+            Err(IniWriteError::InvalidValue {
+                section: "s".to_string(),
+                name: "k".to_string(),
+                reason: "value has leading or trailing whitespace",
+            })
+        );
+    }
+    writer.set("s", "k", "inner  spaces kept").unwrap();
+    assert_eq!(reparse(&writer, &ParseOptions::default()).get("s", "k", ""), "inner  spaces kept");
+}
+
+#[test]
+fn test_keys_without_value() {
+    let mut writer = IniWriter::new();
+    writer.set("flags", "verbose", "").unwrap();
+    writer.set_flag("flags", "debug").unwrap();
+    assert_eq!(writer.to_string(), "[flags]\nverbose = \ndebug\n");
+
+    let options = ParseOptions { allow_no_value: true, ..ParseOptions::default() };
+    let reader = reparse(&writer, &options);
+    assert_eq!(reader.get("flags", "verbose", "default"), "");
+    assert!(reader.is_flag("flags", "debug"));
+    assert!(!reader.is_flag("flags", "verbose"));
+
+    let mut normalized = IniWriter::with_options(WriteOptions { normalize_bare_keys: true, ..WriteOptions::default() });
+    normalized.set_flag("flags", "debug").unwrap();
+    assert_eq!(normalized.to_string(), "[flags]\ndebug = \n");
+}
+
+#[test]
+fn test_newlines_need_multiline() {
+    let mut writer = IniWriter::new();
+    let error = writer.set("s", "text", "first\n  second").unwrap_err();
+    assert_eq!(error.to_string(), "cannot write value of s.text: value contains a line break and allow_multiline is off");
+
+    let mut writer = IniWriter::with_options(WriteOptions { allow_multiline: true, ..WriteOptions::default() });
+    writer.set("s", "text", "first\n  second").unwrap();
+    assert!(writer.set("s", "bad", "first\nsecond").is_err());
+    assert!(writer.set("s", "bad", "first\n  \n  third").is_err());
+    assert!(writer.set("s", "bad", "first\n  ; comment").is_err());
+
+    let options = ParseOptions { allow_multiline: true, ..ParseOptions::default() };
+    assert_eq!(reparse(&writer, &options).get("s", "text", ""), "first\n  second");
+}
+
+#[test]
+fn test_unreadable_names_rejected() {
+    let mut writer = IniWriter::new();
+    assert!(matches!(writer.set_section("a]b"), Err(IniWriteError::InvalidSection { .. })));
+    assert!(matches!(writer.set_section(" padded "), Err(IniWriteError::InvalidSection { .. })));
+    assert!(matches!(writer.set_section("line\nbreak"), Err(IniWriteError::InvalidSection { .. })));
+    for name in ["a=b", "a:b", "", " k", ";k", "[k"] {
+        assert!(matches!(writer.set("s", name, "v"), Err(IniWriteError::InvalidName { .. })), "{:?}", name);
+    }
+    assert!(matches!(writer.set("s", "k", "a ;b"), Err(IniWriteError::InvalidValue { .. })));
+    assert_eq!(writer.to_string(), "");
+}
+
+#[test]
+fn test_round_trip_values() {
+    let values = ["plain", "with = sign", "a;b", "x: y", "quoted \"text\"", "ünïcödé", "[not a header]", ""];
+    let mut writer = IniWriter::new();
+    for (index, value) in values.iter().enumerate() {
+        writer.set("values", &format!("key{}", index), value).unwrap();
+    }
+
+    let reader = reparse(&writer, &ParseOptions::default());
+    for (index, value) in values.iter().enumerate() {
+        assert_eq!(reader.get("values", format!("key{}", index), "missing"), *value);
+    }
+}
+
+#[test]
+fn test_write_to_file() {
+    let mut writer = IniWriter::new();
+    writer.set("server", "port", "8080").unwrap();
+    let path = std::env::temp_dir().join(format!("inih_writer_test_{}.ini", std::process::id()));
+    writer.write_to_file(&path).unwrap();
+
+    let reader = IniReader::from_file(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(reader.get_integer("server", "port", 0), 8080);
+}